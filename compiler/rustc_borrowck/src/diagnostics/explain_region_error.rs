@@ -0,0 +1,146 @@
+//! Explanations of region errors for `-Z borrowck-explain`: the shortest chain of outlives
+//! constraints that requires one region to outlive another, which is otherwise only visible in
+//! the constraint graph.
+
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+
+use rustc_errors::Diag;
+use rustc_graphviz as dot;
+use rustc_middle::mir::ConstraintCategory;
+use rustc_middle::ty::RegionVid;
+use rustc_session::config::SwitchWithOptPath;
+
+use crate::nll::ConstraintDescription;
+use crate::session_diagnostics::BorrowckExplainWriteFailed;
+use crate::MirBorrowckCtxt;
+
+impl<'cx, 'tcx> MirBorrowckCtxt<'cx, 'tcx> {
+    /// Adds the chain of outlives constraints that requires `fr: outlived_fr` to `diag`, with the
+    /// span and category of each constraint. If `-Z borrowck-explain` was given a directory, the
+    /// chain is also written there as a graphviz file.
+    // FIXME: make this translatable
+    #[allow(rustc::diagnostic_outside_of_impl)]
+    #[allow(rustc::untranslatable_diagnostic)]
+    pub(crate) fn explain_region_error(
+        &self,
+        fr: RegionVid,
+        outlived_fr: RegionVid,
+        diag: &mut Diag<'tcx>,
+    ) {
+        let tcx = self.infcx.tcx;
+        let SwitchWithOptPath::Enabled(dir) = &tcx.sess.opts.unstable_opts.borrowck_explain else {
+            return;
+        };
+        let Some((path, _)) = self.regioncx.find_constraint_paths_between_regions(fr, |r| {
+            self.regioncx.provides_universal_region(r, fr, outlived_fr)
+        }) else {
+            return;
+        };
+
+        diag.note(format!(
+            "`{}: {}` is required by this chain of outlives constraints:",
+            self.region_label(fr),
+            self.region_label(outlived_fr),
+        ));
+        let mut nodes = vec![self.region_label(fr)];
+        let mut edges = vec![];
+        for constraint in &path {
+            let sub = self.region_label(constraint.sub);
+            let category = category_label(constraint.category);
+            let msg = format!("`{}: {sub}` ({category})", nodes[nodes.len() - 1]);
+            if constraint.span.is_dummy() {
+                diag.note(msg);
+                edges.push(category.to_string());
+            } else {
+                diag.span_note(constraint.span, msg);
+                let span = tcx.sess.source_map().span_to_embeddable_string(constraint.span);
+                edges.push(format!("{category} at {span}"));
+            }
+            nodes.push(sub);
+        }
+
+        let Some(dir) = dir else { return };
+        let def_path = tcx.def_path(self.mir_def_id().to_def_id()).to_filename_friendly_no_crate();
+        let file_path = dir.join(format!("{def_path}.{}-{}.dot", fr.index(), outlived_fr.index()));
+        let res: io::Result<()> = try {
+            fs::create_dir_all(dir)?;
+            let mut file = BufWriter::new(File::create(&file_path)?);
+            dot::render(&ConstraintChain { nodes, edges }, &mut file)?;
+            file.flush()?;
+        };
+        if let Err(error) = res {
+            tcx.dcx().emit_err(BorrowckExplainWriteFailed { path: &file_path, error });
+        }
+    }
+
+    /// The name of `r` in diagnostics, or its index if it isn't a universal region.
+    fn region_label(&self, r: RegionVid) -> String {
+        if self.regioncx.universal_regions().is_universal_region(r)
+            && let Some(name) = self.give_region_a_name(r)
+        {
+            name.to_string()
+        } else {
+            format!("{r:?}")
+        }
+    }
+}
+
+fn category_label(category: ConstraintCategory<'_>) -> &'static str {
+    match category {
+        ConstraintCategory::Predicate(_) => "where clause",
+        ConstraintCategory::Boring
+        | ConstraintCategory::BoringNoLocation
+        | ConstraintCategory::Internal => "internal",
+        _ => category.description().trim_end(),
+    }
+}
+
+/// A chain of regions, where each region outlives the next one.
+struct ConstraintChain {
+    nodes: Vec<String>,
+    /// The label of the constraint between each node and the next one.
+    edges: Vec<String>,
+}
+
+impl<'a> dot::Labeller<'a> for ConstraintChain {
+    type Node = usize;
+    type Edge = usize;
+
+    fn graph_id(&'a self) -> dot::Id<'a> {
+        dot::Id::new("ConstraintChain").unwrap()
+    }
+    fn node_id(&'a self, n: &usize) -> dot::Id<'a> {
+        dot::Id::new(format!("r{n}")).unwrap()
+    }
+    fn node_shape(&'a self, _node: &usize) -> Option<dot::LabelText<'a>> {
+        Some(dot::LabelText::LabelStr(Cow::Borrowed("box")))
+    }
+    fn node_label(&'a self, n: &usize) -> dot::LabelText<'a> {
+        dot::LabelText::LabelStr(Cow::Borrowed(&self.nodes[*n]))
+    }
+    fn edge_label(&'a self, e: &usize) -> dot::LabelText<'a> {
+        dot::LabelText::LabelStr(Cow::Borrowed(&self.edges[*e]))
+    }
+}
+
+impl<'a> dot::GraphWalk<'a> for ConstraintChain {
+    type Node = usize;
+    type Edge = usize;
+
+    fn nodes(&'a self) -> dot::Nodes<'a, usize> {
+        (0..self.nodes.len()).collect()
+    }
+    fn edges(&'a self) -> dot::Edges<'a, usize> {
+        (0..self.edges.len()).collect()
+    }
+
+    // Like the other constraint graphs, `a: b` is rendered as `a -> b`.
+    fn source(&'a self, edge: &usize) -> usize {
+        *edge
+    }
+    fn target(&'a self, edge: &usize) -> usize {
+        *edge + 1
+    }
+}
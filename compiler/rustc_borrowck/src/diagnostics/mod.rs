@@ -42,6 +42,7 @@
 mod bound_region_errors;
 mod conflict_errors;
 mod explain_borrow;
+mod explain_region_error;
 mod move_errors;
 mod mutability_errors;
 mod region_errors;
@@ -452,7 +452,8 @@ pub(crate) fn report_region_error(
         if let (Some(f), Some(o)) = (self.to_error_region(fr), self.to_error_region(outlived_fr)) {
             let infer_err = self.infcx.err_ctxt();
             let nice = NiceRegionError::new_from_span(&infer_err, cause.span, o, f);
-            if let Some(diag) = nice.try_report_from_nll() {
+            if let Some(mut diag) = nice.try_report_from_nll() {
+                self.explain_region_error(fr, outlived_fr, &mut diag);
                 self.buffer_error(diag);
                 return;
             }
@@ -561,6 +562,7 @@ pub(crate) fn report_region_error(
             }
         }
 
+        self.explain_region_error(fr, outlived_fr, &mut diag);
         self.buffer_error(diag);
     }
 
@@ -2,6 +2,8 @@
 use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_middle::ty::{GenericArg, Ty};
 use rustc_span::Span;
+use std::io;
+use std::path::Path;
 
 use crate::diagnostics::RegionName;
 
@@ -461,3 +463,10 @@ pub(crate) struct SimdIntrinsicArgConst {
     pub arg: usize,
     pub intrinsic: String,
 }
+
+#[derive(Diagnostic)]
+#[diag(borrowck_explain_write_failed)]
+pub(crate) struct BorrowckExplainWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
@@ -100,6 +100,26 @@ builtin_macros_concat_missing_literal = expected a literal
 builtin_macros_default_arg = `#[default]` attribute does not accept a value
     .suggestion = try using `#[default]`
 
+builtin_macros_default_param_attr = the `#[default]` attribute on type parameters only accepts `no_bound`
+    .suggestion = exempt the type parameter from the `Default` bound
+
+builtin_macros_derive_field_attr_args = `#[{$name}]` expects a list of options
+    .label = expected something like `#[{$name}(skip)]`
+
+builtin_macros_derive_field_attr_inconsistent_skip = field is skipped by `#[{$skipped}(skip)]`, but not by `#[{$other}(skip)]`
+    .note = {$other ->
+        [hash] values that are equal must have the same hash, so fields ignored by `PartialEq` must also be ignored by `Hash`
+        *[other] `PartialEq` must agree with `PartialOrd` and `Ord`, so they must ignore the same fields
+    }
+    .help = add `#[{$other}(skip)]` to the field
+
+builtin_macros_derive_field_attr_unknown_option = unknown option `{$option}` in `#[{$name}]`
+    .label = expected {$name ->
+        [ord] `skip` or `reverse`
+        [hash] `skip` or `with = path`
+        *[other] `skip`
+    }
+
 builtin_macros_derive_macro_call = `derive` cannot be used on items with type macros
 
 builtin_macros_derive_path_args_list = traits in `#[derive(...)]` don't accept arguments
@@ -212,7 +232,10 @@ builtin_macros_non_exhaustive_default = default variant must be exhaustive
     .label = declared `#[non_exhaustive]` here
     .help = consider a manual implementation of `Default`
 
-builtin_macros_non_unit_default = the `#[default]` attribute may only be used on unit enum variants
+builtin_macros_non_unit_default = the `#[default]` attribute may only be used on {$unit_only ->
+    [true] unit enum variants
+    *[false] enum variants
+    }
     .help = consider a manual implementation of `Default`
 
 builtin_macros_proc_macro = `proc-macro` crate types currently cannot export any items other than functions tagged with `#[proc_macro]`, `#[proc_macro_derive]`, or `#[proc_macro_attribute]`
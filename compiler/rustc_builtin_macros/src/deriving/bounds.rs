@@ -23,6 +23,8 @@ pub fn expand_deriving_copy(
         methods: Vec::new(),
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: None,
     };
 
     trait_def.expand(cx, mitem, item, push);
@@ -46,6 +48,8 @@ pub fn expand_deriving_const_param_ty(
         methods: Vec::new(),
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: None,
     };
 
     trait_def.expand(cx, mitem, item, push);
@@ -87,6 +87,8 @@ pub fn expand_deriving_clone(
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: None,
     };
 
     trait_def.expand_ext(cx, mitem, item, push, is_simple)
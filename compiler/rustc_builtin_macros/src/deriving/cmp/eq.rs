@@ -1,3 +1,4 @@
+use crate::deriving::field_options::parse_field_options;
 use crate::deriving::generic::ty::*;
 use crate::deriving::generic::*;
 use crate::deriving::path_std;
@@ -44,6 +45,8 @@ pub fn expand_deriving_eq(
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: None,
     };
     trait_def.expand_ext(cx, mitem, item, push, true)
 }
@@ -57,6 +60,10 @@ fn cs_total_eq_assert(
     let mut seen_type_names = FxHashSet::default();
     let mut process_variant = |variant: &ast::VariantData| {
         for field in variant.fields() {
+            if parse_field_options(cx, field, sym::eq).skip {
+                // `PartialEq` ignores the field, so it doesn't need to be `Eq`.
+                continue;
+            }
             // This basic redundancy checking only prevents duplication of
             // assertions like `AssertParamIsEq<Foo>` where the type is a
             // simple name. That's enough to get a lot of cases, though.
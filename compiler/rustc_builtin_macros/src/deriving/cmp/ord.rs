@@ -34,6 +34,8 @@ pub fn expand_deriving_ord(
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: Some(sym::ord),
+        bound_attr: None,
     };
 
     trait_def.expand(cx, mitem, item, push)
@@ -63,7 +65,10 @@ pub fn cs_cmp(cx: &mut ExtCtxt<'_>, span: Span, substr: &Substructure<'_>) -> Bl
                 let [other_expr] = &field.other_selflike_exprs[..] else {
                     cx.dcx().span_bug(field.span, "not exactly 2 arguments in `derive(Ord)`");
                 };
-                let args = thin_vec![field.self_expr.clone(), other_expr.clone()];
+                let mut args = thin_vec![field.self_expr.clone(), other_expr.clone()];
+                if field.options.reverse {
+                    args.reverse();
+                }
                 cx.expr_call_global(field.span, cmp_path.clone(), args)
             }
             CsFold::Combine(span, expr1, expr2) => {
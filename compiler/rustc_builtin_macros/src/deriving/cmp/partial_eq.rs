@@ -1,3 +1,4 @@
+use crate::deriving::field_options::any_field_skipped;
 use crate::deriving::generic::ty::*;
 use crate::deriving::generic::*;
 use crate::deriving::{path_local, path_std};
@@ -85,8 +86,14 @@ fn cs_eq(cx: &mut ExtCtxt<'_>, span: Span, substr: &Substructure<'_>) -> BlockOr
         methods: Vec::new(),
         associated_types: Vec::new(),
         is_const: false,
+        field_attr: None,
+        bound_attr: None,
     };
-    structural_trait_def.expand(cx, mitem, item, push);
+    // Constants used as patterns are compared field by field, so the type can't be structurally
+    // matched if `eq` ignores some of its fields.
+    if !any_field_skipped(cx, item, sym::eq) {
+        structural_trait_def.expand(cx, mitem, item, push);
+    }
 
     // No need to generate `ne`, the default suffices, and not generating it is
     // faster.
@@ -111,6 +118,8 @@ fn cs_eq(cx: &mut ExtCtxt<'_>, span: Span, substr: &Substructure<'_>) -> BlockOr
         methods,
         associated_types: Vec::new(),
         is_const,
+        field_attr: Some(sym::eq),
+        bound_attr: None,
     };
     trait_def.expand(cx, mitem, item, push)
 }
@@ -64,6 +64,8 @@ pub fn expand_deriving_partial_ord(
         methods: vec![partial_cmp_def],
         associated_types: Vec::new(),
         is_const,
+        field_attr: Some(sym::ord),
+        bound_attr: None,
     };
     trait_def.expand(cx, mitem, item, push)
 }
@@ -97,7 +99,10 @@ fn cs_partial_cmp(
                 let [other_expr] = &field.other_selflike_exprs[..] else {
                     cx.dcx().span_bug(field.span, "not exactly 2 arguments in `derive(Ord)`");
                 };
-                let args = thin_vec![field.self_expr.clone(), other_expr.clone()];
+                let mut args = thin_vec![field.self_expr.clone(), other_expr.clone()];
+                if field.options.reverse {
+                    args.reverse();
+                }
                 cx.expr_call_global(field.span, partial_cmp_path.clone(), args)
             }
             CsFold::Combine(span, mut expr1, expr2) => {
@@ -42,6 +42,8 @@ pub fn expand_deriving_debug(
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: None,
     };
     trait_def.expand(cx, mitem, item, push)
 }
@@ -57,6 +57,8 @@ pub fn expand_deriving_rustc_decodable(
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: None,
     };
 
     trait_def.expand(cx, mitem, item, push)
@@ -2,12 +2,16 @@
 use crate::deriving::generic::*;
 use crate::errors;
 use rustc_ast as ast;
-use rustc_ast::{attr, walk_list, EnumDef, VariantData};
+use rustc_ast::ptr::P;
+use rustc_ast::visit::BoundKind;
+use rustc_ast::{attr, walk_list, EnumDef, GenericParamKind, VariantData};
 use rustc_expand::base::{Annotatable, DummyResult, ExtCtxt};
+use rustc_session::parse::feature_err;
 use rustc_span::symbol::Ident;
 use rustc_span::symbol::{kw, sym};
 use rustc_span::{ErrorGuaranteed, Span};
 use smallvec::SmallVec;
+use std::{iter, ptr};
 use thin_vec::{thin_vec, ThinVec};
 
 pub fn expand_deriving_default(
@@ -23,7 +27,8 @@ pub fn expand_deriving_default(
     let trait_def = TraitDef {
         span,
         path: Path::new(vec![kw::Default, sym::Default]),
-        skip_path_as_bound: has_a_default_variant(item),
+        // Type parameters and field types are bound according to `bound_attr`.
+        skip_path_as_bound: false,
         needs_copy_as_bound_if_packed: false,
         additional_bounds: Vec::new(),
         supports_unions: false,
@@ -40,13 +45,17 @@ pub fn expand_deriving_default(
                     StaticStruct(_, fields) => {
                         default_struct_substructure(cx, trait_span, substr, fields)
                     }
-                    StaticEnum(enum_def, _) => default_enum_substructure(cx, trait_span, enum_def),
+                    StaticEnum(enum_def, summaries) => {
+                        default_enum_substructure(cx, trait_span, enum_def, summaries)
+                    }
                     _ => cx.dcx().span_bug(trait_span, "method in `derive(Default)`"),
                 }
             })),
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: None,
+        bound_attr: Some(kw::Default),
     };
     trait_def.expand(cx, mitem, item, push)
 }
@@ -57,31 +66,42 @@ fn default_struct_substructure(
     substr: &Substructure<'_>,
     summary: &StaticFields,
 ) -> BlockOrExpr {
+    let path = cx.path_ident(trait_span, substr.type_ident);
+    BlockOrExpr::new_expr(default_fields(cx, trait_span, path, summary))
+}
+
+/// Builds the struct or enum variant at `path` with each field set to `Default::default()`.
+fn default_fields(
+    cx: &ExtCtxt<'_>,
+    trait_span: Span,
+    path: ast::Path,
+    summary: &StaticFields,
+) -> P<ast::Expr> {
     // Note that `kw::Default` is "default" and `sym::Default` is "Default"!
     let default_ident = cx.std_path(&[kw::Default, sym::Default, kw::Default]);
     let default_call = |span| cx.expr_call_global(span, default_ident.clone(), ThinVec::new());
 
-    let expr = match summary {
-        Unnamed(_, IsTuple::No) => cx.expr_ident(trait_span, substr.type_ident),
+    match summary {
+        Unnamed(_, IsTuple::No) => cx.expr_path(path),
         Unnamed(fields, IsTuple::Yes) => {
             let exprs = fields.iter().map(|sp| default_call(*sp)).collect();
-            cx.expr_call_ident(trait_span, substr.type_ident, exprs)
+            cx.expr_call(trait_span, cx.expr_path(path), exprs)
         }
         Named(fields) => {
             let default_fields = fields
                 .iter()
                 .map(|&(ident, span)| cx.field_imm(span, ident, default_call(span)))
                 .collect();
-            cx.expr_struct_ident(trait_span, substr.type_ident, default_fields)
+            cx.expr_struct(trait_span, path, default_fields)
         }
-    };
-    BlockOrExpr::new_expr(expr)
+    }
 }
 
 fn default_enum_substructure(
     cx: &mut ExtCtxt<'_>,
     trait_span: Span,
     enum_def: &EnumDef,
+    summaries: &[(Ident, Span, StaticFields)],
 ) -> BlockOrExpr {
     let expr = match try {
         let default_variant = extract_default_variant(cx, enum_def, trait_span)?;
@@ -89,11 +109,21 @@ fn default_enum_substructure(
         default_variant
     } {
         Ok(default_variant) => {
-            // We now know there is exactly one unit variant with exactly one `#[default]` attribute.
-            cx.expr_path(cx.path(
+            // We now know there is exactly one variant with exactly one `#[default]` attribute.
+            let path = cx.path(
                 default_variant.span,
                 vec![Ident::new(kw::SelfUpper, default_variant.span), default_variant.ident],
-            ))
+            );
+            if let VariantData::Unit(..) = default_variant.data {
+                cx.expr_path(path)
+            } else {
+                let summary = iter::zip(&enum_def.variants, summaries)
+                    .find_map(|(variant, (_, _, summary))| {
+                        ptr::eq(variant, default_variant).then_some(summary)
+                    })
+                    .unwrap();
+                default_fields(cx, trait_span, path, summary)
+            }
         }
         Err(guar) => DummyResult::raw_expr(trait_span, Some(guar)),
     };
@@ -153,8 +183,16 @@ fn extract_default_variant<'a>(
         }
     };
 
-    if !matches!(variant.data, VariantData::Unit(..)) {
-        let guar = cx.dcx().emit_err(errors::NonUnitDefault { span: variant.ident.span });
+    if !matches!(variant.data, VariantData::Unit(..))
+        && !cx.ecfg.features.derive_default_extensions
+    {
+        let guar = feature_err(
+            cx.sess,
+            sym::derive_default_extensions,
+            variant.ident.span,
+            "`#[default]` on enum variants with fields is experimental",
+        )
+        .emit();
         return Err(guar);
     }
 
@@ -213,11 +251,35 @@ struct DetectNonVariantDefaultAttr<'a, 'b> {
 impl<'a, 'b> rustc_ast::visit::Visitor<'a> for DetectNonVariantDefaultAttr<'a, 'b> {
     fn visit_attribute(&mut self, attr: &'a rustc_ast::Attribute) {
         if attr.has_name(kw::Default) {
-            self.cx.dcx().emit_err(errors::NonUnitDefault { span: attr.span });
+            self.cx.dcx().emit_err(errors::NonUnitDefault {
+                span: attr.span,
+                unit_only: !self.cx.ecfg.features.derive_default_extensions,
+            });
         }
 
         rustc_ast::visit::walk_attribute(self, attr);
     }
+    fn visit_generic_param(&mut self, param: &'a rustc_ast::GenericParam) {
+        self.visit_ident(param.ident);
+        for attr in &param.attrs {
+            if let GenericParamKind::Type { .. } = param.kind
+                && attr.has_name(kw::Default)
+            {
+                validate_no_bound_attribute(self.cx, attr);
+            } else {
+                self.visit_attribute(attr);
+            }
+        }
+        walk_list!(self, visit_param_bound, &param.bounds, BoundKind::Bound);
+        match &param.kind {
+            GenericParamKind::Lifetime => {}
+            GenericParamKind::Type { default } => walk_list!(self, visit_ty, default),
+            GenericParamKind::Const { ty, default, .. } => {
+                self.visit_ty(ty);
+                walk_list!(self, visit_anon_const, default);
+            }
+        }
+    }
     fn visit_variant(&mut self, v: &'a rustc_ast::Variant) {
         self.visit_ident(v.ident);
         self.visit_vis(&v.vis);
@@ -229,21 +291,21 @@ fn visit_variant(&mut self, v: &'a rustc_ast::Variant) {
     }
 }
 
-fn has_a_default_variant(item: &Annotatable) -> bool {
-    struct HasDefaultAttrOnVariant {
-        found: bool,
+/// Checks a `#[default]` attribute on a type parameter, which has to be `#[default(no_bound)]`.
+fn validate_no_bound_attribute(cx: &ExtCtxt<'_>, attr: &ast::Attribute) {
+    if !cx.ecfg.features.derive_default_extensions {
+        feature_err(
+            cx.sess,
+            sym::derive_default_extensions,
+            attr.span,
+            "`#[default(no_bound)]` on type parameters is experimental",
+        )
+        .emit();
     }
-
-    impl<'ast> rustc_ast::visit::Visitor<'ast> for HasDefaultAttrOnVariant {
-        fn visit_variant(&mut self, v: &'ast rustc_ast::Variant) {
-            if v.attrs.iter().any(|attr| attr.has_name(kw::Default)) {
-                self.found = true;
-            }
-            // no need to subrecurse.
-        }
+    let is_no_bound = attr.meta_item_list().is_some_and(|list| {
+        matches!(&list[..], [item] if item.is_word() && item.has_name(sym::no_bound))
+    });
+    if !is_no_bound {
+        cx.dcx().emit_err(errors::DefaultParamAttr { span: attr.span });
     }
-
-    let mut visitor = HasDefaultAttrOnVariant { found: false };
-    item.visit_with(&mut visitor);
-    visitor.found
 }
@@ -0,0 +1,118 @@
+//! The helper attributes that customize how the comparison and hashing derives handle a field:
+//! `#[eq(skip)]` for `PartialEq` and `Eq`, `#[ord(skip, reverse)]` for `PartialOrd` and `Ord`, and
+//! `#[hash(skip, with = path)]` for `Hash`.
+
+use crate::errors;
+use rustc_ast::ptr::P;
+use rustc_ast::token::{self, Delimiter};
+use rustc_ast::{self as ast, AttrArgs, DelimArgs, Expr};
+use rustc_errors::PResult;
+use rustc_expand::base::{Annotatable, ExtCtxt};
+use rustc_parse::parser::Parser;
+use rustc_span::symbol::{sym, Symbol};
+
+/// The options given to a field by the helper attribute of a derive.
+#[derive(Default)]
+pub struct FieldOptions {
+    /// `skip`: the field is ignored.
+    pub skip: bool,
+    /// `reverse`: the field is ordered from largest to smallest.
+    pub reverse: bool,
+    /// `with = path`: the field is hashed by calling `path(&field, state)` instead of
+    /// `Hash::hash`.
+    pub with: Option<P<Expr>>,
+}
+
+/// Parses the options that the `#[name(..)]` attributes of `field` give. Without
+/// `#![feature(derive_field_attrs)]`, these attributes aren't helpers of the derives, so they are
+/// ignored and left to the attribute macros they resolve to.
+///
+/// Every derive of an item that needs an attribute parses it again, but the errors are identical
+/// and so only reported once.
+pub fn parse_field_options(cx: &ExtCtxt<'_>, field: &ast::FieldDef, name: Symbol) -> FieldOptions {
+    let mut options = FieldOptions::default();
+    if !cx.ecfg.features.derive_field_attrs {
+        return options;
+    }
+    for attr in field.attrs.iter().filter(|attr| attr.has_name(name)) {
+        let ast::AttrKind::Normal(normal) = &attr.kind else { continue };
+        let AttrArgs::Delimited(DelimArgs { delim: Delimiter::Parenthesis, tokens, .. }) =
+            &normal.item.args
+        else {
+            cx.dcx().emit_err(errors::DeriveFieldAttrArgs { span: attr.span, name });
+            continue;
+        };
+        let mut parser = cx.new_parser_from_tts(tokens.clone());
+        if let Err(err) = parse_options(cx, &mut parser, name, &mut options) {
+            err.emit();
+        }
+    }
+    options
+}
+
+fn parse_options<'a>(
+    cx: &ExtCtxt<'a>,
+    p: &mut Parser<'a>,
+    name: Symbol,
+    options: &mut FieldOptions,
+) -> PResult<'a, ()> {
+    while p.token != token::Eof {
+        let option = p.parse_ident()?;
+        match (name, option.name) {
+            (_, sym::skip) => options.skip = true,
+            (sym::ord, sym::reverse) => options.reverse = true,
+            (sym::hash, sym::with) => {
+                p.expect(&token::Eq)?;
+                options.with = Some(p.parse_expr()?);
+            }
+            _ => {
+                cx.dcx().emit_err(errors::DeriveFieldAttrUnknownOption {
+                    span: option.span,
+                    name,
+                    option: option.name,
+                });
+                return Ok(());
+            }
+        }
+        if !p.eat(&token::Comma) {
+            p.expect(&token::Eof)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reports fields that `PartialEq` skips but the derive using the `name` attribute doesn't, or
+/// the other way around for `PartialOrd` and `Ord`, as the impls would then disagree.
+pub fn check_consistent_skip(
+    cx: &ExtCtxt<'_>,
+    field: &ast::FieldDef,
+    name: Symbol,
+    options: &FieldOptions,
+) {
+    if name == sym::eq {
+        return;
+    }
+    let eq_skip = parse_field_options(cx, field, sym::eq).skip;
+    let (skipped, other) = if eq_skip && !options.skip {
+        (sym::eq, name)
+    } else if options.skip && !eq_skip && name == sym::ord {
+        (name, sym::eq)
+    } else {
+        return;
+    };
+    cx.dcx().emit_err(errors::DeriveFieldAttrInconsistentSkip { span: field.span, skipped, other });
+}
+
+/// Whether any field of `item` is skipped by `#[name(skip)]`.
+pub fn any_field_skipped(cx: &ExtCtxt<'_>, item: &Annotatable, name: Symbol) -> bool {
+    let Annotatable::Item(item) = item else { return false };
+    let variants: Vec<&ast::VariantData> = match &item.kind {
+        ast::ItemKind::Struct(data, _) | ast::ItemKind::Union(data, _) => vec![data],
+        ast::ItemKind::Enum(def, _) => def.variants.iter().map(|v| &v.data).collect(),
+        _ => return false,
+    };
+    variants
+        .into_iter()
+        .flat_map(|data| data.fields())
+        .any(|field| parse_field_options(cx, field, name).skip)
+}
@@ -177,6 +177,7 @@
 pub use StaticFields::*;
 pub use SubstructureFields::*;
 
+use crate::deriving::field_options::{check_consistent_skip, parse_field_options, FieldOptions};
 use crate::{deriving, errors};
 use rustc_ast::ptr::P;
 use rustc_ast::{
@@ -190,7 +191,7 @@
 use rustc_span::{Span, DUMMY_SP};
 use std::cell::RefCell;
 use std::iter;
-use std::ops::Not;
+use std::ptr;
 use std::vec;
 use thin_vec::{thin_vec, ThinVec};
 use ty::{Bounds, Path, Ref, Self_, Ty};
@@ -222,6 +223,14 @@ pub struct TraitDef<'a> {
     pub associated_types: Vec<(Ident, Ty)>,
 
     pub is_const: bool,
+
+    /// The helper attribute customizing how fields are handled, like `eq` for `#[eq(skip)]`.
+    pub field_attr: Option<Symbol>,
+
+    /// The helper attribute changing which types need to implement the current trait, like
+    /// `default`: type parameters marked `#[default(no_bound)]` don't, and if a variant of an
+    /// enum is marked `#[default]`, only the types of its fields do.
+    pub bound_attr: Option<Symbol>,
 }
 
 pub struct MethodDef<'a> {
@@ -284,6 +293,8 @@ pub struct FieldInfo {
     /// The expressions corresponding to references to this field in
     /// the other selflike arguments.
     pub other_selflike_exprs: Vec<P<Expr>>,
+    /// The options given by the helper attribute of the trait.
+    pub options: FieldOptions,
 }
 
 #[derive(Copy, Clone)]
@@ -575,6 +586,9 @@ pub fn expand_ext(
     ///
     /// where B1, ..., BN are the bounds given by `bounds_paths`.'. Z is a phantom type, and
     /// therefore does not get bound by the derived trait.
+    ///
+    /// If `bound_params` is false, only the field types get bound, even when they are type
+    /// parameters.
     fn create_derived_impl(
         &self,
         cx: &mut ExtCtxt<'_>,
@@ -583,6 +597,7 @@ fn create_derived_impl(
         field_tys: Vec<P<ast::Ty>>,
         methods: Vec<P<ast::AssocItem>>,
         is_packed: bool,
+        bound_params: bool,
     ) -> P<ast::Item> {
         let trait_path = self.path.to_path(cx, self.span, type_ident, generics);
 
@@ -614,6 +629,15 @@ fn create_derived_impl(
         let ctxt = self.span.ctxt();
         let span = generics.span.with_ctxt(ctxt);
 
+        // The type parameters that get bound by the current trait.
+        let bound_param_names: Vec<Symbol> = generics
+            .params
+            .iter()
+            .filter(|param| matches!(param.kind, GenericParamKind::Type { .. }))
+            .filter(|param| bound_params && !self.is_exempt_from_bound(param))
+            .map(|param| param.ident.name)
+            .collect();
+
         // Create the generic parameters
         let params: ThinVec<_> = generics
             .params
@@ -634,9 +658,9 @@ fn create_derived_impl(
                         })
                         .chain(
                             // Add a bound for the current trait.
-                            self.skip_path_as_bound
-                                .not()
-                                .then(|| cx.trait_bound(trait_path.clone(), self.is_const)),
+                            (!self.skip_path_as_bound
+                                && bound_param_names.contains(&param.ident.name))
+                            .then(|| cx.trait_bound(trait_path.clone(), self.is_const)),
                         )
                         .chain({
                             // Add a `Copy` bound if required.
@@ -711,7 +735,7 @@ fn create_derived_impl(
                     // if we have already handled this type, skip it
                     if let ast::TyKind::Path(_, p) = &field_ty_param.ty.kind
                         && let [sole_segment] = &*p.segments
-                        && ty_param_names.contains(&sole_segment.ident.name)
+                        && bound_param_names.contains(&sole_segment.ident.name)
                     {
                         continue;
                     }
@@ -851,7 +875,16 @@ fn expand_struct_def(
             })
             .collect();
 
-        self.create_derived_impl(cx, type_ident, generics, field_tys, methods, is_packed)
+        let bound_params = true;
+        self.create_derived_impl(
+            cx,
+            type_ident,
+            generics,
+            field_tys,
+            methods,
+            is_packed,
+            bound_params,
+        )
     }
 
     fn expand_enum_def(
@@ -862,9 +895,17 @@ fn expand_enum_def(
         generics: &Generics,
         from_scratch: bool,
     ) -> P<ast::Item> {
+        // If a variant is marked with `bound_attr`, only the types of its fields get bound.
+        let bound_variant = self.bound_attr.and_then(|name| {
+            enum_def.variants.iter().find(|variant| variant.attrs.iter().any(|a| a.has_name(name)))
+        });
+
         let mut field_tys = Vec::new();
 
         for variant in &enum_def.variants {
+            if bound_variant.is_some_and(|bound_variant| !ptr::eq(variant, bound_variant)) {
+                continue;
+            }
             field_tys.extend(variant.data.fields().iter().map(|field| field.ty.clone()));
         }
 
@@ -907,7 +948,27 @@ fn expand_enum_def(
             .collect();
 
         let is_packed = false; // enums are never packed
-        self.create_derived_impl(cx, type_ident, generics, field_tys, methods, is_packed)
+        let bound_params = bound_variant.is_none();
+        self.create_derived_impl(
+            cx,
+            type_ident,
+            generics,
+            field_tys,
+            methods,
+            is_packed,
+            bound_params,
+        )
+    }
+
+    /// Whether `param` is marked `#[bound_attr(no_bound)]`, so that it doesn't need to implement
+    /// the current trait.
+    fn is_exempt_from_bound(&self, param: &ast::GenericParam) -> bool {
+        let Some(name) = self.bound_attr else { return false };
+        param.attrs.iter().filter(|attr| attr.has_name(name)).any(|attr| {
+            attr.meta_item_list().is_some_and(|list| {
+                list.iter().any(|item| item.is_word() && item.has_name(sym::no_bound))
+            })
+        })
     }
 }
 
@@ -1215,7 +1276,13 @@ fn expand_enum_method_body<'b>(
 
             let self_expr = tag_exprs.remove(0);
             let other_selflike_exprs = tag_exprs;
-            let tag_field = FieldInfo { span, name: None, self_expr, other_selflike_exprs };
+            let tag_field = FieldInfo {
+                span,
+                name: None,
+                self_expr,
+                other_selflike_exprs,
+                options: FieldOptions::default(),
+            };
 
             let tag_let_stmts: ThinVec<_> = iter::zip(&tag_idents, &selflike_args)
                 .map(|(&ident, selflike_arg)| {
@@ -1522,27 +1589,50 @@ fn create_struct_patterns(
             .collect()
     }
 
-    fn create_fields<F>(&self, struct_def: &'a VariantData, mk_exprs: F) -> Vec<FieldInfo>
-    where
-        F: Fn(usize, &ast::FieldDef, Span) -> Vec<P<ast::Expr>>,
-    {
+    /// Parses the options given to each field of `struct_def` by the helper attribute of the
+    /// trait.
+    fn field_options(&self, cx: &ExtCtxt<'_>, struct_def: &VariantData) -> Vec<FieldOptions> {
         struct_def
             .fields()
             .iter()
-            .enumerate()
-            .map(|(i, struct_field)| {
+            .map(|struct_field| match self.field_attr {
+                Some(name) => {
+                    let options = parse_field_options(cx, struct_field, name);
+                    check_consistent_skip(cx, struct_field, name, &options);
+                    options
+                }
+                None => FieldOptions::default(),
+            })
+            .collect()
+    }
+
+    fn create_fields<F>(
+        &self,
+        struct_def: &'a VariantData,
+        field_options: Vec<FieldOptions>,
+        mk_exprs: F,
+    ) -> Vec<FieldInfo>
+    where
+        F: Fn(usize, &ast::FieldDef, Span) -> Vec<P<ast::Expr>>,
+    {
+        iter::zip(struct_def.fields().iter().enumerate(), field_options)
+            .filter_map(|((i, struct_field), options)| {
+                if options.skip {
+                    return None;
+                }
                 // For this field, get an expr for each selflike_arg. E.g. for
                 // `PartialEq::eq`, one for each of `&self` and `other`.
                 let sp = struct_field.span.with_ctxt(self.span.ctxt());
                 let mut exprs: Vec<_> = mk_exprs(i, struct_field, sp);
                 let self_expr = exprs.remove(0);
                 let other_selflike_exprs = exprs;
-                FieldInfo {
+                Some(FieldInfo {
                     span: sp.with_ctxt(self.span.ctxt()),
                     name: struct_field.ident,
                     self_expr,
                     other_selflike_exprs,
-                }
+                    options,
+                })
             })
             .collect()
     }
@@ -1557,7 +1647,8 @@ fn create_struct_pattern_fields(
         struct_def: &'a VariantData,
         prefixes: &[String],
     ) -> Vec<FieldInfo> {
-        self.create_fields(struct_def, |i, _struct_field, sp| {
+        let field_options = self.field_options(cx, struct_def);
+        self.create_fields(struct_def, field_options, |i, _struct_field, sp| {
             prefixes
                 .iter()
                 .map(|prefix| {
@@ -1575,7 +1666,8 @@ fn create_struct_field_access_fields(
         struct_def: &'a VariantData,
         is_packed: bool,
     ) -> Vec<FieldInfo> {
-        self.create_fields(struct_def, |i, struct_field, sp| {
+        let field_options = self.field_options(cx, struct_def);
+        self.create_fields(struct_def, field_options, |i, struct_field, sp| {
             selflike_args
                 .iter()
                 .map(|selflike_arg| {
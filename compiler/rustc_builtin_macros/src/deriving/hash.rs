@@ -1,7 +1,8 @@
 use crate::deriving::generic::ty::*;
 use crate::deriving::generic::*;
 use crate::deriving::{path_std, pathvec_std};
-use rustc_ast::{MetaItem, Mutability};
+use rustc_ast::ptr::P;
+use rustc_ast::{Expr, MetaItem, Mutability};
 use rustc_expand::base::{Annotatable, ExtCtxt};
 use rustc_span::symbol::sym;
 use rustc_span::Span;
@@ -41,6 +42,8 @@ pub fn expand_deriving_hash(
         }],
         associated_types: Vec::new(),
         is_const,
+        field_attr: Some(sym::hash),
+        bound_attr: None,
     };
 
     hash_trait_def.expand(cx, mitem, item, push);
@@ -54,11 +57,15 @@ fn hash_substructure(
     let [state_expr] = substr.nonselflike_args else {
         cx.dcx().span_bug(trait_span, "incorrect number of arguments in `derive(Hash)`");
     };
-    let call_hash = |span, expr| {
-        let hash_path = {
-            let strs = cx.std_path(&[sym::hash, sym::Hash, sym::hash]);
+    let call_hash = |span, expr, with: &Option<P<Expr>>| {
+        let hash_path = match with {
+            // A `#[hash(with = path)]` field.
+            Some(with) => with.clone(),
+            None => {
+                let strs = cx.std_path(&[sym::hash, sym::Hash, sym::hash]);
 
-            cx.expr_path(cx.path_global(span, strs))
+                cx.expr_path(cx.path_global(span, strs))
+            }
         };
         let expr = cx.expr_call(span, hash_path, thin_vec![expr, state_expr.clone()]);
         cx.stmt_expr(expr)
@@ -66,13 +73,15 @@ fn hash_substructure(
 
     let (stmts, match_expr) = match substr.fields {
         Struct(_, fields) | EnumMatching(.., fields) => {
-            let stmts =
-                fields.iter().map(|field| call_hash(field.span, field.self_expr.clone())).collect();
+            let stmts = fields
+                .iter()
+                .map(|field| call_hash(field.span, field.self_expr.clone(), &field.options.with))
+                .collect();
             (stmts, None)
         }
         EnumTag(tag_field, match_expr) => {
             assert!(tag_field.other_selflike_exprs.is_empty());
-            let stmts = thin_vec![call_hash(tag_field.span, tag_field.self_expr.clone())];
+            let stmts = thin_vec![call_hash(tag_field.span, tag_field.self_expr.clone(), &None)];
             (stmts, match_expr.clone())
         }
         _ => cx.dcx().span_bug(trait_span, "impossible substructure in `derive(Hash)`"),
@@ -26,6 +26,7 @@
 pub mod decodable;
 pub mod default;
 pub mod encodable;
+pub mod field_options;
 pub mod hash;
 
 #[path = "cmp/eq.rs"]
@@ -350,6 +350,7 @@ pub(crate) struct MultipleDefaultsSugg {
 pub(crate) struct NonUnitDefault {
     #[primary_span]
     pub(crate) span: Span,
+    pub(crate) unit_only: bool,
 }
 
 #[derive(Diagnostic)]
@@ -398,6 +399,44 @@ pub(crate) struct DefaultHasArg {
     pub(crate) span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(builtin_macros_default_param_attr)]
+pub(crate) struct DefaultParamAttr {
+    #[primary_span]
+    #[suggestion(code = "#[default(no_bound)]", applicability = "maybe-incorrect")]
+    pub(crate) span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(builtin_macros_derive_field_attr_args)]
+pub(crate) struct DeriveFieldAttrArgs {
+    #[primary_span]
+    #[label]
+    pub(crate) span: Span,
+    pub(crate) name: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag(builtin_macros_derive_field_attr_unknown_option)]
+pub(crate) struct DeriveFieldAttrUnknownOption {
+    #[primary_span]
+    #[label]
+    pub(crate) span: Span,
+    pub(crate) name: Symbol,
+    pub(crate) option: Symbol,
+}
+
+#[derive(Diagnostic)]
+#[diag(builtin_macros_derive_field_attr_inconsistent_skip)]
+#[note]
+#[help]
+pub(crate) struct DeriveFieldAttrInconsistentSkip {
+    #[primary_span]
+    pub(crate) span: Span,
+    pub(crate) skipped: Symbol,
+    pub(crate) other: Symbol,
+}
+
 #[derive(Diagnostic)]
 #[diag(builtin_macros_derive_macro_call)]
 pub(crate) struct DeriveMacroCall {
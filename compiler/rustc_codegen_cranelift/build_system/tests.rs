@@ -99,6 +99,11 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
         runner.run_out_command("polymorphize_coroutine", &[]);
     }),
     TestCase::build_bin_and_run("aot.neon", "example/neon.rs", &[]),
+    TestCase::build_bin_and_run("aot.inline_asm", "example/inline_asm.rs", &[]),
+    TestCase::custom("aot.inline_asm_external_assembler", &|runner| {
+        runner.run_rustc(["example/inline_asm.rs", "-Cllvm-args=disable_integrated_asm=true"]);
+        runner.run_out_command("inline_asm", &[]);
+    }),
     TestCase::custom("aot.gen_block_iterate", &|runner| {
         runner.run_rustc([
             "example/gen_block_iterate.rs",
@@ -108,6 +113,22 @@ const fn jit_bin(config: &'static str, source: &'static str, args: &'static str)
         ]);
         runner.run_out_command("gen_block_iterate", &[]);
     }),
+    TestCase::custom("aot.debuginfo", &|runner| {
+        runner.run_rustc(["example/debuginfo.rs"]);
+        runner.run_out_command("debuginfo", &[]);
+        runner.run_gdb(
+            "debuginfo",
+            &["break debuginfo::breakpoint", "run", "up", "info args", "info locals"],
+            &[
+                "number = 7",
+                "pair = (1, 35)",
+                "Rect{w: 3, h: 4}",
+                "text = \"cranelift\"",
+                "copy = 7",
+                "array = [7, 2, 3]",
+            ],
+        );
+    }),
 ];
 
 pub(crate) static RAND_REPO: GitRepo = GitRepo::github(
@@ -487,6 +508,33 @@ fn run_rustc<I, S>(&self, args: I)
         spawn_and_wait(self.rustc_command(args));
     }
 
+    /// Runs an example under gdb and checks that the output of the gdb commands contains all of
+    /// the expected lines. This is skipped when gdb can't run the example on the host.
+    fn run_gdb(&self, name: &str, commands: &[&str], expected: &[&str]) {
+        if !self.target_compiler.runner.is_empty()
+            || Command::new("gdb").arg("--version").output().is_err()
+        {
+            eprintln!("[GDB] {name} (skipped, gdb not available)");
+            return;
+        }
+
+        let mut cmd = Command::new("gdb");
+        cmd.args(["-batch", "-nx"]);
+        for command in commands {
+            cmd.arg("-ex").arg(command);
+        }
+        cmd.arg(BUILD_EXAMPLE_OUT_DIR.to_path(&self.dirs).join(name));
+        let output = cmd.output().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+
+        let missing: Vec<_> = expected.iter().filter(|line| !stdout.contains(*line)).collect();
+        if !missing.is_empty() {
+            eprintln!("{stdout}");
+            eprintln!("{cmd:?} output is missing {missing:?}");
+            std::process::exit(1);
+        }
+    }
+
     fn run_out_command(&self, name: &str, args: &[&str]) {
         let mut full_cmd = vec![];
 
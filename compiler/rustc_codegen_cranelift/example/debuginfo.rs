@@ -0,0 +1,31 @@
+// Inspected with gdb by the aot.debuginfo test to check the locations and types of variables.
+
+#[derive(Clone, Copy)]
+enum Shape {
+    Circle(u32),
+    Rect { w: u16, h: u16 },
+}
+
+#[inline(never)]
+fn breakpoint() {
+    std::hint::black_box(());
+}
+
+#[inline(never)]
+fn inspect(number: u32, pair: (u8, i64), shape: Shape, text: &str) -> u64 {
+    let copy = number;
+    let array = [copy, 2, 3];
+    breakpoint();
+    let area = match shape {
+        Shape::Circle(r) => u64::from(r) * u64::from(r) * 3,
+        Shape::Rect { w, h } => u64::from(w) * u64::from(h),
+    };
+    let lengths = array.len() + text.len();
+    u64::from(copy) + u64::from(pair.0) + pair.1 as u64 + area + lengths as u64
+}
+
+fn main() {
+    let res = inspect(7, (1, 35), Shape::Rect { w: 3, h: 4 }, "cranelift");
+    assert_eq!(res, 7 + 1 + 35 + 12 + 3 + 9);
+    assert_eq!(inspect(0, (0, 0), Shape::Circle(1), ""), 3 + 3);
+}
@@ -0,0 +1,132 @@
+// Exercises both the integrated assembler and the fallback to the external assembler.
+
+use std::arch::asm;
+
+#[cfg(target_arch = "x86_64")]
+fn test_arch() {
+    // Loop with a numeric local label and a scaled index memory operand.
+    let values = [1u64, 2, 3, 4, 5];
+    let sum: u64;
+    unsafe {
+        asm!(
+            "xor {sum:e}, {sum:e}",
+            "2:",
+            "add {sum}, qword ptr [{ptr} + {i}*8 - 8]",
+            "dec {i}",
+            "jnz 2b",
+            ptr = in(reg) values.as_ptr(),
+            i = inout(reg) values.len() => _,
+            sum = out(reg) sum,
+        );
+    }
+    assert_eq!(sum, 15);
+
+    // Byte registers, immediates and conditional instructions.
+    let (a, b): (u64, u8);
+    unsafe {
+        asm!(
+            "mov {a}, 0x123456789",
+            "shr {a}, 4",
+            "cmp {a}, 0x12345678",
+            "sete {b}",
+            "movzx {a:e}, {b}",
+            "lea {a}, [{a} + {a}*2 + 7]",
+            a = out(reg) a,
+            b = out(reg_byte) b,
+        );
+    }
+    assert_eq!((a, b), (10, 1));
+
+    // Atomic read-modify-write through a memory operand.
+    let mut counter = 40u32;
+    let old: u32;
+    unsafe {
+        asm!(
+            "lock xadd dword ptr [{counter}], {old:e}",
+            counter = in(reg) &mut counter,
+            old = inout(reg) 2u32 => old,
+        );
+    }
+    assert_eq!((old, counter), (40, 42));
+
+    // xmm registers are saved and restored using movups.
+    let x: f64;
+    unsafe {
+        asm!("movaps {x}, {y}", x = out(xmm_reg) x, y = in(xmm_reg) 2.5f64);
+    }
+    assert_eq!(x, 2.5);
+
+    // AT&T syntax isn't supported by the integrated assembler.
+    let y: u64;
+    unsafe {
+        asm!("movq $42, {y}", y = out(reg) y, options(att_syntax));
+    }
+    assert_eq!(y, 42);
+}
+
+#[cfg(target_arch = "aarch64")]
+fn test_arch() {
+    // Loop with a numeric local label and post-indexed loads.
+    let values = [1u64, 2, 3, 4, 5];
+    let sum: u64;
+    unsafe {
+        asm!(
+            "mov {sum}, xzr",
+            "2:",
+            "ldr {tmp}, [{ptr}], #8",
+            "add {sum}, {sum}, {tmp}",
+            "subs {i}, {i}, #1",
+            "b.ne 2b",
+            ptr = inout(reg) values.as_ptr() => _,
+            i = inout(reg) values.len() => _,
+            sum = out(reg) sum,
+            tmp = out(reg) _,
+        );
+    }
+    assert_eq!(sum, 15);
+
+    // Logical immediates, shifts and conditional selects.
+    let (a, b): (u64, u64);
+    unsafe {
+        asm!(
+            "movz {a}, #0x6789",
+            "movk {a}, #0x2345, lsl #16",
+            "movk {a}, #0x1, lsl #32",
+            "and {a}, {a}, #0xff00ff00ff00ff00",
+            "lsr {a}, {a}, #8",
+            "cmp {a}, #0x23",
+            "cset {b:w}, hi",
+            "madd {a}, {a}, {b}, {b}",
+            a = out(reg) a,
+            b = out(reg) b,
+        );
+    }
+    assert_eq!((a, b), (0x230068, 1));
+
+    // Register offset stores.
+    let mut array = [0u32; 4];
+    unsafe {
+        asm!(
+            "mov {tmp:w}, #7",
+            "str {tmp:w}, [{ptr}, {i}, lsl #2]",
+            ptr = in(reg) array.as_mut_ptr(),
+            i = in(reg) 2usize,
+            tmp = out(reg) _,
+        );
+    }
+    assert_eq!(array, [0, 0, 7, 0]);
+
+    // rbit isn't supported by the integrated assembler.
+    let x: u64;
+    unsafe {
+        asm!("rbit {x}, {y}", x = out(reg) x, y = in(reg) 1u64);
+    }
+    assert_eq!(x, 1 << 63);
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn test_arch() {}
+
+fn main() {
+    test_arch();
+}
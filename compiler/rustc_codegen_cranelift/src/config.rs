@@ -57,6 +57,13 @@ pub struct BackendConfig {
     /// Defaults to true when the `CG_CLIF_DISABLE_INCR_CACHE` env var is set to 1 or false
     /// otherwise. Can be set using `-Cllvm-args=disable_incr_cache=...`.
     pub disable_incr_cache: bool,
+
+    /// Assemble all inline asm with the external assembler, even when the integrated assembler
+    /// supports it. Useful to check whether a bug is caused by the integrated assembler.
+    ///
+    /// Defaults to true when the `CG_CLIF_DISABLE_INTEGRATED_ASM` env var is set to 1 or false
+    /// otherwise. Can be set using `-Cllvm-args=disable_integrated_asm=...`.
+    pub disable_integrated_asm: bool,
 }
 
 impl Default for BackendConfig {
@@ -69,6 +76,7 @@ fn default() -> Self {
             },
             enable_verifier: cfg!(debug_assertions) || bool_env_var("CG_CLIF_ENABLE_VERIFIER"),
             disable_incr_cache: bool_env_var("CG_CLIF_DISABLE_INCR_CACHE"),
+            disable_integrated_asm: bool_env_var("CG_CLIF_DISABLE_INTEGRATED_ASM"),
         }
     }
 }
@@ -92,6 +100,9 @@ fn parse_bool(name: &str, value: &str) -> Result<bool, String> {
                     "mode" => config.codegen_mode = value.parse()?,
                     "enable_verifier" => config.enable_verifier = parse_bool(name, value)?,
                     "disable_incr_cache" => config.disable_incr_cache = parse_bool(name, value)?,
+                    "disable_integrated_asm" => {
+                        config.disable_integrated_asm = parse_bool(name, value)?
+                    }
                     _ => return Err(format!("Unknown option `{}`", name)),
                 }
             } else {
@@ -0,0 +1,395 @@
+//! Variable debuginfo generation (`DW_TAG_variable` and `DW_TAG_formal_parameter` entries)
+
+use cranelift_codegen::ir::{ValueLabel, ValueLabelAssignments, ValueLabelStart};
+use cranelift_codegen::isa::TargetIsa;
+use cranelift_codegen::{LabelValueLoc, ValueLabelsRanges};
+use gimli::write::{Address, AttributeValue, Expression, Location, LocationList, UnitEntryId};
+use gimli::Register;
+use smallvec::{smallvec, SmallVec};
+
+use crate::debuginfo::types::TypeDebugContext;
+use crate::debuginfo::{DebugContext, FunctionDebugContext};
+use crate::pointer::PointerBase;
+use crate::prelude::*;
+use crate::value_and_place::CPlaceInner;
+
+/// Where the value of a variable can be found in terms of Cranelift IR. This is translated to a
+/// DWARF location once register allocation is done.
+#[derive(Debug)]
+pub(super) enum VariableLocation {
+    /// Stored in a stack slot at the given offset.
+    StackSlot(StackSlot, i64),
+    /// Stored in memory at the given offset from the address in the value with the given label.
+    Indirect(ValueLabel, i64),
+    /// Stored in SSA values. Each piece consists of the label of the SSA value, and the offset and
+    /// size of the part of the variable it holds. The last field is the size of the variable.
+    Ssa(SmallVec<[(ValueLabel, Size, Size); 2]>, Size),
+}
+
+/// Defines debuginfo for all user variables of the function in `fx.mir.var_debug_info`.
+///
+/// This must be called after the function body has been codegened, as it relies on all locals
+/// having been assigned a place.
+pub(crate) fn define_variables<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    type_dbg: &mut TypeDebugContext<'tcx>,
+) {
+    let Some(mut func_debug_cx) = fx.func_debug_cx.take() else {
+        return;
+    };
+
+    // All variables have been declared at this point, so any labels above the variable indices
+    // are free to use for other values.
+    let mut next_label = fx.next_ssa_var;
+
+    let mir = fx.mir;
+    for var in &mir.var_debug_info {
+        let (ty, location) = match &var.value {
+            VarDebugInfoContents::Place(place) => {
+                let ty = match &var.composite {
+                    Some(composite) => composite.ty,
+                    None => place.ty(&mir.local_decls, fx.tcx).ty,
+                };
+                // FIXME support variables split into fragments
+                let location = if var.composite.is_none() {
+                    variable_location(fx, *place, &mut next_label)
+                } else {
+                    None
+                };
+                (fx.monomorphize(ty), location)
+            }
+            // FIXME emit DW_AT_const_value for constants
+            VarDebugInfoContents::Const(constant) => (fx.monomorphize(constant.ty()), None),
+        };
+
+        let debug_context = fx.cx.debug_context.as_mut().unwrap();
+        let entry_id = func_debug_cx.define_variable(
+            debug_context,
+            fx.tcx,
+            type_dbg,
+            mir.span,
+            var,
+            ty,
+        );
+        if let Some(location) = location {
+            func_debug_cx.variables.push((entry_id, location));
+        }
+    }
+
+    fx.func_debug_cx = Some(func_debug_cx);
+}
+
+/// Adds `label` to the labels of `val`.
+///
+/// Unlike `FunctionBuilder::set_val_label` this keeps the labels `val` already has. A single value
+/// is often shared by several variables, for example after `let b = a;`, or used both as a variable
+/// and as the address of another one, and all of them need to keep their location.
+pub(crate) fn add_val_label(fx: &mut FunctionCx<'_, '_, '_>, val: Value, label: ValueLabel) {
+    let Some(values_labels) = fx.bcx.func.dfg.values_labels.as_mut() else {
+        // Value labels are only collected when emitting debuginfo.
+        return;
+    };
+    if let Some(ValueLabelAssignments::Starts(starts)) = values_labels.get_mut(&val) {
+        if starts.iter().all(|start| start.label != label) {
+            let from = starts[0].from;
+            starts.push(ValueLabelStart { from, label });
+        }
+        return;
+    }
+    fx.bcx.set_val_label(val, label);
+}
+
+fn variable_location<'tcx>(
+    fx: &mut FunctionCx<'_, '_, 'tcx>,
+    place: Place<'tcx>,
+    next_label: &mut u32,
+) -> Option<VariableLocation> {
+    let cplace = fx.local_map[place.local];
+    let mut layout = cplace.layout();
+
+    let mut location = match *cplace.inner() {
+        CPlaceInner::Var(_local, var) => VariableLocation::Ssa(
+            smallvec![(ValueLabel::new(var.index()), Size::ZERO, layout.size)],
+            layout.size,
+        ),
+        CPlaceInner::VarPair(_local, var1, var2) => {
+            let Abi::ScalarPair(a, b) = layout.abi else {
+                bug!("VarPair for non ScalarPair layout {:?}", layout);
+            };
+            let b_offset = a.size(fx).align_to(b.align(fx).abi);
+            VariableLocation::Ssa(
+                smallvec![
+                    (ValueLabel::new(var1.index()), Size::ZERO, a.size(fx)),
+                    (ValueLabel::new(var2.index()), b_offset, b.size(fx)),
+                ],
+                layout.size,
+            )
+        }
+        CPlaceInner::Addr(ptr, None) => match ptr.debug_base_and_offset() {
+            (PointerBase::Stack(stack_slot), offset) => {
+                VariableLocation::StackSlot(stack_slot, offset.into())
+            }
+            (PointerBase::Addr(addr), offset) => {
+                let label = ValueLabel::from_u32(*next_label);
+                *next_label += 1;
+                add_val_label(fx, addr, label);
+                VariableLocation::Indirect(label, offset.into())
+            }
+            // Zero sized variables don't have a location.
+            (PointerBase::Dangling(_), _) => return None,
+        },
+        // FIXME support unsized locals
+        CPlaceInner::Addr(_, Some(_)) => return None,
+    };
+
+    for elem in place.projection {
+        match elem {
+            PlaceElem::Field(field, _) => {
+                let field_offset = layout.fields.offset(field.index());
+                let field_layout = layout.field(fx, field.index());
+                location = match location {
+                    VariableLocation::StackSlot(stack_slot, offset) => {
+                        let field_offset = i64::try_from(field_offset.bytes()).unwrap();
+                        VariableLocation::StackSlot(stack_slot, offset + field_offset)
+                    }
+                    VariableLocation::Indirect(label, offset) => {
+                        let field_offset = i64::try_from(field_offset.bytes()).unwrap();
+                        VariableLocation::Indirect(label, offset + field_offset)
+                    }
+                    VariableLocation::Ssa(pieces, _) => {
+                        let field_end = field_offset + field_layout.size;
+                        let pieces: SmallVec<_> = pieces
+                            .into_iter()
+                            .filter(|&(_, offset, size)| {
+                                offset >= field_offset && offset + size <= field_end
+                            })
+                            .map(|(label, offset, size)| (label, offset - field_offset, size))
+                            .collect();
+                        if pieces.is_empty() {
+                            return None;
+                        }
+                        VariableLocation::Ssa(pieces, field_layout.size)
+                    }
+                };
+                layout = field_layout;
+            }
+            PlaceElem::Deref => {
+                // Only thin pointers stored in a single SSA value can be followed for now.
+                let VariableLocation::Ssa(pieces, size) = &location else {
+                    return None;
+                };
+                let [(label, offset, piece_size)] = pieces[..] else {
+                    return None;
+                };
+                if offset != Size::ZERO || piece_size != *size || !layout.abi.is_scalar() {
+                    return None;
+                }
+                let pointee_ty = layout.ty.builtin_deref(true)?.ty;
+                location = VariableLocation::Indirect(label, 0);
+                layout = fx.layout_of(pointee_ty);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(location)
+}
+
+impl FunctionDebugContext {
+    fn define_variable<'tcx>(
+        &mut self,
+        debug_context: &mut DebugContext,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        function_span: Span,
+        var: &VarDebugInfo<'tcx>,
+        ty: Ty<'tcx>,
+    ) -> UnitEntryId {
+        let (file, line, column) =
+            DebugContext::get_span_loc(tcx, function_span, var.source_info.span);
+        let file_id = debug_context.add_source_file(&file);
+        let type_id = debug_context.debug_type(tcx, type_dbg, ty);
+
+        // FIXME add to the lexical block of the variable's scope instead
+        let tag = if var.argument_index.is_some() {
+            gimli::DW_TAG_formal_parameter
+        } else {
+            gimli::DW_TAG_variable
+        };
+        let entry_id = debug_context.dwarf.unit.add(self.entry_id, tag);
+        let name_id = debug_context.dwarf.strings.add(var.name.as_str());
+        let entry = debug_context.dwarf.unit.get_mut(entry_id);
+        entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(type_id));
+        entry.set(gimli::DW_AT_decl_file, AttributeValue::FileIndex(Some(file_id)));
+        entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+        entry.set(gimli::DW_AT_decl_column, AttributeValue::Udata(column));
+
+        entry_id
+    }
+
+    pub(super) fn create_variable_locations(
+        &mut self,
+        debug_context: &mut DebugContext,
+        isa: &dyn TargetIsa,
+        symbol: usize,
+        context: &Context,
+    ) {
+        let compiled_code = context.compiled_code().unwrap();
+
+        for (entry_id, location) in std::mem::take(&mut self.variables) {
+            let location = match location {
+                VariableLocation::StackSlot(stack_slot, offset) => {
+                    let Some(stack_pointer) = stack_pointer_register(isa) else { continue };
+                    // Stack slot offsets are relative to the stack pointer after the prologue,
+                    // which is where the variable is accessible anyway.
+                    let slot_offset = compiled_code.sized_stackslot_offsets[stack_slot];
+                    let mut expr = Expression::new();
+                    expr.op_breg(stack_pointer, i64::from(slot_offset) + offset);
+                    AttributeValue::Exprloc(expr)
+                }
+                VariableLocation::Indirect(label, offset) => {
+                    let Some(location) = location_list(
+                        debug_context,
+                        isa,
+                        symbol,
+                        &compiled_code.value_labels_ranges,
+                        &[(label, Size::ZERO, Size::ZERO)],
+                        Size::ZERO,
+                        Some(offset),
+                    ) else {
+                        continue;
+                    };
+                    location
+                }
+                VariableLocation::Ssa(pieces, size) => {
+                    let Some(location) = location_list(
+                        debug_context,
+                        isa,
+                        symbol,
+                        &compiled_code.value_labels_ranges,
+                        &pieces,
+                        size,
+                        None,
+                    ) else {
+                        continue;
+                    };
+                    location
+                }
+            };
+            debug_context.dwarf.unit.get_mut(entry_id).set(gimli::DW_AT_location, location);
+        }
+    }
+}
+
+/// Creates a location list for a variable stored in the values with the given labels.
+///
+/// If `indirect_offset` is `Some`, the single piece holds the address of the variable instead.
+fn location_list(
+    debug_context: &mut DebugContext,
+    isa: &dyn TargetIsa,
+    symbol: usize,
+    value_labels_ranges: &ValueLabelsRanges,
+    pieces: &[(ValueLabel, Size, Size)],
+    size: Size,
+    indirect_offset: Option<i64>,
+) -> Option<AttributeValue> {
+    let is_composite = match pieces {
+        [(_, offset, piece_size)] => *offset != Size::ZERO || *piece_size != size,
+        _ => true,
+    };
+
+    // Split the function into ranges in which none of the pieces changes its location.
+    let mut boundaries = pieces
+        .iter()
+        .flat_map(|(label, _, _)| value_labels_ranges.get(label).into_iter().flatten())
+        .flat_map(|range| [range.start, range.end])
+        .collect::<Vec<_>>();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+
+    let mut locations = vec![];
+    for window in boundaries.windows(2) {
+        let (start, end) = (window[0], window[1]);
+
+        let mut expr = Expression::new();
+        let mut has_location = false;
+        let mut covered = Size::ZERO;
+        for &(label, offset, piece_size) in pieces {
+            let loc = value_labels_ranges.get(&label).and_then(|ranges| {
+                ranges.iter().find(|range| range.start <= start && end <= range.end)
+            });
+            if is_composite && offset > covered {
+                // Padding between pieces
+                expr.op_piece((offset - covered).bytes());
+            }
+            if let Some(loc) = loc {
+                has_location |= translate_loc(&mut expr, isa, loc.loc, indirect_offset).is_some();
+            }
+            if is_composite {
+                expr.op_piece(piece_size.bytes());
+            }
+            covered = offset + piece_size;
+        }
+        if is_composite && size > covered {
+            expr.op_piece((size - covered).bytes());
+        }
+
+        if has_location {
+            locations.push(Location::StartEnd {
+                begin: Address::Symbol { symbol, addend: i64::from(start) },
+                end: Address::Symbol { symbol, addend: i64::from(end) },
+                data: expr,
+            });
+        }
+    }
+
+    if locations.is_empty() {
+        return None;
+    }
+    let location_list_id = debug_context.dwarf.unit.locations.add(LocationList(locations));
+    Some(AttributeValue::LocationListRef(location_list_id))
+}
+
+// Adapted from https://github.com/bytecodealliance/wasmtime/blob/5a1845b4caf7a5dba8eda1fef05213a532ed4259/crates/debug/src/transform/expression.rs#L59-L137
+fn translate_loc(
+    expr: &mut Expression,
+    isa: &dyn TargetIsa,
+    loc: LabelValueLoc,
+    indirect_offset: Option<i64>,
+) -> Option<()> {
+    match loc {
+        LabelValueLoc::Reg(reg) => {
+            let reg = Register(isa.map_regalloc_reg_to_dwarf(reg).ok()?);
+            match indirect_offset {
+                None => expr.op_reg(reg),
+                Some(offset) => expr.op_breg(reg, offset),
+            }
+        }
+        // The frame base of every function is its CFA.
+        LabelValueLoc::CFAOffset(cfa_offset) => match indirect_offset {
+            None => expr.op_fbreg(cfa_offset),
+            Some(offset) => {
+                let offset = u64::try_from(offset).ok()?;
+                expr.op_fbreg(cfa_offset);
+                expr.op_deref();
+                if offset != 0 {
+                    expr.op_plus_uconst(offset);
+                }
+            }
+        },
+    }
+    Some(())
+}
+
+fn stack_pointer_register(isa: &dyn TargetIsa) -> Option<Register> {
+    match isa.triple().architecture {
+        target_lexicon::Architecture::X86_64 => Some(gimli::X86_64::RSP),
+        target_lexicon::Architecture::Aarch64(_) => Some(gimli::AArch64::SP),
+        // x2 is the stack pointer
+        target_lexicon::Architecture::Riscv64(_) => Some(Register(2)),
+        // r15 is the stack pointer
+        target_lexicon::Architecture::S390x => Some(Register(15)),
+        _ => None,
+    }
+}
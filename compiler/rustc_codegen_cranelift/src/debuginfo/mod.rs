@@ -2,20 +2,25 @@
 
 mod emit;
 mod line_info;
+mod locals;
 mod object;
+mod types;
 mod unwind;
 
 use cranelift_codegen::ir::Endianness;
 use cranelift_codegen::isa::TargetIsa;
 use gimli::write::{
-    Address, AttributeValue, DwarfUnit, FileId, LineProgram, LineString, Range, RangeList,
-    UnitEntryId,
+    Address, AttributeValue, DwarfUnit, Expression, FileId, LineProgram, LineString, Range,
+    RangeList, UnitEntryId,
 };
 use gimli::{Encoding, Format, LineEncoding, RunTimeEndian};
 use indexmap::IndexSet;
 use rustc_session::Session;
 
 pub(crate) use self::emit::{DebugReloc, DebugRelocName};
+pub(crate) use self::locals::{add_val_label, define_variables};
+use self::locals::VariableLocation;
+pub(crate) use self::types::TypeDebugContext;
 pub(crate) use self::unwind::UnwindContext;
 use crate::prelude::*;
 
@@ -36,6 +41,7 @@ pub(crate) struct FunctionDebugContext {
     entry_id: UnitEntryId,
     function_source_loc: (FileId, u64, u64),
     source_loc_set: IndexSet<(FileId, u64, u64)>,
+    variables: Vec<(UnitEntryId, VariableLocation)>,
 }
 
 impl DebugContext {
@@ -143,6 +149,7 @@ pub(crate) fn define_function(
             entry_id,
             function_source_loc: (file_id, line, column),
             source_loc_set: IndexSet::new(),
+            variables: Vec::new(),
         }
     }
 }
@@ -151,12 +158,14 @@ impl FunctionDebugContext {
     pub(crate) fn finalize(
         mut self,
         debug_context: &mut DebugContext,
+        isa: &dyn TargetIsa,
         func_id: FuncId,
         context: &Context,
     ) {
         let symbol = func_id.as_u32() as usize;
 
         let end = self.create_debug_lines(debug_context, symbol, context);
+        self.create_variable_locations(debug_context, isa, symbol, context);
 
         debug_context.unit_range_list.0.push(Range::StartLength {
             begin: Address::Symbol { symbol, addend: 0 },
@@ -171,5 +180,9 @@ pub(crate) fn finalize(
         );
         // Using Udata for DW_AT_high_pc requires at least DWARF4
         func_entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(u64::from(end)));
+        // Variable locations on the stack are relative to the CFA.
+        let mut frame_base = Expression::new();
+        frame_base.op(gimli::DW_OP_call_frame_cfa);
+        func_entry.set(gimli::DW_AT_frame_base, AttributeValue::Exprloc(frame_base));
     }
 }
@@ -0,0 +1,301 @@
+//! Type debuginfo generation (`DW_TAG_*_type` entries in `.debug_info`)
+
+use gimli::write::{AttributeValue, UnitEntryId};
+use rustc_codegen_ssa::debuginfo::type_names;
+use rustc_target::abi::{FieldsShape, TagEncoding, Variants};
+
+use crate::debuginfo::DebugContext;
+use crate::prelude::*;
+
+/// Per codegen unit cache of the debuginfo entries generated for each type.
+#[derive(Default)]
+pub(crate) struct TypeDebugContext<'tcx> {
+    type_map: FxHashMap<Ty<'tcx>, UnitEntryId>,
+}
+
+impl DebugContext {
+    pub(crate) fn debug_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        ty: Ty<'tcx>,
+    ) -> UnitEntryId {
+        if let Some(&type_id) = type_dbg.type_map.get(&ty) {
+            return type_id;
+        }
+
+        let layout = RevealAllLayoutCx(tcx).layout_of(ty);
+        let name = type_names::compute_debuginfo_type_name(tcx, ty, true);
+
+        let type_id = match ty.kind() {
+            ty::Bool => self.basic_type(&name, gimli::DW_ATE_boolean, layout.size),
+            ty::Char => self.basic_type(&name, gimli::DW_ATE_UTF, layout.size),
+            ty::Int(_) => self.basic_type(&name, gimli::DW_ATE_signed, layout.size),
+            ty::Uint(_) => self.basic_type(&name, gimli::DW_ATE_unsigned, layout.size),
+            ty::Float(_) => self.basic_type(&name, gimli::DW_ATE_float, layout.size),
+            ty::Ref(_, pointee_ty, _) | ty::RawPtr(ty::TypeAndMut { ty: pointee_ty, .. }) => {
+                // Register the pointer type before describing the pointee to support recursive
+                // types like linked lists.
+                if layout.abi.is_scalar() {
+                    let type_id = self.new_type(gimli::DW_TAG_pointer_type, &name, layout.size);
+                    type_dbg.type_map.insert(ty, type_id);
+                    let pointee_id = self.debug_type(tcx, type_dbg, *pointee_ty);
+                    self.dwarf
+                        .unit
+                        .get_mut(type_id)
+                        .set(gimli::DW_AT_type, AttributeValue::UnitRef(pointee_id));
+                    type_id
+                } else {
+                    let type_id = self.new_type(gimli::DW_TAG_structure_type, &name, layout.size);
+                    type_dbg.type_map.insert(ty, type_id);
+                    self.fat_pointer_type(tcx, type_dbg, type_id, *pointee_ty, layout);
+                    type_id
+                }
+            }
+            ty::Array(elem_ty, len) => {
+                let type_id = self.new_type(gimli::DW_TAG_array_type, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                let len = len.eval_target_usize(tcx, ParamEnv::reveal_all());
+                self.array_type(tcx, type_dbg, type_id, *elem_ty, Some(len));
+                type_id
+            }
+            ty::Slice(elem_ty) => {
+                let type_id = self.new_type(gimli::DW_TAG_array_type, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                self.array_type(tcx, type_dbg, type_id, *elem_ty, None);
+                type_id
+            }
+            ty::Str => {
+                let type_id = self.new_type(gimli::DW_TAG_array_type, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                self.array_type(tcx, type_dbg, type_id, tcx.types.u8, None);
+                type_id
+            }
+            ty::Tuple(_) => {
+                let type_id = self.new_type(gimli::DW_TAG_structure_type, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                self.add_members(tcx, type_dbg, type_id, layout, |i| format!("__{i}"));
+                type_id
+            }
+            ty::Closure(..) => {
+                let type_id = self.new_type(gimli::DW_TAG_structure_type, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                self.add_members(tcx, type_dbg, type_id, layout, |i| format!("__{i}"));
+                type_id
+            }
+            ty::Adt(adt_def, _) if adt_def.is_struct() || adt_def.is_union() => {
+                let tag = if adt_def.is_union() {
+                    gimli::DW_TAG_union_type
+                } else {
+                    gimli::DW_TAG_structure_type
+                };
+                let type_id = self.new_type(tag, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                let variant = adt_def.non_enum_variant();
+                self.add_members(tcx, type_dbg, type_id, layout, |i| {
+                    variant.fields[FieldIdx::from_usize(i)].name.to_string()
+                });
+                type_id
+            }
+            ty::Adt(adt_def, _) if adt_def.is_enum() => {
+                let type_id = self.new_type(gimli::DW_TAG_structure_type, &name, layout.size);
+                type_dbg.type_map.insert(ty, type_id);
+                self.enum_type(tcx, type_dbg, type_id, layout);
+                type_id
+            }
+            // FIXME describe the remaining types, like function pointers, trait objects and
+            // coroutines. For now they are emitted as opaque types of the right size.
+            _ => self.new_type(gimli::DW_TAG_structure_type, &name, layout.size),
+        };
+
+        type_dbg.type_map.insert(ty, type_id);
+        type_id
+    }
+
+    fn new_type(&mut self, tag: gimli::DwTag, name: &str, size: Size) -> UnitEntryId {
+        let type_id = self.dwarf.unit.add(self.dwarf.unit.root(), tag);
+        let name_id = self.dwarf.strings.add(name);
+        let type_entry = self.dwarf.unit.get_mut(type_id);
+        type_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        if tag != gimli::DW_TAG_array_type || size != Size::ZERO {
+            type_entry.set(gimli::DW_AT_byte_size, AttributeValue::Udata(size.bytes()));
+        }
+        type_id
+    }
+
+    fn basic_type(&mut self, name: &str, encoding: gimli::DwAte, size: Size) -> UnitEntryId {
+        let type_id = self.new_type(gimli::DW_TAG_base_type, name, size);
+        self.dwarf
+            .unit
+            .get_mut(type_id)
+            .set(gimli::DW_AT_encoding, AttributeValue::Encoding(encoding));
+        type_id
+    }
+
+    fn add_member(
+        &mut self,
+        parent: UnitEntryId,
+        name: &str,
+        member_type: UnitEntryId,
+        offset: Size,
+    ) -> UnitEntryId {
+        let member_id = self.dwarf.unit.add(parent, gimli::DW_TAG_member);
+        let name_id = self.dwarf.strings.add(name);
+        let member_entry = self.dwarf.unit.get_mut(member_id);
+        member_entry.set(gimli::DW_AT_name, AttributeValue::StringRef(name_id));
+        member_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(member_type));
+        member_entry.set(gimli::DW_AT_data_member_location, AttributeValue::Udata(offset.bytes()));
+        member_id
+    }
+
+    /// Adds a member for every field of `layout` to `type_id`.
+    fn add_members<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        type_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+        field_name: impl Fn(usize) -> String,
+    ) {
+        if let FieldsShape::Primitive = layout.fields {
+            return;
+        }
+        let cx = RevealAllLayoutCx(tcx);
+        for i in 0..layout.fields.count() {
+            let field_layout = layout.field(&cx, i);
+            let field_type = self.debug_type(tcx, type_dbg, field_layout.ty);
+            self.add_member(type_id, &field_name(i), field_type, layout.fields.offset(i));
+        }
+    }
+
+    fn array_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        type_id: UnitEntryId,
+        elem_ty: Ty<'tcx>,
+        len: Option<u64>,
+    ) {
+        let elem_id = self.debug_type(tcx, type_dbg, elem_ty);
+        let usize_id = self.debug_type(tcx, type_dbg, tcx.types.usize);
+
+        self.dwarf.unit.get_mut(type_id).set(gimli::DW_AT_type, AttributeValue::UnitRef(elem_id));
+
+        let subrange_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_subrange_type);
+        let subrange_entry = self.dwarf.unit.get_mut(subrange_id);
+        subrange_entry.set(gimli::DW_AT_type, AttributeValue::UnitRef(usize_id));
+        subrange_entry.set(gimli::DW_AT_lower_bound, AttributeValue::Udata(0));
+        if let Some(len) = len {
+            subrange_entry.set(gimli::DW_AT_count, AttributeValue::Udata(len));
+        }
+    }
+
+    /// Describes a pointer to an unsized type as a struct of the data pointer and the metadata,
+    /// like the LLVM backend does.
+    fn fat_pointer_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        type_id: UnitEntryId,
+        pointee_ty: Ty<'tcx>,
+        layout: TyAndLayout<'tcx>,
+    ) {
+        let cx = RevealAllLayoutCx(tcx);
+        let (data_name, data_pointee_ty, metadata_name) =
+            match tcx.struct_tail_erasing_lifetimes(pointee_ty, ParamEnv::reveal_all()).kind() {
+                ty::Slice(elem_ty) => ("data_ptr", *elem_ty, "length"),
+                ty::Str => ("data_ptr", tcx.types.u8, "length"),
+                _ => ("pointer", tcx.types.unit, "vtable"),
+            };
+
+        let data_ptr_ty = Ty::new_imm_ptr(tcx, data_pointee_ty);
+        let data_ptr_id = self.debug_type(tcx, type_dbg, data_ptr_ty);
+        self.add_member(type_id, data_name, data_ptr_id, layout.fields.offset(0));
+
+        let metadata_ty = layout.field(&cx, 1).ty;
+        let metadata_id = self.debug_type(tcx, type_dbg, metadata_ty);
+        self.add_member(type_id, metadata_name, metadata_id, layout.fields.offset(1));
+    }
+
+    /// Describes an enum as a struct containing a `DW_TAG_variant_part`, with one
+    /// `DW_TAG_variant` per enum variant, selected by the value of the tag.
+    fn enum_type<'tcx>(
+        &mut self,
+        tcx: TyCtxt<'tcx>,
+        type_dbg: &mut TypeDebugContext<'tcx>,
+        type_id: UnitEntryId,
+        layout: TyAndLayout<'tcx>,
+    ) {
+        let ty::Adt(adt_def, _) = layout.ty.kind() else { unreachable!() };
+        let cx = RevealAllLayoutCx(tcx);
+
+        if adt_def.variants().is_empty() {
+            return;
+        }
+
+        let (tag, tag_field) = match &layout.variants {
+            Variants::Single { index } => {
+                // Only a single variant is inhabited, so no tag is necessary.
+                let variant_layout = layout.for_variant(&cx, *index);
+                let variant = &adt_def.variants()[*index];
+                self.add_members(tcx, type_dbg, type_id, variant_layout, |i| {
+                    variant.fields[FieldIdx::from_usize(i)].name.to_string()
+                });
+                return;
+            }
+            Variants::Multiple {
+                tag,
+                tag_encoding: TagEncoding::Direct,
+                tag_field,
+                variants: _,
+            } => (tag, *tag_field),
+            // FIXME describe niche encoded enums. A niche variant is only selected by a range of
+            // tag values, which `DW_AT_discr_value` can't express. For now they are emitted as
+            // opaque types of the right size.
+            Variants::Multiple { tag_encoding: TagEncoding::Niche { .. }, .. } => return,
+        };
+
+        let tag_size = tag.size(&cx);
+        if tag_size.bits() > 64 {
+            // FIXME support 128bit tags. `DW_AT_discr_value` can't hold them.
+            return;
+        }
+
+        let variant_part_id = self.dwarf.unit.add(type_id, gimli::DW_TAG_variant_part);
+
+        let tag_ty = layout.field(&cx, tag_field).ty;
+        let tag_type_id = self.debug_type(tcx, type_dbg, tag_ty);
+        let tag_member_id = self.add_member(
+            variant_part_id,
+            "<discriminant>",
+            tag_type_id,
+            layout.fields.offset(tag_field),
+        );
+        let tag_member_entry = self.dwarf.unit.get_mut(tag_member_id);
+        tag_member_entry.set(gimli::DW_AT_artificial, AttributeValue::Flag(true));
+        self.dwarf
+            .unit
+            .get_mut(variant_part_id)
+            .set(gimli::DW_AT_discr, AttributeValue::UnitRef(tag_member_id));
+
+        for (variant_index, variant) in adt_def.variants().iter_enumerated() {
+            let discr_value = layout.ty.discriminant_for_variant(tcx, variant_index).unwrap().val;
+            // The tag is at most 64 bits, so this can't fail.
+            let discr_value = u64::try_from(tag_size.truncate(discr_value)).unwrap();
+            let variant_id = self.dwarf.unit.add(variant_part_id, gimli::DW_TAG_variant);
+            self.dwarf
+                .unit
+                .get_mut(variant_id)
+                .set(gimli::DW_AT_discr_value, AttributeValue::Udata(discr_value));
+
+            let variant_layout = layout.for_variant(&cx, variant_index);
+            let name = variant.name.as_str();
+            let variant_type_id =
+                self.new_type(gimli::DW_TAG_structure_type, name, variant_layout.size);
+            self.add_members(tcx, type_dbg, variant_type_id, variant_layout, |i| {
+                variant.fields[FieldIdx::from_usize(i)].name.to_string()
+            });
+            self.add_member(variant_id, name, variant_type_id, Size::ZERO);
+        }
+    }
+}
@@ -177,8 +177,24 @@ pub(crate) fn codegen_inline_asm_inner<'tcx>(
         inline_asm_index
     );
 
-    let generated_asm = asm_gen.generate_asm_wrapper(&asm_name);
-    fx.cx.global_asm.push_str(&generated_asm);
+    let generated_asm = asm_gen.generate_asm_body();
+    let assembled = if fx.cx.disable_integrated_asm {
+        Err(crate::integrated_asm::Unsupported("integrated assembler is disabled".to_owned()))
+    } else {
+        crate::integrated_asm::assemble(asm_gen.arch, &generated_asm)
+    };
+    let code = match assembled {
+        Ok(code) => Some(code),
+        Err(crate::integrated_asm::Unsupported(reason)) => {
+            // Fall back to the external assembler for everything the integrated assembler
+            // doesn't understand.
+            if fx.clif_comments.enabled() {
+                fx.add_global_comment(format!("inline asm uses external assembler: {reason}"));
+            }
+            fx.cx.global_asm.push_str(&asm_gen.generate_asm_wrapper(&asm_name, &generated_asm));
+            None
+        }
+    };
 
     let mut inputs = Vec::new();
     let mut outputs = Vec::new();
@@ -202,7 +218,7 @@ pub(crate) fn codegen_inline_asm_inner<'tcx>(
         }
     }
 
-    call_inline_asm(fx, &asm_name, asm_gen.stack_slot_size, inputs, outputs);
+    call_inline_asm(fx, &asm_name, code.as_deref(), asm_gen.stack_slot_size, inputs, outputs);
 }
 
 struct InlineAssemblyGenerator<'a, 'tcx> {
@@ -434,30 +450,10 @@ fn allocate_stack_slots(&mut self) {
         self.stack_slot_size = slot_size;
     }
 
-    fn generate_asm_wrapper(&self, asm_name: &str) -> String {
-        let binary_format = crate::target_triple(self.tcx.sess).binary_format;
-
+    /// Generates the body of the wrapper function, which is valid input for both the integrated
+    /// and the external assembler.
+    fn generate_asm_body(&self) -> String {
         let mut generated_asm = String::new();
-        match binary_format {
-            BinaryFormat::Elf => {
-                writeln!(generated_asm, ".globl {}", asm_name).unwrap();
-                writeln!(generated_asm, ".type {},@function", asm_name).unwrap();
-                writeln!(generated_asm, ".section .text.{},\"ax\",@progbits", asm_name).unwrap();
-                writeln!(generated_asm, "{}:", asm_name).unwrap();
-            }
-            BinaryFormat::Macho => {
-                writeln!(generated_asm, ".globl _{}", asm_name).unwrap();
-                writeln!(generated_asm, "_{}:", asm_name).unwrap();
-            }
-            BinaryFormat::Coff => {
-                writeln!(generated_asm, ".globl {}", asm_name).unwrap();
-                writeln!(generated_asm, "{}:", asm_name).unwrap();
-            }
-            _ => self
-                .tcx
-                .dcx()
-                .fatal(format!("Unsupported binary format for inline asm: {binary_format:?}")),
-        }
 
         let is_x86 = matches!(self.arch, InlineAsmArch::X86 | InlineAsmArch::X86_64);
 
@@ -579,6 +575,38 @@ fn generate_asm_wrapper(&self, asm_name: &str) -> String {
             generated_asm.push_str(".att_syntax\n");
         }
 
+        generated_asm
+    }
+
+    /// Wraps the output of `generate_asm_body` in a function definition for the external
+    /// assembler.
+    fn generate_asm_wrapper(&self, asm_name: &str, body: &str) -> String {
+        let binary_format = crate::target_triple(self.tcx.sess).binary_format;
+
+        let mut generated_asm = String::new();
+        match binary_format {
+            BinaryFormat::Elf => {
+                writeln!(generated_asm, ".globl {}", asm_name).unwrap();
+                writeln!(generated_asm, ".type {},@function", asm_name).unwrap();
+                writeln!(generated_asm, ".section .text.{},\"ax\",@progbits", asm_name).unwrap();
+                writeln!(generated_asm, "{}:", asm_name).unwrap();
+            }
+            BinaryFormat::Macho => {
+                writeln!(generated_asm, ".globl _{}", asm_name).unwrap();
+                writeln!(generated_asm, "_{}:", asm_name).unwrap();
+            }
+            BinaryFormat::Coff => {
+                writeln!(generated_asm, ".globl {}", asm_name).unwrap();
+                writeln!(generated_asm, "{}:", asm_name).unwrap();
+            }
+            _ => self
+                .tcx
+                .dcx()
+                .fatal(format!("Unsupported binary format for inline asm: {binary_format:?}")),
+        }
+
+        generated_asm.push_str(body);
+
         match binary_format {
             BinaryFormat::Elf => {
                 writeln!(generated_asm, ".size {name}, .-{name}", name = asm_name).unwrap();
@@ -742,9 +770,13 @@ fn restore_register(
     }
 }
 
+/// Calls the wrapper function `asm_name`. If `code` is `Some`, the wrapper was assembled by the
+/// integrated assembler and gets defined in the current module. Otherwise it is defined by the
+/// `global_asm` of the codegen unit.
 fn call_inline_asm<'tcx>(
     fx: &mut FunctionCx<'_, '_, 'tcx>,
     asm_name: &str,
+    code: Option<&[u8]>,
     slot_size: Size,
     inputs: Vec<(Size, Value)>,
     outputs: Vec<(Size, CPlace<'tcx>)>,
@@ -755,7 +787,7 @@ fn call_inline_asm<'tcx>(
         .module
         .declare_function(
             asm_name,
-            Linkage::Import,
+            if code.is_some() { Linkage::Local } else { Linkage::Import },
             &Signature {
                 call_conv: CallConv::SystemV,
                 params: vec![AbiParam::new(fx.pointer_type)],
@@ -763,6 +795,11 @@ fn call_inline_asm<'tcx>(
             },
         )
         .unwrap();
+    if let Some(code) = code {
+        // The code doesn't contain any relocations, so the function is only needed to satisfy
+        // the api.
+        fx.module.define_function_bytes(inline_asm_func, &Function::new(), 16, code, &[]).unwrap();
+    }
     let inline_asm_func = fx.module.declare_func_in_func(inline_asm_func, fx.bcx.func);
     if fx.clif_comments.enabled() {
         fx.add_comment(inline_asm_func, asm_name);
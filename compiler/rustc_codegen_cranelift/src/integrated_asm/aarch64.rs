@@ -0,0 +1,933 @@
+//! Encoder for the subset of AArch64 assembly supported by the integrated assembler.
+
+use super::{parse_int, split_operands, unsupported, CodeBuffer, FixupKind, LabelRef, Unsupported};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RegKind {
+    X,
+    W,
+    /// A scalar floating point or vector register of the given size in bytes.
+    Fp(u8),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Reg {
+    kind: RegKind,
+    num: u32,
+    /// Register number 31 is either `sp` or the zero register depending on the instruction.
+    is_sp: bool,
+}
+
+impl Reg {
+    fn zr(sf: u32) -> Reg {
+        Reg { kind: if sf == 1 { RegKind::X } else { RegKind::W }, num: 31, is_sp: false }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum MemOffset {
+    Imm(i64),
+    /// Offset register, extend option and shift amount.
+    Reg(Reg, u32, Option<i64>),
+}
+
+#[derive(Debug)]
+struct Mem {
+    base: Reg,
+    offset: MemOffset,
+    pre_index: bool,
+}
+
+#[derive(Debug)]
+enum Operand {
+    Reg(Reg),
+    Imm(i64),
+    Mem(Mem),
+    /// `lsl`, `lsr`, `asr` or `ror` (encoded as 0 to 3) and the shift amount.
+    Shift(u32, i64),
+    /// Extend option as encoded in the instruction and the optional shift amount.
+    Extend(u32, Option<i64>),
+    Label(LabelRef),
+}
+
+const SHIFTS: [&str; 4] = ["lsl", "lsr", "asr", "ror"];
+const EXTENDS: [&str; 8] = ["uxtb", "uxth", "uxtw", "uxtx", "sxtb", "sxth", "sxtw", "sxtx"];
+const CONDITIONS: [&str; 16] = [
+    "eq", "ne", "hs", "lo", "mi", "pl", "vs", "vc", "hi", "ls", "ge", "lt", "gt", "le", "al", "nv",
+];
+
+fn parse_reg(name: &str) -> Option<Reg> {
+    let reg = |kind, num| Some(Reg { kind, num, is_sp: false });
+    match name {
+        "sp" => return Some(Reg { kind: RegKind::X, num: 31, is_sp: true }),
+        "wsp" => return Some(Reg { kind: RegKind::W, num: 31, is_sp: true }),
+        "xzr" => return reg(RegKind::X, 31),
+        "wzr" => return reg(RegKind::W, 31),
+        "fp" => return reg(RegKind::X, 29),
+        "lr" => return reg(RegKind::X, 30),
+        _ => {}
+    }
+    let kind = match name.chars().next()? {
+        'x' => RegKind::X,
+        'w' => RegKind::W,
+        'b' => RegKind::Fp(1),
+        'h' => RegKind::Fp(2),
+        's' => RegKind::Fp(4),
+        'd' => RegKind::Fp(8),
+        'q' => RegKind::Fp(16),
+        _ => return None,
+    };
+    let num = name[1..].parse::<u32>().ok()?;
+    // x31 and w31 don't exist, register 31 is always written as sp or zr.
+    let max = if matches!(kind, RegKind::Fp(_)) { 31 } else { 30 };
+    if num > max || (name.len() > 2 && name.as_bytes()[1] == b'0') {
+        return None;
+    }
+    reg(kind, num)
+}
+
+fn parse_imm(s: &str) -> Option<i64> {
+    let s = s.trim();
+    parse_int(s.strip_prefix('#').unwrap_or(s))
+}
+
+fn parse_modifier(s: &str) -> Option<Operand> {
+    let (name, amount) = match s.split_once(char::is_whitespace) {
+        Some((name, amount)) => (name, Some(parse_imm(amount)?)),
+        None => (s, None),
+    };
+    if let Some(shift) = SHIFTS.iter().position(|&shift| shift == name) {
+        return Some(Operand::Shift(shift as u32, amount?));
+    }
+    let extend = EXTENDS.iter().position(|&extend| extend == name)?;
+    Some(Operand::Extend(extend as u32, amount))
+}
+
+fn parse_operand(operand: &str) -> Result<Operand, Unsupported> {
+    let lower = operand.to_ascii_lowercase();
+    if let Some(inner) = lower.strip_prefix('[') {
+        let (inner, pre_index) = match inner.strip_suffix("]!") {
+            Some(inner) => (inner, true),
+            None => match inner.strip_suffix(']') {
+                Some(inner) => (inner, false),
+                None => unsupported!("memory operand `{operand}`"),
+            },
+        };
+        let parts = split_operands(inner);
+        let base = match parse_reg(parts[0]) {
+            Some(base @ Reg { kind: RegKind::X, .. }) if base.num != 31 || base.is_sp => base,
+            _ => unsupported!("memory operand `{operand}`"),
+        };
+        let offset = match &parts[1..] {
+            [] => MemOffset::Imm(0),
+            [offset] if parse_imm(offset).is_some() => MemOffset::Imm(parse_imm(offset).unwrap()),
+            [offset, modifier @ ..] => {
+                let offset = match parse_reg(offset) {
+                    Some(reg @ Reg { kind: RegKind::X | RegKind::W, is_sp: false, .. }) => reg,
+                    _ => unsupported!("memory operand `{operand}`"),
+                };
+                let (option, amount) = match modifier {
+                    [] => (0b011, None),
+                    [modifier] => match parse_modifier(modifier) {
+                        Some(Operand::Shift(0, amount)) => (0b011, Some(amount)),
+                        Some(Operand::Extend(option @ (0b010 | 0b011 | 0b110 | 0b111), amount)) => {
+                            (option, amount)
+                        }
+                        _ => unsupported!("memory operand `{operand}`"),
+                    },
+                    _ => unsupported!("memory operand `{operand}`"),
+                };
+                // The 64bit extends require an X register, the 32bit extends a W register.
+                let expected_kind = if option & 1 == 1 { RegKind::X } else { RegKind::W };
+                if offset.kind != expected_kind {
+                    unsupported!("memory operand `{operand}`");
+                }
+                MemOffset::Reg(offset, option, amount)
+            }
+        };
+        return Ok(Operand::Mem(Mem { base, offset, pre_index }));
+    }
+    if let Some(reg) = parse_reg(&lower) {
+        return Ok(Operand::Reg(reg));
+    }
+    if let Some(imm) = parse_imm(&lower) {
+        return Ok(Operand::Imm(imm));
+    }
+    if let Some(modifier) = parse_modifier(&lower) {
+        return Ok(modifier);
+    }
+    if let Some(label) = LabelRef::parse(operand) {
+        return Ok(Operand::Label(label));
+    }
+    unsupported!("operand `{operand}`")
+}
+
+fn parse_cond(cond: &str) -> Result<u32, Unsupported> {
+    let cond = match cond.trim().to_ascii_lowercase().as_str() {
+        "cs" => "hs".to_owned(),
+        "cc" => "lo".to_owned(),
+        cond => cond.to_owned(),
+    };
+    match CONDITIONS.iter().position(|&c| c == cond) {
+        Some(cond) => Ok(cond as u32),
+        None => unsupported!("condition `{cond}`"),
+    }
+}
+
+/// Returns the `sf` bit for instructions operating on the given general purpose registers.
+fn width(regs: &[Reg]) -> Result<u32, Unsupported> {
+    let kind = regs[0].kind;
+    if regs.iter().any(|reg| reg.kind != kind) {
+        unsupported!("mixed register widths");
+    }
+    match kind {
+        RegKind::X => Ok(1),
+        RegKind::W => Ok(0),
+        RegKind::Fp(_) => unsupported!("floating point register"),
+    }
+}
+
+/// Encodes a register in a field where register 31 is the zero register.
+fn zr(reg: Reg) -> Result<u32, Unsupported> {
+    if reg.is_sp || matches!(reg.kind, RegKind::Fp(_)) {
+        unsupported!("invalid register {reg:?}");
+    }
+    Ok(reg.num)
+}
+
+/// Encodes a register in a field where register 31 is the stack pointer.
+fn sp(reg: Reg) -> Result<u32, Unsupported> {
+    if (reg.num == 31 && !reg.is_sp) || matches!(reg.kind, RegKind::Fp(_)) {
+        unsupported!("invalid register {reg:?}");
+    }
+    Ok(reg.num)
+}
+
+/// Encodes `value` as logical immediate, returning the `N`, `immr` and `imms` fields.
+fn encode_bitmask(value: u64, sf: u32) -> Option<(u32, u32, u32)> {
+    let value = if sf == 1 { value } else { (value & 0xffff_ffff) | (value << 32) };
+    if value == 0 || value == u64::MAX {
+        return None;
+    }
+
+    // Find the smallest element size by which the value repeats.
+    let mut size = 64u32;
+    while size > 2 {
+        let half = size / 2;
+        let mask = (1u64 << half) - 1;
+        if value & mask != (value >> half) & mask {
+            break;
+        }
+        size = half;
+    }
+    let mask = if size == 64 { u64::MAX } else { (1u64 << size) - 1 };
+    let element = value & mask;
+
+    // The element must be a rotated run of ones.
+    let ones = element.count_ones();
+    let run = (1u64 << ones) - 1;
+    let rotation = (0..size).find(|&r| {
+        let rotated = if r == 0 {
+            element
+        } else {
+            ((element >> r) | (element << (size - r))) & mask
+        };
+        rotated == run
+    })?;
+    let immr = (size - rotation) % size;
+    let imms = ((!(size - 1) << 1) & 0x3f) | (ones - 1);
+    Some((u32::from(size == 64), immr, imms))
+}
+
+fn emit_mov_imm(buf: &mut CodeBuffer, rd: Reg, imm: i64) -> Result<(), Unsupported> {
+    let sf = width(&[rd])?;
+    let (value, halfwords) = if sf == 1 {
+        (imm as u64, 4)
+    } else {
+        if !(i64::from(i32::MIN)..=i64::from(u32::MAX)).contains(&imm) {
+            unsupported!("immediate {imm} out of range");
+        }
+        (imm as u64 & 0xffff_ffff, 2)
+    };
+    let mask = if sf == 1 { u64::MAX } else { 0xffff_ffff };
+
+    if !rd.is_sp {
+        for (base, value) in [(0x5280_0000, value), (0x1280_0000, !value & mask)] {
+            for hw in 0..halfwords {
+                if value & !(0xffff << (16 * hw)) == 0 {
+                    let imm16 = (value >> (16 * hw)) as u32;
+                    buf.put4(base | sf << 31 | hw << 21 | imm16 << 5 | rd.num);
+                    return Ok(());
+                }
+            }
+        }
+    }
+    match encode_bitmask(value, sf) {
+        // orr rd, zr, #imm
+        Some((n, immr, imms)) => {
+            let rd = sp(rd)?;
+            buf.put4(0x3200_0000 | sf << 31 | n << 22 | immr << 16 | imms << 10 | 31 << 5 | rd);
+            Ok(())
+        }
+        None => unsupported!("immediate {imm} can't be moved with a single instruction"),
+    }
+}
+
+fn emit_add_sub(
+    buf: &mut CodeBuffer,
+    op: u32,
+    s: u32,
+    rd: Reg,
+    rn: Reg,
+    op2: &[Operand],
+) -> Result<(), Unsupported> {
+    let sf = width(&[rd, rn])?;
+    // The flag setting variants use the zero register as destination.
+    let encode_rd = |rd| if s == 1 { zr(rd) } else { sp(rd) };
+    match op2 {
+        [Operand::Imm(imm), shift @ ..] => {
+            let shift12 = match shift {
+                [] | [Operand::Shift(0, 0)] => false,
+                [Operand::Shift(0, 12)] => true,
+                _ => unsupported!("invalid shift for immediate"),
+            };
+            // `add x0, x1, #-1` is an alias for `sub x0, x1, #1`.
+            let (op, imm) = if *imm < 0 { (op ^ 1, imm.unsigned_abs()) } else { (op, *imm as u64) };
+            let (sh, imm12) = if shift12 {
+                (1, imm)
+            } else if imm < 0x1000 {
+                (0, imm)
+            } else if imm & 0xfff == 0 && imm < 0x100_0000 {
+                (1, imm >> 12)
+            } else {
+                unsupported!("immediate {imm} out of range");
+            };
+            if imm12 >= 0x1000 {
+                unsupported!("immediate {imm} out of range");
+            }
+            let imm12 = imm12 as u32;
+            buf.put4(
+                0x1100_0000
+                    | sf << 31
+                    | op << 30
+                    | s << 29
+                    | sh << 22
+                    | imm12 << 10
+                    | sp(rn)? << 5
+                    | encode_rd(rd)?,
+            );
+        }
+        [Operand::Reg(rm), modifier @ ..]
+            if rd.is_sp || rn.is_sp || matches!(modifier, [Operand::Extend(..)]) =>
+        {
+            let (option, amount) = match modifier {
+                [] => (0b010 | sf, 0),
+                [Operand::Shift(0, amount)] => (0b010 | sf, *amount),
+                [Operand::Extend(option, amount)] => (*option, amount.unwrap_or(0)),
+                _ => unsupported!("invalid extend"),
+            };
+            if !(0..=4).contains(&amount) {
+                unsupported!("invalid extend amount {amount}");
+            }
+            // Only the 64bit extends of 64bit operations take an X register.
+            let rm_kind = if sf == 1 && option & 0b011 == 0b011 { RegKind::X } else { RegKind::W };
+            if rm.kind != rm_kind {
+                unsupported!("invalid register {rm:?}");
+            }
+            buf.put4(
+                0x0b20_0000
+                    | sf << 31
+                    | op << 30
+                    | s << 29
+                    | zr(*rm)? << 16
+                    | option << 13
+                    | (amount as u32) << 10
+                    | sp(rn)? << 5
+                    | encode_rd(rd)?,
+            );
+        }
+        [Operand::Reg(rm), modifier @ ..] => {
+            width(&[rd, rn, *rm])?;
+            let (shift, amount) = shift_modifier(modifier, sf)?;
+            if shift == 3 {
+                unsupported!("invalid shift");
+            }
+            buf.put4(
+                0x0b00_0000
+                    | sf << 31
+                    | op << 30
+                    | s << 29
+                    | shift << 22
+                    | zr(*rm)? << 16
+                    | amount << 10
+                    | zr(rn)? << 5
+                    | zr(rd)?,
+            );
+        }
+        _ => unsupported!("invalid operands"),
+    }
+    Ok(())
+}
+
+/// Parses an optional shift of a shifted register operand.
+fn shift_modifier(modifier: &[Operand], sf: u32) -> Result<(u32, u32), Unsupported> {
+    match modifier {
+        [] => Ok((0, 0)),
+        [Operand::Shift(shift, amount)] if (0..(32 << sf)).contains(amount) => {
+            Ok((*shift, *amount as u32))
+        }
+        _ => unsupported!("invalid shift"),
+    }
+}
+
+fn emit_logical(
+    buf: &mut CodeBuffer,
+    opc: u32,
+    n: u32,
+    rd: Reg,
+    rn: Reg,
+    op2: &[Operand],
+) -> Result<(), Unsupported> {
+    let sf = width(&[rd, rn])?;
+    match op2 {
+        [Operand::Imm(imm)] => {
+            // The inverting variants are aliases for the normal ones with an inverted immediate.
+            let value = if n == 1 { !*imm as u64 } else { *imm as u64 };
+            let Some((n, immr, imms)) = encode_bitmask(value, sf) else {
+                unsupported!("invalid logical immediate {imm}");
+            };
+            let rd = if opc == 0b11 { zr(rd)? } else { sp(rd)? };
+            buf.put4(
+                0x1200_0000
+                    | sf << 31
+                    | opc << 29
+                    | n << 22
+                    | immr << 16
+                    | imms << 10
+                    | zr(rn)? << 5
+                    | rd,
+            );
+        }
+        [Operand::Reg(rm), modifier @ ..] => {
+            width(&[rd, rn, *rm])?;
+            let (shift, amount) = shift_modifier(modifier, sf)?;
+            buf.put4(
+                0x0a00_0000
+                    | sf << 31
+                    | opc << 29
+                    | shift << 22
+                    | n << 21
+                    | zr(*rm)? << 16
+                    | amount << 10
+                    | zr(rn)? << 5
+                    | zr(rd)?,
+            );
+        }
+        _ => unsupported!("invalid operands"),
+    }
+    Ok(())
+}
+
+/// Returns the `size`, `V` and `opc` fields and the access size of a single register load or
+/// store.
+fn load_store_kind(mnemonic: &str, rt: Reg) -> Result<(u32, u32, u32, u32), Unsupported> {
+    let is_load = mnemonic.starts_with("ld");
+    let opc = u32::from(is_load);
+    let (size, v, opc) = match (mnemonic.trim_start_matches("ld").trim_start_matches("st"), rt.kind)
+    {
+        ("r" | "ur", RegKind::X) => (3, 0, opc),
+        ("r" | "ur", RegKind::W) => (2, 0, opc),
+        ("r" | "ur", RegKind::Fp(1)) => (0, 1, opc),
+        ("r" | "ur", RegKind::Fp(2)) => (1, 1, opc),
+        ("r" | "ur", RegKind::Fp(4)) => (2, 1, opc),
+        ("r" | "ur", RegKind::Fp(8)) => (3, 1, opc),
+        ("r" | "ur", RegKind::Fp(16)) => (0, 1, opc | 0b10),
+        ("rb" | "urb", RegKind::W) => (0, 0, opc),
+        ("rh" | "urh", RegKind::W) => (1, 0, opc),
+        ("rsb" | "ursb", RegKind::X) if is_load => (0, 0, 0b10),
+        ("rsb" | "ursb", RegKind::W) if is_load => (0, 0, 0b11),
+        ("rsh" | "ursh", RegKind::X) if is_load => (1, 0, 0b10),
+        ("rsh" | "ursh", RegKind::W) if is_load => (1, 0, 0b11),
+        ("rsw" | "ursw", RegKind::X) if is_load => (2, 0, 0b10),
+        _ => unsupported!("`{mnemonic}` with register {rt:?}"),
+    };
+    let scale = if rt.kind == RegKind::Fp(16) { 4 } else { size };
+    Ok((size, v, opc, scale))
+}
+
+fn emit_load_store(
+    buf: &mut CodeBuffer,
+    mnemonic: &str,
+    rt: Reg,
+    operands: &[Operand],
+) -> Result<(), Unsupported> {
+    let (size, v, opc, scale) = load_store_kind(mnemonic, rt)?;
+    let unscaled_only = mnemonic[2..].starts_with('u');
+    let rt_num = if v == 1 { rt.num } else { zr(rt)? };
+    let base = size << 30 | v << 26 | opc << 22 | rt_num;
+
+    match operands {
+        [Operand::Label(target)] if mnemonic == "ldr" => {
+            let opcode = match rt.kind {
+                RegKind::W => 0x1800_0000,
+                RegKind::X => 0x5800_0000,
+                RegKind::Fp(4) => 0x1c00_0000,
+                RegKind::Fp(8) => 0x5c00_0000,
+                RegKind::Fp(16) => 0x9c00_0000,
+                _ => unsupported!("literal load into {rt:?}"),
+            };
+            buf.put_fixup(FixupKind::Arm64Branch19, target.clone());
+            buf.put4(opcode | rt.num);
+        }
+        [Operand::Mem(Mem { base: rn, offset: MemOffset::Imm(imm), pre_index: true })]
+        | [
+            Operand::Mem(Mem { base: rn, offset: MemOffset::Imm(0), pre_index: false }),
+            Operand::Imm(imm),
+        ] => {
+            if unscaled_only || !(-256..256).contains(imm) {
+                unsupported!("invalid offset {imm}");
+            }
+            let mode = if operands.len() == 1 { 0xc00 } else { 0x400 };
+            buf.put4(0x3800_0000 | base | ((*imm as u32) & 0x1ff) << 12 | mode | sp(*rn)? << 5);
+        }
+        [Operand::Mem(Mem { base: rn, offset: MemOffset::Imm(imm), pre_index: false })] => {
+            let imm = *imm;
+            if !unscaled_only && imm >= 0 && imm % (1 << scale) == 0 && (imm >> scale) < 0x1000 {
+                buf.put4(0x3900_0000 | base | ((imm >> scale) as u32) << 10 | sp(*rn)? << 5);
+            } else if (-256..256).contains(&imm) {
+                buf.put4(0x3800_0000 | base | ((imm as u32) & 0x1ff) << 12 | sp(*rn)? << 5);
+            } else {
+                unsupported!("invalid offset {imm}");
+            }
+        }
+        [
+            Operand::Mem(Mem {
+                base: rn,
+                offset: MemOffset::Reg(rm, option, amount),
+                pre_index: false,
+            }),
+        ] if !unscaled_only => {
+            let s = match amount {
+                None => 0,
+                Some(amount) if *amount == i64::from(scale) => 1,
+                Some(0) => 0,
+                Some(amount) => unsupported!("invalid shift amount {amount}"),
+            };
+            buf.put4(
+                0x3820_0800
+                    | base
+                    | rm.num << 16
+                    | option << 13
+                    | s << 12
+                    | sp(*rn)? << 5,
+            );
+        }
+        _ => unsupported!("invalid operands for `{mnemonic}`"),
+    }
+    Ok(())
+}
+
+fn emit_load_store_pair(
+    buf: &mut CodeBuffer,
+    is_load: bool,
+    rt: Reg,
+    rt2: Reg,
+    operands: &[Operand],
+) -> Result<(), Unsupported> {
+    if rt.kind != rt2.kind {
+        unsupported!("mixed register kinds");
+    }
+    let (opc, v, scale) = match rt.kind {
+        RegKind::W => (0b00, 0, 2),
+        RegKind::X => (0b10, 0, 3),
+        RegKind::Fp(4) => (0b00, 1, 2),
+        RegKind::Fp(8) => (0b01, 1, 3),
+        RegKind::Fp(16) => (0b10, 1, 4),
+        RegKind::Fp(_) => unsupported!("invalid register {rt:?}"),
+    };
+    let (rn, imm, mode) = match operands {
+        [Operand::Mem(Mem { base, offset: MemOffset::Imm(imm), pre_index })] => {
+            (base, *imm, if *pre_index { 0b11 } else { 0b10 })
+        }
+        [
+            Operand::Mem(Mem { base, offset: MemOffset::Imm(0), pre_index: false }),
+            Operand::Imm(imm),
+        ] => (base, *imm, 0b01),
+        _ => unsupported!("invalid operands for load/store pair"),
+    };
+    if imm % (1 << scale) != 0 || !(-64..64).contains(&(imm >> scale)) {
+        unsupported!("invalid offset {imm}");
+    }
+    let (rt, rt2) = if v == 1 { (rt.num, rt2.num) } else { (zr(rt)?, zr(rt2)?) };
+    buf.put4(
+        opc << 30
+            | 0x2800_0000
+            | v << 26
+            | mode << 23
+            | u32::from(is_load) << 22
+            | (((imm >> scale) as u32) & 0x7f) << 15
+            | rt2 << 10
+            | sp(*rn)? << 5
+            | rt,
+    );
+    Ok(())
+}
+
+/// Returns the base register of a memory operand without offset.
+fn exclusive_address(operand: &Operand) -> Result<u32, Unsupported> {
+    match operand {
+        Operand::Mem(Mem { base, offset: MemOffset::Imm(0), pre_index: false }) => sp(*base),
+        _ => unsupported!("invalid address for exclusive access"),
+    }
+}
+
+const HINTS: [(&str, u32); 6] = [
+    ("nop", 0xd503_201f),
+    ("yield", 0xd503_203f),
+    ("wfe", 0xd503_205f),
+    ("wfi", 0xd503_207f),
+    ("sev", 0xd503_209f),
+    ("sevl", 0xd503_20bf),
+];
+
+const BARRIER_OPTIONS: [(&str, u32); 12] = [
+    ("oshld", 1),
+    ("oshst", 2),
+    ("osh", 3),
+    ("nshld", 5),
+    ("nshst", 6),
+    ("nsh", 7),
+    ("ishld", 9),
+    ("ishst", 10),
+    ("ish", 11),
+    ("ld", 13),
+    ("st", 14),
+    ("sy", 15),
+];
+
+/// The `opc` and `N` fields of the logical instructions.
+const LOGICAL_OPS: [(&str, u32, u32); 8] = [
+    ("and", 0b00, 0),
+    ("orr", 0b01, 0),
+    ("eor", 0b10, 0),
+    ("ands", 0b11, 0),
+    ("bic", 0b00, 1),
+    ("orn", 0b01, 1),
+    ("eon", 0b10, 1),
+    ("bics", 0b11, 1),
+];
+
+pub(super) fn assemble_instruction(
+    buf: &mut CodeBuffer,
+    mnemonic: &str,
+    operands: &[&str],
+) -> Result<(), Unsupported> {
+    // Condition and barrier operands are parsed as labels here and reparsed where necessary.
+    let ops = operands.iter().map(|operand| parse_operand(operand)).collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(&(_, insn)) = HINTS.iter().find(|(name, _)| *name == mnemonic) {
+        if !ops.is_empty() {
+            unsupported!("operands for `{mnemonic}`");
+        }
+        buf.put4(insn);
+        return Ok(());
+    }
+
+    if let Some(&(_, opc, n)) = LOGICAL_OPS.iter().find(|(name, _, _)| *name == mnemonic) {
+        let [Operand::Reg(rd), Operand::Reg(rn), op2 @ ..] = ops.as_slice() else {
+            unsupported!("operands for `{mnemonic}`");
+        };
+        return emit_logical(buf, opc, n, *rd, *rn, op2);
+    }
+
+    if let Some(cond) = mnemonic.strip_prefix("b.") {
+        let cond = parse_cond(cond)?;
+        let [Operand::Label(target)] = ops.as_slice() else {
+            unsupported!("operands for `{mnemonic}`");
+        };
+        buf.put_fixup(FixupKind::Arm64Branch19, target.clone());
+        buf.put4(0x5400_0000 | cond);
+        return Ok(());
+    }
+
+    if mnemonic.starts_with("ld") || mnemonic.starts_with("st") {
+        match (mnemonic, ops.as_slice()) {
+            ("ldp" | "stp", [Operand::Reg(rt), Operand::Reg(rt2), rest @ ..]) => {
+                return emit_load_store_pair(buf, mnemonic == "ldp", *rt, *rt2, rest);
+            }
+            ("ldxr" | "ldaxr" | "ldar", [Operand::Reg(rt), address]) => {
+                let base = match mnemonic {
+                    "ldxr" => 0x885f_7c00,
+                    "ldaxr" => 0x885f_fc00,
+                    _ => 0x88df_fc00,
+                };
+                let size = width(&[*rt])?;
+                buf.put4(base | size << 30 | exclusive_address(address)? << 5 | zr(*rt)?);
+                return Ok(());
+            }
+            ("stxr" | "stlxr", [Operand::Reg(ws), Operand::Reg(rt), address]) => {
+                if ws.kind != RegKind::W {
+                    unsupported!("invalid status register {ws:?}");
+                }
+                let base = if mnemonic == "stxr" { 0x8800_7c00 } else { 0x8800_fc00 };
+                let size = width(&[*rt])?;
+                buf.put4(
+                    base | size << 30
+                        | zr(*ws)? << 16
+                        | exclusive_address(address)? << 5
+                        | zr(*rt)?,
+                );
+                return Ok(());
+            }
+            ("stlr", [Operand::Reg(rt), address]) => {
+                let size = width(&[*rt])?;
+                buf.put4(0x889f_fc00 | size << 30 | exclusive_address(address)? << 5 | zr(*rt)?);
+                return Ok(());
+            }
+            (_, [Operand::Reg(rt), rest @ ..]) => {
+                return emit_load_store(buf, mnemonic, *rt, rest);
+            }
+            _ => unsupported!("operands for `{mnemonic}`"),
+        }
+    }
+
+    match (mnemonic, ops.as_slice()) {
+        ("mov", [Operand::Reg(rd), Operand::Reg(rm)]) => {
+            let sf = width(&[*rd, *rm])?;
+            if rd.is_sp || rm.is_sp {
+                // add rd, rm, #0
+                buf.put4(0x1100_0000 | sf << 31 | sp(*rm)? << 5 | sp(*rd)?);
+            } else {
+                // orr rd, zr, rm
+                buf.put4(0x2a00_03e0 | sf << 31 | zr(*rm)? << 16 | zr(*rd)?);
+            }
+        }
+        ("mov", [Operand::Reg(rd), Operand::Imm(imm)]) => emit_mov_imm(buf, *rd, *imm)?,
+        ("movz" | "movn" | "movk", [Operand::Reg(rd), Operand::Imm(imm), shift @ ..]) => {
+            let sf = width(&[*rd])?;
+            let shift = match shift {
+                [] => 0,
+                [Operand::Shift(0, amount @ (0 | 16 | 32 | 48))] if *amount < 32 << sf => {
+                    *amount as u32
+                }
+                _ => unsupported!("invalid shift for `{mnemonic}`"),
+            };
+            if !(0..=0xffff).contains(imm) {
+                unsupported!("immediate {imm} out of range");
+            }
+            let base = match mnemonic {
+                "movz" => 0x5280_0000,
+                "movn" => 0x1280_0000,
+                _ => 0x7280_0000,
+            };
+            buf.put4(base | sf << 31 | (shift / 16) << 21 | (*imm as u32) << 5 | zr(*rd)?);
+        }
+        ("add" | "adds" | "sub" | "subs", [Operand::Reg(rd), Operand::Reg(rn), op2 @ ..]) => {
+            let op = u32::from(mnemonic.starts_with("sub"));
+            let s = u32::from(mnemonic.ends_with('s'));
+            emit_add_sub(buf, op, s, *rd, *rn, op2)?;
+        }
+        ("cmp" | "cmn", [Operand::Reg(rn), op2 @ ..]) => {
+            let op = u32::from(mnemonic == "cmp");
+            emit_add_sub(buf, op, 1, Reg::zr(width(&[*rn])?), *rn, op2)?;
+        }
+        ("neg" | "negs", [Operand::Reg(rd), op2 @ ..]) => {
+            let s = u32::from(mnemonic == "negs");
+            emit_add_sub(buf, 1, s, *rd, Reg::zr(width(&[*rd])?), op2)?;
+        }
+        ("tst", [Operand::Reg(rn), op2 @ ..]) => {
+            emit_logical(buf, 0b11, 0, Reg::zr(width(&[*rn])?), *rn, op2)?;
+        }
+        ("mvn", [Operand::Reg(rd), op2 @ ..]) => {
+            emit_logical(buf, 0b01, 1, *rd, Reg::zr(width(&[*rd])?), op2)?;
+        }
+        (
+            "lsl" | "lsr" | "asr" | "ror",
+            [Operand::Reg(rd), Operand::Reg(rn), Operand::Reg(rm)],
+        ) => {
+            let sf = width(&[*rd, *rn, *rm])?;
+            let op2 = SHIFTS.iter().position(|&shift| shift == mnemonic).unwrap() as u32;
+            buf.put4(
+                0x1ac0_2000 | sf << 31 | zr(*rm)? << 16 | op2 << 10 | zr(*rn)? << 5 | zr(*rd)?,
+            );
+        }
+        (
+            "lsl" | "lsr" | "asr" | "ror",
+            [Operand::Reg(rd), Operand::Reg(rn), Operand::Imm(amount)],
+        ) => {
+            let sf = width(&[*rd, *rn])?;
+            let bits = 32 << sf;
+            if !(0..i64::from(bits)).contains(amount) {
+                unsupported!("shift amount {amount} out of range");
+            }
+            let amount = *amount as u32;
+            let (rd, rn) = (zr(*rd)?, zr(*rn)?);
+            let insn = match mnemonic {
+                // ubfm rd, rn, #(-amount % bits), #(bits - 1 - amount)
+                "lsl" => 0x5300_0000 | ((bits - amount) % bits) << 16 | (bits - 1 - amount) << 10,
+                // ubfm rd, rn, #amount, #(bits - 1)
+                "lsr" => 0x5300_0000 | amount << 16 | (bits - 1) << 10,
+                // sbfm rd, rn, #amount, #(bits - 1)
+                "asr" => 0x1300_0000 | amount << 16 | (bits - 1) << 10,
+                // extr rd, rn, rn, #amount
+                _ => 0x1380_0000 | rn << 16 | amount << 10,
+            };
+            buf.put4(insn | sf << 31 | sf << 22 | rn << 5 | rd);
+        }
+        ("uxtb" | "uxth" | "sxtb" | "sxth" | "sxtw", [Operand::Reg(rd), Operand::Reg(rn)]) => {
+            let sf = width(&[*rd])?;
+            let valid_width = match mnemonic {
+                "uxtb" | "uxth" => sf == 0,
+                "sxtw" => sf == 1,
+                _ => true,
+            };
+            if rn.kind != RegKind::W || !valid_width {
+                unsupported!("operands for `{mnemonic}`");
+            }
+            let imms = match &mnemonic[3..] {
+                "b" => 7,
+                "h" => 15,
+                _ => 31,
+            };
+            // ubfm/sbfm rd, rn, #0, #imms
+            let base = if mnemonic.starts_with('u') { 0x5300_0000 } else { 0x1300_0000 };
+            buf.put4(base | sf << 31 | sf << 22 | imms << 10 | zr(*rn)? << 5 | zr(*rd)?);
+        }
+        (
+            "madd" | "msub",
+            [Operand::Reg(rd), Operand::Reg(rn), Operand::Reg(rm), Operand::Reg(ra)],
+        ) => {
+            let sf = width(&[*rd, *rn, *rm, *ra])?;
+            let o0 = u32::from(mnemonic == "msub");
+            buf.put4(
+                0x1b00_0000
+                    | sf << 31
+                    | zr(*rm)? << 16
+                    | o0 << 15
+                    | zr(*ra)? << 10
+                    | zr(*rn)? << 5
+                    | zr(*rd)?,
+            );
+        }
+        ("mul" | "mneg", [Operand::Reg(rd), Operand::Reg(rn), Operand::Reg(rm)]) => {
+            let sf = width(&[*rd, *rn, *rm])?;
+            let o0 = u32::from(mnemonic == "mneg");
+            buf.put4(
+                0x1b00_7c00 | sf << 31 | zr(*rm)? << 16 | o0 << 15 | zr(*rn)? << 5 | zr(*rd)?,
+            );
+        }
+        ("udiv" | "sdiv", [Operand::Reg(rd), Operand::Reg(rn), Operand::Reg(rm)]) => {
+            let sf = width(&[*rd, *rn, *rm])?;
+            let base = if mnemonic == "udiv" { 0x1ac0_0800 } else { 0x1ac0_0c00 };
+            buf.put4(base | sf << 31 | zr(*rm)? << 16 | zr(*rn)? << 5 | zr(*rd)?);
+        }
+        ("umulh" | "smulh", [Operand::Reg(rd), Operand::Reg(rn), Operand::Reg(rm)]) => {
+            if width(&[*rd, *rn, *rm])? != 1 {
+                unsupported!("operands for `{mnemonic}`");
+            }
+            let base = if mnemonic == "umulh" { 0x9bc0_7c00 } else { 0x9b40_7c00 };
+            buf.put4(base | zr(*rm)? << 16 | zr(*rn)? << 5 | zr(*rd)?);
+        }
+        (
+            "csel" | "csinc" | "csinv" | "csneg",
+            [Operand::Reg(rd), Operand::Reg(rn), Operand::Reg(rm), _],
+        ) => {
+            let sf = width(&[*rd, *rn, *rm])?;
+            let cond = parse_cond(operands[3])?;
+            emit_cond_select(buf, mnemonic, sf, *rd, *rn, *rm, cond)?;
+        }
+        ("cset" | "csetm", [Operand::Reg(rd), _]) => {
+            let sf = width(&[*rd])?;
+            let cond = parse_cond(operands[1])?;
+            if cond >= 14 {
+                unsupported!("condition for `{mnemonic}`");
+            }
+            let insn = if mnemonic == "cset" { "csinc" } else { "csinv" };
+            let zr = Reg::zr(sf);
+            emit_cond_select(buf, insn, sf, *rd, zr, zr, cond ^ 1)?;
+        }
+        ("cinc" | "cinv" | "cneg", [Operand::Reg(rd), Operand::Reg(rn), _]) => {
+            let sf = width(&[*rd, *rn])?;
+            let cond = parse_cond(operands[2])?;
+            if cond >= 14 {
+                unsupported!("condition for `{mnemonic}`");
+            }
+            let insn = match mnemonic {
+                "cinc" => "csinc",
+                "cinv" => "csinv",
+                _ => "csneg",
+            };
+            emit_cond_select(buf, insn, sf, *rd, *rn, *rn, cond ^ 1)?;
+        }
+        ("b" | "bl", [Operand::Label(target)]) => {
+            buf.put_fixup(FixupKind::Arm64Branch26, target.clone());
+            buf.put4(if mnemonic == "b" { 0x1400_0000 } else { 0x9400_0000 });
+        }
+        ("cbz" | "cbnz", [Operand::Reg(rt), Operand::Label(target)]) => {
+            let sf = width(&[*rt])?;
+            let base = if mnemonic == "cbz" { 0x3400_0000 } else { 0x3500_0000 };
+            buf.put_fixup(FixupKind::Arm64Branch19, target.clone());
+            buf.put4(base | sf << 31 | zr(*rt)?);
+        }
+        ("ret", []) => buf.put4(0xd65f_0000 | 30 << 5),
+        ("ret" | "br" | "blr", [Operand::Reg(rn @ Reg { kind: RegKind::X, .. })]) => {
+            let base = match mnemonic {
+                "ret" => 0xd65f_0000,
+                "br" => 0xd61f_0000,
+                _ => 0xd63f_0000,
+            };
+            buf.put4(base | zr(*rn)? << 5);
+        }
+        ("brk" | "svc" | "hlt", [Operand::Imm(imm @ 0..=0xffff)]) => {
+            let base = match mnemonic {
+                "brk" => 0xd420_0000,
+                "svc" => 0xd400_0001,
+                _ => 0xd440_0000,
+            };
+            buf.put4(base | (*imm as u32) << 5);
+        }
+        ("dmb" | "dsb" | "isb", [_]) | ("isb", []) => {
+            let option = match operands.first() {
+                None => 15,
+                Some(option) => match parse_imm(option) {
+                    Some(imm @ 0..=15) => imm as u32,
+                    Some(_) => unsupported!("barrier option `{option}`"),
+                    None => {
+                        let option = option.to_ascii_lowercase();
+                        match BARRIER_OPTIONS.iter().find(|(name, _)| *name == option) {
+                            Some(&(_, option)) => option,
+                            None => unsupported!("barrier option `{option}`"),
+                        }
+                    }
+                },
+            };
+            let base = match mnemonic {
+                "dmb" => 0xd503_30bf,
+                "dsb" => 0xd503_309f,
+                _ => 0xd503_30df,
+            };
+            buf.put4(base | option << 8);
+        }
+        _ => unsupported!("instruction `{mnemonic}` with operands {operands:?}"),
+    }
+    Ok(())
+}
+
+fn emit_cond_select(
+    buf: &mut CodeBuffer,
+    mnemonic: &str,
+    sf: u32,
+    rd: Reg,
+    rn: Reg,
+    rm: Reg,
+    cond: u32,
+) -> Result<(), Unsupported> {
+    let base = match mnemonic {
+        "csel" => 0x1a80_0000,
+        "csinc" => 0x1a80_0400,
+        "csinv" => 0x5a80_0000,
+        _ => 0x5a80_0400,
+    };
+    buf.put4(base | sf << 31 | zr(rm)? << 16 | cond << 12 | zr(rn)? << 5 | zr(rd)?);
+    Ok(())
+}
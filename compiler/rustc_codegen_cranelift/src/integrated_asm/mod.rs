@@ -0,0 +1,293 @@
+//! A minimal assembler for the code generated for `asm!` invocations.
+//!
+//! By default the wrapper functions generated for `asm!` are assembled by an external assembler
+//! together with all `global_asm!` of the codegen unit. Spawning the external assembler is slow and
+//! requires a toolchain, which isn't available in hermetic build environments. This module
+//! encodes the instructions that are commonly used in `asm!` directly, so the wrapper can be
+//! defined in the object file emitted by Cranelift. Anything it doesn't understand is rejected
+//! with [`Unsupported`], in which case the caller falls back to the external assembler.
+
+mod aarch64;
+#[cfg(test)]
+mod tests;
+mod x86_64;
+
+use rustc_target::asm::InlineAsmArch;
+
+use crate::prelude::*;
+
+/// The reason why a piece of assembly can't be handled by the integrated assembler.
+#[derive(Debug)]
+pub(crate) struct Unsupported(pub(crate) String);
+
+macro_rules! unsupported {
+    ($($arg:tt)*) => {
+        return Err(crate::integrated_asm::Unsupported(format!($($arg)*)))
+    };
+}
+pub(crate) use unsupported;
+
+/// Assembles `asm` into machine code for `arch`.
+///
+/// The code may only reference labels defined within `asm`, as no relocations are emitted.
+pub(crate) fn assemble(arch: InlineAsmArch, asm: &str) -> Result<Vec<u8>, Unsupported> {
+    let mut buf = CodeBuffer::default();
+    // Only the Intel syntax is supported on x86. The wrapper switches back to AT&T syntax at the
+    // end, so this isn't an error by itself.
+    let mut att_syntax = false;
+    for statement in statements(arch, asm) {
+        let mut statement = statement.trim();
+        while let Some((label, rest)) = split_label(statement) {
+            buf.define_label(label);
+            statement = rest.trim_start();
+        }
+        if statement.is_empty() {
+            continue;
+        }
+
+        if statement.starts_with('.') {
+            match (arch, statement.split_whitespace().collect::<Vec<_>>().as_slice()) {
+                (InlineAsmArch::X86_64, [".intel_syntax", "noprefix"]) => att_syntax = false,
+                (InlineAsmArch::X86_64, [".att_syntax"] | [".att_syntax", "prefix"]) => {
+                    att_syntax = true
+                }
+                _ => unsupported!("directive `{statement}`"),
+            }
+            continue;
+        }
+        if att_syntax {
+            unsupported!("AT&T syntax");
+        }
+
+        let (mnemonic, operands) = match statement.split_once(char::is_whitespace) {
+            Some((mnemonic, operands)) => (mnemonic, split_operands(operands)),
+            None => (statement, vec![]),
+        };
+        let mnemonic = mnemonic.to_ascii_lowercase();
+        match arch {
+            InlineAsmArch::X86_64 => x86_64::assemble_instruction(&mut buf, &mnemonic, &operands)?,
+            InlineAsmArch::AArch64 => {
+                aarch64::assemble_instruction(&mut buf, &mnemonic, &operands)?
+            }
+            _ => unsupported!("architecture {arch:?}"),
+        }
+    }
+    buf.finish()
+}
+
+/// Splits `asm` into individual statements, removing all comments.
+fn statements(arch: InlineAsmArch, asm: &str) -> Vec<String> {
+    let mut statements = vec![];
+    let mut current = String::new();
+    let mut chars = asm.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            // On AArch64 `#` is the prefix of immediates rather than a comment.
+            '#' if arch == InlineAsmArch::X86_64 => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '\n' | ';' => statements.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    statements.push(current);
+    statements
+}
+
+/// Splits a leading `label:` off `statement`.
+fn split_label(statement: &str) -> Option<(&str, &str)> {
+    let (label, rest) = statement.split_once(':')?;
+    let is_label = !label.is_empty()
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$'));
+    is_label.then_some((label, rest))
+}
+
+/// Splits the operands of an instruction at all commas that aren't inside brackets.
+fn split_operands(operands: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in operands.char_indices() {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                result.push(operands[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    result.push(operands[start..].trim());
+    result
+}
+
+/// Parses an integer literal like `-12`, `0x1f` or `0b101`.
+fn parse_int(s: &str) -> Option<i64> {
+    let s = s.trim();
+    let (negative, s) = match s.strip_prefix('-') {
+        Some(s) => (true, s.trim_start()),
+        None => (false, s.strip_prefix('+').unwrap_or(s).trim_start()),
+    };
+    let value = if let Some(hex) = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()?
+    } else if let Some(bin) = s.strip_prefix("0b").or_else(|| s.strip_prefix("0B")) {
+        u64::from_str_radix(bin, 2).ok()?
+    } else if s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.parse::<u64>().ok()?
+    } else {
+        return None;
+    };
+    Some(if negative { (value as i64).wrapping_neg() } else { value as i64 })
+}
+
+/// A reference to a label from an instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum LabelRef {
+    Named(String),
+    /// `1f`: the next definition of the numeric label.
+    Forward(u32),
+    /// `1b`: the previous definition of the numeric label.
+    Backward(u32),
+}
+
+impl LabelRef {
+    fn parse(s: &str) -> Option<LabelRef> {
+        let s = s.trim();
+        if let Some(number) = s.strip_suffix('f').and_then(|n| n.parse().ok()) {
+            return Some(LabelRef::Forward(number));
+        }
+        if let Some(number) = s.strip_suffix('b').and_then(|n| n.parse().ok()) {
+            return Some(LabelRef::Backward(number));
+        }
+        let is_label = s.starts_with(|c: char| c.is_ascii_alphabetic() || matches!(c, '_' | '.'))
+            && s.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$'));
+        is_label.then(|| LabelRef::Named(s.to_owned()))
+    }
+}
+
+/// How the distance to a label is encoded into an instruction.
+#[derive(Debug, Clone, Copy)]
+enum FixupKind {
+    /// x86 32bit displacement relative to the end of the 4 byte field.
+    X86Rel32,
+    /// AArch64 26bit word offset in bits 0..26, relative to the start of the instruction.
+    Arm64Branch26,
+    /// AArch64 19bit word offset in bits 5..24, relative to the start of the instruction.
+    Arm64Branch19,
+}
+
+#[derive(Debug)]
+struct Fixup {
+    /// Offset of the instruction or field to patch.
+    offset: usize,
+    kind: FixupKind,
+    target: LabelRef,
+    /// The number of definitions of the numeric label that preceded the reference.
+    numeric_defs_before: usize,
+}
+
+#[derive(Default)]
+struct CodeBuffer {
+    code: Vec<u8>,
+    named_labels: FxHashMap<String, usize>,
+    numeric_labels: FxHashMap<u32, Vec<usize>>,
+    fixups: Vec<Fixup>,
+}
+
+impl CodeBuffer {
+    fn define_label(&mut self, label: &str) {
+        let offset = self.code.len();
+        match label.parse::<u32>() {
+            Ok(number) => self.numeric_labels.entry(number).or_default().push(offset),
+            // Duplicate labels are reported by `finish`.
+            Err(_) => {
+                if self.named_labels.insert(label.to_owned(), offset).is_some() {
+                    self.named_labels.insert(label.to_owned(), usize::MAX);
+                }
+            }
+        }
+    }
+
+    fn put1(&mut self, byte: u8) {
+        self.code.push(byte);
+    }
+
+    fn put4(&mut self, word: u32) {
+        self.code.extend_from_slice(&word.to_le_bytes());
+    }
+
+    fn put_fixup(&mut self, kind: FixupKind, target: LabelRef) {
+        let numeric_defs_before = match target {
+            LabelRef::Forward(number) | LabelRef::Backward(number) => {
+                self.numeric_labels.get(&number).map_or(0, |defs| defs.len())
+            }
+            LabelRef::Named(_) => 0,
+        };
+        self.fixups.push(Fixup { offset: self.code.len(), kind, target, numeric_defs_before });
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, Unsupported> {
+        for fixup in std::mem::take(&mut self.fixups) {
+            let target = match &fixup.target {
+                LabelRef::Named(name) => self.named_labels.get(name).copied(),
+                LabelRef::Forward(number) => self
+                    .numeric_labels
+                    .get(number)
+                    .and_then(|defs| defs.get(fixup.numeric_defs_before))
+                    .copied(),
+                LabelRef::Backward(number) => self
+                    .numeric_labels
+                    .get(number)
+                    .and_then(|defs| defs.get(fixup.numeric_defs_before.checked_sub(1)?))
+                    .copied(),
+            };
+            let target = match target {
+                Some(usize::MAX) => {
+                    unsupported!("label {:?} is defined multiple times", fixup.target)
+                }
+                Some(target) => target as i64,
+                // Likely a symbol, which would need a relocation.
+                None => unsupported!("reference to undefined label {:?}", fixup.target),
+            };
+
+            let field = &mut self.code[fixup.offset..fixup.offset + 4];
+            let mut word = u32::from_le_bytes(field.try_into().unwrap());
+            match fixup.kind {
+                FixupKind::X86Rel32 => {
+                    let distance = target - (fixup.offset as i64 + 4);
+                    word = i32::try_from(distance).unwrap() as u32;
+                }
+                FixupKind::Arm64Branch26 => {
+                    let distance = (target - fixup.offset as i64) / 4;
+                    if !(-(1 << 25)..(1 << 25)).contains(&distance) {
+                        unsupported!("branch target out of range");
+                    }
+                    word |= (distance as u32) & 0x03ff_ffff;
+                }
+                FixupKind::Arm64Branch19 => {
+                    let distance = (target - fixup.offset as i64) / 4;
+                    if !(-(1 << 18)..(1 << 18)).contains(&distance) {
+                        unsupported!("branch target out of range");
+                    }
+                    word |= ((distance as u32) & 0x7_ffff) << 5;
+                }
+            }
+            field.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(self.code)
+    }
+}
@@ -0,0 +1,240 @@
+//! The expected encodings are the output of GNU as for the same instructions, except where noted.
+
+use rustc_target::asm::InlineAsmArch;
+
+use super::assemble;
+
+#[track_caller]
+fn check_x86_64(asm: &str, expected: &[u8]) {
+    match assemble(InlineAsmArch::X86_64, asm) {
+        Ok(code) => assert_eq!(code, expected, "encoding of `{asm}`"),
+        Err(err) => panic!("failed to assemble `{asm}`: {err:?}"),
+    }
+}
+
+#[track_caller]
+fn check_aarch64(asm: &str, expected: &[u32]) {
+    let expected = expected.iter().flat_map(|insn| insn.to_le_bytes()).collect::<Vec<_>>();
+    match assemble(InlineAsmArch::AArch64, asm) {
+        Ok(code) => assert_eq!(code, expected, "encoding of `{asm}`"),
+        Err(err) => panic!("failed to assemble `{asm}`: {err:?}"),
+    }
+}
+
+#[test]
+fn x86_64_mov() {
+    check_x86_64("mov rax, rbx", &[0x48, 0x89, 0xd8]);
+    check_x86_64("mov eax, 1", &[0xb8, 0x01, 0x00, 0x00, 0x00]);
+    check_x86_64("mov rax, 1", &[0x48, 0xc7, 0xc0, 0x01, 0x00, 0x00, 0x00]);
+    check_x86_64(
+        "mov rax, 0x123456789",
+        &[0x48, 0xb8, 0x89, 0x67, 0x45, 0x23, 0x01, 0x00, 0x00, 0x00],
+    );
+    check_x86_64("mov sil, 1", &[0x40, 0xb6, 0x01]);
+    check_x86_64("mov ah, bl", &[0x88, 0xdc]);
+    check_x86_64("mov r9w, 0x1234", &[0x66, 0x41, 0xb9, 0x34, 0x12]);
+    check_x86_64("movzx eax, byte ptr [rsi]", &[0x0f, 0xb6, 0x06]);
+    check_x86_64("movsx rax, word ptr [rdi]", &[0x48, 0x0f, 0xbf, 0x07]);
+    check_x86_64("movsxd rax, ecx", &[0x48, 0x63, 0xc1]);
+    check_x86_64("cmove rax, rcx", &[0x48, 0x0f, 0x44, 0xc1]);
+    check_x86_64("sete al", &[0x0f, 0x94, 0xc0]);
+    check_x86_64("bswap eax", &[0x0f, 0xc8]);
+    check_x86_64("bswap r9", &[0x49, 0x0f, 0xc9]);
+}
+
+#[test]
+fn x86_64_memory_operands() {
+    check_x86_64("mov qword ptr [rsp + 8], rax", &[0x48, 0x89, 0x44, 0x24, 0x08]);
+    check_x86_64("mov rax, [rbp - 8]", &[0x48, 0x8b, 0x45, 0xf8]);
+    check_x86_64("mov rax, [r13]", &[0x49, 0x8b, 0x45, 0x00]);
+    check_x86_64("mov rax, [r12]", &[0x49, 0x8b, 0x04, 0x24]);
+    check_x86_64("mov r8d, dword ptr [r12 + r13*4 + 0x10]", &[0x47, 0x8b, 0x44, 0xac, 0x10]);
+    check_x86_64("mov ecx, [rax + 0x1000]", &[0x8b, 0x88, 0x00, 0x10, 0x00, 0x00]);
+    check_x86_64("mov eax, [rcx*8]", &[0x8b, 0x04, 0xcd, 0x00, 0x00, 0x00, 0x00]);
+    check_x86_64("lea rax, [rip + 0x10]", &[0x48, 0x8d, 0x05, 0x10, 0x00, 0x00, 0x00]);
+    check_x86_64("mov byte ptr [rdi], 0xff", &[0xc6, 0x07, 0xff]);
+    check_x86_64("mov qword ptr [rdi], -1", &[0x48, 0xc7, 0x07, 0xff, 0xff, 0xff, 0xff]);
+    check_x86_64("movups xmm0, xmmword ptr [rdi]", &[0x0f, 0x10, 0x07]);
+    check_x86_64("movaps xmmword ptr [rsp], xmm8", &[0x44, 0x0f, 0x29, 0x04, 0x24]);
+}
+
+#[test]
+fn x86_64_arithmetic() {
+    check_x86_64("add rax, 8", &[0x48, 0x83, 0xc0, 0x08]);
+    // GNU as uses the shorter accumulator form for `add eax, 0x1000`, so use another register.
+    check_x86_64("add ecx, 0x1000", &[0x81, 0xc1, 0x00, 0x10, 0x00, 0x00]);
+    check_x86_64("sub rsp, 16", &[0x48, 0x83, 0xec, 0x10]);
+    check_x86_64("xor eax, eax", &[0x31, 0xc0]);
+    check_x86_64("or r10, [rbx]", &[0x4c, 0x0b, 0x13]);
+    check_x86_64("cmp byte ptr [rdi], 0", &[0x80, 0x3f, 0x00]);
+    check_x86_64("and dl, 0x7f", &[0x80, 0xe2, 0x7f]);
+    check_x86_64("test eax, eax", &[0x85, 0xc0]);
+    check_x86_64("test cl, 1", &[0xf6, 0xc1, 0x01]);
+    check_x86_64("shl rax, 3", &[0x48, 0xc1, 0xe0, 0x03]);
+    check_x86_64("shr rdx, 1", &[0x48, 0xd1, 0xea]);
+    check_x86_64("sar ecx, cl", &[0xd3, 0xf9]);
+    check_x86_64("imul rax, rcx", &[0x48, 0x0f, 0xaf, 0xc1]);
+    check_x86_64("imul eax, ecx, 10", &[0x6b, 0xc1, 0x0a]);
+    check_x86_64("mul rcx", &[0x48, 0xf7, 0xe1]);
+    check_x86_64("neg rax", &[0x48, 0xf7, 0xd8]);
+    check_x86_64("not ecx", &[0xf7, 0xd1]);
+    check_x86_64("inc dword ptr [rax]", &[0xff, 0x00]);
+    check_x86_64("dec r10", &[0x49, 0xff, 0xca]);
+    check_x86_64("xchg rcx, rdx", &[0x48, 0x87, 0xd1]);
+}
+
+#[test]
+fn x86_64_atomics() {
+    check_x86_64("lock xadd qword ptr [rdi], rax", &[0xf0, 0x48, 0x0f, 0xc1, 0x07]);
+    check_x86_64("lock cmpxchg [rdi], rcx", &[0xf0, 0x48, 0x0f, 0xb1, 0x0f]);
+    check_x86_64("lock add dword ptr [rsi], 1", &[0xf0, 0x83, 0x06, 0x01]);
+    check_x86_64("mfence", &[0x0f, 0xae, 0xf0]);
+}
+
+#[test]
+fn x86_64_control_flow() {
+    check_x86_64("push rbp", &[0x55]);
+    check_x86_64("pop r12", &[0x41, 0x5c]);
+    check_x86_64("push 1", &[0x6a, 0x01]);
+    check_x86_64("call rax", &[0xff, 0xd0]);
+    check_x86_64("jmp qword ptr [rax]", &[0xff, 0x20]);
+    check_x86_64("int3", &[0xcc]);
+    check_x86_64("ud2", &[0x0f, 0x0b]);
+    check_x86_64("cpuid; rdtsc; ret", &[0x0f, 0xa2, 0x0f, 0x31, 0xc3]);
+    // GNU as relaxes jumps to labels to the shorter rel8 forms, while the integrated assembler
+    // always uses rel32. These match the output of GNU as for `{disp32} jnz 2b`.
+    check_x86_64("2:\ndec rcx\njnz 2b", &[0x48, 0xff, 0xc9, 0x0f, 0x85, 0xf7, 0xff, 0xff, 0xff]);
+    check_x86_64("jmp 3f\nnop\n3:", &[0xe9, 0x01, 0x00, 0x00, 0x00, 0x90]);
+    check_x86_64("call foo\nfoo: ret", &[0xe8, 0x00, 0x00, 0x00, 0x00, 0xc3]);
+}
+
+#[test]
+fn x86_64_syntax() {
+    check_x86_64(
+        ".intel_syntax noprefix\n  mov rax, rbx // comment\n  /* block */ nop # comment\n\
+         .att_syntax",
+        &[0x48, 0x89, 0xd8, 0x90],
+    );
+    check_x86_64("MOV RAX, QWORD PTR [RSP+0x8]", &[0x48, 0x8b, 0x44, 0x24, 0x08]);
+}
+
+#[test]
+fn x86_64_unsupported() {
+    for asm in [
+        // References to symbols need relocations.
+        "mov rax, [rip + foo]",
+        "call foo",
+        ".att_syntax\nmovl $1, %eax",
+        ".byte 0x90",
+        "mov rax, 0x1ffffffff0 + 1",
+        "mov al, 0x100",
+        "mov ah, sil",
+        "mov eax, rbx",
+        "add [rax], 1",
+        "lock mov [rax], rbx",
+        "mov rax, [rsp*2]",
+        "vaddps ymm0, ymm1, ymm2",
+        "1:\n1:\njmp foo\nfoo:\nfoo:",
+    ] {
+        assert!(assemble(InlineAsmArch::X86_64, asm).is_err(), "`{asm}` should be rejected");
+    }
+}
+
+#[test]
+fn aarch64_data_processing() {
+    check_aarch64("mov x0, x1", &[0xaa0103e0]);
+    check_aarch64("mov x29, sp", &[0x910003fd]);
+    check_aarch64("mov w0, #42", &[0x52800540]);
+    check_aarch64("mov x0, #0x10000", &[0xd2a00020]);
+    check_aarch64("mov x0, #-1", &[0x92800000]);
+    check_aarch64("mov w1, #0xffff0000", &[0x52bfffe1]);
+    check_aarch64("mov x2, #0x5555555555555555", &[0xb200f3e2]);
+    check_aarch64("movk x0, #0x1234, lsl #16", &[0xf2a24680]);
+    check_aarch64("add x0, x1, #16", &[0x91004020]);
+    check_aarch64("add x0, x1, #1, lsl #12", &[0x91400420]);
+    check_aarch64("sub sp, sp, #32", &[0xd10083ff]);
+    check_aarch64("add x0, x1, x2", &[0x8b020020]);
+    check_aarch64("add x0, x1, x2, lsl #3", &[0x8b020c20]);
+    check_aarch64("add x0, sp, w1, uxtw #2", &[0x8b214be0]);
+    check_aarch64("subs w0, w1, w2", &[0x6b020020]);
+    check_aarch64("cmp x0, #1", &[0xf100041f]);
+    check_aarch64("neg x0, x1", &[0xcb0103e0]);
+    check_aarch64("and x0, x1, #0xff", &[0x92401c20]);
+    check_aarch64("orr w0, w1, w2, lsr #4", &[0x2a421020]);
+    check_aarch64("tst w0, #1", &[0x7200001f]);
+    check_aarch64("mvn x0, x1", &[0xaa2103e0]);
+    check_aarch64("lsl x0, x1, #3", &[0xd37df020]);
+    check_aarch64("lsr w0, w1, #4", &[0x53047c20]);
+    check_aarch64("asr x0, x1, x2", &[0x9ac22820]);
+    check_aarch64("uxtb w0, w1", &[0x53001c20]);
+    check_aarch64("sxtw x0, w1", &[0x93407c20]);
+    check_aarch64("mul x0, x1, x2", &[0x9b027c20]);
+    check_aarch64("madd x0, x1, x2, x3", &[0x9b020c20]);
+    check_aarch64("udiv w0, w1, w2", &[0x1ac20820]);
+    check_aarch64("umulh x0, x1, x2", &[0x9bc27c20]);
+    check_aarch64("csel x0, x1, x2, eq", &[0x9a820020]);
+    check_aarch64("cset w0, ne", &[0x1a9f07e0]);
+}
+
+#[test]
+fn aarch64_loads_and_stores() {
+    check_aarch64("ldr x0, [x1]", &[0xf9400020]);
+    check_aarch64("ldr x0, [x1, #8]", &[0xf9400420]);
+    check_aarch64("ldr w0, [sp, #12]", &[0xb9400fe0]);
+    check_aarch64("ldur x0, [x1, #-8]", &[0xf85f8020]);
+    check_aarch64("str x0, [sp, #-16]!", &[0xf81f0fe0]);
+    check_aarch64("ldr x0, [sp], #16", &[0xf84107e0]);
+    check_aarch64("ldrb w0, [x1, x2]", &[0x38626820]);
+    check_aarch64("ldr x0, [x1, x2, lsl #3]", &[0xf8627820]);
+    check_aarch64("ldrsw x0, [x1]", &[0xb9800020]);
+    check_aarch64("strh w0, [x1, #2]", &[0x79000420]);
+    check_aarch64("ldr q0, [x0]", &[0x3dc00000]);
+    check_aarch64("str d1, [sp, #8]", &[0xfd0007e1]);
+    check_aarch64("stp x29, x30, [sp, #-16]!", &[0xa9bf7bfd]);
+    check_aarch64("ldp x29, x30, [sp], #16", &[0xa8c17bfd]);
+    check_aarch64("ldp w0, w1, [x2, #8]", &[0x29410440]);
+    check_aarch64("ldxr x0, [x1]", &[0xc85f7c20]);
+    check_aarch64("ldaxr w0, [x1]", &[0x885ffc20]);
+    check_aarch64("stxr w2, x0, [x1]", &[0xc8027c20]);
+    check_aarch64("stlxr w2, w0, [x1]", &[0x8802fc20]);
+    check_aarch64("ldar w0, [x1]", &[0x88dffc20]);
+    check_aarch64("stlr x0, [x1]", &[0xc89ffc20]);
+}
+
+#[test]
+fn aarch64_control_flow() {
+    check_aarch64("ret", &[0xd65f03c0]);
+    check_aarch64("nop", &[0xd503201f]);
+    check_aarch64("blr x8", &[0xd63f0100]);
+    check_aarch64("br x16", &[0xd61f0200]);
+    check_aarch64("brk #0x1", &[0xd4200020]);
+    check_aarch64("svc #0", &[0xd4000001]);
+    check_aarch64("dmb ish", &[0xd5033bbf]);
+    check_aarch64("dsb sy", &[0xd5033f9f]);
+    check_aarch64("isb", &[0xd5033fdf]);
+    check_aarch64("1:\nsubs x0, x0, #1\nb.ne 1b", &[0xf1000400, 0x54ffffe1]);
+    check_aarch64("cbz x0, 2f\nnop\n2:", &[0xb4000040, 0xd503201f]);
+    check_aarch64("bl 1f\n1:", &[0x94000001]);
+    check_aarch64("b loop\nnop\nloop: ret", &[0x14000002, 0xd503201f, 0xd65f03c0]);
+}
+
+#[test]
+fn aarch64_unsupported() {
+    for asm in [
+        // References to symbols need relocations.
+        "adrp x0, foo",
+        "bl foo",
+        "ldr x0, =0x1234",
+        ".word 0",
+        "mov x0, #0x1234567",
+        "add x0, x1, #0x1001",
+        "and x0, x1, #0",
+        "mov x0, w1",
+        "ldr x0, [x1, #256]!",
+        "ldp x0, x1, [x2, #4]",
+        "add v0.4s, v1.4s, v2.4s",
+        "x31",
+    ] {
+        assert!(assemble(InlineAsmArch::AArch64, asm).is_err(), "`{asm}` should be rejected");
+    }
+}
@@ -0,0 +1,773 @@
+//! Encoder for the Intel syntax subset of x86_64 assembly supported by the integrated assembler.
+
+use super::{parse_int, unsupported, CodeBuffer, FixupKind, LabelRef, Unsupported};
+
+#[derive(Debug, Clone, Copy)]
+struct Reg {
+    num: u8,
+    /// Size in bytes. 16 for xmm registers.
+    size: u8,
+    /// `spl`, `bpl`, `sil` and `dil` can only be encoded with a REX prefix.
+    needs_rex: bool,
+    /// `ah`, `ch`, `dh` and `bh` can't be encoded when a REX prefix is present.
+    high_byte: bool,
+}
+
+#[derive(Debug)]
+struct Mem {
+    base: Option<u8>,
+    /// Register and scale.
+    index: Option<(u8, u8)>,
+    disp: i32,
+    rip_relative: bool,
+    /// The size given by a `byte ptr`, `word ptr`, ... prefix.
+    size: Option<u8>,
+}
+
+#[derive(Debug)]
+enum Operand {
+    Reg(Reg),
+    Mem(Mem),
+    Imm(i64),
+    Label(LabelRef),
+}
+
+/// The ModRM.reg field is either a register operand or an opcode extension.
+#[derive(Clone, Copy)]
+enum RegField {
+    Reg(Reg),
+    Ext(u8),
+}
+
+/// The operand size attributes of an encoding.
+#[derive(Clone, Copy)]
+struct OpSize {
+    prefix66: bool,
+    rex_w: bool,
+}
+
+impl OpSize {
+    const DEFAULT: OpSize = OpSize { prefix66: false, rex_w: false };
+
+    fn of(size: u8) -> OpSize {
+        OpSize { prefix66: size == 2, rex_w: size == 8 }
+    }
+}
+
+const GPR64: [&str; 16] = [
+    "rax", "rcx", "rdx", "rbx", "rsp", "rbp", "rsi", "rdi", "r8", "r9", "r10", "r11", "r12",
+    "r13", "r14", "r15",
+];
+const GPR32: [&str; 8] = ["eax", "ecx", "edx", "ebx", "esp", "ebp", "esi", "edi"];
+const GPR16: [&str; 8] = ["ax", "cx", "dx", "bx", "sp", "bp", "si", "di"];
+const GPR8: [&str; 8] = ["al", "cl", "dl", "bl", "spl", "bpl", "sil", "dil"];
+const GPR8_HIGH: [&str; 4] = ["ah", "ch", "dh", "bh"];
+
+fn parse_reg(name: &str) -> Option<Reg> {
+    let reg = |num: usize, size| Reg { num: num as u8, size, needs_rex: false, high_byte: false };
+    if let Some(num) = GPR64.iter().position(|&r| r == name) {
+        return Some(reg(num, 8));
+    }
+    if let Some(num) = GPR32.iter().position(|&r| r == name) {
+        return Some(reg(num, 4));
+    }
+    if let Some(num) = GPR16.iter().position(|&r| r == name) {
+        return Some(reg(num, 2));
+    }
+    if let Some(num) = GPR8.iter().position(|&r| r == name) {
+        return Some(Reg { needs_rex: num >= 4, ..reg(num, 1) });
+    }
+    if let Some(num) = GPR8_HIGH.iter().position(|&r| r == name) {
+        return Some(Reg { high_byte: true, ..reg(num + 4, 1) });
+    }
+    if let Some(num) = name.strip_prefix("xmm").and_then(|n| n.parse::<usize>().ok()) {
+        return (num < 16).then(|| reg(num, 16));
+    }
+    let rest = name.strip_prefix('r')?;
+    let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+    let num = rest[..digits].parse::<usize>().ok().filter(|num| (8..16).contains(num))?;
+    let size = match &rest[digits..] {
+        "d" => 4,
+        "w" => 2,
+        "b" => 1,
+        _ => return None,
+    };
+    Some(reg(num, size))
+}
+
+fn parse_operand(operand: &str) -> Result<Operand, Unsupported> {
+    let lower = operand.to_ascii_lowercase();
+    if lower.contains('[') {
+        return parse_mem(&lower).map(Operand::Mem);
+    }
+    if let Some(reg) = parse_reg(&lower) {
+        return Ok(Operand::Reg(reg));
+    }
+    if let Some(imm) = parse_int(&lower) {
+        return Ok(Operand::Imm(imm));
+    }
+    if let Some(label) = LabelRef::parse(operand) {
+        return Ok(Operand::Label(label));
+    }
+    unsupported!("operand `{operand}`")
+}
+
+fn parse_mem(operand: &str) -> Result<Mem, Unsupported> {
+    let mut size = None;
+    let mut rest = operand.trim();
+    if let Some((prefix, after)) = rest.split_once("ptr") {
+        size = Some(match prefix.trim() {
+            "byte" => 1,
+            "word" => 2,
+            "dword" => 4,
+            "qword" => 8,
+            "xmmword" => 16,
+            _ => unsupported!("memory operand `{operand}`"),
+        });
+        rest = after.trim();
+    }
+    let Some(inner) = rest.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) else {
+        unsupported!("memory operand `{operand}`");
+    };
+
+    let mut terms = vec![];
+    let mut negative = false;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        if c == '+' || c == '-' {
+            terms.push((negative, inner[start..i].trim()));
+            negative = c == '-';
+            start = i + 1;
+        }
+    }
+    terms.push((negative, inner[start..].trim()));
+
+    let mut mem = Mem { base: None, index: None, disp: 0, rip_relative: false, size };
+    let mut disp = 0i64;
+    for (i, (negative, term)) in terms.into_iter().enumerate() {
+        // A leading sign results in an empty first term.
+        if term.is_empty() && i == 0 {
+            continue;
+        }
+        let gpr64 = |name: &str| parse_reg(name.trim()).filter(|reg| reg.size == 8);
+        if let Some((a, b)) = term.split_once('*') {
+            let (reg, scale) = match (gpr64(a), gpr64(b)) {
+                (Some(reg), None) => (reg, parse_int(b)),
+                (None, Some(reg)) => (reg, parse_int(a)),
+                _ => unsupported!("memory operand `{operand}`"),
+            };
+            let scale = match scale {
+                Some(scale @ (1 | 2 | 4 | 8)) => scale as u8,
+                _ => unsupported!("memory operand `{operand}`"),
+            };
+            if negative || mem.index.is_some() {
+                unsupported!("memory operand `{operand}`");
+            }
+            mem.index = Some((reg.num, scale));
+        } else if term == "rip" {
+            if negative || mem.base.is_some() || mem.rip_relative {
+                unsupported!("memory operand `{operand}`");
+            }
+            mem.rip_relative = true;
+        } else if let Some(reg) = gpr64(term) {
+            if negative {
+                unsupported!("memory operand `{operand}`");
+            }
+            if mem.base.is_none() {
+                mem.base = Some(reg.num);
+            } else if mem.index.is_none() {
+                mem.index = Some((reg.num, 1));
+            } else {
+                unsupported!("memory operand `{operand}`");
+            }
+        } else if let Some(value) = parse_int(term) {
+            disp = disp.wrapping_add(if negative { value.wrapping_neg() } else { value });
+        } else {
+            // Most likely a symbol, which would need a relocation.
+            unsupported!("memory operand `{operand}`");
+        }
+    }
+
+    mem.disp = match i32::try_from(disp) {
+        Ok(disp) => disp,
+        Err(_) => unsupported!("displacement out of range in `{operand}`"),
+    };
+    if mem.rip_relative && (mem.base.is_some() || mem.index.is_some()) {
+        unsupported!("memory operand `{operand}`");
+    }
+    if let Some((4, scale)) = mem.index {
+        // rsp can't be used as index register, but `[rax+rsp]` can be encoded as `[rsp+rax]`.
+        match mem.base {
+            Some(base) if scale == 1 && base != 4 => {
+                mem.index = Some((base, 1));
+                mem.base = Some(4);
+            }
+            _ => unsupported!("rsp used as index register in `{operand}`"),
+        }
+    }
+    Ok(mem)
+}
+
+/// Determines the operand size of an instruction operating on general purpose registers.
+fn op_size(operands: &[&Operand]) -> Result<u8, Unsupported> {
+    let mut size = None;
+    for operand in operands {
+        let operand_size = match operand {
+            Operand::Reg(reg) => Some(reg.size),
+            Operand::Mem(mem) => mem.size,
+            Operand::Imm(_) | Operand::Label(_) => None,
+        };
+        match (size, operand_size) {
+            (_, Some(16)) => unsupported!("vector operand for general purpose instruction"),
+            (Some(size), Some(operand_size)) if size != operand_size => {
+                unsupported!("operand size mismatch")
+            }
+            (_, Some(operand_size)) => size = Some(operand_size),
+            (_, None) => {}
+        }
+    }
+    match size {
+        Some(size) => Ok(size),
+        None => unsupported!("ambiguous operand size"),
+    }
+}
+
+fn check_imm(imm: i64, size: u8) -> Result<(), Unsupported> {
+    let in_range = match size {
+        1 => (-0x80..=0xff).contains(&imm),
+        2 => (-0x8000..=0xffff).contains(&imm),
+        4 => (i64::from(i32::MIN)..=i64::from(u32::MAX)).contains(&imm),
+        // 64bit operations sign extend their 32bit immediate.
+        _ => i32::try_from(imm).is_ok(),
+    };
+    if !in_range {
+        unsupported!("immediate {imm} out of range");
+    }
+    Ok(())
+}
+
+/// Reinterprets `imm` as signed value of the given size.
+fn sign_extend_imm(imm: i64, size: u8) -> i64 {
+    match size {
+        1 => i64::from(imm as i8),
+        2 => i64::from(imm as i16),
+        4 => i64::from(imm as i32),
+        _ => imm,
+    }
+}
+
+fn put_imm(buf: &mut CodeBuffer, imm: i64, len: u8) {
+    match len {
+        1 => buf.put1(imm as u8),
+        2 => buf.code.extend_from_slice(&(imm as u16).to_le_bytes()),
+        _ => buf.put4(imm as u32),
+    }
+}
+
+/// Emits an instruction with a ModRM byte, including all required prefixes.
+fn emit_rm(
+    buf: &mut CodeBuffer,
+    op_size: OpSize,
+    opcode: &[u8],
+    reg: RegField,
+    rm: &Operand,
+) -> Result<(), Unsupported> {
+    let (reg_num, mut needs_rex, mut high_byte) = match reg {
+        RegField::Reg(reg) => (reg.num, reg.needs_rex, reg.high_byte),
+        RegField::Ext(ext) => (ext, false, false),
+    };
+    let mut rex = 0x40;
+    if op_size.rex_w {
+        rex |= 0x08;
+    }
+    if reg_num & 8 != 0 {
+        rex |= 0x04;
+    }
+    match rm {
+        Operand::Reg(rm) => {
+            if rm.num & 8 != 0 {
+                rex |= 0x01;
+            }
+            needs_rex |= rm.needs_rex;
+            high_byte |= rm.high_byte;
+        }
+        Operand::Mem(mem) => {
+            if mem.base.is_some_and(|base| base & 8 != 0) {
+                rex |= 0x01;
+            }
+            if mem.index.is_some_and(|(index, _)| index & 8 != 0) {
+                rex |= 0x02;
+            }
+        }
+        Operand::Imm(_) | Operand::Label(_) => unsupported!("invalid operand {rm:?}"),
+    }
+    let needs_rex = needs_rex || rex != 0x40;
+    if needs_rex && high_byte {
+        unsupported!("high byte register in instruction requiring a REX prefix");
+    }
+
+    if op_size.prefix66 {
+        buf.put1(0x66);
+    }
+    if needs_rex {
+        buf.put1(rex);
+    }
+    buf.code.extend_from_slice(opcode);
+
+    let reg = (reg_num & 7) << 3;
+    match rm {
+        Operand::Reg(rm) => buf.put1(0xc0 | reg | (rm.num & 7)),
+        Operand::Mem(mem) if mem.rip_relative => {
+            buf.put1(reg | 0b101);
+            buf.put4(mem.disp as u32);
+        }
+        Operand::Mem(Mem { base: None, index, disp, .. }) => {
+            // SIB byte without base register and a 32bit displacement.
+            buf.put1(reg | 0b100);
+            let (index, scale) = index.unwrap_or((4, 1));
+            buf.put1(scale_bits(scale) | ((index & 7) << 3) | 0b101);
+            buf.put4(*disp as u32);
+        }
+        Operand::Mem(Mem { base: Some(base), index, disp, .. }) => {
+            // rbp and r13 as base always need a displacement.
+            let (mode, disp_len) = if *disp == 0 && base & 7 != 0b101 {
+                (0b00, 0)
+            } else if i8::try_from(*disp).is_ok() {
+                (0b01, 1)
+            } else {
+                (0b10, 4)
+            };
+            // rsp and r12 as base always need a SIB byte.
+            if index.is_none() && base & 7 != 0b100 {
+                buf.put1((mode << 6) | reg | (base & 7));
+            } else {
+                let (index, scale) = index.unwrap_or((4, 1));
+                buf.put1((mode << 6) | reg | 0b100);
+                buf.put1(scale_bits(scale) | ((index & 7) << 3) | (base & 7));
+            }
+            put_imm(buf, i64::from(*disp), disp_len);
+        }
+        Operand::Imm(_) | Operand::Label(_) => unreachable!(),
+    }
+    Ok(())
+}
+
+fn scale_bits(scale: u8) -> u8 {
+    (scale.trailing_zeros() as u8) << 6
+}
+
+/// Emits an instruction which encodes a register in the low bits of the last opcode byte.
+fn emit_o(
+    buf: &mut CodeBuffer,
+    op_size: OpSize,
+    opcode: &[u8],
+    reg: Reg,
+) -> Result<(), Unsupported> {
+    let mut rex = 0x40;
+    if op_size.rex_w {
+        rex |= 0x08;
+    }
+    if reg.num & 8 != 0 {
+        rex |= 0x01;
+    }
+    let needs_rex = reg.needs_rex || rex != 0x40;
+    if needs_rex && reg.high_byte {
+        unsupported!("high byte register in instruction requiring a REX prefix");
+    }
+    if op_size.prefix66 {
+        buf.put1(0x66);
+    }
+    if needs_rex {
+        buf.put1(rex);
+    }
+    let (last, rest) = opcode.split_last().unwrap();
+    buf.code.extend_from_slice(rest);
+    buf.put1(last + (reg.num & 7));
+    Ok(())
+}
+
+/// Emits one of the classic two operand forms `op r/m8, r8`, `op r/m, r`, `op r8, r/m8` and
+/// `op r, r/m`, which use `opcode`, `opcode + 1`, `opcode + 2` and `opcode + 3` respectively.
+fn emit_binary(
+    buf: &mut CodeBuffer,
+    opcode: u8,
+    dst: &Operand,
+    src: &Operand,
+) -> Result<(), Unsupported> {
+    let size = op_size(&[dst, src])?;
+    let wide = u8::from(size != 1);
+    match (dst, src) {
+        (Operand::Reg(_) | Operand::Mem(_), Operand::Reg(src)) => {
+            emit_rm(buf, OpSize::of(size), &[opcode + wide], RegField::Reg(*src), dst)
+        }
+        (Operand::Reg(dst), Operand::Mem(_)) => {
+            emit_rm(buf, OpSize::of(size), &[opcode + 2 + wide], RegField::Reg(*dst), src)
+        }
+        _ => unsupported!("invalid operands"),
+    }
+}
+
+fn condition_code(cond: &str) -> Option<u8> {
+    Some(match cond {
+        "o" => 0x0,
+        "no" => 0x1,
+        "b" | "c" | "nae" => 0x2,
+        "ae" | "nb" | "nc" => 0x3,
+        "e" | "z" => 0x4,
+        "ne" | "nz" => 0x5,
+        "be" | "na" => 0x6,
+        "a" | "nbe" => 0x7,
+        "s" => 0x8,
+        "ns" => 0x9,
+        "p" | "pe" => 0xa,
+        "np" | "po" => 0xb,
+        "l" | "nge" => 0xc,
+        "ge" | "nl" => 0xd,
+        "le" | "ng" => 0xe,
+        "g" | "nle" => 0xf,
+        _ => return None,
+    })
+}
+
+const ALU_OPS: [&str; 8] = ["add", "or", "adc", "sbb", "and", "sub", "xor", "cmp"];
+
+const SHIFT_OPS: [(&str, u8); 8] = [
+    ("rol", 0),
+    ("ror", 1),
+    ("rcl", 2),
+    ("rcr", 3),
+    ("shl", 4),
+    ("sal", 4),
+    ("shr", 5),
+    ("sar", 7),
+];
+
+const UNARY_OPS: [(&str, u8); 6] =
+    [("not", 2), ("neg", 3), ("mul", 4), ("imul", 5), ("div", 6), ("idiv", 7)];
+
+const NO_OPERAND_OPS: [(&str, &[u8]); 21] = [
+    ("ret", &[0xc3]),
+    ("nop", &[0x90]),
+    ("ud2", &[0x0f, 0x0b]),
+    ("int3", &[0xcc]),
+    ("hlt", &[0xf4]),
+    ("syscall", &[0x0f, 0x05]),
+    ("cpuid", &[0x0f, 0xa2]),
+    ("rdtsc", &[0x0f, 0x31]),
+    ("rdtscp", &[0x0f, 0x01, 0xf9]),
+    ("pause", &[0xf3, 0x90]),
+    ("mfence", &[0x0f, 0xae, 0xf0]),
+    ("lfence", &[0x0f, 0xae, 0xe8]),
+    ("sfence", &[0x0f, 0xae, 0xf8]),
+    ("cld", &[0xfc]),
+    ("std", &[0xfd]),
+    ("clc", &[0xf8]),
+    ("stc", &[0xf9]),
+    ("cdq", &[0x99]),
+    ("cqo", &[0x48, 0x99]),
+    ("cdqe", &[0x48, 0x98]),
+    ("leave", &[0xc9]),
+];
+
+/// Instructions which may be combined with the `lock` prefix.
+const LOCKABLE_OPS: [&str; 14] = [
+    "add", "or", "adc", "sbb", "and", "sub", "xor", "not", "neg", "inc", "dec", "xchg", "xadd",
+    "cmpxchg",
+];
+
+pub(super) fn assemble_instruction(
+    buf: &mut CodeBuffer,
+    mnemonic: &str,
+    operands: &[&str],
+) -> Result<(), Unsupported> {
+    if mnemonic == "lock" {
+        // The operands of the prefixed instruction were split together with its mnemonic.
+        let Some((first, rest)) = operands.split_first() else {
+            unsupported!("`lock` without instruction");
+        };
+        let (mnemonic, first) = first.split_once(char::is_whitespace).unwrap_or((first, ""));
+        let mnemonic = mnemonic.to_ascii_lowercase();
+        let operands =
+            std::iter::once(first.trim()).chain(rest.iter().copied()).collect::<Vec<_>>();
+        if !LOCKABLE_OPS.contains(&&*mnemonic) || !operands[0].contains('[') {
+            unsupported!("`lock {mnemonic}`");
+        }
+        buf.put1(0xf0);
+        return assemble_instruction(buf, &mnemonic, &operands);
+    }
+
+    let ops = operands.iter().map(|operand| parse_operand(operand)).collect::<Result<Vec<_>, _>>()?;
+
+    if let Some((_, opcode)) = NO_OPERAND_OPS.iter().find(|(name, _)| *name == mnemonic) {
+        if !ops.is_empty() {
+            unsupported!("operands for `{mnemonic}`");
+        }
+        buf.code.extend_from_slice(opcode);
+        return Ok(());
+    }
+
+    if let Some(ext) = ALU_OPS.iter().position(|&op| op == mnemonic) {
+        let ext = ext as u8;
+        return match ops.as_slice() {
+            [dst @ (Operand::Reg(_) | Operand::Mem(_)), Operand::Imm(imm)] => {
+                let size = op_size(&[dst])?;
+                check_imm(*imm, size)?;
+                let imm = sign_extend_imm(*imm, size);
+                let (opcode, imm_len) = if size == 1 {
+                    (0x80, 1)
+                } else if i8::try_from(imm).is_ok() {
+                    (0x83, 1)
+                } else {
+                    (0x81, size.min(4))
+                };
+                emit_rm(buf, OpSize::of(size), &[opcode], RegField::Ext(ext), dst)?;
+                put_imm(buf, imm, imm_len);
+                Ok(())
+            }
+            [dst, src] => emit_binary(buf, ext << 3, dst, src),
+            _ => unsupported!("operands for `{mnemonic}`"),
+        };
+    }
+
+    if let Some(&(_, ext)) = SHIFT_OPS.iter().find(|(name, _)| *name == mnemonic) {
+        let (dst, count) = match ops.as_slice() {
+            [dst @ (Operand::Reg(_) | Operand::Mem(_)), count] => (dst, Some(count)),
+            [dst @ (Operand::Reg(_) | Operand::Mem(_))] => (dst, None),
+            _ => unsupported!("operands for `{mnemonic}`"),
+        };
+        let size = op_size(&[dst])?;
+        let wide = u8::from(size != 1);
+        return match count {
+            None | Some(Operand::Imm(1)) => {
+                emit_rm(buf, OpSize::of(size), &[0xd0 + wide], RegField::Ext(ext), dst)
+            }
+            Some(Operand::Imm(count @ 0..=0xff)) => {
+                emit_rm(buf, OpSize::of(size), &[0xc0 + wide], RegField::Ext(ext), dst)?;
+                buf.put1(*count as u8);
+                Ok(())
+            }
+            Some(Operand::Reg(Reg { num: 1, size: 1, high_byte: false, .. })) => {
+                emit_rm(buf, OpSize::of(size), &[0xd2 + wide], RegField::Ext(ext), dst)
+            }
+            _ => unsupported!("shift count for `{mnemonic}`"),
+        };
+    }
+
+    if let Some(&(_, ext)) = UNARY_OPS.iter().find(|(name, _)| *name == mnemonic) {
+        match ops.as_slice() {
+            [operand @ (Operand::Reg(_) | Operand::Mem(_))] => {
+                let size = op_size(&[operand])?;
+                let wide = u8::from(size != 1);
+                return emit_rm(buf, OpSize::of(size), &[0xf6 + wide], RegField::Ext(ext), operand);
+            }
+            // The two and three operand forms of `imul` are handled below.
+            _ if mnemonic == "imul" => {}
+            _ => unsupported!("operands for `{mnemonic}`"),
+        }
+    }
+
+    if let Some(cc) = mnemonic.strip_prefix('j').and_then(condition_code) {
+        let [Operand::Label(target)] = ops.as_slice() else {
+            unsupported!("operands for `{mnemonic}`");
+        };
+        buf.code.extend_from_slice(&[0x0f, 0x80 + cc]);
+        buf.put_fixup(FixupKind::X86Rel32, target.clone());
+        buf.put4(0);
+        return Ok(());
+    }
+
+    if let Some(cc) = mnemonic.strip_prefix("set").and_then(condition_code) {
+        let [dst @ (Operand::Reg(_) | Operand::Mem(_))] = ops.as_slice() else {
+            unsupported!("operands for `{mnemonic}`");
+        };
+        if op_size(&[dst]).ok() != Some(1) {
+            unsupported!("operands for `{mnemonic}`");
+        }
+        return emit_rm(buf, OpSize::DEFAULT, &[0x0f, 0x90 + cc], RegField::Ext(0), dst);
+    }
+
+    if let Some(cc) = mnemonic.strip_prefix("cmov").and_then(condition_code) {
+        let [Operand::Reg(dst), src @ (Operand::Reg(_) | Operand::Mem(_))] = ops.as_slice() else {
+            unsupported!("operands for `{mnemonic}`");
+        };
+        let size = op_size(&[&ops[0], src])?;
+        if size == 1 {
+            unsupported!("operands for `{mnemonic}`");
+        }
+        return emit_rm(buf, OpSize::of(size), &[0x0f, 0x40 + cc], RegField::Reg(*dst), src);
+    }
+
+    match (mnemonic, ops.as_slice()) {
+        ("mov", [Operand::Reg(dst), Operand::Imm(imm)]) if dst.size <= 8 => {
+            // Any 64bit immediate can be moved into a 64bit register.
+            if dst.size != 8 {
+                check_imm(*imm, dst.size)?;
+            }
+            match dst.size {
+                1 => {
+                    emit_o(buf, OpSize::DEFAULT, &[0xb0], *dst)?;
+                    put_imm(buf, *imm, 1);
+                }
+                8 if i32::try_from(*imm).is_ok() => {
+                    emit_rm(buf, OpSize::of(8), &[0xc7], RegField::Ext(0), &ops[0])?;
+                    put_imm(buf, *imm, 4);
+                }
+                // Writing a 32bit register zero extends the value to 64bit.
+                8 if u32::try_from(*imm).is_ok() => {
+                    emit_o(buf, OpSize::DEFAULT, &[0xb8], *dst)?;
+                    put_imm(buf, *imm, 4);
+                }
+                8 => {
+                    emit_o(buf, OpSize::of(8), &[0xb8], *dst)?;
+                    buf.code.extend_from_slice(&imm.to_le_bytes());
+                }
+                size => {
+                    emit_o(buf, OpSize::of(size), &[0xb8], *dst)?;
+                    put_imm(buf, *imm, size);
+                }
+            }
+            Ok(())
+        }
+        ("mov", [dst @ Operand::Mem(_), Operand::Imm(imm)]) => {
+            let size = op_size(&[dst])?;
+            check_imm(*imm, size)?;
+            let wide = u8::from(size != 1);
+            emit_rm(buf, OpSize::of(size), &[0xc6 + wide], RegField::Ext(0), dst)?;
+            put_imm(buf, *imm, size.min(4));
+            Ok(())
+        }
+        ("mov", [dst, src]) => emit_binary(buf, 0x88, dst, src),
+        ("test", [dst @ (Operand::Reg(_) | Operand::Mem(_)), Operand::Imm(imm)]) => {
+            let size = op_size(&[dst])?;
+            check_imm(*imm, size)?;
+            let wide = u8::from(size != 1);
+            emit_rm(buf, OpSize::of(size), &[0xf6 + wide], RegField::Ext(0), dst)?;
+            put_imm(buf, *imm, size.min(4));
+            Ok(())
+        }
+        ("test" | "xchg", [dst, src]) => {
+            let opcode = if mnemonic == "test" { 0x84 } else { 0x86 };
+            // Both operand orders use the same encoding.
+            match (dst, src) {
+                (Operand::Reg(_), Operand::Mem(_)) => emit_binary(buf, opcode, src, dst),
+                _ => emit_binary(buf, opcode, dst, src),
+            }
+        }
+        ("xadd" | "cmpxchg", [dst @ (Operand::Reg(_) | Operand::Mem(_)), Operand::Reg(src)]) => {
+            let size = op_size(&[dst, &ops[1]])?;
+            let opcode = if mnemonic == "xadd" { 0xc0 } else { 0xb0 } + u8::from(size != 1);
+            emit_rm(buf, OpSize::of(size), &[0x0f, opcode], RegField::Reg(*src), dst)
+        }
+        ("lea", [Operand::Reg(dst), src @ Operand::Mem(_)]) if matches!(dst.size, 2 | 4 | 8) => {
+            emit_rm(buf, OpSize::of(dst.size), &[0x8d], RegField::Reg(*dst), src)
+        }
+        ("movzx" | "movsx", [Operand::Reg(dst), src @ (Operand::Reg(_) | Operand::Mem(_))]) => {
+            let src_size = op_size(&[src])?;
+            if !matches!(dst.size, 2 | 4 | 8) || src_size >= dst.size || src_size > 2 {
+                unsupported!("operands for `{mnemonic}`");
+            }
+            let opcode = if mnemonic == "movzx" { 0xb6 } else { 0xbe } + u8::from(src_size == 2);
+            emit_rm(buf, OpSize::of(dst.size), &[0x0f, opcode], RegField::Reg(*dst), src)
+        }
+        ("movsxd", [Operand::Reg(dst), src @ (Operand::Reg(_) | Operand::Mem(_))])
+            if dst.size == 8 && op_size(&[src]).ok() == Some(4) =>
+        {
+            emit_rm(buf, OpSize::of(8), &[0x63], RegField::Reg(*dst), src)
+        }
+        ("imul", [Operand::Reg(dst), src @ (Operand::Reg(_) | Operand::Mem(_))]) => {
+            let size = op_size(&[&ops[0], src])?;
+            if size == 1 {
+                unsupported!("operands for `imul`");
+            }
+            emit_rm(buf, OpSize::of(size), &[0x0f, 0xaf], RegField::Reg(*dst), src)
+        }
+        (
+            "imul",
+            [Operand::Reg(dst), src @ (Operand::Reg(_) | Operand::Mem(_)), Operand::Imm(imm)],
+        ) => {
+            let size = op_size(&[&ops[0], src])?;
+            if size == 1 {
+                unsupported!("operands for `imul`");
+            }
+            check_imm(*imm, size)?;
+            let imm = sign_extend_imm(*imm, size);
+            if i8::try_from(imm).is_ok() {
+                emit_rm(buf, OpSize::of(size), &[0x6b], RegField::Reg(*dst), src)?;
+                put_imm(buf, imm, 1);
+            } else {
+                emit_rm(buf, OpSize::of(size), &[0x69], RegField::Reg(*dst), src)?;
+                put_imm(buf, imm, size.min(4));
+            }
+            Ok(())
+        }
+        ("inc" | "dec", [operand @ (Operand::Reg(_) | Operand::Mem(_))]) => {
+            let size = op_size(&[operand])?;
+            let ext = if mnemonic == "inc" { 0 } else { 1 };
+            let wide = u8::from(size != 1);
+            emit_rm(buf, OpSize::of(size), &[0xfe + wide], RegField::Ext(ext), operand)
+        }
+        ("push" | "pop", [Operand::Reg(reg)]) if matches!(reg.size, 2 | 8) => {
+            let opcode = if mnemonic == "push" { 0x50 } else { 0x58 };
+            emit_o(buf, OpSize { prefix66: reg.size == 2, rex_w: false }, &[opcode], *reg)
+        }
+        ("push" | "pop", [operand @ Operand::Mem(mem)]) if matches!(mem.size, Some(2 | 8)) => {
+            let op_size = OpSize { prefix66: mem.size == Some(2), rex_w: false };
+            if mnemonic == "push" {
+                emit_rm(buf, op_size, &[0xff], RegField::Ext(6), operand)
+            } else {
+                emit_rm(buf, op_size, &[0x8f], RegField::Ext(0), operand)
+            }
+        }
+        ("push", [Operand::Imm(imm)]) => {
+            check_imm(*imm, 8)?;
+            if i8::try_from(*imm).is_ok() {
+                buf.put1(0x6a);
+                put_imm(buf, *imm, 1);
+            } else {
+                buf.put1(0x68);
+                put_imm(buf, *imm, 4);
+            }
+            Ok(())
+        }
+        ("bswap", [Operand::Reg(reg)]) if matches!(reg.size, 4 | 8) => {
+            emit_o(buf, OpSize::of(reg.size), &[0x0f, 0xc8], *reg)
+        }
+        ("jmp" | "call", [Operand::Label(target)]) => {
+            buf.put1(if mnemonic == "jmp" { 0xe9 } else { 0xe8 });
+            buf.put_fixup(FixupKind::X86Rel32, target.clone());
+            buf.put4(0);
+            Ok(())
+        }
+        ("jmp" | "call", [target @ (Operand::Reg(_) | Operand::Mem(_))]) => {
+            if !matches!(target, Operand::Reg(Reg { size: 8, .. }) | Operand::Mem(_))
+                || op_size(&[target]).is_ok_and(|size| size != 8)
+            {
+                unsupported!("operands for `{mnemonic}`");
+            }
+            let ext = if mnemonic == "jmp" { 4 } else { 2 };
+            emit_rm(buf, OpSize::DEFAULT, &[0xff], RegField::Ext(ext), target)
+        }
+        ("int", [Operand::Imm(imm @ 0..=0xff)]) => {
+            buf.put1(0xcd);
+            buf.put1(*imm as u8);
+            Ok(())
+        }
+        ("movups" | "movaps", [dst, src]) => {
+            let is_xmm = |operand: &Operand| matches!(operand, Operand::Reg(Reg { size: 16, .. }));
+            let is_m128 = |operand: &Operand| {
+                matches!(operand, Operand::Mem(Mem { size: None | Some(16), .. }))
+            };
+            let base = if mnemonic == "movups" { 0x10 } else { 0x28 };
+            match (dst, src) {
+                (Operand::Reg(dst), _) if is_xmm(&ops[0]) && (is_xmm(src) || is_m128(src)) => {
+                    emit_rm(buf, OpSize::DEFAULT, &[0x0f, base], RegField::Reg(*dst), src)
+                }
+                (_, Operand::Reg(src)) if is_m128(dst) && is_xmm(&ops[1]) => {
+                    emit_rm(buf, OpSize::DEFAULT, &[0x0f, base + 1], RegField::Reg(*src), dst)
+                }
+                _ => unsupported!("operands for `{mnemonic}`"),
+            }
+        }
+        _ => unsupported!("instruction `{mnemonic}` with operands {operands:?}"),
+    }
+}
@@ -65,6 +65,7 @@
 mod driver;
 mod global_asm;
 mod inline_asm;
+mod integrated_asm;
 mod intrinsics;
 mod linkage;
 mod main_shim;
@@ -129,6 +130,7 @@ struct CodegenCx {
     should_write_ir: bool,
     global_asm: String,
     inline_asm_index: Cell<usize>,
+    disable_integrated_asm: bool,
     debug_context: Option<DebugContext>,
     unwind_context: UnwindContext,
     cgu_name: Symbol,
@@ -157,6 +159,7 @@ fn new(
             should_write_ir: crate::pretty_clif::should_write_ir(tcx),
             global_asm: String::new(),
             inline_asm_index: Cell::new(0),
+            disable_integrated_asm: backend_config.disable_integrated_asm,
             debug_context,
             unwind_context,
             cgu_name,
@@ -2,8 +2,10 @@
 
 use cranelift_codegen::entity::EntityRef;
 use cranelift_codegen::ir::immediates::Offset32;
+use cranelift_codegen::ir::ValueLabel;
 use rustc_middle::ty::FnSig;
 
+use crate::debuginfo::add_val_label;
 use crate::prelude::*;
 
 fn codegen_field<'tcx>(
@@ -372,7 +374,7 @@ pub(crate) struct CPlace<'tcx> {
 }
 
 #[derive(Debug, Copy, Clone)]
-enum CPlaceInner {
+pub(crate) enum CPlaceInner {
     Var(Local, Variable),
     VarPair(Local, Variable, Variable),
     Addr(Pointer, Option<Value>),
@@ -383,6 +385,10 @@ pub(crate) fn layout(&self) -> TyAndLayout<'tcx> {
         self.layout
     }
 
+    pub(crate) fn inner(&self) -> &CPlaceInner {
+        &self.inner
+    }
+
     pub(crate) fn new_stack_slot(
         fx: &mut FunctionCx<'_, '_, 'tcx>,
         layout: TyAndLayout<'tcx>,
@@ -452,14 +458,14 @@ pub(crate) fn to_cvalue(self, fx: &mut FunctionCx<'_, '_, 'tcx>) -> CValue<'tcx>
         match self.inner {
             CPlaceInner::Var(_local, var) => {
                 let val = fx.bcx.use_var(var);
-                //fx.bcx.set_val_label(val, cranelift_codegen::ir::ValueLabel::new(var.index()));
+                add_val_label(fx, val, ValueLabel::new(var.index()));
                 CValue::by_val(val, layout)
             }
             CPlaceInner::VarPair(_local, var1, var2) => {
                 let val1 = fx.bcx.use_var(var1);
-                //fx.bcx.set_val_label(val1, cranelift_codegen::ir::ValueLabel::new(var1.index()));
+                add_val_label(fx, val1, ValueLabel::new(var1.index()));
                 let val2 = fx.bcx.use_var(var2);
-                //fx.bcx.set_val_label(val2, cranelift_codegen::ir::ValueLabel::new(var2.index()));
+                add_val_label(fx, val2, ValueLabel::new(var2.index()));
                 CValue::by_val_pair(val1, val2, layout)
             }
             CPlaceInner::Addr(ptr, extra) => {
@@ -580,7 +586,7 @@ fn transmute_scalar<'tcx>(
                 // calling `write_cvalue` you need to add a `bint` instruction.
                 _ => unreachable!("write_cvalue_transmute: {:?} -> {:?}", src_ty, dst_ty),
             };
-            //fx.bcx.set_val_label(data, cranelift_codegen::ir::ValueLabel::new(var.index()));
+            add_val_label(fx, data, ValueLabel::new(var.index()));
             fx.bcx.def_var(var, data);
         }
 
@@ -11,6 +11,35 @@ codegen_ssa_atomic_compare_exchange = Atomic compare-exchange intrinsic missing
 
 codegen_ssa_binary_output_to_tty = option `-o` or `--emit` is used to write binary output type `{$shorthand}` to stdout, but stdout is a tty
 
+codegen_ssa_builtin_linker_duplicate_symbol = duplicate definition of symbol `{$symbol}`
+    .note = defined in `{$first}` and in `{$second}`
+
+codegen_ssa_builtin_linker_malformed_input = builtin linker failed to read `{$file}`: {$error}
+
+codegen_ssa_builtin_linker_missing_entry = entry point symbol `{$symbol}` is not defined
+
+codegen_ssa_builtin_linker_read_input = failed to read linker input {$path}: {$error}
+
+codegen_ssa_builtin_linker_relocation_overflow = relocation {$relocation} against `{$symbol}` in section `{$section}` of `{$file}` is out of range
+
+codegen_ssa_builtin_linker_undefined_symbol = undefined symbol `{$symbol}`
+    .note = referenced by `{$file}`
+
+codegen_ssa_builtin_linker_unsupported_argument = the builtin linker doesn't support the argument `{$arg}`
+
+codegen_ssa_builtin_linker_unsupported_dylib = the builtin linker can't link to the dynamic library `{$name}`
+
+codegen_ssa_builtin_linker_unsupported_output_kind = the builtin linker can only produce statically linked executables
+
+codegen_ssa_builtin_linker_unsupported_relocation = unsupported relocation {$relocation} in section `{$section}` of `{$file}`
+    .note = the builtin linker only supports the relocations used by static non-PIC x86_64 executables
+
+codegen_ssa_builtin_linker_unsupported_symbol = symbol `{$symbol}` in `{$file}` is an indirect function, which the builtin linker doesn't support
+
+codegen_ssa_builtin_linker_unsupported_target = the builtin linker only supports x86_64 ELF targets, not `{$target}`
+
+codegen_ssa_builtin_linker_write_output = failed to write {$path}: {$error}
+
 codegen_ssa_cgu_not_recorded =
     CGU-reuse for `{$cgu_user_name}` is (mangled: `{$cgu_name}`) was not recorded
 
@@ -149,6 +178,10 @@ codegen_ssa_lib_def_write_failure = failed to write lib.def file: {$error}
 
 codegen_ssa_link_exe_unexpected_error = `link.exe` returned an unexpected error
 
+codegen_ssa_link_reproducer = a script reproducing this link was written to {$script}
+
+codegen_ssa_link_reproducer_failed = failed to write link reproducer to {$dir}: {$error}
+
 codegen_ssa_link_script_unavailable = can only use link script when linking with GNU-like linker
 
 codegen_ssa_link_script_write_failure = failed to write link script to {$path}: {$error}
@@ -211,6 +244,13 @@ codegen_ssa_select_cpp_build_tool_workload = in the Visual Studio installer, ens
 
 codegen_ssa_shuffle_indices_evaluation = could not evaluate shuffle_indices at compile time
 
+codegen_ssa_size_report_analyze_failed = failed to analyze the size of {$path}: {$error}
+
+codegen_ssa_size_report_summary = size report for {$path}:
+    {$summary}
+
+codegen_ssa_size_report_write_failed = failed to write size report to {$path}: {$error}
+
 codegen_ssa_specify_libraries_to_link = use the `-l` flag to specify native libraries to link
 
 codegen_ssa_static_library_native_artifacts = Link against the following native artifacts when linking against this static library. The order and any duplication can be significant on some platforms.
@@ -0,0 +1,1619 @@
+//! The in-process linker used for `-C linker-flavor=builtin`.
+//!
+//! It links the objects and static archives (including rlibs) recorded by
+//! [`BuiltinLinker`](super::linker::BuiltinLinker) into a statically linked ELF executable without
+//! spawning any external tool, which allows building fully static Linux binaries in hermetic
+//! environments. Only the subset of ELF needed by `x86_64-unknown-linux-musl` is supported:
+//! x86_64 relocatable objects, non-PIC executables, static TLS and `.eh_frame` based unwinding.
+//! Debuginfo isn't linked. Anything outside of this subset is reported as an error rather than
+//! producing a broken executable.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use object::elf;
+use object::read::archive::ArchiveFile;
+use object::read::elf::{FileHeader as _, Rela as _, SectionHeader as _, Sym as _};
+use object::LittleEndian;
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap};
+use rustc_errors::ErrorGuaranteed;
+use rustc_session::Session;
+
+use super::command::Command;
+use crate::errors::BuiltinLinkerError as Error;
+
+type FileHeader = elf::FileHeader64<LittleEndian>;
+type Rela = elf::Rela64<LittleEndian>;
+type Sym = elf::Sym64<LittleEndian>;
+
+/// The address the executable is loaded at, which is the default of GNU ld and lld.
+const BASE_ADDRESS: u64 = 0x40_0000;
+const PAGE_SIZE: u64 = 0x1000;
+const EHDR_SIZE: u64 = 64;
+const PHDR_SIZE: u64 = 56;
+const SHDR_SIZE: u64 = 64;
+const SYM_SIZE: u64 = 24;
+
+/// The largest accepted size and alignment of input sections and common symbols. Anything larger
+/// can't be reached by the 32-bit relocations of the non-PIC code model anyway, and the limit
+/// keeps the layout arithmetic from overflowing on malformed inputs.
+const MAX_SIZE: u64 = 1 << 32;
+
+/// The section flags that are propagated to output sections.
+const OUTPUT_FLAGS: u64 =
+    (elf::SHF_ALLOC | elf::SHF_WRITE | elf::SHF_EXECINSTR | elf::SHF_TLS) as u64;
+
+/// The program entry point, defined by the `crt1.o` of the C runtime.
+const ENTRY: &str = "_start";
+
+/// Links the inputs listed in `cmd` into a static executable.
+pub fn link(sess: &Session, cmd: &Command) -> Result<(), ErrorGuaranteed> {
+    link_inner(sess, cmd).map_err(|error| sess.dcx().emit_err(error))
+}
+
+fn link_inner(sess: &Session, cmd: &Command) -> Result<(), Error> {
+    let target = &sess.target;
+    if target.arch != "x86_64"
+        || target.pointer_width != 64
+        || target.is_like_windows
+        || target.is_like_osx
+    {
+        return Err(Error::UnsupportedTarget { target: sess.opts.target_triple.to_string() });
+    }
+
+    let args = Args::parse(cmd)?;
+    let data = args
+        .inputs
+        .iter()
+        .map(|input| {
+            fs::read(&input.path)
+                .map_err(|error| Error::ReadInput { path: input.path.clone(), error })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut linker = Linker::default();
+    for (input, data) in args.inputs.iter().zip(&data) {
+        if data.starts_with(b"!<arch>\n") {
+            linker.add_archive(&input.path, data, input.whole_archive)?;
+        } else {
+            linker.add_object(input.path.display().to_string(), data)?;
+        }
+        linker.load_pending()?;
+    }
+    let image = linker.link(args.strip)?;
+
+    fs::write(&args.output, image)
+        .map_err(|error| Error::WriteOutput { path: args.output.clone(), error })?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&args.output, fs::Permissions::from_mode(0o755))
+            .map_err(|error| Error::WriteOutput { path: args.output.clone(), error })?;
+    }
+    Ok(())
+}
+
+/// The command line built by `BuiltinLinker`, which uses a small subset of the GNU ld syntax.
+struct Args {
+    output: PathBuf,
+    inputs: Vec<Input>,
+    strip: bool,
+}
+
+struct Input {
+    path: PathBuf,
+    whole_archive: bool,
+}
+
+impl Args {
+    fn parse(cmd: &Command) -> Result<Args, Error> {
+        let mut args = Args { output: PathBuf::from("a.out"), inputs: vec![], strip: false };
+        let mut whole_archive = false;
+        let mut iter = cmd.get_args().iter();
+        while let Some(arg) = iter.next() {
+            match arg.to_str() {
+                Some("-o") => {
+                    let Some(output) = iter.next() else {
+                        return Err(Error::UnsupportedArgument { arg: "-o".to_owned() });
+                    };
+                    args.output = PathBuf::from(output);
+                }
+                Some("--whole-archive") => whole_archive = true,
+                Some("--no-whole-archive") => whole_archive = false,
+                Some("--strip-all") => args.strip = true,
+                Some(flag) if flag.starts_with('-') => {
+                    return Err(Error::UnsupportedArgument { arg: flag.to_owned() });
+                }
+                _ => args.inputs.push(Input { path: PathBuf::from(arg), whole_archive }),
+            }
+        }
+        Ok(args)
+    }
+}
+
+fn malformed(file: &str, error: impl ToString) -> Error {
+    Error::MalformedInput { file: file.to_owned(), error: error.to_string() }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum Definition {
+    #[default]
+    Undefined,
+    /// Defined in a section that isn't part of the executable, like a discarded COMDAT group.
+    Discarded,
+    Absolute(u64),
+    Section {
+        object: usize,
+        section: usize,
+        value: u64,
+    },
+    /// A common symbol, which is allocated in `.bss` unless a real definition is found.
+    Common {
+        size: u64,
+        align: u64,
+    },
+    /// An offset into an output section, used for commons and linker defined symbols.
+    Output {
+        output: usize,
+        offset: u64,
+    },
+    Special(Special),
+}
+
+/// Linker defined symbols that refer to the boundaries of segments.
+#[derive(Clone, Copy, Debug)]
+enum Special {
+    ImageBase,
+    TextEnd,
+    DataEnd,
+    ImageEnd,
+}
+
+#[derive(Clone, Copy)]
+enum Symbol {
+    Local(Definition),
+    /// An index into `Linker::globals`.
+    Global(usize),
+}
+
+#[derive(Default)]
+struct Global {
+    definition: Definition,
+    weak: bool,
+    /// The object and symbol index of the definition.
+    defined_by: Option<(usize, usize)>,
+    /// The first object with a non-weak reference to the symbol.
+    referenced_by: Option<usize>,
+    /// The archive member defining the symbol, which is loaded once the symbol is referenced.
+    lazy: Option<usize>,
+}
+
+struct Object<'data> {
+    name: String,
+    sections: Vec<Option<InputSection<'data>>>,
+    symbols: Vec<Symbol>,
+    elf_symbols: &'data [Sym],
+    symbol_names: Vec<&'data str>,
+}
+
+struct InputSection<'data> {
+    name: &'data str,
+    data: &'data [u8],
+    size: u64,
+    align: u64,
+    flags: u64,
+    nobits: bool,
+    relocations: &'data [Rela],
+    /// The index of the output section and the offset within it, assigned by `Linker::link`.
+    output: usize,
+    offset: u64,
+}
+
+struct LazyMember<'data> {
+    name: String,
+    data: &'data [u8],
+    loaded: bool,
+}
+
+struct OutputSection<'data> {
+    name: &'data str,
+    flags: u64,
+    nobits: bool,
+    /// The input sections as `(object, section)` pairs.
+    inputs: Vec<(usize, usize)>,
+    size: u64,
+    align: u64,
+    addr: u64,
+}
+
+impl OutputSection<'_> {
+    fn new(name: &str, flags: u64, nobits: bool) -> OutputSection<'_> {
+        OutputSection { name, flags, nobits, inputs: vec![], size: 0, align: 1, addr: 0 }
+    }
+
+    fn is_tls(&self) -> bool {
+        self.flags & u64::from(elf::SHF_TLS) != 0
+    }
+
+    /// The index of the `PT_LOAD` segment: read-only, executable or writable.
+    fn segment(&self) -> usize {
+        if self.flags & u64::from(elf::SHF_EXECINSTR) != 0 {
+            1
+        } else if self.flags & u64::from(elf::SHF_WRITE) != 0 || self.is_tls() {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// The position within the segment. TLS comes first, so the `PT_TLS` segment is contiguous,
+    /// and sections without file contents come last, so they don't take up space in the file.
+    fn rank(&self) -> u8 {
+        match self.name {
+            ".rodata" | ".init" | ".tdata" => 0,
+            ".eh_frame_hdr" | ".text" | ".tbss" => 1,
+            ".eh_frame" | ".fini" | ".preinit_array" => 2,
+            ".gcc_except_table" | ".init_array" => 3,
+            ".fini_array" => 4,
+            ".data.rel.ro" => 5,
+            ".got" => 7,
+            ".bss" => 9,
+            _ if self.nobits => 8,
+            _ => 6,
+        }
+    }
+
+    fn sh_type(&self) -> u32 {
+        match self.name {
+            _ if self.nobits => elf::SHT_NOBITS,
+            ".init_array" => elf::SHT_INIT_ARRAY,
+            ".fini_array" => elf::SHT_FINI_ARRAY,
+            ".preinit_array" => elf::SHT_PREINIT_ARRAY,
+            _ => elf::SHT_PROGBITS,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Target {
+    Global(usize),
+    Local(usize, usize),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum GotEntry {
+    /// The address of the symbol.
+    Address(Target),
+    /// The offset of a thread local from the thread pointer, for the initial-exec TLS model.
+    TpOffset(Target),
+    /// The module id and offset of a thread local, for the general-dynamic TLS model.
+    TlsIndex(Target),
+    /// The module id of the executable, for the local-dynamic TLS model.
+    TlsModule,
+}
+
+#[derive(Default)]
+struct Segment {
+    start: u64,
+    file_end: u64,
+    mem_end: u64,
+}
+
+/// The addresses assigned by `Linker::layout`. All offsets are relative to `BASE_ADDRESS`, which
+/// is also the file offset of the contents.
+struct Layout {
+    order: Vec<usize>,
+    segments: [Segment; 3],
+    has_segment: [bool; 3],
+    has_tls: bool,
+    phnum: u64,
+    got: Option<usize>,
+    eh_frame: Option<usize>,
+    eh_frame_hdr: Option<usize>,
+    tls_start: u64,
+    tls_file_size: u64,
+    tls_size: u64,
+    tls_align: u64,
+}
+
+impl Layout {
+    /// The address of the thread pointer relative to the TLS block. On x86_64 the TLS block is
+    /// placed right below the thread pointer.
+    fn thread_pointer(&self) -> u64 {
+        self.tls_start + align_up(self.tls_size, self.tls_align)
+    }
+}
+
+#[derive(Default)]
+struct Linker<'data> {
+    objects: Vec<Object<'data>>,
+    globals: FxIndexMap<&'data str, Global>,
+    members: Vec<LazyMember<'data>>,
+    /// Archive members that need to be loaded to resolve a reference.
+    pending: Vec<usize>,
+    comdat_groups: FxHashSet<&'data [u8]>,
+    outputs: Vec<OutputSection<'data>>,
+    got: FxIndexMap<GotEntry, u64>,
+}
+
+impl<'data> Linker<'data> {
+    fn add_archive(
+        &mut self,
+        path: &Path,
+        data: &'data [u8],
+        whole_archive: bool,
+    ) -> Result<(), Error> {
+        let file = path.display().to_string();
+        let archive = ArchiveFile::parse(data).map_err(|e| malformed(&file, e))?;
+        for member in archive.members() {
+            let member = member.map_err(|e| malformed(&file, e))?;
+            let member_data = member.data(data).map_err(|e| malformed(&file, e))?;
+            // Skip anything that isn't an object file, like bitcode.
+            if !member_data.starts_with(&elf::ELFMAG) {
+                continue;
+            }
+            let name = format!("{file}({})", String::from_utf8_lossy(member.name()));
+            if whole_archive {
+                self.add_object(name, member_data)?;
+                continue;
+            }
+
+            let index = self.members.len();
+            for symbol in defined_globals(&name, member_data)? {
+                let global = self.globals.entry(symbol).or_default();
+                if matches!(global.definition, Definition::Undefined) && global.lazy.is_none() {
+                    global.lazy = Some(index);
+                    if global.referenced_by.is_some() {
+                        self.pending.push(index);
+                    }
+                }
+            }
+            self.members.push(LazyMember { name, data: member_data, loaded: false });
+        }
+        Ok(())
+    }
+
+    /// Loads the archive members defining symbols that are referenced but not yet defined.
+    fn load_pending(&mut self) -> Result<(), Error> {
+        while let Some(index) = self.pending.pop() {
+            let member = &mut self.members[index];
+            if !member.loaded {
+                member.loaded = true;
+                let (name, data) = (member.name.clone(), member.data);
+                self.add_object(name, data)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn add_object(&mut self, name: String, data: &'data [u8]) -> Result<(), Error> {
+        let header = FileHeader::parse(data).map_err(|e| malformed(&name, e))?;
+        let endian = header.endian().map_err(|e| malformed(&name, e))?;
+        if header.e_type(endian) != elf::ET_REL || header.e_machine(endian) != elf::EM_X86_64 {
+            return Err(malformed(&name, "not an x86_64 relocatable object"));
+        }
+        let sections = header.sections(endian, data).map_err(|e| malformed(&name, e))?;
+        let symtab =
+            sections.symbols(endian, data, elf::SHT_SYMTAB).map_err(|e| malformed(&name, e))?;
+        let object = self.objects.len();
+
+        // Only the first copy of each COMDAT group is kept.
+        let mut discarded = vec![false; sections.len()];
+        for section in sections.iter() {
+            let Some((flags, members)) =
+                section.group(endian, data).map_err(|e| malformed(&name, e))?
+            else {
+                continue;
+            };
+            if flags & elf::GRP_COMDAT == 0 {
+                continue;
+            }
+            let signature = symtab
+                .symbol(section.sh_info(endian) as usize)
+                .and_then(|symbol| symtab.symbol_name(endian, symbol))
+                .map_err(|e| malformed(&name, e))?;
+            if !self.comdat_groups.insert(signature) {
+                for member in members {
+                    if let Some(discard) = discarded.get_mut(member.get(endian) as usize) {
+                        *discard = true;
+                    }
+                }
+            }
+        }
+
+        let mut input_sections = Vec::with_capacity(sections.len());
+        for (index, section) in sections.iter().enumerate() {
+            let sh_type = section.sh_type(endian);
+            let flags = section.sh_flags(endian);
+            // Debuginfo and other non-allocated sections aren't linked.
+            let included = !discarded[index]
+                && flags & u64::from(elf::SHF_ALLOC) != 0
+                && matches!(
+                    sh_type,
+                    elf::SHT_PROGBITS
+                        | elf::SHT_NOBITS
+                        | elf::SHT_INIT_ARRAY
+                        | elf::SHT_FINI_ARRAY
+                        | elf::SHT_PREINIT_ARRAY
+                        | elf::SHT_X86_64_UNWIND
+                );
+            if !included {
+                input_sections.push(None);
+                continue;
+            }
+            let section_name =
+                sections.section_name(endian, section).map_err(|e| malformed(&name, e))?;
+            let section_name =
+                std::str::from_utf8(section_name).map_err(|e| malformed(&name, e))?;
+            let size = section.sh_size(endian);
+            let align = section.sh_addralign(endian).max(1);
+            if size > MAX_SIZE || align > MAX_SIZE || !align.is_power_of_two() {
+                let error = format!("invalid size or alignment of section `{section_name}`");
+                return Err(malformed(&name, error));
+            }
+            let nobits = sh_type == elf::SHT_NOBITS;
+            let section_data = section.data(endian, data).map_err(|e| malformed(&name, e))?;
+            if !nobits && section_data.len() as u64 != size {
+                return Err(malformed(&name, "truncated section"));
+            }
+            input_sections.push(Some(InputSection {
+                name: section_name,
+                data: section_data,
+                size,
+                align,
+                flags,
+                nobits,
+                relocations: &[],
+                output: usize::MAX,
+                offset: 0,
+            }));
+        }
+        for section in sections.iter() {
+            let Some(Some(target)) = input_sections.get_mut(section.sh_info(endian) as usize)
+            else {
+                continue;
+            };
+            match section.sh_type(endian) {
+                elf::SHT_RELA => {
+                    if let Some((relocations, _)) =
+                        section.rela(endian, data).map_err(|e| malformed(&name, e))?
+                    {
+                        target.relocations = relocations;
+                    }
+                }
+                elf::SHT_REL => return Err(malformed(&name, "REL relocations are not supported")),
+                _ => {}
+            }
+        }
+
+        let elf_symbols = symtab.symbols();
+        let mut symbols = Vec::with_capacity(elf_symbols.len());
+        let mut symbol_names = Vec::with_capacity(elf_symbols.len());
+        for (index, symbol) in elf_symbols.iter().enumerate() {
+            let symbol_name =
+                symtab.symbol_name(endian, symbol).map_err(|e| malformed(&name, e))?;
+            let symbol_name = std::str::from_utf8(symbol_name).map_err(|e| malformed(&name, e))?;
+            symbol_names.push(symbol_name);
+
+            let shndx = match symbol.st_shndx(endian) {
+                elf::SHN_XINDEX => symtab
+                    .shndx(endian, index)
+                    .ok_or_else(|| malformed(&name, "missing extended section index"))?
+                    as usize,
+                shndx => usize::from(shndx),
+            };
+            let definition = if symbol.is_undefined(endian) {
+                Definition::Undefined
+            } else if symbol.is_common(endian) {
+                let (size, align) = (symbol.st_size(endian), symbol.st_value(endian).max(1));
+                if size > MAX_SIZE || align > MAX_SIZE || !align.is_power_of_two() {
+                    return Err(malformed(&name, format!("invalid common symbol `{symbol_name}`")));
+                }
+                Definition::Common { size, align }
+            } else if symbol.is_absolute(endian) {
+                Definition::Absolute(symbol.st_value(endian))
+            } else if input_sections.get(shndx).is_some_and(Option::is_some) {
+                Definition::Section { object, section: shndx, value: symbol.st_value(endian) }
+            } else {
+                Definition::Discarded
+            };
+
+            if symbol.st_bind() == elf::STB_LOCAL {
+                symbols.push(Symbol::Local(definition));
+                continue;
+            }
+            if symbol.st_type() == elf::STT_GNU_IFUNC
+                && !matches!(definition, Definition::Undefined)
+            {
+                return Err(Error::UnsupportedSymbol {
+                    symbol: symbol_name.to_owned(),
+                    file: name.clone(),
+                });
+            }
+
+            let weak = symbol.st_bind() == elf::STB_WEAK;
+            let entry = self.globals.entry(symbol_name);
+            symbols.push(Symbol::Global(entry.index()));
+            let global = entry.or_default();
+            match definition {
+                Definition::Undefined => {
+                    // Weak references don't cause archive members to be loaded.
+                    if !weak {
+                        global.referenced_by.get_or_insert(object);
+                        if let (Definition::Undefined, Some(member)) =
+                            (global.definition, global.lazy)
+                        {
+                            self.pending.push(member);
+                        }
+                    }
+                }
+                // The kept copy of the COMDAT group defines the symbol.
+                Definition::Discarded => {}
+                _ => {
+                    if define(global, definition, weak, (object, index)).is_err() {
+                        let first = global
+                            .defined_by
+                            .and_then(|(first, _)| self.objects.get(first))
+                            .map_or(&name, |o| &o.name);
+                        return Err(Error::DuplicateSymbol {
+                            symbol: symbol_name.to_owned(),
+                            first: first.clone(),
+                            second: name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        self.objects.push(Object {
+            name,
+            sections: input_sections,
+            symbols,
+            elf_symbols,
+            symbol_names,
+        });
+        Ok(())
+    }
+
+    /// Lays out the loaded objects and writes the executable.
+    fn link(mut self, strip: bool) -> Result<Vec<u8>, Error> {
+        self.assign_output_sections();
+        self.allocate_commons();
+        self.allocate_got()?;
+        self.define_linker_symbols();
+
+        for (name, global) in &self.globals {
+            if let (Definition::Undefined, Some(object)) = (global.definition, global.referenced_by)
+            {
+                return Err(Error::UndefinedSymbol {
+                    symbol: name.to_string(),
+                    file: self.objects[object].name.clone(),
+                });
+            }
+        }
+        let entry = match self.globals.get(ENTRY) {
+            Some(Global { definition: Definition::Undefined, .. }) | None => {
+                return Err(Error::MissingEntry { symbol: ENTRY.to_owned() });
+            }
+            Some(global) => global.definition,
+        };
+
+        let eh_frame_hdr_size = self.eh_frame_hdr_size()?;
+        let layout = self.layout(eh_frame_hdr_size);
+        let entry = self.address(&layout, entry);
+        self.write(&layout, entry, strip)
+    }
+
+    /// Groups the input sections into output sections.
+    fn assign_output_sections(&mut self) {
+        let mut outputs: FxIndexMap<&'data str, OutputSection<'data>> = Default::default();
+        for (object_index, object) in self.objects.iter_mut().enumerate() {
+            for (section_index, section) in object.sections.iter_mut().enumerate() {
+                let Some(section) = section else { continue };
+                let name = output_section_name(section);
+                let flags = section.flags & OUTPUT_FLAGS;
+                let entry = outputs.entry(name);
+                section.output = entry.index();
+                let output = entry.or_insert_with(|| OutputSection::new(name, flags, true));
+                output.flags |= flags;
+                output.nobits &= section.nobits;
+                output.inputs.push((object_index, section_index));
+            }
+        }
+        self.outputs = outputs.into_values().collect();
+
+        for output in &mut self.outputs {
+            // Constructors run in the order of their priority, without a priority they run last.
+            if matches!(output.name, ".init_array" | ".fini_array" | ".preinit_array") {
+                let objects = &self.objects;
+                output.inputs.sort_by_key(|&(object, section)| {
+                    let name = objects[object].sections[section].as_ref().unwrap().name;
+                    name.rsplit_once('.').and_then(|(_, p)| p.parse::<u32>().ok()).unwrap_or(65536)
+                });
+            }
+            for &(object, section) in &output.inputs {
+                let section = self.objects[object].sections[section].as_mut().unwrap();
+                if output.name == ".eh_frame" {
+                    // Drop the zero terminator, a single one is added to the end of the output.
+                    if let Ok((_, end)) = eh_frame_records(section.data) {
+                        if end + 4 == section.data.len() {
+                            section.size = end as u64;
+                        }
+                    }
+                }
+                output.align = output.align.max(section.align);
+                section.offset = align_up(output.size, section.align);
+                output.size = section.offset + section.size;
+            }
+            if output.name == ".eh_frame" {
+                output.size += 4;
+            }
+        }
+    }
+
+    fn output_index(&mut self, name: &'data str, flags: u32, nobits: bool) -> usize {
+        if let Some(index) = self.outputs.iter().position(|output| output.name == name) {
+            return index;
+        }
+        self.outputs.push(OutputSection::new(name, u64::from(elf::SHF_ALLOC | flags), nobits));
+        self.outputs.len() - 1
+    }
+
+    /// Allocates the common symbols without a real definition in `.bss`.
+    fn allocate_commons(&mut self) {
+        let mut bss = None;
+        for index in 0..self.globals.len() {
+            let Definition::Common { size, align } = self.globals[index].definition else {
+                continue;
+            };
+            let output =
+                *bss.get_or_insert_with(|| self.output_index(".bss", elf::SHF_WRITE, true));
+            let output_section = &mut self.outputs[output];
+            let offset = align_up(output_section.size, align);
+            output_section.size = offset + size;
+            output_section.align = output_section.align.max(align);
+            self.globals[index].definition = Definition::Output { output, offset };
+        }
+    }
+
+    /// Allocates the GOT entries needed by the relocations. As there is no dynamic linker, all
+    /// entries are filled in at link time.
+    fn allocate_got(&mut self) -> Result<(), Error> {
+        let mut size = 0;
+        for (object_index, object) in self.objects.iter().enumerate() {
+            for section in object.sections.iter().flatten() {
+                for relocation in section.relocations {
+                    let target = || self.target(object_index, relocation);
+                    let entry = match relocation.r_type(LittleEndian, false) {
+                        elf::R_X86_64_GOTPCREL
+                        | elf::R_X86_64_GOTPCRELX
+                        | elf::R_X86_64_REX_GOTPCRELX
+                        | elf::R_X86_64_GOTPCREL64 => GotEntry::Address(target()?),
+                        elf::R_X86_64_GOTTPOFF => GotEntry::TpOffset(target()?),
+                        elf::R_X86_64_TLSGD => GotEntry::TlsIndex(target()?),
+                        elf::R_X86_64_TLSLD => GotEntry::TlsModule,
+                        _ => continue,
+                    };
+                    self.got.entry(entry).or_insert_with(|| {
+                        let offset = size;
+                        size += match entry {
+                            GotEntry::TlsIndex(_) | GotEntry::TlsModule => 16,
+                            GotEntry::Address(_) | GotEntry::TpOffset(_) => 8,
+                        };
+                        offset
+                    });
+                }
+            }
+        }
+
+        let referenced = self.globals.get("_GLOBAL_OFFSET_TABLE_").is_some();
+        if size > 0 || referenced {
+            let output = self.output_index(".got", elf::SHF_WRITE, false);
+            self.outputs[output].size = size;
+            self.outputs[output].align = 8;
+        }
+        Ok(())
+    }
+
+    /// Defines the symbols provided by the linker, if they are referenced but not defined.
+    fn define_linker_symbols(&mut self) {
+        for index in 0..self.globals.len() {
+            let (&name, global) = self.globals.get_index(index).unwrap();
+            if !matches!(global.definition, Definition::Undefined) {
+                continue;
+            }
+            let definition = match name {
+                "__ehdr_start" | "__executable_start" => Definition::Special(Special::ImageBase),
+                "_etext" | "etext" | "__etext" => Definition::Special(Special::TextEnd),
+                "_edata" | "edata" => Definition::Special(Special::DataEnd),
+                "_end" | "end" => Definition::Special(Special::ImageEnd),
+                "_GLOBAL_OFFSET_TABLE_" => {
+                    let output = self.outputs.iter().position(|o| o.name == ".got").unwrap();
+                    Definition::Output { output, offset: 0 }
+                }
+                _ => {
+                    let Some((section, is_stop)) = section_bounds(name) else { continue };
+                    match self.outputs.iter().position(|output| output.name == section) {
+                        Some(output) => Definition::Output {
+                            output,
+                            offset: if is_stop { self.outputs[output].size } else { 0 },
+                        },
+                        // `__start_*` and `__stop_*` are only defined for existing sections.
+                        None if name.starts_with("__start_") || name.starts_with("__stop_") => {
+                            continue;
+                        }
+                        None => Definition::Absolute(0),
+                    }
+                }
+            };
+            self.globals[index].definition = definition;
+        }
+    }
+
+    /// Returns the size of `.eh_frame_hdr`, the binary search table used by the unwinder to find
+    /// the frame description entry of a function.
+    fn eh_frame_hdr_size(&self) -> Result<Option<u64>, Error> {
+        let Some(eh_frame) = self.outputs.iter().find(|output| output.name == ".eh_frame") else {
+            return Ok(None);
+        };
+        let mut fdes = 0;
+        for &(object, section) in &eh_frame.inputs {
+            let object = &self.objects[object];
+            let section = object.sections[section].as_ref().unwrap();
+            let (records, _) =
+                eh_frame_records(section.data).map_err(|e| malformed(&object.name, e))?;
+            fdes += records.iter().filter(|record| record.is_fde).count() as u64;
+        }
+        Ok(Some(12 + 8 * fdes))
+    }
+
+    /// Assigns addresses to all output sections.
+    fn layout(&mut self, eh_frame_hdr_size: Option<u64>) -> Layout {
+        let eh_frame_hdr = eh_frame_hdr_size.map(|size| {
+            let output = self.output_index(".eh_frame_hdr", 0, false);
+            self.outputs[output].size = size;
+            self.outputs[output].align = 4;
+            output
+        });
+
+        let mut order: Vec<usize> = (0..self.outputs.len()).collect();
+        order.sort_by_key(|&i| (self.outputs[i].segment(), self.outputs[i].rank()));
+
+        // The read-only segment always exists as it contains the headers.
+        let mut has_segment = [true, false, false];
+        for output in &self.outputs {
+            if output.size > 0 {
+                has_segment[output.segment()] = true;
+            }
+        }
+        let tls_align = self.outputs.iter().filter(|o| o.is_tls()).map(|o| o.align).max();
+        // `PT_PHDR`, the `PT_LOAD`s, `PT_TLS`, `PT_GNU_EH_FRAME` and `PT_GNU_STACK`.
+        let phnum = 2
+            + has_segment.iter().filter(|&&has| has).count() as u64
+            + u64::from(tls_align.is_some())
+            + u64::from(eh_frame_hdr.is_some());
+
+        let mut layout = Layout {
+            order,
+            segments: Default::default(),
+            has_segment,
+            has_tls: tls_align.is_some(),
+            phnum,
+            got: self.outputs.iter().position(|output| output.name == ".got"),
+            eh_frame: self.outputs.iter().position(|output| output.name == ".eh_frame"),
+            eh_frame_hdr,
+            tls_start: 0,
+            tls_file_size: 0,
+            tls_size: 0,
+            tls_align: tls_align.unwrap_or(1),
+        };
+
+        let mut offset = EHDR_SIZE + PHDR_SIZE * phnum;
+        let mut tls_end = None;
+        for segment in 0..3 {
+            if segment > 0 {
+                offset = align_up(offset, PAGE_SIZE);
+            }
+            let start = offset;
+            let mut file_end = offset;
+            for &index in &layout.order {
+                let output = &mut self.outputs[index];
+                if output.segment() != segment {
+                    continue;
+                }
+                if output.is_tls() && tls_end.is_none() {
+                    offset = align_up(offset, layout.tls_align);
+                    layout.tls_start = BASE_ADDRESS + offset;
+                    tls_end = Some(layout.tls_start);
+                }
+                // `.tbss` only exists in the TLS template, so it takes up no address space.
+                if output.is_tls() && output.nobits {
+                    output.addr = align_up(tls_end.unwrap(), output.align);
+                    tls_end = Some(output.addr + output.size);
+                    continue;
+                }
+                offset = align_up(offset, output.align);
+                output.addr = BASE_ADDRESS + offset;
+                offset += output.size;
+                if !output.nobits {
+                    file_end = offset;
+                }
+                if output.is_tls() {
+                    tls_end = Some(output.addr + output.size);
+                    layout.tls_file_size = output.addr + output.size - layout.tls_start;
+                }
+            }
+            layout.segments[segment] = Segment { start, file_end, mem_end: offset };
+        }
+        if let Some(tls_end) = tls_end {
+            layout.tls_size = tls_end - layout.tls_start;
+        }
+        layout
+    }
+
+    fn target(&self, object: usize, relocation: &Rela) -> Result<Target, Error> {
+        let object_data = &self.objects[object];
+        let index = relocation.r_sym(LittleEndian, false) as usize;
+        match object_data.symbols.get(index) {
+            Some(Symbol::Global(global)) => Ok(Target::Global(*global)),
+            Some(Symbol::Local(_)) => Ok(Target::Local(object, index)),
+            None => Err(malformed(&object_data.name, "relocation against an invalid symbol")),
+        }
+    }
+
+    fn definition(&self, target: Target) -> Definition {
+        match target {
+            Target::Global(global) => self.globals[global].definition,
+            Target::Local(object, index) => match self.objects[object].symbols[index] {
+                Symbol::Local(definition) => definition,
+                Symbol::Global(global) => self.globals[global].definition,
+            },
+        }
+    }
+
+    fn target_name(&self, target: Target) -> String {
+        match target {
+            Target::Global(global) => self.globals.get_index(global).unwrap().0.to_string(),
+            Target::Local(object, index) => {
+                let name = self.objects[object].symbol_names[index];
+                match self.definition(target) {
+                    // Relocations against section symbols have an empty symbol name.
+                    Definition::Section { object, section, .. } if name.is_empty() => {
+                        self.objects[object].sections[section].as_ref().unwrap().name.to_owned()
+                    }
+                    _ => name.to_owned(),
+                }
+            }
+        }
+    }
+
+    fn address(&self, layout: &Layout, definition: Definition) -> u64 {
+        match definition {
+            // Undefined weak symbols and references to discarded sections resolve to zero.
+            Definition::Undefined | Definition::Discarded | Definition::Common { .. } => 0,
+            Definition::Absolute(value) => value,
+            Definition::Section { object, section, value } => {
+                let section = self.objects[object].sections[section].as_ref().unwrap();
+                // The value comes from the input object, so it may point anywhere.
+                (self.outputs[section.output].addr + section.offset).wrapping_add(value)
+            }
+            Definition::Output { output, offset } => self.outputs[output].addr + offset,
+            Definition::Special(special) => {
+                BASE_ADDRESS
+                    + match special {
+                        Special::ImageBase => 0,
+                        Special::TextEnd => layout.segments[1].mem_end,
+                        Special::DataEnd => layout.segments[2].file_end,
+                        Special::ImageEnd => layout.segments[2].mem_end,
+                    }
+            }
+        }
+    }
+
+    fn got_address(&self, layout: &Layout, entry: GotEntry) -> u64 {
+        self.outputs[layout.got.unwrap()].addr + self.got[&entry]
+    }
+
+    fn write(&self, layout: &Layout, entry: u64, strip: bool) -> Result<Vec<u8>, Error> {
+        let file_size = layout.segments.iter().map(|segment| segment.file_end).max().unwrap();
+        let mut image = vec![0; file_size as usize];
+
+        for output in &self.outputs {
+            if output.nobits {
+                continue;
+            }
+            for &(object, section) in &output.inputs {
+                let section = self.objects[object].sections[section].as_ref().unwrap();
+                if section.nobits {
+                    continue;
+                }
+                let start = (output.addr - BASE_ADDRESS + section.offset) as usize;
+                let size = section.size as usize;
+                image[start..start + size].copy_from_slice(&section.data[..size]);
+            }
+        }
+        for (object_index, object) in self.objects.iter().enumerate() {
+            for section in object.sections.iter().flatten().filter(|section| !section.nobits) {
+                for relocation in section.relocations {
+                    self.apply_relocation(layout, &mut image, object_index, section, relocation)?;
+                }
+            }
+        }
+
+        if let Some(got) = layout.got {
+            let got_offset = (self.outputs[got].addr - BASE_ADDRESS) as usize;
+            for (&entry, &offset) in &self.got {
+                let address = |target| self.address(layout, self.definition(target));
+                let (values, slots) = match entry {
+                    GotEntry::Address(target) => ([address(target), 0], 1),
+                    GotEntry::TpOffset(target) => {
+                        ([address(target).wrapping_sub(layout.thread_pointer()), 0], 1)
+                    }
+                    // The executable is always the TLS module with id 1.
+                    GotEntry::TlsIndex(target) => {
+                        ([1, address(target).wrapping_sub(layout.tls_start)], 2)
+                    }
+                    GotEntry::TlsModule => ([1, 0], 2),
+                };
+                for (i, value) in values.iter().take(slots).enumerate() {
+                    let pos = got_offset + offset as usize + i * 8;
+                    image[pos..pos + 8].copy_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+
+        if let Some(eh_frame_hdr) = layout.eh_frame_hdr {
+            self.write_eh_frame_hdr(layout, &mut image, eh_frame_hdr)?;
+        }
+
+        self.write_headers(layout, &mut image, entry, strip);
+        Ok(image)
+    }
+
+    fn apply_relocation(
+        &self,
+        layout: &Layout,
+        image: &mut [u8],
+        object: usize,
+        section: &InputSection<'_>,
+        relocation: &Rela,
+    ) -> Result<(), Error> {
+        enum Field {
+            Word64,
+            Signed32,
+            Unsigned32,
+        }
+
+        let r_type = relocation.r_type(LittleEndian, false);
+        if r_type == elf::R_X86_64_NONE {
+            return Ok(());
+        }
+        let target = self.target(object, relocation)?;
+        // All values are computed with 128 bits so that arbitrary addends can't overflow. They
+        // are checked against or truncated to the size of the field below.
+        let s = i128::from(self.address(layout, self.definition(target)));
+        let a = i128::from(relocation.r_addend(LittleEndian));
+        let offset = relocation.r_offset(LittleEndian);
+        let p = i128::from(self.outputs[section.output].addr + section.offset) + i128::from(offset);
+        let got_base = i128::from(layout.got.map_or(0, |got| self.outputs[got].addr));
+        let got = |entry| i128::from(self.got_address(layout, entry));
+        let tls_start = i128::from(layout.tls_start);
+        let thread_pointer = i128::from(layout.thread_pointer());
+
+        let (field, value) = match r_type {
+            elf::R_X86_64_64 => (Field::Word64, s + a),
+            elf::R_X86_64_PC64 => (Field::Word64, s + a - p),
+            // There is no PLT in a static executable, calls go to the function directly.
+            elf::R_X86_64_PC32 | elf::R_X86_64_PLT32 => (Field::Signed32, s + a - p),
+            elf::R_X86_64_32 => (Field::Unsigned32, s + a),
+            elf::R_X86_64_32S => (Field::Signed32, s + a),
+            elf::R_X86_64_GOTPCREL | elf::R_X86_64_GOTPCRELX | elf::R_X86_64_REX_GOTPCRELX => {
+                (Field::Signed32, got(GotEntry::Address(target)) + a - p)
+            }
+            elf::R_X86_64_GOTPCREL64 => (Field::Word64, got(GotEntry::Address(target)) + a - p),
+            elf::R_X86_64_GOTPC32 => (Field::Signed32, got_base + a - p),
+            elf::R_X86_64_GOTPC64 => (Field::Word64, got_base + a - p),
+            elf::R_X86_64_GOTOFF64 => (Field::Word64, s + a - got_base),
+            elf::R_X86_64_GOTTPOFF => (Field::Signed32, got(GotEntry::TpOffset(target)) + a - p),
+            elf::R_X86_64_TLSGD => (Field::Signed32, got(GotEntry::TlsIndex(target)) + a - p),
+            elf::R_X86_64_TLSLD => (Field::Signed32, got(GotEntry::TlsModule) + a - p),
+            elf::R_X86_64_DTPOFF32 => (Field::Signed32, s + a - tls_start),
+            elf::R_X86_64_DTPOFF64 => (Field::Word64, s + a - tls_start),
+            elf::R_X86_64_TPOFF32 => (Field::Signed32, s + a - thread_pointer),
+            elf::R_X86_64_TPOFF64 => (Field::Word64, s + a - thread_pointer),
+            elf::R_X86_64_DTPMOD64 => (Field::Word64, 1),
+            _ => {
+                return Err(Error::UnsupportedRelocation {
+                    relocation: relocation_name(r_type),
+                    section: section.name.to_owned(),
+                    file: self.objects[object].name.clone(),
+                });
+            }
+        };
+
+        let overflow = || Error::RelocationOverflow {
+            relocation: relocation_name(r_type),
+            symbol: self.target_name(target),
+            section: section.name.to_owned(),
+            file: self.objects[object].name.clone(),
+        };
+        let len = match field {
+            Field::Word64 => 8,
+            Field::Signed32 => {
+                i32::try_from(value).map_err(|_| overflow())?;
+                4
+            }
+            Field::Unsigned32 => {
+                u32::try_from(value).map_err(|_| overflow())?;
+                4
+            }
+        };
+        if offset.checked_add(len as u64).map_or(true, |end| end > section.size) {
+            return Err(malformed(&self.objects[object].name, "relocation out of bounds"));
+        }
+        // The low bytes of the two's complement value are the truncated little endian field.
+        let pos = (p as u64 - BASE_ADDRESS) as usize;
+        image[pos..pos + len].copy_from_slice(&value.to_le_bytes()[..len]);
+        Ok(())
+    }
+
+    fn write_eh_frame_hdr(
+        &self,
+        layout: &Layout,
+        image: &mut [u8],
+        eh_frame_hdr: usize,
+    ) -> Result<(), Error> {
+        let eh_frame = &self.outputs[layout.eh_frame.unwrap()];
+        let hdr_addr = self.outputs[eh_frame_hdr].addr;
+        let mut table = vec![];
+        for &(object, section) in &eh_frame.inputs {
+            let object = &self.objects[object];
+            let section = object.sections[section].as_ref().unwrap();
+            let section_addr = eh_frame.addr + section.offset;
+            let section_pos = (section_addr - BASE_ADDRESS) as usize;
+            let data = &image[section_pos..section_pos + section.size as usize];
+            let (records, _) = eh_frame_records(data).map_err(|e| malformed(&object.name, e))?;
+            for record in records.iter().filter(|record| record.is_fde) {
+                let cie_pointer = record.cie_pointer as usize;
+                let encoding = (record.offset + 4)
+                    .checked_sub(cie_pointer)
+                    .and_then(|cie| cie_pointer_encoding(data.get(cie..)?))
+                    .ok_or_else(|| malformed(&object.name, "unsupported CIE in .eh_frame"))?;
+                let field = record.offset + 8;
+                let pc_begin = read_encoded(data, field, section_addr + field as u64, encoding)
+                    .ok_or_else(|| malformed(&object.name, "unsupported pointer encoding"))?;
+                // The function was in a discarded section.
+                if pc_begin < BASE_ADDRESS {
+                    continue;
+                }
+                // Relocations may have changed the record lengths since the table was allocated.
+                if 12 + 8 * (table.len() as u64 + 1) > self.outputs[eh_frame_hdr].size {
+                    return Err(malformed(&object.name, "inconsistent .eh_frame records"));
+                }
+                let fde_addr = section_addr + record.offset as u64;
+                table.push((pc_begin.wrapping_sub(hdr_addr), fde_addr.wrapping_sub(hdr_addr)));
+            }
+        }
+        table.sort_by_key(|&(pc_begin, _)| pc_begin as i64);
+
+        let pos = (hdr_addr - BASE_ADDRESS) as usize;
+        let hdr = &mut image[pos..pos + 12 + 8 * table.len()];
+        // Version 1, pcrel sdata4 pointer to `.eh_frame`, udata4 count, datarel sdata4 table.
+        hdr[..4].copy_from_slice(&[1, 0x1b, 0x03, 0x3b]);
+        let eh_frame_ptr = eh_frame.addr.wrapping_sub(hdr_addr + 4) as u32;
+        hdr[4..8].copy_from_slice(&eh_frame_ptr.to_le_bytes());
+        hdr[8..12].copy_from_slice(&(table.len() as u32).to_le_bytes());
+        for (i, (pc_begin, fde)) in table.into_iter().enumerate() {
+            let entry = &mut hdr[12 + 8 * i..20 + 8 * i];
+            entry[..4].copy_from_slice(&(pc_begin as u32).to_le_bytes());
+            entry[4..].copy_from_slice(&(fde as u32).to_le_bytes());
+        }
+        Ok(())
+    }
+
+    /// Writes the ELF header and program headers to the start of `image` and appends the
+    /// symbol table and section headers.
+    fn write_headers(&self, layout: &Layout, image: &mut Vec<u8>, entry: u64, strip: bool) {
+        let mut shstrtab = vec![0];
+        let mut add_name = |name: &str| {
+            let offset = shstrtab.len() as u32;
+            shstrtab.extend_from_slice(name.as_bytes());
+            shstrtab.push(0);
+            offset
+        };
+        let mut shdrs = vec![[0; SHDR_SIZE as usize]];
+        let mut shndx = vec![0; self.outputs.len()];
+        for &index in &layout.order {
+            let output = &self.outputs[index];
+            shndx[index] = shdrs.len() as u16;
+            shdrs.push(section_header(SectionHeader {
+                name: add_name(output.name),
+                sh_type: output.sh_type(),
+                flags: output.flags,
+                addr: output.addr,
+                offset: if output.is_tls() && output.nobits {
+                    layout.tls_start - BASE_ADDRESS + layout.tls_file_size
+                } else {
+                    output.addr - BASE_ADDRESS
+                },
+                size: output.size,
+                link: 0,
+                info: 0,
+                align: output.align,
+                entsize: 0,
+            }));
+        }
+
+        if !strip {
+            let (symtab, strtab, first_global) = self.symbol_table(layout, &shndx);
+            let symtab_offset = align_up(image.len() as u64, 8);
+            image.resize(symtab_offset as usize, 0);
+            image.extend_from_slice(&symtab);
+            let strtab_offset = image.len() as u64;
+            image.extend_from_slice(&strtab);
+            let strtab_index = shdrs.len() as u32 + 1;
+            shdrs.push(section_header(SectionHeader {
+                name: add_name(".symtab"),
+                sh_type: elf::SHT_SYMTAB,
+                flags: 0,
+                addr: 0,
+                offset: symtab_offset,
+                size: symtab.len() as u64,
+                link: strtab_index,
+                info: first_global,
+                align: 8,
+                entsize: SYM_SIZE,
+            }));
+            shdrs.push(section_header(SectionHeader {
+                name: add_name(".strtab"),
+                sh_type: elf::SHT_STRTAB,
+                flags: 0,
+                addr: 0,
+                offset: strtab_offset,
+                size: strtab.len() as u64,
+                link: 0,
+                info: 0,
+                align: 1,
+                entsize: 0,
+            }));
+        }
+        let shstrtab_name = add_name(".shstrtab");
+        let shstrndx = shdrs.len() as u16;
+        shdrs.push(section_header(SectionHeader {
+            name: shstrtab_name,
+            sh_type: elf::SHT_STRTAB,
+            flags: 0,
+            addr: 0,
+            offset: image.len() as u64,
+            size: shstrtab.len() as u64,
+            link: 0,
+            info: 0,
+            align: 1,
+            entsize: 0,
+        }));
+        image.extend_from_slice(&shstrtab);
+        let shoff = align_up(image.len() as u64, 8);
+        image.resize(shoff as usize, 0);
+        for shdr in &shdrs {
+            image.extend_from_slice(shdr);
+        }
+
+        let mut header = Vec::with_capacity((EHDR_SIZE + PHDR_SIZE * layout.phnum) as usize);
+        header.extend_from_slice(&elf::ELFMAG);
+        header.extend_from_slice(&[elf::ELFCLASS64, elf::ELFDATA2LSB, elf::EV_CURRENT, 0]);
+        header.extend_from_slice(&[0; 8]);
+        header.extend_from_slice(&elf::ET_EXEC.to_le_bytes());
+        header.extend_from_slice(&elf::EM_X86_64.to_le_bytes());
+        header.extend_from_slice(&u32::from(elf::EV_CURRENT).to_le_bytes());
+        header.extend_from_slice(&entry.to_le_bytes());
+        header.extend_from_slice(&EHDR_SIZE.to_le_bytes());
+        header.extend_from_slice(&shoff.to_le_bytes());
+        header.extend_from_slice(&0u32.to_le_bytes());
+        header.extend_from_slice(&(EHDR_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&(PHDR_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&(layout.phnum as u16).to_le_bytes());
+        header.extend_from_slice(&(SHDR_SIZE as u16).to_le_bytes());
+        header.extend_from_slice(&(shdrs.len() as u16).to_le_bytes());
+        header.extend_from_slice(&shstrndx.to_le_bytes());
+
+        let mut phdrs = vec![];
+        let mut phdr = |p_type, flags, offset, file_size, mem_size, align| {
+            let addr = BASE_ADDRESS + offset;
+            phdrs.push(ProgramHeader { p_type, flags, offset, addr, file_size, mem_size, align });
+        };
+        let phdrs_size = PHDR_SIZE * layout.phnum;
+        phdr(elf::PT_PHDR, elf::PF_R, EHDR_SIZE, phdrs_size, phdrs_size, 8);
+        let segment_flags = [elf::PF_R, elf::PF_R | elf::PF_X, elf::PF_R | elf::PF_W];
+        for (i, segment) in layout.segments.iter().enumerate() {
+            if layout.has_segment[i] {
+                phdr(
+                    elf::PT_LOAD,
+                    segment_flags[i],
+                    segment.start,
+                    segment.file_end - segment.start,
+                    segment.mem_end - segment.start,
+                    PAGE_SIZE,
+                );
+            }
+        }
+        if layout.has_tls {
+            phdr(
+                elf::PT_TLS,
+                elf::PF_R,
+                layout.tls_start - BASE_ADDRESS,
+                layout.tls_file_size,
+                layout.tls_size,
+                layout.tls_align,
+            );
+        }
+        if let Some(eh_frame_hdr) = layout.eh_frame_hdr {
+            let output = &self.outputs[eh_frame_hdr];
+            let offset = output.addr - BASE_ADDRESS;
+            phdr(elf::PT_GNU_EH_FRAME, elf::PF_R, offset, output.size, output.size, 4);
+        }
+        phdrs.push(ProgramHeader {
+            p_type: elf::PT_GNU_STACK,
+            flags: elf::PF_R | elf::PF_W,
+            offset: 0,
+            addr: 0,
+            file_size: 0,
+            mem_size: 0,
+            align: 0,
+        });
+        for phdr in phdrs {
+            header.extend_from_slice(&program_header(phdr));
+        }
+
+        image[..header.len()].copy_from_slice(&header);
+    }
+
+    /// Builds the symbol table, containing the functions and objects of all objects. Returns the
+    /// table, its string table and the index of the first global symbol.
+    fn symbol_table(&self, layout: &Layout, shndx: &[u16]) -> (Vec<u8>, Vec<u8>, u32) {
+        let mut symtab = vec![0; SYM_SIZE as usize];
+        let mut strtab = vec![0];
+        let mut add = |name: &str, bind: u8, symbol: Option<&Sym>, definition: Definition| {
+            let shndx = match definition {
+                Definition::Section { object, section, .. } => {
+                    shndx[self.objects[object].sections[section].as_ref().unwrap().output]
+                }
+                Definition::Output { output, .. } => shndx[output],
+                _ => elf::SHN_ABS,
+            };
+            let (st_type, size) = symbol.map_or((elf::STT_OBJECT, 0), |symbol| {
+                (symbol.st_type(), symbol.st_size(LittleEndian))
+            });
+            symtab.extend_from_slice(&(strtab.len() as u32).to_le_bytes());
+            symtab.push((bind << 4) | st_type);
+            symtab.push(0);
+            symtab.extend_from_slice(&shndx.to_le_bytes());
+            symtab.extend_from_slice(&self.address(layout, definition).to_le_bytes());
+            symtab.extend_from_slice(&size.to_le_bytes());
+            strtab.extend_from_slice(name.as_bytes());
+            strtab.push(0);
+        };
+
+        for object in &self.objects {
+            for (index, symbol) in object.elf_symbols.iter().enumerate() {
+                let Symbol::Local(definition @ Definition::Section { .. }) = object.symbols[index]
+                else {
+                    continue;
+                };
+                let name = object.symbol_names[index];
+                if !name.is_empty() && matches!(symbol.st_type(), elf::STT_FUNC | elf::STT_OBJECT)
+                {
+                    add(name, elf::STB_LOCAL, Some(symbol), definition);
+                }
+            }
+        }
+        let first_global = (symtab.len() as u64 / SYM_SIZE) as u32;
+        for (name, global) in &self.globals {
+            let symbol = global.defined_by.map(|(object, index)| {
+                &self.objects[object].elf_symbols[index]
+            });
+            if symbol.is_some_and(|symbol| symbol.st_type() == elf::STT_TLS) {
+                continue;
+            }
+            let bind = if global.weak { elf::STB_WEAK } else { elf::STB_GLOBAL };
+            let defined = matches!(
+                global.definition,
+                Definition::Absolute(_) | Definition::Section { .. } | Definition::Output { .. }
+            );
+            if defined {
+                add(name, bind, symbol, global.definition);
+            }
+        }
+        (symtab, strtab, first_global)
+    }
+}
+
+/// Records a definition of `global`. Non-weak definitions take precedence over weak and common
+/// ones, while two non-weak definitions conflict.
+fn define(
+    global: &mut Global,
+    definition: Definition,
+    weak: bool,
+    symbol: (usize, usize),
+) -> Result<(), ()> {
+    let replace = match (global.definition, definition) {
+        (Definition::Undefined, _) => true,
+        (Definition::Common { size, align }, Definition::Common { size: s, align: a }) => {
+            global.definition = Definition::Common { size: size.max(s), align: align.max(a) };
+            false
+        }
+        (_, Definition::Common { .. }) => false,
+        (Definition::Common { .. }, _) => true,
+        _ if global.weak => !weak,
+        _ if weak => false,
+        _ => return Err(()),
+    };
+    if replace {
+        global.definition = definition;
+        global.weak = weak;
+        global.defined_by = Some(symbol);
+    }
+    Ok(())
+}
+
+/// Returns the names of the global symbols an archive member defines.
+fn defined_globals<'data>(name: &str, data: &'data [u8]) -> Result<Vec<&'data str>, Error> {
+    let header = FileHeader::parse(data).map_err(|e| malformed(name, e))?;
+    let endian = header.endian().map_err(|e| malformed(name, e))?;
+    let sections = header.sections(endian, data).map_err(|e| malformed(name, e))?;
+    let symtab = sections.symbols(endian, data, elf::SHT_SYMTAB).map_err(|e| malformed(name, e))?;
+    let mut names = vec![];
+    for symbol in symtab.symbols() {
+        if symbol.st_bind() != elf::STB_LOCAL
+            && !symbol.is_undefined(endian)
+            && !symbol.is_common(endian)
+        {
+            let symbol_name = symtab.symbol_name(endian, symbol).map_err(|e| malformed(name, e))?;
+            names.push(std::str::from_utf8(symbol_name).map_err(|e| malformed(name, e))?);
+        }
+    }
+    Ok(names)
+}
+
+/// Maps an input section to the name of the output section it is placed in.
+fn output_section_name<'data>(section: &InputSection<'data>) -> &'data str {
+    const MERGED: &[&str] = &[
+        ".text",
+        ".rodata",
+        ".data.rel.ro",
+        ".data",
+        ".bss",
+        ".tdata",
+        ".tbss",
+        ".init_array",
+        ".fini_array",
+        ".preinit_array",
+        ".gcc_except_table",
+        ".eh_frame",
+    ];
+
+    let name = section.name;
+    let flag = |flag: u32| section.flags & u64::from(flag) != 0;
+    if flag(elf::SHF_TLS) {
+        return if section.nobits { ".tbss" } else { ".tdata" };
+    }
+    if matches!(name, ".init" | ".fini") {
+        return name;
+    }
+    for &merged in MERGED {
+        if name.strip_prefix(merged).is_some_and(|rest| rest.is_empty() || rest.starts_with('.')) {
+            return merged;
+        }
+    }
+    // Sections named like C identifiers are kept separate for `__start_*` and `__stop_*`.
+    if is_c_identifier(name) {
+        return name;
+    }
+    if flag(elf::SHF_EXECINSTR) {
+        ".text"
+    } else if section.nobits {
+        ".bss"
+    } else if flag(elf::SHF_WRITE) {
+        ".data"
+    } else {
+        ".rodata"
+    }
+}
+
+/// Returns the output section and whether the end is referenced for a linker defined symbol
+/// marking the boundaries of a section.
+fn section_bounds(name: &str) -> Option<(&str, bool)> {
+    Some(match name {
+        "__bss_start" => (".bss", false),
+        "__preinit_array_start" => (".preinit_array", false),
+        "__preinit_array_end" => (".preinit_array", true),
+        "__init_array_start" => (".init_array", false),
+        "__init_array_end" => (".init_array", true),
+        "__fini_array_start" => (".fini_array", false),
+        "__fini_array_end" => (".fini_array", true),
+        _ => match name.strip_prefix("__start_") {
+            Some(section) => (section, false),
+            None => (name.strip_prefix("__stop_")?, true),
+        },
+    })
+}
+
+fn is_c_identifier(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn align_up(value: u64, align: u64) -> u64 {
+    value.next_multiple_of(align.max(1))
+}
+
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(*data.get(pos..)?.first_chunk()?))
+}
+
+struct EhFrameRecord {
+    offset: usize,
+    /// Zero for CIEs, the distance back to the CIE for FDEs.
+    cie_pointer: u32,
+    is_fde: bool,
+}
+
+/// Splits the contents of an `.eh_frame` section into CIE and FDE records. Also returns where
+/// the records end, which is either the zero terminator or the end of the section.
+fn eh_frame_records(data: &[u8]) -> Result<(Vec<EhFrameRecord>, usize), &'static str> {
+    let mut records = vec![];
+    let mut offset = 0;
+    while let Some(length) = read_u32(data, offset) {
+        let length = length as usize;
+        if length == 0 {
+            return Ok((records, offset));
+        }
+        if length == 0xffff_ffff {
+            return Err("64-bit DWARF is not supported in .eh_frame");
+        }
+        let cie_pointer = match read_u32(data, offset + 4) {
+            Some(cie_pointer) if length >= 4 && offset + 4 + length <= data.len() => cie_pointer,
+            _ => return Err("truncated .eh_frame record"),
+        };
+        records.push(EhFrameRecord { offset, cie_pointer, is_fde: cie_pointer != 0 });
+        offset += 4 + length;
+    }
+    Ok((records, data.len()))
+}
+
+const DW_EH_PE_ABSPTR: u8 = 0x00;
+
+/// Returns the encoding of the code pointers in the FDEs of a CIE.
+fn cie_pointer_encoding(cie: &[u8]) -> Option<u8> {
+    fn skip_leb128(data: &[u8], pos: &mut usize) -> Option<()> {
+        while *data.get(*pos)? & 0x80 != 0 {
+            *pos += 1;
+        }
+        *pos += 1;
+        Some(())
+    }
+
+    let version = *cie.get(8)?;
+    let augmentation_len = cie.get(9..)?.iter().position(|&b| b == 0)?;
+    let augmentation = &cie[9..9 + augmentation_len];
+    let mut pos = 9 + augmentation_len + 1;
+    if augmentation.first() != Some(&b'z') {
+        return Some(DW_EH_PE_ABSPTR);
+    }
+    skip_leb128(cie, &mut pos)?; // code alignment factor
+    skip_leb128(cie, &mut pos)?; // data alignment factor
+    if version == 1 {
+        pos += 1;
+    } else {
+        skip_leb128(cie, &mut pos)?;
+    }
+    skip_leb128(cie, &mut pos)?; // augmentation data length
+    for &c in &augmentation[1..] {
+        match c {
+            b'R' => return cie.get(pos).copied(),
+            b'L' => pos += 1,
+            b'P' => {
+                let encoding = *cie.get(pos)?;
+                pos += 1 + encoded_size(encoding)?;
+            }
+            b'S' | b'B' | b'G' => {}
+            _ => return None,
+        }
+    }
+    Some(DW_EH_PE_ABSPTR)
+}
+
+fn encoded_size(encoding: u8) -> Option<usize> {
+    match encoding & 0x0f {
+        0x00 | 0x04 | 0x0c => Some(8),
+        0x03 | 0x0b => Some(4),
+        0x02 | 0x0a => Some(2),
+        _ => None,
+    }
+}
+
+/// Reads a pointer encoded with a `DW_EH_PE_*` encoding.
+fn read_encoded(data: &[u8], pos: usize, addr: u64, encoding: u8) -> Option<u64> {
+    let bytes = data.get(pos..)?;
+    let value = match encoding & 0x0f {
+        0x00 | 0x04 | 0x0c => u64::from_le_bytes(*bytes.first_chunk()?),
+        0x03 => u64::from(u32::from_le_bytes(*bytes.first_chunk()?)),
+        0x0b => i32::from_le_bytes(*bytes.first_chunk()?) as u64,
+        0x02 => u64::from(u16::from_le_bytes(*bytes.first_chunk()?)),
+        0x0a => i16::from_le_bytes(*bytes.first_chunk()?) as u64,
+        _ => return None,
+    };
+    match encoding & 0x70 {
+        0x00 => Some(value),
+        0x10 => Some(addr.wrapping_add(value)),
+        _ => None,
+    }
+}
+
+fn relocation_name(r_type: u32) -> String {
+    const NAMES: &[&str] = &[
+        "NONE", "64", "PC32", "GOT32", "PLT32", "COPY", "GLOB_DAT", "JUMP_SLOT", "RELATIVE",
+        "GOTPCREL", "32", "32S", "16", "PC16", "8", "PC8", "DTPMOD64", "DTPOFF64", "TPOFF64",
+        "TLSGD", "TLSLD", "DTPOFF32", "GOTTPOFF", "TPOFF32", "PC64", "GOTOFF64", "GOTPC32",
+        "GOT64", "GOTPCREL64", "GOTPC64", "GOTPLT64", "PLTOFF64", "SIZE32", "SIZE64",
+        "GOTPC32_TLSDESC", "TLSDESC_CALL", "TLSDESC", "IRELATIVE", "RELATIVE64", "PC32_BND",
+        "PLT32_BND", "GOTPCRELX", "REX_GOTPCRELX",
+    ];
+    match NAMES.get(r_type as usize) {
+        Some(name) => format!("R_X86_64_{name}"),
+        None => format!("type {r_type}"),
+    }
+}
+
+struct ProgramHeader {
+    p_type: u32,
+    flags: u32,
+    offset: u64,
+    addr: u64,
+    file_size: u64,
+    mem_size: u64,
+    align: u64,
+}
+
+fn program_header(phdr: ProgramHeader) -> [u8; PHDR_SIZE as usize] {
+    let mut bytes = [0; PHDR_SIZE as usize];
+    bytes[0..4].copy_from_slice(&phdr.p_type.to_le_bytes());
+    bytes[4..8].copy_from_slice(&phdr.flags.to_le_bytes());
+    bytes[8..16].copy_from_slice(&phdr.offset.to_le_bytes());
+    bytes[16..24].copy_from_slice(&phdr.addr.to_le_bytes());
+    bytes[24..32].copy_from_slice(&phdr.addr.to_le_bytes());
+    bytes[32..40].copy_from_slice(&phdr.file_size.to_le_bytes());
+    bytes[40..48].copy_from_slice(&phdr.mem_size.to_le_bytes());
+    bytes[48..56].copy_from_slice(&phdr.align.to_le_bytes());
+    bytes
+}
+
+struct SectionHeader {
+    name: u32,
+    sh_type: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    align: u64,
+    entsize: u64,
+}
+
+fn section_header(shdr: SectionHeader) -> [u8; SHDR_SIZE as usize] {
+    let mut bytes = [0; SHDR_SIZE as usize];
+    bytes[0..4].copy_from_slice(&shdr.name.to_le_bytes());
+    bytes[4..8].copy_from_slice(&shdr.sh_type.to_le_bytes());
+    bytes[8..16].copy_from_slice(&shdr.flags.to_le_bytes());
+    bytes[16..24].copy_from_slice(&shdr.addr.to_le_bytes());
+    bytes[24..32].copy_from_slice(&shdr.offset.to_le_bytes());
+    bytes[32..40].copy_from_slice(&shdr.size.to_le_bytes());
+    bytes[40..44].copy_from_slice(&shdr.link.to_le_bytes());
+    bytes[44..48].copy_from_slice(&shdr.info.to_le_bytes());
+    bytes[48..56].copy_from_slice(&shdr.align.to_le_bytes());
+    bytes[56..64].copy_from_slice(&shdr.entsize.to_le_bytes());
+    bytes
+}
+
+#[cfg(test)]
+mod tests;
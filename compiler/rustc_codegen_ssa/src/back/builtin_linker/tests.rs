@@ -0,0 +1,255 @@
+use object::read::elf::{ElfFile64, ProgramHeader as _};
+use object::write::{self, SectionId, StandardSection, SymbolId, SymbolSection};
+use object::{
+    Architecture, BinaryFormat, Endianness, Object as _, ObjectSection as _, ObjectSymbol as _,
+    RelocationEncoding, RelocationKind, SectionKind, SymbolFlags, SymbolKind, SymbolScope,
+};
+
+use super::*;
+
+fn object(build: impl FnOnce(&mut write::Object<'static>)) -> Vec<u8> {
+    let mut object =
+        write::Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+    build(&mut object);
+    object.write().unwrap()
+}
+
+fn add_symbol(
+    object: &mut write::Object<'static>,
+    name: &str,
+    kind: SymbolKind,
+    section: SymbolSection,
+    value: u64,
+) -> SymbolId {
+    object.add_symbol(write::Symbol {
+        name: name.as_bytes().to_vec(),
+        value,
+        size: 0,
+        kind,
+        scope: SymbolScope::Dynamic,
+        weak: false,
+        section,
+        flags: SymbolFlags::None,
+    })
+}
+
+fn add_relocation(
+    object: &mut write::Object<'static>,
+    section: SectionId,
+    offset: u64,
+    symbol: SymbolId,
+    r_type: u32,
+    addend: i64,
+) {
+    let relocation = write::Relocation {
+        offset,
+        size: 0,
+        kind: RelocationKind::Elf(r_type),
+        encoding: RelocationEncoding::Generic,
+        symbol,
+        addend,
+    };
+    object.add_relocation(section, relocation).unwrap();
+}
+
+fn link(objects: &[&[u8]]) -> Result<Vec<u8>, Error> {
+    let mut linker = Linker::default();
+    for (i, data) in objects.iter().enumerate() {
+        linker.add_object(format!("{i}.o"), data)?;
+    }
+    linker.load_pending()?;
+    linker.link(false)
+}
+
+#[track_caller]
+fn link_ok(objects: &[&[u8]]) -> Vec<u8> {
+    let Ok(image) = link(objects) else { panic!("failed to link") };
+    image
+}
+
+#[track_caller]
+fn symbol_address(file: &ElfFile64<'_, LittleEndian>, name: &str) -> u64 {
+    file.symbols().find(|symbol| symbol.name() == Ok(name)).unwrap().address()
+}
+
+/// An object whose `.text` refers to the symbols defined by `callee_object` through various
+/// relocations.
+fn caller_object() -> Vec<u8> {
+    object(|object| {
+        let text = object.section_id(StandardSection::Text);
+        object.append_section_data(text, &[0; 32], 16);
+        add_symbol(object, "_start", SymbolKind::Text, SymbolSection::Section(text), 0);
+        let callee = add_symbol(object, "callee", SymbolKind::Text, SymbolSection::Undefined, 0);
+        let value = add_symbol(object, "value", SymbolKind::Data, SymbolSection::Undefined, 0);
+        let tls = add_symbol(object, "tls", SymbolKind::Tls, SymbolSection::Undefined, 0);
+        add_relocation(object, text, 1, callee, elf::R_X86_64_PLT32, -4);
+        add_relocation(object, text, 8, value, elf::R_X86_64_64, 2);
+        add_relocation(object, text, 16, value, elf::R_X86_64_32S, 0);
+        add_relocation(object, text, 20, value, elf::R_X86_64_REX_GOTPCRELX, -4);
+        add_relocation(object, text, 24, tls, elf::R_X86_64_TPOFF32, 0);
+    })
+}
+
+fn callee_object() -> Vec<u8> {
+    object(|object| {
+        let text = object.section_id(StandardSection::Text);
+        object.append_section_data(text, &[0xc3], 16);
+        add_symbol(object, "callee", SymbolKind::Text, SymbolSection::Section(text), 0);
+        let data = object.section_id(StandardSection::Data);
+        object.append_section_data(data, &[0; 16], 8);
+        add_symbol(object, "value", SymbolKind::Data, SymbolSection::Section(data), 4);
+        let tdata = object.section_id(StandardSection::Tls);
+        object.append_section_data(tdata, &[0; 8], 8);
+        add_symbol(object, "tls", SymbolKind::Tls, SymbolSection::Section(tdata), 0);
+    })
+}
+
+#[test]
+fn relocations() {
+    let image = link_ok(&[&caller_object(), &callee_object()]);
+    let file = ElfFile64::<LittleEndian>::parse(&*image).unwrap();
+    let text = file.section_by_name(".text").unwrap();
+    let code = text.data().unwrap();
+    let read_i32 = |pos: usize| i32::from_le_bytes(code[pos..pos + 4].try_into().unwrap());
+
+    let start = symbol_address(&file, "_start");
+    let callee = symbol_address(&file, "callee");
+    let value = symbol_address(&file, "value");
+    assert_eq!(start, text.address());
+    // The second `.text` is placed after the first one, aligned to 16 bytes.
+    assert_eq!(callee, start + 32);
+    assert_eq!(i64::from(read_i32(1)), callee as i64 - 4 - (start + 1) as i64);
+    assert_eq!(u64::from_le_bytes(code[8..16].try_into().unwrap()), value + 2);
+    assert_eq!(i64::from(read_i32(16)), value as i64);
+
+    // There is no dynamic linker, so the GOT entry is filled in by the linker.
+    let got = file.section_by_name(".got").unwrap();
+    let entry = (start + 24).wrapping_add_signed(read_i32(20).into());
+    let entry = (entry - got.address()) as usize;
+    let got_data = got.data().unwrap();
+    assert_eq!(u64::from_le_bytes(got_data[entry..entry + 8].try_into().unwrap()), value);
+
+    // `tls` is the only thread local, so it is right below the thread pointer.
+    assert_eq!(read_i32(24), -8);
+}
+
+#[test]
+fn relocation_overflow() {
+    let data = object(|object| {
+        let text = object.section_id(StandardSection::Text);
+        object.append_section_data(text, &[0; 8], 16);
+        add_symbol(object, "_start", SymbolKind::Text, SymbolSection::Section(text), 0);
+        let far = add_symbol(object, "far", SymbolKind::Data, SymbolSection::Absolute, 1 << 40);
+        add_relocation(object, text, 0, far, elf::R_X86_64_32, 0);
+    });
+    assert!(matches!(link(&[&data]), Err(Error::RelocationOverflow { .. })));
+}
+
+#[test]
+fn undefined_symbol() {
+    assert!(matches!(link(&[&caller_object()]), Err(Error::UndefinedSymbol { .. })));
+}
+
+#[test]
+fn section_layout() {
+    let data = object(|object| {
+        let text = object.section_id(StandardSection::Text);
+        object.append_section_data(text, &[0xc3; 16], 16);
+        add_symbol(object, "_start", SymbolKind::Text, SymbolSection::Section(text), 0);
+        let rodata = object.section_id(StandardSection::ReadOnlyData);
+        object.append_section_data(rodata, &[1; 8], 8);
+        let data = object.section_id(StandardSection::Data);
+        object.append_section_data(data, &[2; 8], 8);
+        let bss = object.section_id(StandardSection::UninitializedData);
+        object.append_section_bss(bss, 0x100, 16);
+        let tdata = object.section_id(StandardSection::Tls);
+        object.append_section_data(tdata, &[3; 4], 4);
+        let tbss = object.section_id(StandardSection::UninitializedTls);
+        object.append_section_bss(tbss, 8, 8);
+    });
+    let image = link_ok(&[&data]);
+    let file = ElfFile64::<LittleEndian>::parse(&*image).unwrap();
+    let section = |name| file.section_by_name(name).unwrap();
+    let (rodata, text, data) = (section(".rodata"), section(".text"), section(".data"));
+    let (bss, tdata, tbss) = (section(".bss"), section(".tdata"), section(".tbss"));
+
+    // Read-only data, code and writable data are in separate segments, in that order.
+    assert!(rodata.address() >= BASE_ADDRESS + EHDR_SIZE);
+    assert!(text.address() >= rodata.address() + rodata.size());
+    assert_eq!(text.address() % PAGE_SIZE, 0);
+    assert_eq!(tdata.address() % PAGE_SIZE, 0);
+    // `.tbss` only exists in the TLS template, so `.data` is placed right after `.tdata`.
+    assert_eq!(tbss.address(), tdata.address() + 8);
+    assert_eq!(data.address(), tdata.address() + 8);
+    assert_eq!(bss.address(), data.address() + 8);
+    assert_eq!(bss.size(), 0x100);
+
+    // The file offsets of the contents are their addresses relative to the base address.
+    assert_eq!(text.file_range(), Some((text.address() - BASE_ADDRESS, 16)));
+    assert_eq!(data.file_range(), Some((data.address() - BASE_ADDRESS, 8)));
+    assert_eq!(bss.file_range(), None);
+    assert_eq!(text.data().unwrap(), [0xc3; 16]);
+    assert_eq!(data.data().unwrap(), [2; 8]);
+
+    let endian = LittleEndian;
+    let segment = |p_type| {
+        let mut segments = file.raw_segments().iter().filter(|s| s.p_type(endian) == p_type);
+        let segment = segments.next().unwrap();
+        (segment.p_vaddr(endian), segment.p_filesz(endian), segment.p_memsz(endian))
+    };
+    assert_eq!(segment(elf::PT_TLS), (tdata.address(), 4, 16));
+    assert_eq!(segment(elf::PT_PHDR), (BASE_ADDRESS + EHDR_SIZE, 6 * PHDR_SIZE, 6 * PHDR_SIZE));
+    assert_eq!(file.raw_segments().iter().filter(|s| s.p_type(endian) == elf::PT_LOAD).count(), 3);
+    assert_eq!(file.entry(), text.address());
+}
+
+#[test]
+fn constructor_order() {
+    let data = object(|object| {
+        let text = object.section_id(StandardSection::Text);
+        object.append_section_data(text, &[0xc3], 16);
+        add_symbol(object, "_start", SymbolKind::Text, SymbolSection::Section(text), 0);
+        for (name, contents) in
+            [(".init_array", 3), (".init_array.00100", 2), (".init_array.00050", 1)]
+        {
+            let section = object.add_section(vec![], name.as_bytes().to_vec(), SectionKind::Data);
+            object.append_section_data(section, &[contents; 8], 8);
+        }
+    });
+    let image = link_ok(&[&data]);
+    let file = ElfFile64::<LittleEndian>::parse(&*image).unwrap();
+    let init_array = file.section_by_name(".init_array").unwrap();
+    let expected: Vec<u8> = [1, 2, 3].into_iter().flat_map(|c| [c; 8]).collect();
+    assert_eq!(init_array.data().unwrap(), expected);
+}
+
+#[test]
+fn malformed_input() {
+    // Truncated or corrupted objects have to be reported as errors rather than panicking.
+    let caller = caller_object();
+    let callee = callee_object();
+    for len in 0..caller.len() {
+        assert!(link(&[&caller[..len], &callee]).is_err(), "truncated to {len} bytes");
+    }
+    for position in 0..caller.len() {
+        let mut corrupted = caller.clone();
+        corrupted[position] ^= 0x55;
+        let _ = link(&[&corrupted, &callee]);
+    }
+}
+
+#[test]
+fn eh_frame_parsing() {
+    let cie = [&16u32.to_le_bytes()[..], &[0; 16]].concat();
+    let fde = [&12u32.to_le_bytes()[..], &24u32.to_le_bytes(), &[0; 8]].concat();
+    let data = [&cie[..], &fde, &[0; 4]].concat();
+    let (records, end) = eh_frame_records(&data).unwrap();
+    assert_eq!(end, 36);
+    let records: Vec<_> =
+        records.iter().map(|record| (record.offset, record.cie_pointer, record.is_fde)).collect();
+    assert_eq!(records, [(0, 0, false), (20, 24, true)]);
+
+    assert!(eh_frame_records(&data[..30]).is_err());
+    assert!(eh_frame_records(&[0xff; 4]).is_err());
+    assert_eq!(eh_frame_records(&data[..2]).map(|(_, end)| end), Ok(2));
+}
@@ -28,10 +28,12 @@
 use rustc_target::spec::{RelocModel, RelroLevel, SanitizerSet, SplitDebuginfo};
 
 use super::archive::{ArchiveBuilder, ArchiveBuilderBuilder};
+use super::builtin_linker;
 use super::command::Command;
 use super::linker::{self, Linker};
 use super::metadata::{create_wrapper_file, MetadataPosition};
 use super::rpath::{self, RPathConfig};
+use super::size_report;
 use crate::{
     errors, looks_like_rust_object_file, CodegenResults, CompiledModule, CrateInfo, NativeLib,
 };
@@ -48,7 +50,7 @@
 use std::io::{BufWriter, Write};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
-use std::process::{ExitStatus, Output, Stdio};
+use std::process::{self, ExitStatus, Output, Stdio};
 use std::{env, fmt, fs, io, mem, str};
 
 #[derive(Default)]
@@ -79,6 +81,7 @@ pub fn link_binary<'a>(
     let _timer = sess.timer("link_binary");
     let output_metadata = sess.opts.output_types.contains_key(&OutputType::Metadata);
     let mut tempfiles_for_stdout_output: Vec<PathBuf> = Vec::new();
+    let mut linked_natively: Vec<PathBuf> = Vec::new();
     for &crate_type in &codegen_results.crate_info.crate_types {
         // Ignore executable crates if we have -Z no-codegen, as they will error.
         if (sess.opts.unstable_opts.no_codegen || !sess.opts.output_types.should_codegen())
@@ -148,6 +151,7 @@ pub fn link_binary<'a>(
                         codegen_results,
                         path.as_ref(),
                     )?;
+                    linked_natively.push(out_filename.clone());
                 }
             }
             if sess.opts.json_artifact_notifications {
@@ -180,6 +184,12 @@ pub fn link_binary<'a>(
         }
     }
 
+    if let Some(path) = &sess.opts.unstable_opts.size_report {
+        sess.time("size_report", || {
+            size_report::write_report(sess, &codegen_results.crate_info, &linked_natively, path)
+        });
+    }
+
     // Remove the temporary object file and metadata if we aren't saving temps.
     sess.time("link_binary_remove_temps", || {
         // If the user requests that temporaries are saved, don't delete any.
@@ -775,6 +785,12 @@ fn link_natively<'a>(
     // May have not found libraries in the right formats.
     sess.dcx().abort_if_errors();
 
+    // The builtin linker runs in-process and writes the final executable itself, so none of the
+    // error recovery or post-processing below applies to it.
+    if flavor == LinkerFlavor::Builtin {
+        return sess.time("run_linker", || builtin_linker::link(sess, &cmd));
+    }
+
     // Invoke the system linker
     info!("{:?}", &cmd);
     let retry_on_segfault = env::var("RUSTC_RETRY_LINKER_ON_SEGFAULT").is_ok();
@@ -928,6 +944,12 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
         }
     }
 
+    let reproducer = sess.opts.unstable_opts.link_reproducer.as_deref().and_then(|dir| {
+        write_link_reproducer(sess, &cmd, out_filename, dir)
+            .map_err(|error| sess.dcx().emit_warn(errors::LinkReproducerFailed { dir, error }))
+            .ok()
+    });
+
     match prog {
         Ok(prog) => {
             if !prog.status.success() {
@@ -942,6 +964,9 @@ fn is_illegal_instruction(_status: &ExitStatus) -> bool {
                     escaped_output,
                 };
                 sess.dcx().emit_err(err);
+                if let Some(script) = &reproducer {
+                    sess.dcx().emit_note(errors::LinkReproducer { script });
+                }
                 // If MSVC's `link.exe` was expected but the return code
                 // is not a Microsoft LNK error then suggest a way to fix or
                 // install the Visual Studio build tools.
@@ -1347,6 +1372,7 @@ fn infer_from(
                         }
                     }
                     LinkerFlavor::Bpf => "bpf-linker",
+                    LinkerFlavor::Builtin => "builtin",
                     LinkerFlavor::Ptx => "rust-ptx-linker",
                 }),
                 flavor,
@@ -1565,28 +1591,8 @@ fn exec_linker(
 
     info!("falling back to passing arguments to linker via an @-file");
     let mut cmd2 = cmd.clone();
-    let mut args = String::new();
-    for arg in cmd2.take_args() {
-        args.push_str(
-            &Escape { arg: arg.to_str().unwrap(), is_like_msvc: sess.target.is_like_msvc }
-                .to_string(),
-        );
-        args.push('\n');
-    }
     let file = tmpdir.join("linker-arguments");
-    let bytes = if sess.target.is_like_msvc {
-        let mut out = Vec::with_capacity((1 + args.len()) * 2);
-        // start the stream with a UTF-16 BOM
-        for c in std::iter::once(0xFEFF).chain(args.encode_utf16()) {
-            // encode in little endian
-            out.push(c as u8);
-            out.push((c >> 8) as u8);
-        }
-        out
-    } else {
-        args.into_bytes()
-    };
-    fs::write(&file, &bytes)?;
+    fs::write(&file, response_file_contents(sess, cmd2.take_args()))?;
     cmd2.arg(format!("@{}", file.display()));
     info!("invoking linker {:?}", cmd2);
     let output = cmd2.output();
@@ -1637,51 +1643,399 @@ fn command_line_too_big(err: &io::Error) -> bool {
     fn command_line_too_big(_: &io::Error) -> bool {
         false
     }
+}
 
-    struct Escape<'a> {
-        arg: &'a str,
-        is_like_msvc: bool,
-    }
-
-    impl<'a> fmt::Display for Escape<'a> {
-        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-            if self.is_like_msvc {
-                // This is "documented" at
-                // https://docs.microsoft.com/en-us/cpp/build/reference/at-specify-a-linker-response-file
-                //
-                // Unfortunately there's not a great specification of the
-                // syntax I could find online (at least) but some local
-                // testing showed that this seemed sufficient-ish to catch
-                // at least a few edge cases.
-                write!(f, "\"")?;
-                for c in self.arg.chars() {
-                    match c {
-                        '"' => write!(f, "\\{c}")?,
-                        c => write!(f, "{c}")?,
-                    }
+/// Formats `args` as the contents of an `@`-file for the linker of the current target.
+fn response_file_contents(sess: &Session, args: Vec<OsString>) -> Vec<u8> {
+    let mut contents = String::new();
+    for arg in args {
+        contents.push_str(
+            &Escape { arg: arg.to_str().unwrap(), is_like_msvc: sess.target.is_like_msvc }
+                .to_string(),
+        );
+        contents.push('\n');
+    }
+    if sess.target.is_like_msvc {
+        let mut out = Vec::with_capacity((1 + contents.len()) * 2);
+        // start the stream with a UTF-16 BOM
+        for c in std::iter::once(0xFEFF).chain(contents.encode_utf16()) {
+            // encode in little endian
+            out.push(c as u8);
+            out.push((c >> 8) as u8);
+        }
+        out
+    } else {
+        contents.into_bytes()
+    }
+}
+
+struct Escape<'a> {
+    arg: &'a str,
+    is_like_msvc: bool,
+}
+
+impl<'a> fmt::Display for Escape<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_like_msvc {
+            // This is "documented" at
+            // https://docs.microsoft.com/en-us/cpp/build/reference/at-specify-a-linker-response-file
+            //
+            // Unfortunately there's not a great specification of the
+            // syntax I could find online (at least) but some local
+            // testing showed that this seemed sufficient-ish to catch
+            // at least a few edge cases.
+            write!(f, "\"")?;
+            for c in self.arg.chars() {
+                match c {
+                    '"' => write!(f, "\\{c}")?,
+                    c => write!(f, "{c}")?,
                 }
-                write!(f, "\"")?;
-            } else {
-                // This is documented at https://linux.die.net/man/1/ld, namely:
-                //
-                // > Options in file are separated by whitespace. A whitespace
-                // > character may be included in an option by surrounding the
-                // > entire option in either single or double quotes. Any
-                // > character (including a backslash) may be included by
-                // > prefixing the character to be included with a backslash.
-                //
-                // We put an argument on each line, so all we need to do is
-                // ensure the line is interpreted as one whole argument.
-                for c in self.arg.chars() {
-                    match c {
-                        '\\' | ' ' => write!(f, "\\{c}")?,
-                        c => write!(f, "{c}")?,
+            }
+            write!(f, "\"")?;
+        } else {
+            // This is documented at https://linux.die.net/man/1/ld, namely:
+            //
+            // > Options in file are separated by whitespace. A whitespace
+            // > character may be included in an option by surrounding the
+            // > entire option in either single or double quotes. Any
+            // > character (including a backslash) may be included by
+            // > prefixing the character to be included with a backslash.
+            //
+            // We put an argument on each line, so all we need to do is
+            // ensure the line is interpreted as one whole argument.
+            for c in self.arg.chars() {
+                match c {
+                    '\\' | ' ' => write!(f, "\\{c}")?,
+                    c => write!(f, "{c}")?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copies the inputs of `cmd` into a new subdirectory of `dir`, along with a script that reruns the
+/// link with the same linker, arguments and environment, and returns the path of that script.
+///
+/// Every argument naming an existing file is copied, including paths following an `=` or a `:`
+/// (like `--version-script=` or `/DEF:`) and paths in comma separated lists (like `-Wl,`). The
+/// arguments in `@`-files are rewritten the same way. Search paths (`-L` and `/LIBPATH:`) are
+/// replaced by directories holding copies of the libraries the link finds in them.
+///
+/// An existing directory for the same output is only replaced if it is a previous reproducer.
+fn write_link_reproducer(
+    sess: &Session,
+    cmd: &Command,
+    out_filename: &Path,
+    dir: &Path,
+) -> io::Result<PathBuf> {
+    let dir = dir.join(out_filename.file_name().unwrap_or(OsStr::new("output")));
+    if dir.exists() {
+        let is_reproducer = ["link.sh", "link.bat"].iter().any(|script| dir.join(script).is_file())
+            && dir.join("inputs").is_dir();
+        if !is_reproducer {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists and is not a link reproducer", dir.display()),
+            ));
+        }
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(dir.join("inputs"))?;
+    fs::create_dir_all(dir.join("output"))?;
+
+    let mut reproducer = LinkReproducer {
+        sess,
+        dir: &dir,
+        out_filename,
+        copied: Default::default(),
+        search_paths: Default::default(),
+    };
+    let mut cmd = cmd.clone();
+    reproducer.copy_libraries(cmd.get_args())?;
+    for arg in cmd.take_args() {
+        let arg = reproducer.rewrite_arg(arg)?;
+        cmd.arg(arg);
+    }
+    // Mirror `exec_linker`, which would pass the arguments through an `@`-file as well.
+    if cmd.very_likely_to_exceed_some_spawn_limit() {
+        fs::write(dir.join("linker-arguments"), response_file_contents(sess, cmd.take_args()))?;
+        cmd.arg("@linker-arguments");
+    }
+
+    let command = cmd.command();
+    let script = if cfg!(windows) {
+        let script = dir.join("link.bat");
+        fs::write(&script, batch_script(&command))?;
+        script
+    } else {
+        let script = dir.join("link.sh");
+        fs::write(&script, shell_script(&command))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&script, fs::Permissions::from_mode(0o755))?;
+        }
+        script
+    };
+    Ok(script)
+}
+
+struct LinkReproducer<'a> {
+    sess: &'a Session,
+    dir: &'a Path,
+    out_filename: &'a Path,
+    /// The inputs copied so far, and their path relative to `dir`.
+    copied: FxIndexMap<PathBuf, String>,
+    /// The search paths, and the path relative to `dir` of the directory replacing them.
+    search_paths: FxIndexMap<PathBuf, String>,
+}
+
+impl LinkReproducer<'_> {
+    /// Creates the directories replacing the search paths in `args`, and copies the libraries
+    /// the linker would find in the search paths into them.
+    fn copy_libraries(&mut self, args: &[OsString]) -> io::Result<()> {
+        let mut pieces = vec![];
+        for arg in args.iter().filter_map(|arg| arg.to_str()) {
+            match arg.strip_prefix("-Wl,") {
+                Some(linker_args) => pieces.extend(linker_args.split(',')),
+                None => pieces.push(arg),
+            }
+        }
+
+        let mut libraries = vec![];
+        let mut pieces = pieces.into_iter();
+        while let Some(piece) = pieces.next() {
+            let mut value = |flag: &str| match piece.strip_prefix(flag)? {
+                "" => pieces.next(),
+                value => Some(value),
+            };
+            if let Some(path) = value("-L").or_else(|| piece.strip_prefix("/LIBPATH:")) {
+                let path = Path::new(path);
+                if path.is_dir() && !self.search_paths.contains_key(path) {
+                    let copy = format!("search-paths/{}", self.search_paths.len());
+                    fs::create_dir_all(self.dir.join(&copy))?;
+                    self.search_paths.insert(path.to_owned(), copy);
+                }
+            } else if let Some(name) = value("-l") {
+                libraries.push(match name.strip_prefix(':') {
+                    Some(file_name) => vec![file_name.to_owned()],
+                    None => ["lib{}.a", "lib{}.so", "lib{}.dylib", "lib{}.tbd", "{}.lib"]
+                        .map(|pattern| pattern.replace("{}", name))
+                        .to_vec(),
+                });
+            } else if piece.ends_with(".lib")
+                && !piece.starts_with(['-', '/'])
+                && !Path::new(piece).is_file()
+            {
+                // MSVC looks up libraries passed by name in the search paths.
+                libraries.push(vec![piece.to_owned()]);
+            }
+        }
+
+        for file_names in libraries {
+            // Like the linker, only use the first search path containing the library.
+            let Some((path, copy)) = self
+                .search_paths
+                .iter()
+                .find(|(path, _)| file_names.iter().any(|name| path.join(name).is_file()))
+            else {
+                continue;
+            };
+            for name in &file_names {
+                let library = path.join(name);
+                if library.is_file() {
+                    let library_copy = self.dir.join(copy).join(name);
+                    if let Some(parent) = library_copy.parent() {
+                        fs::create_dir_all(parent)?;
                     }
+                    fs::copy(&library, library_copy)?;
                 }
             }
-            Ok(())
+        }
+        Ok(())
+    }
+
+    fn rewrite_arg(&mut self, arg: OsString) -> io::Result<OsString> {
+        let Some(arg_str) = arg.to_str() else {
+            return Ok(arg);
+        };
+        if let Some(file) = arg_str.strip_prefix('@')
+            && let Some(file) = self.rewrite_response_file(file)?
+        {
+            return Ok(format!("@{file}").into());
+        }
+        if let Some(arg) = self.rewrite_piece(arg_str)? {
+            return Ok(arg.into());
+        }
+        if !arg_str.contains(',') {
+            return Ok(arg);
+        }
+        let mut pieces = vec![];
+        for piece in arg_str.split(',') {
+            pieces.push(self.rewrite_piece(piece)?.unwrap_or_else(|| piece.to_owned()));
+        }
+        Ok(pieces.join(",").into())
+    }
+
+    fn rewrite_piece(&mut self, piece: &str) -> io::Result<Option<String>> {
+        if let Some(path) = self.rewrite_path(piece)? {
+            return Ok(Some(path));
+        }
+        for flag in ["-L", "/LIBPATH:"] {
+            if let Some(path) = piece.strip_prefix(flag)
+                && let Some(copy) = self.search_paths.get(Path::new(path))
+            {
+                return Ok(Some(format!("{flag}{copy}")));
+            }
+        }
+        for separator in ['=', ':'] {
+            if let Some((flag, path)) = piece.split_once(separator)
+                && flag.starts_with(['-', '/'])
+                && let Some(path) = self.rewrite_path(path)?
+            {
+                return Ok(Some(format!("{flag}{separator}{path}")));
+            }
+        }
+        Ok(None)
+    }
+
+    fn rewrite_path(&mut self, path: &str) -> io::Result<Option<String>> {
+        let path = Path::new(path);
+        let Some(file_name) = path.file_name() else {
+            return Ok(None);
+        };
+        if path == self.out_filename {
+            return Ok(Some(format!("output/{}", file_name.to_string_lossy())));
+        }
+        if path.is_dir() {
+            return Ok(self.search_paths.get(path).cloned());
+        }
+        if !path.is_file() {
+            return Ok(None);
+        }
+        if let Some(copied) = self.copied.get(path) {
+            return Ok(Some(copied.clone()));
+        }
+        let copy = self.copy_name(path);
+        fs::copy(path, self.dir.join(&copy))?;
+        self.copied.insert(path.to_owned(), copy.clone());
+        Ok(Some(copy))
+    }
+
+    /// Copies an `@`-file, rewriting the arguments in it like the ones on the command line.
+    fn rewrite_response_file(&mut self, path: &str) -> io::Result<Option<String>> {
+        let path = Path::new(path);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        if let Some(copied) = self.copied.get(path) {
+            return Ok(Some(copied.clone()));
+        }
+        let Some(contents) = decode_response_file(&fs::read(path)?) else {
+            return self.rewrite_path(&path.to_string_lossy());
+        };
+        // Record the copy before rewriting the contents, so that an `@`-file including itself
+        // doesn't recurse forever.
+        let copy = self.copy_name(path);
+        self.copied.insert(path.to_owned(), copy.clone());
+        let mut args = vec![];
+        for arg in parse_response_file(&contents, self.sess.target.is_like_msvc) {
+            args.push(self.rewrite_arg(arg.into())?);
+        }
+        fs::write(self.dir.join(&copy), response_file_contents(self.sess, args))?;
+        Ok(Some(copy))
+    }
+
+    fn copy_name(&self, path: &Path) -> String {
+        // Prefix the copies with their index, since inputs from different directories often share
+        // the same name.
+        let file_name = path.file_name().unwrap_or(OsStr::new("input")).to_string_lossy();
+        format!("inputs/{}-{file_name}", self.copied.len())
+    }
+}
+
+/// Decodes an `@`-file, which is either UTF-8 or UTF-16 with a byte order mark.
+fn decode_response_file(bytes: &[u8]) -> Option<String> {
+    match bytes {
+        [0xff, 0xfe, rest @ ..] if rest.len() % 2 == 0 => {
+            let units = rest.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+            char::decode_utf16(units).collect::<Result<_, _>>().ok()
+        }
+        _ => String::from_utf8(bytes.to_vec()).ok(),
+    }
+}
+
+/// Splits the contents of an `@`-file into arguments, undoing the quoting done by `Escape`.
+fn parse_response_file(contents: &str, is_like_msvc: bool) -> Vec<String> {
+    let mut args = vec![];
+    let mut arg: Option<String> = None;
+    let mut quote = None;
+    let mut chars = contents.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            // GNU style response files can escape any character, MSVC ones only quotes.
+            '\\' if !is_like_msvc => arg.get_or_insert_with(String::new).extend(chars.next()),
+            '\\' if chars.peek() == Some(&'"') => {
+                chars.next();
+                arg.get_or_insert_with(String::new).push('"');
+            }
+            '"' | '\'' if quote == Some(c) => quote = None,
+            '"' | '\'' if quote.is_none() && (c == '"' || !is_like_msvc) => {
+                quote = Some(c);
+                arg.get_or_insert_with(String::new);
+            }
+            c if c.is_whitespace() && quote.is_none() => args.extend(arg.take()),
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+    args
+}
+
+/// Renders `command` as a POSIX shell script that runs it from the directory of the script.
+fn shell_script(command: &process::Command) -> String {
+    use std::fmt::Write as _;
+
+    let quote = |s: &OsStr| format!("'{}'", s.to_string_lossy().replace('\'', r"'\''"));
+    let mut script = String::from("#!/bin/sh\ncd \"$(dirname \"$0\")\" || exit 1\n");
+    for (key, value) in command.get_envs() {
+        let key = key.to_string_lossy();
+        match value {
+            Some(value) => writeln!(script, "export {key}={}", quote(value)).unwrap(),
+            None => writeln!(script, "unset {key}").unwrap(),
         }
     }
+    script.push_str("exec ");
+    script.push_str(&quote(command.get_program()));
+    for arg in command.get_args() {
+        script.push_str(" \\\n    ");
+        script.push_str(&quote(arg));
+    }
+    script.push('\n');
+    script
+}
+
+/// Renders `command` as a batch script that runs it from the directory of the script.
+fn batch_script(command: &process::Command) -> String {
+    use std::fmt::Write as _;
+
+    let quote = |s: &OsStr| {
+        format!("\"{}\"", s.to_string_lossy().replace('%', "%%").replace('"', "\\\""))
+    };
+    let mut script = String::from("@echo off\r\ncd /d \"%~dp0\"\r\n");
+    for (key, value) in command.get_envs() {
+        let key = key.to_string_lossy().replace('%', "%%");
+        let value = value.map(|value| value.to_string_lossy().replace('%', "%%"));
+        write!(script, "set \"{key}={}\"\r\n", value.unwrap_or_default()).unwrap();
+    }
+    script.push_str(&quote(command.get_program()));
+    for arg in command.get_args() {
+        script.push_str(" ^\r\n    ");
+        script.push_str(&quote(arg));
+    }
+    script.push_str("\r\n");
+    script
 }
 
 fn link_output_kind(sess: &Session, crate_type: CrateType) -> LinkOutputKind {
@@ -1704,9 +2058,13 @@ fn link_output_kind(sess: &Session, crate_type: CrateType) -> LinkOutputKind {
     let pic_exe_supported = opts.position_independent_executables;
     let static_pic_exe_supported = opts.static_position_independent_executables;
     let static_dylib_supported = opts.crt_static_allows_dylibs;
+    // The builtin linker doesn't support position independent executables.
+    let builtin_linker = linker_and_flavor(sess).1 == LinkerFlavor::Builtin;
     match kind {
         LinkOutputKind::DynamicPicExe if !pic_exe_supported => LinkOutputKind::DynamicNoPicExe,
-        LinkOutputKind::StaticPicExe if !static_pic_exe_supported => LinkOutputKind::StaticNoPicExe,
+        LinkOutputKind::StaticPicExe if !static_pic_exe_supported || builtin_linker => {
+            LinkOutputKind::StaticNoPicExe
+        }
         LinkOutputKind::StaticDylib if !static_dylib_supported => LinkOutputKind::DynamicDylib,
         _ => kind,
     }
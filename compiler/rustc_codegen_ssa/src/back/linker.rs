@@ -153,6 +153,7 @@ pub fn get_linker<'a>(
         LinkerFlavor::Msvc(..) => Box::new(MsvcLinker { cmd, sess }) as Box<dyn Linker>,
         LinkerFlavor::EmCc => Box::new(EmLinker { cmd, sess }) as Box<dyn Linker>,
         LinkerFlavor::Bpf => Box::new(BpfLinker { cmd, sess }) as Box<dyn Linker>,
+        LinkerFlavor::Builtin => Box::new(BuiltinLinker { cmd, sess }) as Box<dyn Linker>,
         LinkerFlavor::Ptx => Box::new(PtxLinker { cmd, sess }) as Box<dyn Linker>,
     }
 }
@@ -1862,6 +1863,98 @@ fn subsystem(&mut self, _subsystem: &str) {}
     fn linker_plugin_lto(&mut self) {}
 }
 
+/// Collects the inputs for `back::builtin_linker`, using the small subset of the GNU ld command
+/// line syntax that it understands.
+pub struct BuiltinLinker<'a> {
+    cmd: Command,
+    sess: &'a Session,
+}
+
+impl<'a> Linker for BuiltinLinker<'a> {
+    fn cmd(&mut self) -> &mut Command {
+        &mut self.cmd
+    }
+
+    fn set_output_kind(&mut self, output_kind: LinkOutputKind, _out_filename: &Path) {
+        if output_kind != LinkOutputKind::StaticNoPicExe {
+            self.sess.dcx().emit_err(errors::BuiltinLinkerError::UnsupportedOutputKind);
+        }
+    }
+
+    fn link_dylib_by_name(&mut self, name: &str, _verbatim: bool, _as_needed: bool) {
+        self.sess.dcx().emit_err(errors::BuiltinLinkerError::UnsupportedDylib {
+            name: name.to_owned(),
+        });
+    }
+
+    fn link_staticlib_by_name(
+        &mut self,
+        name: &str,
+        verbatim: bool,
+        whole_archive: bool,
+        search_paths: &SearchPaths,
+    ) {
+        let search_paths = search_paths.get(self.sess);
+        let path = find_native_static_library(name, verbatim, search_paths, self.sess);
+        self.link_staticlib_by_path(&path, whole_archive);
+    }
+
+    fn link_staticlib_by_path(&mut self, path: &Path, whole_archive: bool) {
+        if whole_archive {
+            self.cmd.arg("--whole-archive").arg(path).arg("--no-whole-archive");
+        } else {
+            self.cmd.arg(path);
+        }
+    }
+
+    fn include_path(&mut self, _path: &Path) {}
+
+    fn debuginfo(&mut self, strip: Strip, _: &[PathBuf]) {
+        // The builtin linker never emits debuginfo, so only the symbol table can be stripped.
+        if strip == Strip::Symbols {
+            self.cmd.arg("--strip-all");
+        }
+    }
+
+    fn add_object(&mut self, path: &Path) {
+        self.cmd.arg(path);
+    }
+
+    fn optimize(&mut self) {}
+
+    fn output_filename(&mut self, path: &Path) {
+        self.cmd.arg("-o").arg(path);
+    }
+
+    fn framework_path(&mut self, _path: &Path) {}
+
+    fn full_relro(&mut self) {}
+
+    fn partial_relro(&mut self) {}
+
+    fn no_relro(&mut self) {}
+
+    fn gc_sections(&mut self, _keep_metadata: bool) {}
+
+    fn no_gc_sections(&mut self) {}
+
+    fn pgo_gen(&mut self) {}
+
+    fn no_crt_objects(&mut self) {}
+
+    fn no_default_libraries(&mut self) {}
+
+    fn control_flow_guard(&mut self) {}
+
+    fn ehcont_guard(&mut self) {}
+
+    fn export_symbols(&mut self, _tmpdir: &Path, _crate_type: CrateType, _symbols: &[String]) {}
+
+    fn subsystem(&mut self, _subsystem: &str) {}
+
+    fn linker_plugin_lto(&mut self) {}
+}
+
 pub struct BpfLinker<'a> {
     cmd: Command,
     sess: &'a Session,
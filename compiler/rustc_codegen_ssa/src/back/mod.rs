@@ -1,9 +1,11 @@
 pub mod archive;
+pub mod builtin_linker;
 pub mod command;
 pub mod link;
 pub mod linker;
 pub mod lto;
 pub mod metadata;
 pub mod rpath;
+pub mod size_report;
 pub mod symbol_export;
 pub mod write;
@@ -0,0 +1,357 @@
+//! Support for `-Z size-report`, which attributes the size of each linked output to the crates,
+//! modules and items its code and data come from.
+//!
+//! Symbols of the mono items codegened by the local crate are attributed using the origins
+//! recorded in [`CrateInfo::mono_item_origins`], which accounts every instance of a generic item
+//! to the item and the crate defining it. All other symbols, most notably the non-generic code of
+//! upstream crates, are attributed by demangling their name. That is only approximate, as the
+//! module has to be guessed from the path. Symbols that aren't Rust symbols are attributed to
+//! `<native>`.
+
+use std::fmt::Write as _;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use object::{Object, ObjectSection, ObjectSymbol, SectionIndex, SectionKind, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::unord::UnordMap;
+use rustc_session::Session;
+
+use crate::{errors, CrateInfo, MonoItemOrigin};
+
+/// The number of modules and items listed in the summary printed for each output.
+const SUMMARY_LEN: usize = 25;
+
+/// The crate and module that symbols are attributed to if they aren't Rust symbols.
+const NATIVE: &str = "<native>";
+
+#[derive(serde::Serialize)]
+struct Report {
+    outputs: Vec<OutputReport>,
+}
+
+#[derive(serde::Serialize)]
+struct OutputReport {
+    path: PathBuf,
+    /// The total size of the text sections and of the data sections of the output.
+    total: Sizes,
+    /// The part of `total` not covered by any symbol, like alignment padding, or everything if
+    /// the output was stripped.
+    unattributed: Sizes,
+    crates: Vec<Entry>,
+    modules: Vec<Entry>,
+    items: Vec<ItemEntry>,
+}
+
+#[derive(Clone, Copy, Default, serde::Serialize)]
+struct Sizes {
+    text: u64,
+    data: u64,
+}
+
+impl Sizes {
+    fn add(&mut self, kind: Kind, size: u64) {
+        match kind {
+            Kind::Text => self.text += size,
+            Kind::Data => self.data += size,
+        }
+    }
+
+    fn sub(&mut self, kind: Kind, size: u64) {
+        match kind {
+            Kind::Text => self.text = self.text.saturating_sub(size),
+            Kind::Data => self.data = self.data.saturating_sub(size),
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.text + self.data
+    }
+}
+
+#[derive(serde::Serialize)]
+struct Entry {
+    name: String,
+    #[serde(flatten)]
+    sizes: Sizes,
+}
+
+#[derive(serde::Serialize)]
+struct ItemEntry {
+    name: String,
+    #[serde(rename = "crate")]
+    krate: String,
+    module: String,
+    /// The number of symbols attributed to the item, which is the number of instances that were
+    /// kept for generic items.
+    instances: usize,
+    #[serde(flatten)]
+    sizes: Sizes,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Text,
+    Data,
+}
+
+struct Origin {
+    krate: String,
+    module: String,
+    item: String,
+}
+
+/// Writes the size report of `outputs` to `path`, and emits a summary of it as a note.
+pub fn write_report(sess: &Session, crate_info: &CrateInfo, outputs: &[PathBuf], path: &Path) {
+    let mut report = Report { outputs: vec![] };
+    for output in outputs {
+        match analyze_output(output, &crate_info.mono_item_origins) {
+            Ok(output) => report.outputs.push(output),
+            Err(error) => {
+                sess.dcx().emit_warn(errors::SizeReportAnalyzeFailed { path: output, error });
+            }
+        }
+    }
+
+    // The summary goes through the diagnostic context rather than to stdout, so that it doesn't
+    // mix with `--print` or `--emit=-` output.
+    for output in &report.outputs {
+        let summary = summary(output);
+        sess.dcx().emit_note(errors::SizeReportSummary { path: &output.path, summary });
+    }
+    let res: io::Result<()> = try {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, &report)?;
+        file.flush()?;
+    };
+    if let Err(error) = res {
+        sess.dcx().emit_err(errors::SizeReportWriteFailed { path, error });
+    }
+}
+
+fn analyze_output(
+    path: &Path,
+    origins: &UnordMap<String, MonoItemOrigin>,
+) -> Result<OutputReport, String> {
+    let data = fs::read(path).map_err(|error| error.to_string())?;
+    let file = object::File::parse(&*data).map_err(|error| error.to_string())?;
+
+    let mut total = Sizes::default();
+    let mut sections = FxHashMap::default();
+    for section in file.sections() {
+        let kind = match section.kind() {
+            SectionKind::Text => Kind::Text,
+            SectionKind::Data
+            | SectionKind::ReadOnlyData
+            | SectionKind::ReadOnlyDataWithRel
+            | SectionKind::ReadOnlyString
+            | SectionKind::UninitializedData
+            | SectionKind::Tls
+            | SectionKind::UninitializedTls => Kind::Data,
+            _ => continue,
+        };
+        total.add(kind, section.size());
+        sections.insert(section.index(), (kind, section.address() + section.size()));
+    }
+
+    // Executables usually keep their full symbol table unless stripped, in which case the
+    // dynamic symbol table still covers the exported symbols of shared libraries.
+    let mut symbols = collect_symbols(file.symbols(), &sections);
+    if symbols.is_empty() {
+        symbols = collect_symbols(file.dynamic_symbols(), &sections);
+    }
+
+    let mut unattributed = total;
+    let mut crates = FxIndexMap::<String, Sizes>::default();
+    let mut modules = FxIndexMap::<String, Sizes>::default();
+    let mut items = FxIndexMap::<String, ItemEntry>::default();
+    for (i, symbol) in symbols.iter().enumerate() {
+        let (kind, section_end) = sections[&symbol.section];
+        // Not all object formats record the size of symbols, so assume that those extend up to
+        // the next symbol of their section.
+        let size = if symbol.size != 0 {
+            symbol.size
+        } else {
+            let end = match symbols.get(i + 1) {
+                Some(next) if next.section == symbol.section => next.address,
+                _ => section_end,
+            };
+            end.saturating_sub(symbol.address)
+        };
+        unattributed.sub(kind, size);
+
+        let origin = origin(symbol.name, origins);
+        crates.entry(origin.krate.clone()).or_default().add(kind, size);
+        modules.entry(origin.module.clone()).or_default().add(kind, size);
+        let item = items.entry(origin.item.clone()).or_insert_with(|| ItemEntry {
+            name: origin.item,
+            krate: origin.krate,
+            module: origin.module,
+            instances: 0,
+            sizes: Sizes::default(),
+        });
+        item.instances += 1;
+        item.sizes.add(kind, size);
+    }
+
+    let entries = |map: FxIndexMap<String, Sizes>| {
+        let mut entries: Vec<_> =
+            map.into_iter().map(|(name, sizes)| Entry { name, sizes }).collect();
+        entries.sort_by(|a, b| b.sizes.total().cmp(&a.sizes.total()).then(a.name.cmp(&b.name)));
+        entries
+    };
+    let mut items: Vec<_> = items.into_values().collect();
+    items.sort_by(|a, b| b.sizes.total().cmp(&a.sizes.total()).then(a.name.cmp(&b.name)));
+    Ok(OutputReport {
+        path: path.to_owned(),
+        total,
+        unattributed,
+        crates: entries(crates),
+        modules: entries(modules),
+        items,
+    })
+}
+
+struct Symbol<'data> {
+    name: &'data str,
+    section: SectionIndex,
+    address: u64,
+    size: u64,
+}
+
+/// Returns the symbols defined in the text and data `sections`, sorted by address. Of several
+/// symbols at the same address, only the largest one is kept, so aliases aren't counted twice.
+fn collect_symbols<'data>(
+    symbols: impl Iterator<Item = impl ObjectSymbol<'data>>,
+    sections: &FxHashMap<SectionIndex, (Kind, u64)>,
+) -> Vec<Symbol<'data>> {
+    let mut symbols: Vec<_> = symbols
+        .filter(|symbol| {
+            symbol.is_definition()
+                && matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls)
+        })
+        .filter_map(|symbol| {
+            let section = symbol.section_index()?;
+            if !sections.contains_key(&section) {
+                return None;
+            }
+            let name = symbol.name().ok()?;
+            Some(Symbol { name, section, address: symbol.address(), size: symbol.size() })
+        })
+        .collect();
+    symbols.sort_by(|a, b| {
+        (a.section.0, a.address, b.size).cmp(&(b.section.0, b.address, a.size))
+    });
+    symbols.dedup_by(|next, prev| next.section == prev.section && next.address == prev.address);
+    symbols
+}
+
+fn origin(name: &str, origins: &UnordMap<String, MonoItemOrigin>) -> Origin {
+    // Mach-O and 32-bit Windows prefix symbol names with an underscore.
+    let origin = origins.get(name).or_else(|| origins.get(name.strip_prefix('_')?));
+    if let Some(origin) = origin {
+        return Origin {
+            krate: origin.krate.to_string(),
+            module: origin.module.clone(),
+            item: origin.item.clone(),
+        };
+    }
+
+    let Ok(demangled) = rustc_demangle::try_demangle(name) else {
+        let native = || NATIVE.to_owned();
+        return Origin { krate: native(), module: native(), item: name.to_owned() };
+    };
+    // The alternate format omits the hash of legacy symbol names.
+    let item = strip_generic_args(&format!("{demangled:#}"));
+    let path = primary_path(&item);
+    let mut segments: Vec<_> = path.split("::").collect();
+    let krate = segments[0].to_owned();
+    // The last segment names the item itself, and the ones before it that look like type names
+    // are the types it was defined in.
+    segments.pop();
+    while segments.len() > 1
+        && segments.last().is_some_and(|segment| {
+            segment.starts_with(|c: char| c == '{' || c.is_ascii_uppercase())
+        })
+    {
+        segments.pop();
+    }
+    let module = if segments.is_empty() { krate.clone() } else { segments.join("::") };
+    Origin { krate, module, item }
+}
+
+/// Removes the generic arguments from a demangled path, so that all instances of a generic item
+/// share the same path, like `<alloc::vec::Vec as core::ops::drop::Drop>::drop`.
+fn strip_generic_args(path: &str) -> String {
+    let mut stripped = String::with_capacity(path.len());
+    let mut depth = 0;
+    let mut prev = '\0';
+    for c in path.chars() {
+        if depth > 0 {
+            match c {
+                '<' => depth += 1,
+                '>' if prev != '-' => depth -= 1,
+                _ => {}
+            }
+        } else if c == '<' && (prev.is_alphanumeric() || prev == '_' || prev == ':') {
+            // Also remove the `::` of turbofish arguments.
+            if stripped.ends_with("::") {
+                stripped.truncate(stripped.len() - 2);
+            }
+            depth = 1;
+        } else {
+            stripped.push(c);
+        }
+        prev = c;
+    }
+    stripped
+}
+
+/// Returns the path that determines the crate and module of a demangled item. For items of impls,
+/// like `<alloc::string::String as core::fmt::Display>::fmt`, that's the path of the self type,
+/// or the path of the trait if the self type is a primitive.
+fn primary_path(item: &str) -> &str {
+    let Some(qualified) = item.strip_prefix('<') else {
+        return item;
+    };
+    let qualified = qualified.split('>').next().unwrap_or(qualified);
+    qualified
+        .split(" as ")
+        .map(|path| path.trim_start_matches(['&', '*', '[', '(']))
+        .map(|path| {
+            ["mut ", "const ", "dyn "]
+                .iter()
+                .fold(path, |path, prefix| path.strip_prefix(prefix).unwrap_or(path))
+        })
+        .find(|path| path.contains("::"))
+        .unwrap_or(item)
+}
+
+fn summary(output: &OutputReport) -> String {
+    fn row(summary: &mut String, sizes: &Sizes, name: &str) {
+        writeln!(summary, "{:>12} {:>12}  {name}", sizes.text, sizes.data).unwrap();
+    }
+
+    let mut summary = String::new();
+    row(&mut summary, &output.total, "total");
+    row(&mut summary, &output.unattributed, "not attributed to any symbol");
+
+    writeln!(summary, "\ncrates:\n{:>12} {:>12}  crate", "text", "data").unwrap();
+    for entry in &output.crates {
+        row(&mut summary, &entry.sizes, &entry.name);
+    }
+
+    writeln!(summary, "\nlargest modules:\n{:>12} {:>12}  module", "text", "data").unwrap();
+    for entry in output.modules.iter().take(SUMMARY_LEN) {
+        row(&mut summary, &entry.sizes, &entry.name);
+    }
+
+    writeln!(summary, "\nlargest items:\n{:>12} {:>12}  item (instances)", "text", "data").unwrap();
+    for item in output.items.iter().take(SUMMARY_LEN) {
+        row(&mut summary, &item.sizes, &format!("{} ({})", item.name, item.instances));
+    }
+    // The note adds its own line break.
+    summary.pop();
+    summary
+}
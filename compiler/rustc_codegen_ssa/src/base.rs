@@ -12,7 +12,10 @@
 use crate::mir::operand::OperandValue;
 use crate::mir::place::PlaceRef;
 use crate::traits::*;
-use crate::{CachedModuleCodegen, CompiledModule, CrateInfo, MemFlags, ModuleCodegen, ModuleKind};
+use crate::{
+    CachedModuleCodegen, CompiledModule, CrateInfo, MemFlags, ModuleCodegen, ModuleKind,
+    MonoItemOrigin,
+};
 
 use rustc_ast::expand::allocator::{global_fn_name, AllocatorKind, ALLOCATOR_METHODS};
 use rustc_attr as attr;
@@ -21,6 +24,7 @@
 use rustc_data_structures::sync::par_map;
 use rustc_data_structures::unord::UnordMap;
 use rustc_hir as hir;
+use rustc_hir::def::DefKind;
 use rustc_hir::def_id::{DefId, LOCAL_CRATE};
 use rustc_hir::lang_items::LangItem;
 use rustc_metadata::EncodedMetadata;
@@ -32,6 +36,7 @@
 use rustc_middle::mir::mono::{CodegenUnit, CodegenUnitNameBuilder, MonoItem};
 use rustc_middle::query::Providers;
 use rustc_middle::ty::layout::{HasTyCtxt, LayoutOf, TyAndLayout};
+use rustc_middle::ty::print::with_no_trimmed_paths;
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
 use rustc_session::config::{self, CrateType, EntryFnType, OutputType};
 use rustc_session::Session;
@@ -868,6 +873,7 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             dependency_formats: tcx.dependency_formats(()).clone(),
             windows_subsystem,
             natvis_debugger_visualizers: Default::default(),
+            mono_item_origins: Default::default(),
         };
 
         info.native_libraries.reserve(n_crates);
@@ -964,10 +970,46 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
                 collect_debugger_visualizers_transitive(tcx, DebuggerVisualizerType::Natvis);
         }
 
+        if tcx.sess.opts.unstable_opts.size_report.is_some() {
+            info.mono_item_origins = mono_item_origins(tcx);
+        }
+
         info
     }
 }
 
+/// Records where each mono item of the local crate comes from, so that `-Z size-report` can
+/// attribute the size of its symbol to the crate, module and generic item it was instantiated from.
+fn mono_item_origins(tcx: TyCtxt<'_>) -> UnordMap<String, MonoItemOrigin> {
+    let mut origins = UnordMap::default();
+    for cgu in tcx.collect_and_partition_mono_items(()).1 {
+        for &item in cgu.items().keys() {
+            let def_id = match item {
+                MonoItem::Fn(instance) => instance.def_id(),
+                MonoItem::Static(def_id) => def_id,
+                MonoItem::GlobalAsm(_) => continue,
+            };
+            let mut module = def_id;
+            while tcx.def_kind(module) != DefKind::Mod {
+                module = tcx.parent(module);
+            }
+            let krate = tcx.crate_name(def_id.krate);
+            let mut path = with_no_trimmed_paths!(tcx.def_path_str(def_id));
+            // Paths of local items are printed without the crate name.
+            if def_id.is_local() && !path.starts_with('<') {
+                path = format!("{krate}::{path}");
+            }
+            let origin = MonoItemOrigin {
+                krate,
+                module: format!("{krate}{}", tcx.def_path(module).to_string_no_crate_verbose()),
+                item: path,
+            };
+            origins.insert(item.symbol_name(tcx).name.to_owned(), origin);
+        }
+    }
+    origins
+}
+
 pub fn provide(providers: &mut Providers) {
     providers.backend_optimization_level = |tcx, cratenum| {
         let for_speed = match tcx.sess.opts.optimize {
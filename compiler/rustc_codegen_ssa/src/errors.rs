@@ -164,6 +164,51 @@ pub struct BinaryOutputToTty {
     pub shorthand: &'static str,
 }
 
+#[derive(Diagnostic)]
+pub enum BuiltinLinkerError {
+    #[diag(codegen_ssa_builtin_linker_unsupported_target)]
+    UnsupportedTarget { target: String },
+
+    #[diag(codegen_ssa_builtin_linker_unsupported_output_kind)]
+    UnsupportedOutputKind,
+
+    #[diag(codegen_ssa_builtin_linker_unsupported_dylib)]
+    UnsupportedDylib { name: String },
+
+    #[diag(codegen_ssa_builtin_linker_unsupported_argument)]
+    UnsupportedArgument { arg: String },
+
+    #[diag(codegen_ssa_builtin_linker_read_input)]
+    ReadInput { path: PathBuf, error: Error },
+
+    #[diag(codegen_ssa_builtin_linker_malformed_input)]
+    MalformedInput { file: String, error: String },
+
+    #[diag(codegen_ssa_builtin_linker_undefined_symbol)]
+    #[note]
+    UndefinedSymbol { symbol: String, file: String },
+
+    #[diag(codegen_ssa_builtin_linker_duplicate_symbol)]
+    #[note]
+    DuplicateSymbol { symbol: String, first: String, second: String },
+
+    #[diag(codegen_ssa_builtin_linker_missing_entry)]
+    MissingEntry { symbol: String },
+
+    #[diag(codegen_ssa_builtin_linker_unsupported_symbol)]
+    UnsupportedSymbol { symbol: String, file: String },
+
+    #[diag(codegen_ssa_builtin_linker_unsupported_relocation)]
+    #[note]
+    UnsupportedRelocation { relocation: String, section: String, file: String },
+
+    #[diag(codegen_ssa_builtin_linker_relocation_overflow)]
+    RelocationOverflow { relocation: String, symbol: String, section: String, file: String },
+
+    #[diag(codegen_ssa_builtin_linker_write_output)]
+    WriteOutput { path: PathBuf, error: Error },
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_ignoring_emit_path)]
 pub struct IgnoringEmitPath {
@@ -392,6 +437,40 @@ fn into_diagnostic(self, dcx: &DiagCtxt, level: Level) -> Diag<'_, G> {
 #[diag(codegen_ssa_visual_studio_not_installed)]
 pub struct VisualStudioNotInstalled;
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_reproducer)]
+pub struct LinkReproducer<'a> {
+    pub script: &'a Path,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_link_reproducer_failed)]
+pub struct LinkReproducerFailed<'a> {
+    pub dir: &'a Path,
+    pub error: Error,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_analyze_failed)]
+pub struct SizeReportAnalyzeFailed<'a> {
+    pub path: &'a Path,
+    pub error: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_summary)]
+pub struct SizeReportSummary<'a> {
+    pub path: &'a Path,
+    pub summary: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_size_report_write_failed)]
+pub struct SizeReportWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_linker_not_found)]
 #[note]
@@ -168,6 +168,20 @@ pub struct CrateInfo {
     pub dependency_formats: Lrc<Dependencies>,
     pub windows_subsystem: Option<String>,
     pub natvis_debugger_visualizers: BTreeSet<DebuggerVisualizerFile>,
+    /// The origin of the mono items of the local crate, keyed by symbol name. Only collected for
+    /// `-Z size-report`.
+    pub mono_item_origins: UnordMap<String, MonoItemOrigin>,
+}
+
+/// Where a mono item comes from, used to attribute its size in `-Z size-report`.
+#[derive(Debug, Encodable, Decodable)]
+pub struct MonoItemOrigin {
+    /// The crate defining the item, which for generic items is usually not the local crate.
+    pub krate: Symbol,
+    /// The path of the module containing the item.
+    pub module: String,
+    /// The path of the item without generic arguments, which is shared by all its instances.
+    pub item: String,
 }
 
 #[derive(Encodable, Decodable)]
@@ -19,6 +19,13 @@ expand_cannot_be_name_of_macro =
 expand_collapse_debuginfo_illegal =
     illegal value for attribute #[collapse_debuginfo(no|external|yes)]
 
+expand_count_depth_out_of_bounds =
+    depth parameter of meta-variable expression `count` must be less than {$max}
+    .label = `${$ident}` is repeated {$max} {$max ->
+        [one] level
+        *[other] levels
+    } deeper than this expression
+
 expand_count_repetition_misplaced =
     `count` can not be placed inside the inner-most repetition
 
@@ -88,6 +95,9 @@ expand_malformed_feature_attribute =
 
 expand_meta_var_dif_seq_matchers = {$msg}
 
+expand_meta_var_expr_concat_var_not_ident =
+    `concat` can only concatenate identifiers, but `${$ident}` is not bound to one
+
 expand_meta_var_expr_unrecognized_var =
     variable `{$key}` is not recognized in meta-variable expression
 
@@ -140,6 +150,17 @@ expand_remove_expr_not_supported =
 expand_remove_node_not_supported =
     removing {$descr} is not supported in this position
 
+expand_repetition_depth_out_of_bounds =
+    {$max ->
+        [0] meta-variable expression `{$expr}` with depth parameter must be called inside of a macro repetition
+        *[other] depth parameter of meta-variable expression `{$expr}` must be less than {$max}
+    }
+    .label = {$max ->
+        [0] this expression is not inside of a repetition
+        [one] this expression is inside of a single repetition
+        *[other] this expression is inside of {$max} nested repetitions
+    }
+
 expand_resolve_relative_path =
     cannot resolve relative path in non-file source `{$path}`
 
@@ -148,6 +169,9 @@ expand_takes_no_arguments =
 
 expand_trace_macro = trace_macro
 
+expand_trace_macros_json_write_failed =
+    failed to write macro expansion traces to {$path}: {$error}
+
 expand_unsupported_key_value =
     key-value macro attributes are not supported
 
@@ -1,6 +1,7 @@
 use crate::base::ast::NestedMetaItem;
 use crate::errors;
 use crate::expand::{self, AstFragment, Invocation};
+use crate::mbe;
 use crate::module::DirOwnership;
 
 use rustc_ast::attr::MarkedAttrs;
@@ -846,7 +847,7 @@ pub fn new(
         let collapse_debuginfo = Self::get_collapse_debuginfo(sess, attrs, is_local);
         tracing::debug!(?name, ?local_inner_macros, ?collapse_debuginfo, ?allow_internal_unsafe);
 
-        let (builtin_name, helper_attrs) = attr::find_by_name(attrs, sym::rustc_builtin_macro)
+        let (builtin_name, mut helper_attrs) = attr::find_by_name(attrs, sym::rustc_builtin_macro)
             .map(|attr| {
                 // Override `helper_attrs` passed above if it's a built-in macro,
                 // marking `proc_macro_derive` macros as built-in is not a realistic use case.
@@ -856,6 +857,12 @@ pub fn new(
                 )
             })
             .unwrap_or_else(|| (None, helper_attrs));
+        // The field attributes of the comparison and hashing derives are unstable. They are only
+        // registered as helpers with the feature enabled, as they would otherwise make attribute
+        // macros with the same names ambiguous in any item using these derives.
+        if builtin_name.is_some() && !features.derive_field_attrs {
+            helper_attrs.retain(|name| !matches!(*name, sym::eq | sym::ord | sym::hash));
+        }
         let stability = attr::find_stability(sess, attrs, span);
         let const_stability = attr::find_const_stability(sess, attrs, span);
         let body_stability = attr::find_body_stability(sess, attrs);
@@ -1088,6 +1095,8 @@ pub struct ExtCtxt<'a> {
     /// in the AST, but insert it here so that we know
     /// not to expand it again.
     pub(super) expanded_inert_attrs: MarkedAttrs,
+    /// The traces of `macro_rules!` expansions, collected for `-Z trace-macros-json`.
+    pub(crate) macro_traces: Vec<mbe::trace::MacroTrace>,
 }
 
 impl<'a> ExtCtxt<'a> {
@@ -1117,6 +1126,7 @@ pub fn new(
             expansions: FxIndexMap::default(),
             expanded_inert_attrs: MarkedAttrs::new(),
             buffered_early_lint: vec![],
+            macro_traces: vec![],
         }
     }
 
@@ -1191,6 +1201,13 @@ pub fn trace_macros_diag(&mut self) {
         // Fixme: does this result in errors?
         self.expansions.clear();
     }
+    /// Writes the traces of `macro_rules!` expansions to the file given to
+    /// `-Z trace-macros-json`, if any.
+    pub fn write_macro_traces(&self) {
+        if let Some(path) = &self.sess.opts.unstable_opts.trace_macros_json {
+            mbe::trace::write_macro_traces(self.sess, &self.macro_traces, path);
+        }
+    }
     pub fn trace_macros(&self) -> bool {
         self.ecfg.trace_mac
     }
@@ -5,6 +5,8 @@
 use rustc_span::symbol::{Ident, MacroRulesNormalizedIdent};
 use rustc_span::{Span, Symbol};
 use std::borrow::Cow;
+use std::io;
+use std::path::Path;
 
 #[derive(Diagnostic)]
 #[diag(expand_expr_repeat_no_syntax_vars)]
@@ -27,6 +29,34 @@ pub(crate) struct CountRepetitionMisplaced {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_count_depth_out_of_bounds)]
+pub(crate) struct CountDepthOutOfBounds {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub ident: MacroRulesNormalizedIdent,
+    pub max: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand_repetition_depth_out_of_bounds)]
+pub(crate) struct RepetitionDepthOutOfBounds {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub expr: &'static str,
+    pub max: usize,
+}
+
+#[derive(Diagnostic)]
+#[diag(expand_meta_var_expr_concat_var_not_ident)]
+pub(crate) struct MetaVarExprConcatVarNotIdent {
+    #[primary_span]
+    pub span: Span,
+    pub ident: MacroRulesNormalizedIdent,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_meta_var_expr_unrecognized_var)]
 pub(crate) struct MetaVarExprUnrecognizedVar {
@@ -377,6 +407,13 @@ pub struct TraceMacro {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(expand_trace_macros_json_write_failed)]
+pub(crate) struct TraceMacrosJsonWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(expand_proc_macro_panicked)]
 pub(crate) struct ProcMacroPanicked {
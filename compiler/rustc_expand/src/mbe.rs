@@ -9,6 +9,7 @@
 pub(crate) mod macro_rules;
 pub(crate) mod metavar_expr;
 pub(crate) mod quoted;
+pub(crate) mod trace;
 pub(crate) mod transcribe;
 
 use metavar_expr::MetaVarExpr;
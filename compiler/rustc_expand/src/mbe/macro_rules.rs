@@ -6,6 +6,7 @@
 use crate::mbe::macro_check;
 use crate::mbe::macro_parser::{Error, ErrorReported, Failure, Success, TtParser};
 use crate::mbe::macro_parser::{MatchedSeq, MatchedTokenTree, MatcherLoc};
+use crate::mbe::trace::TraceTracker;
 use crate::mbe::transcribe::transcribe;
 
 use ast::token::IdentIsRaw;
@@ -205,8 +206,17 @@ fn expand_macro<'cx>(
         trace_macros_note(&mut cx.expansions, sp, msg);
     }
 
-    // Track nothing for the best performance.
-    let try_success_result = try_match_macro(sess, name, &arg, lhses, &mut NoopTracker);
+    let trace_json = cx.sess.opts.unstable_opts.trace_macros_json.is_some();
+    let try_success_result = if trace_json {
+        let mut tracker = TraceTracker::new(cx.source_map());
+        let result = try_match_macro(sess, name, &arg, lhses, &mut tracker);
+        let trace = tracker.finish(cx, name, sp, def_span, &arg, result.as_ref().ok());
+        cx.macro_traces.push(trace);
+        result
+    } else {
+        // Track nothing for the best performance.
+        try_match_macro(sess, name, &arg, lhses, &mut NoopTracker)
+    };
 
     match try_success_result {
         Ok((i, named_matches)) => {
@@ -229,6 +239,9 @@ fn expand_macro<'cx>(
                 let msg = format!("to `{}`", pprust::tts_to_string(&tts));
                 trace_macros_note(&mut cx.expansions, sp, msg);
             }
+            if trace_json && let Some(trace) = cx.macro_traces.last_mut() {
+                trace.set_output(&tts);
+            }
 
             let p = Parser::new(sess, tts, None);
 
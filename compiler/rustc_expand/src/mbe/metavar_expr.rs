@@ -10,6 +10,9 @@
 /// A meta-variable expression, for expansions based on properties of meta-variables.
 #[derive(Debug, Clone, PartialEq, Encodable, Decodable)]
 pub(crate) enum MetaVarExpr {
+    /// An identifier made by concatenating identifiers and meta-variables bound to identifiers.
+    Concat(Box<[MetaVarExprConcatElem]>),
+
     /// The number of repetitions of an identifier.
     Count(Ident, usize),
 
@@ -25,6 +28,15 @@ pub(crate) enum MetaVarExpr {
     Length(usize),
 }
 
+/// An element of a `concat` meta-variable expression.
+#[derive(Debug, Clone, PartialEq, Encodable, Decodable)]
+pub(crate) enum MetaVarExprConcatElem {
+    /// An identifier written without a dollar sign, which is used as is.
+    Ident(Ident),
+    /// A meta-variable written with a dollar sign, whose identifier is used.
+    Var(Ident),
+}
+
 impl MetaVarExpr {
     /// Attempt to parse a meta-variable expression from a token stream.
     pub(crate) fn parse<'sess>(
@@ -41,6 +53,7 @@ pub(crate) fn parse<'sess>(
         check_trailing_token(&mut tts, sess)?;
         let mut iter = args.trees();
         let rslt = match ident.as_str() {
+            "concat" => parse_concat(&mut iter, sess, ident.span)?,
             "count" => parse_count(&mut iter, sess, ident.span)?,
             "ignore" => {
                 eat_dollar(&mut iter, sess, ident.span)?;
@@ -53,7 +66,7 @@ pub(crate) fn parse<'sess>(
                 let mut err = sess.dcx.struct_span_err(ident.span, err_msg);
                 err.span_suggestion(
                     ident.span,
-                    "supported expressions are count, ignore, index and length",
+                    "supported expressions are concat, count, ignore, index and length",
                     "",
                     Applicability::MachineApplicable,
                 );
@@ -64,10 +77,19 @@ pub(crate) fn parse<'sess>(
         Ok(rslt)
     }
 
-    pub(crate) fn ident(&self) -> Option<Ident> {
-        match *self {
-            MetaVarExpr::Count(ident, _) | MetaVarExpr::Ignore(ident) => Some(ident),
-            MetaVarExpr::Index(..) | MetaVarExpr::Length(..) => None,
+    /// Folds `aux` over the meta-variables referenced by the expression with `cb`.
+    pub(crate) fn for_each_metavar<A>(&self, mut aux: A, mut cb: impl FnMut(A, &Ident) -> A) -> A {
+        match self {
+            MetaVarExpr::Concat(elems) => {
+                for elem in elems.iter() {
+                    if let MetaVarExprConcatElem::Var(ident) = elem {
+                        aux = cb(aux, ident);
+                    }
+                }
+                aux
+            }
+            MetaVarExpr::Count(ident, _) | MetaVarExpr::Ignore(ident) => cb(aux, ident),
+            MetaVarExpr::Index(..) | MetaVarExpr::Length(..) => aux,
         }
     }
 }
@@ -88,6 +110,34 @@ fn check_trailing_token<'sess>(
     }
 }
 
+/// Parse a meta-variable `concat` expression: `concat([$]ident, [$]ident[, ...])`
+fn parse_concat<'sess>(
+    iter: &mut RefTokenTreeCursor<'_>,
+    sess: &'sess ParseSess,
+    span: Span,
+) -> PResult<'sess, MetaVarExpr> {
+    let mut elems = Vec::new();
+    loop {
+        let is_var = try_eat_dollar(iter);
+        let ident = parse_ident(iter, sess, span)?;
+        elems.push(if is_var {
+            MetaVarExprConcatElem::Var(ident)
+        } else {
+            MetaVarExprConcatElem::Ident(ident)
+        });
+        if !try_eat_comma(iter) {
+            break;
+        }
+    }
+    if elems.len() < 2 {
+        return Err(sess.dcx.struct_span_err(
+            span,
+            "`concat` must have at least two elements, separated by commas",
+        ));
+    }
+    Ok(MetaVarExpr::Concat(elems.into()))
+}
+
 /// Parse a meta-variable `count` expression: `count(ident[, depth])`
 fn parse_count<'sess>(
     iter: &mut RefTokenTreeCursor<'_>,
@@ -169,6 +219,17 @@ fn try_eat_comma(iter: &mut RefTokenTreeCursor<'_>) -> bool {
     false
 }
 
+/// Tries to move the iterator forward returning `true` if there is a dollar sign. If not, then the
+/// iterator is not modified and the result is `false`.
+fn try_eat_dollar(iter: &mut RefTokenTreeCursor<'_>) -> bool {
+    if let Some(TokenTree::Token(token::Token { kind: token::Dollar, .. }, _)) = iter.look_ahead(0)
+    {
+        let _ = iter.next();
+        return true;
+    }
+    false
+}
+
 /// Expects that the next item is a dollar sign.
 fn eat_dollar<'sess>(
     iter: &mut RefTokenTreeCursor<'_>,
@@ -0,0 +1,179 @@
+//! Structured traces of `macro_rules!` expansions, written as JSON with `-Z trace-macros-json`.
+//!
+//! Unlike the notes of `trace_macros!`, a trace records why each arm failed to match and which
+//! fragments the metavariables of the matched arm were bound to, so that large macros can be
+//! debugged with an external viewer.
+
+use crate::base::ExtCtxt;
+use crate::errors;
+use crate::mbe::macro_parser::{MatcherLoc, NamedMatch, NamedMatches, NamedParseResult};
+use crate::mbe::macro_parser::{Error, ErrorReported, Failure, Success, TtParser};
+use crate::mbe::macro_rules::Tracker;
+
+use rustc_ast::token::Token;
+use rustc_ast::tokenstream::TokenStream;
+use rustc_ast_pretty::pprust;
+use rustc_session::Session;
+use rustc_span::source_map::SourceMap;
+use rustc_span::symbol::Ident;
+use rustc_span::Span;
+use serde::Serialize;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+/// The trace of one expansion of a `macro_rules!` macro.
+#[derive(Serialize)]
+pub(crate) struct MacroTrace {
+    #[serde(rename = "macro")]
+    name: String,
+    call_site: String,
+    def_site: String,
+    /// The number of expansions this one is nested in.
+    depth: usize,
+    input: String,
+    /// The arms that were tried, in order.
+    arms: Vec<ArmTrace>,
+    matched_arm: Option<usize>,
+    /// The fragments that the metavariables of the matched arm were bound to.
+    bindings: BTreeMap<String, Binding>,
+    /// The tokens produced by the matched arm, if it could be transcribed.
+    output: Option<String>,
+}
+
+impl MacroTrace {
+    pub(crate) fn set_output(&mut self, tts: &TokenStream) {
+        self.output = Some(pprust::tts_to_string(tts));
+    }
+}
+
+#[derive(Serialize)]
+struct ArmTrace {
+    index: usize,
+    #[serde(flatten)]
+    outcome: ArmOutcome,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "result", rename_all = "kebab-case")]
+enum ArmOutcome {
+    Matched,
+    /// The arm expected `expected` where the input had `token`.
+    Failed { token: String, span: String, expected: Option<String>, message: &'static str },
+    /// Matching stopped at a fatal error, such as a fragment that failed to parse.
+    Error { message: Option<String> },
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Binding {
+    Fragment(String),
+    Repetition(Vec<Binding>),
+}
+
+impl Binding {
+    fn new(named_match: &NamedMatch) -> Binding {
+        match named_match {
+            NamedMatch::MatchedSeq(matches) => {
+                Binding::Repetition(matches.iter().map(Binding::new).collect())
+            }
+            NamedMatch::MatchedTokenTree(tt) => Binding::Fragment(pprust::tt_to_string(tt)),
+            NamedMatch::MatchedNonterminal(nt) => {
+                Binding::Fragment(pprust::nonterminal_to_string(&nt.0))
+            }
+        }
+    }
+}
+
+/// Records the outcome of each arm while matching, like `CollectTrackerAndEmitter` does for the
+/// diagnostics of failed matches.
+pub(super) struct TraceTracker<'a, 'matcher> {
+    source_map: &'a SourceMap,
+    arms: Vec<ArmTrace>,
+    remaining_matcher: Option<&'matcher MatcherLoc>,
+}
+
+impl<'a, 'matcher> Tracker<'matcher> for TraceTracker<'a, 'matcher> {
+    type Failure = (Token, usize, &'static str);
+
+    fn build_failure(tok: Token, position: usize, msg: &'static str) -> Self::Failure {
+        (tok, position, msg)
+    }
+
+    fn before_match_loc(&mut self, parser: &TtParser, matcher: &'matcher MatcherLoc) {
+        if self.remaining_matcher.is_none()
+            || (parser.has_no_remaining_items_for_step() && *matcher != MatcherLoc::Eof)
+        {
+            self.remaining_matcher = Some(matcher);
+        }
+    }
+
+    fn after_arm(&mut self, result: &NamedParseResult<Self::Failure>) {
+        let outcome = match result {
+            Success(_) => ArmOutcome::Matched,
+            Failure((token, _, msg)) => ArmOutcome::Failed {
+                token: pprust::token_to_string(token).into_owned(),
+                span: self.source_map.span_to_embeddable_string(token.span),
+                expected: self.remaining_matcher.map(|matcher| matcher.to_string()),
+                message: msg,
+            },
+            Error(_, msg) => ArmOutcome::Error { message: Some(msg.clone()) },
+            ErrorReported(_) => ArmOutcome::Error { message: None },
+        };
+        self.arms.push(ArmTrace { index: self.arms.len(), outcome });
+        self.remaining_matcher = None;
+    }
+
+    fn description() -> &'static str {
+        "trace"
+    }
+}
+
+impl<'a> TraceTracker<'a, '_> {
+    pub(super) fn new(source_map: &'a SourceMap) -> Self {
+        TraceTracker { source_map, arms: vec![], remaining_matcher: None }
+    }
+
+    /// Turns the arms recorded while matching the `input` of the invocation of `name` into a
+    /// trace, with the bindings of the arm that matched, if any.
+    pub(super) fn finish(
+        self,
+        cx: &ExtCtxt<'_>,
+        name: Ident,
+        call_site: Span,
+        def_site: Span,
+        input: &TokenStream,
+        matched: Option<&(usize, NamedMatches)>,
+    ) -> MacroTrace {
+        let bindings = matched
+            .into_iter()
+            .flat_map(|(_, named_matches)| named_matches)
+            .map(|(ident, named_match)| (ident.to_string(), Binding::new(named_match)))
+            .collect();
+        MacroTrace {
+            name: name.to_string(),
+            call_site: self.source_map.span_to_embeddable_string(call_site),
+            def_site: self.source_map.span_to_embeddable_string(def_site),
+            depth: cx.current_expansion.depth,
+            input: pprust::tts_to_string(input),
+            arms: self.arms,
+            matched_arm: matched.map(|&(i, _)| i),
+            bindings,
+            output: None,
+        }
+    }
+}
+
+/// Writes the traces to `path`, as a JSON array in the order the expansions happened.
+pub(crate) fn write_macro_traces(sess: &Session, traces: &[MacroTrace], path: &Path) {
+    let res: io::Result<()> = try {
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, traces)?;
+        file.flush()?;
+    };
+    if let Err(error) = res {
+        sess.dcx().emit_err(errors::TraceMacrosJsonWriteFailed { path, error });
+    }
+}
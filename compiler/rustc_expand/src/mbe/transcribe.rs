@@ -1,18 +1,20 @@
 use crate::base::ExtCtxt;
 use crate::errors::{
-    CountRepetitionMisplaced, MetaVarExprUnrecognizedVar, MetaVarsDifSeqMatchers, MustRepeatOnce,
-    NoSyntaxVarsExprRepeat, VarStillRepeating,
+    CountDepthOutOfBounds, CountRepetitionMisplaced, MetaVarExprConcatVarNotIdent,
+    MetaVarExprUnrecognizedVar, MetaVarsDifSeqMatchers, MustRepeatOnce, NoSyntaxVarsExprRepeat,
+    RepetitionDepthOutOfBounds, VarStillRepeating,
 };
 use crate::mbe::macro_parser::{MatchedNonterminal, MatchedSeq, MatchedTokenTree, NamedMatch};
+use crate::mbe::metavar_expr::MetaVarExprConcatElem;
 use crate::mbe::{self, KleeneOp, MetaVarExpr};
 use rustc_ast::mut_visit::{self, MutVisitor};
-use rustc_ast::token::{self, Delimiter, Token, TokenKind};
+use rustc_ast::token::{self, Delimiter, IdentIsRaw, Nonterminal, Token, TokenKind};
 use rustc_ast::tokenstream::{DelimSpacing, DelimSpan, Spacing, TokenStream, TokenTree};
 use rustc_data_structures::fx::FxHashMap;
 use rustc_errors::Diag;
 use rustc_errors::{pluralize, PResult};
 use rustc_span::hygiene::{LocalExpnId, Transparency};
-use rustc_span::symbol::{sym, Ident, MacroRulesNormalizedIdent};
+use rustc_span::symbol::{sym, Ident, MacroRulesNormalizedIdent, Symbol};
 use rustc_span::{with_metavar_spans, Span, SyntaxContext};
 
 use smallvec::{smallvec, SmallVec};
@@ -521,17 +523,15 @@ fn lockstep_iter_size(
             }
         }
         TokenTree::MetaVarExpr(_, expr) => {
-            let default_rslt = LockstepIterSize::Unconstrained;
-            let Some(ident) = expr.ident() else {
-                return default_rslt;
-            };
-            let name = MacroRulesNormalizedIdent::new(ident);
-            match lookup_cur_matched(name, interpolations, repeats) {
-                Some(MatchedSeq(ads)) => {
-                    default_rslt.with(LockstepIterSize::Constraint(ads.len(), name))
+            expr.for_each_metavar(LockstepIterSize::Unconstrained, |size, ident| {
+                let name = MacroRulesNormalizedIdent::new(*ident);
+                match lookup_cur_matched(name, interpolations, repeats) {
+                    Some(MatchedSeq(ads)) => {
+                        size.with(LockstepIterSize::Constraint(ads.len(), name))
+                    }
+                    _ => size,
                 }
-                _ => default_rslt,
-            }
+            })
         }
         TokenTree::Token(..) => LockstepIterSize::Unconstrained,
     }
@@ -548,6 +548,7 @@ fn lockstep_iter_size(
 ///   declared inside a single repetition and the index `1` implies two nested repetitions.
 fn count_repetitions<'a>(
     cx: &ExtCtxt<'a>,
+    ident: Ident,
     depth_user: usize,
     mut matched: &NamedMatch,
     repeats: &[(usize, usize)],
@@ -584,7 +585,9 @@ fn depth(counter: usize, matched: &NamedMatch) -> usize {
         .and_then(|el| el.checked_sub(repeats.len()))
         .unwrap_or_default();
     if depth_user > depth_max {
-        return Err(out_of_bounds_err(cx, depth_max + 1, sp.entire(), "count"));
+        let ident = MacroRulesNormalizedIdent::new(ident);
+        let max = depth_max + 1;
+        return Err(cx.dcx().create_err(CountDepthOutOfBounds { span: sp.entire(), ident, max }));
     }
 
     // `repeats` records all of the nested levels at which we are currently
@@ -622,19 +625,42 @@ fn matched_from_ident<'ctx, 'interp, 'rslt>(
 
 /// Used by meta-variable expressions when an user input is out of the actual declared bounds. For
 /// example, index(999999) in an repetition of only three elements.
-fn out_of_bounds_err<'a>(cx: &ExtCtxt<'a>, max: usize, span: Span, ty: &str) -> Diag<'a> {
-    let msg = if max == 0 {
-        format!(
-            "meta-variable expression `{ty}` with depth parameter \
-             must be called inside of a macro repetition"
-        )
-    } else {
-        format!(
-            "depth parameter of meta-variable expression `{ty}` \
-             must be less than {max}"
-        )
+fn out_of_bounds_err<'a>(
+    cx: &ExtCtxt<'a>,
+    max: usize,
+    span: Span,
+    expr: &'static str,
+) -> Diag<'a> {
+    cx.dcx().create_err(RepetitionDepthOutOfBounds { span, expr, max })
+}
+
+/// Returns the identifier that the meta-variable `ident` of a `concat` expression is bound to at
+/// the current repetition.
+fn concat_var_ident<'a>(
+    cx: &ExtCtxt<'a>,
+    ident: Ident,
+    interp: &FxHashMap<MacroRulesNormalizedIdent, NamedMatch>,
+    repeats: &[(usize, usize)],
+) -> PResult<'a, Symbol> {
+    let key = MacroRulesNormalizedIdent::new(ident);
+    let span = ident.span;
+    let Some(matched) = lookup_cur_matched(key, interp, repeats) else {
+        return Err(cx.dcx().create_err(MetaVarExprUnrecognizedVar { span, key }));
     };
-    cx.dcx().struct_span_err(span, msg)
+    match matched {
+        MatchedTokenTree(TokenTree::Token(token, _))
+            if let Some((ident, IdentIsRaw::No)) = token.ident() =>
+        {
+            Ok(ident.name)
+        }
+        MatchedNonterminal(nt) if let Nonterminal::NtIdent(ident, IdentIsRaw::No) = nt.0 => {
+            Ok(ident.name)
+        }
+        MatchedSeq(_) => Err(cx.dcx().create_err(VarStillRepeating { span, ident: key })),
+        MatchedTokenTree(_) | MatchedNonterminal(_) => {
+            Err(cx.dcx().create_err(MetaVarExprConcatVarNotIdent { span, ident: key }))
+        }
+    }
 }
 
 fn transcribe_metavar_expr<'a>(
@@ -652,9 +678,25 @@ fn transcribe_metavar_expr<'a>(
         span
     };
     match *expr {
+        MetaVarExpr::Concat(ref elems) => {
+            let mut concatenated = String::new();
+            for elem in elems.iter() {
+                let symbol = match *elem {
+                    MetaVarExprConcatElem::Ident(ident) => ident.name,
+                    MetaVarExprConcatElem::Var(ident) => {
+                        concat_var_ident(cx, ident, interp, repeats)?
+                    }
+                };
+                concatenated.push_str(symbol.as_str());
+            }
+            // The identifier gets the span of the expression in the macro definition, so it is
+            // hygienic like an identifier written there.
+            let ident = Ident::new(Symbol::intern(&concatenated), visited_span());
+            result.push(TokenTree::Token(Token::from_ast_ident(ident), Spacing::Alone));
+        }
         MetaVarExpr::Count(original_ident, depth) => {
             let matched = matched_from_ident(cx, original_ident, interp)?;
-            let count = count_repetitions(cx, depth, matched, repeats, sp)?;
+            let count = count_repetitions(cx, original_ident, depth, matched, repeats, sp)?;
             let tt = TokenTree::token_alone(
                 TokenKind::lit(token::Integer, sym::integer(count), None),
                 visited_span(),
@@ -434,6 +434,11 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, deprecated_safe, "1.61.0", Some(94978)),
     /// Allows having using `suggestion` in the `#[deprecated]` attribute.
     (unstable, deprecated_suggestion, "1.61.0", Some(94785)),
+    /// Allows `#[default]` on enum variants with fields and `#[default(no_bound)]` on type
+    /// parameters in `#[derive(Default)]`.
+    (unstable, derive_default_extensions, "CURRENT_RUSTC_VERSION", None),
+    /// Allows `#[eq(..)]`, `#[ord(..)]` and `#[hash(..)]` on fields to customize builtin derives.
+    (unstable, derive_field_attrs, "CURRENT_RUSTC_VERSION", None),
     /// Allows using the `#[diagnostic]` attribute tool namespace
     (unstable, diagnostic_namespace, "1.73.0", Some(111996)),
     /// Controls errors in trait implementations.
@@ -510,6 +515,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, lint_reasons, "1.31.0", Some(54503)),
     /// Give access to additional metadata about declarative macro meta-variables.
     (unstable, macro_metavar_expr, "1.61.0", Some(83527)),
+    /// Allows `${concat(..)}` in declarative macros, which concatenates identifiers.
+    (unstable, macro_metavar_expr_concat, "CURRENT_RUSTC_VERSION", None),
     /// Allows `#[marker]` on certain traits allowing overlapping implementations.
     (unstable, marker_trait_attr, "1.30.0", Some(29864)),
     /// Allows exhaustive pattern matching on types that contain uninhabited types in cases that are
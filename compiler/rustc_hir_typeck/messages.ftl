@@ -31,6 +31,19 @@ hir_typeck_cast_unknown_pointer = cannot cast {$to ->
     .note = the type information given here is insufficient to check whether the pointer cast is valid
     .label_from = the type information given here is insufficient to check whether the pointer cast is valid
 
+hir_typeck_closure_capture = `{$place}` is captured {$kind ->
+    [by_value] by value
+    [imm_borrow] by immutable borrow
+    [unique_imm_borrow] by unique immutable borrow
+    *[mut_borrow] by mutable borrow
+    }
+
+hir_typeck_closure_captures = this {$descr} captures {$count ->
+    [0] nothing
+    [one] one place
+    *[other] {$count} places
+    }
+
 hir_typeck_const_select_must_be_const = this argument must be a `const fn`
     .help = consult the documentation on `const_eval_select` for more information
 
@@ -74,6 +87,8 @@ hir_typeck_functional_record_update_on_non_struct =
 hir_typeck_help_set_edition_cargo = set `edition = "{$edition}"` in `Cargo.toml`
 hir_typeck_help_set_edition_standalone = pass `--edition {$edition}` to `rustc`
 
+hir_typeck_inferred_types_write_failed = failed to write inferred types to {$path}: {$error}
+
 hir_typeck_int_to_fat = cannot cast `{$expr_ty}` to a pointer that {$known_wide ->
     [true] is
     *[false] may be
@@ -83,6 +98,11 @@ hir_typeck_int_to_fat_label_nightly = consider casting this expression to `*cons
 
 hir_typeck_invalid_callee = expected function, found {$ty}
 
+hir_typeck_large_closure_capture = this `move` {$descr} captures `{$place}` by value, which is {$size} bytes
+    .label = `{$place}` of type `{$ty}` is captured by value here
+    .note = the limit is {$limit} bytes
+    .help = borrow `{$place}` before the {$descr} and move the reference in instead
+
 hir_typeck_lossy_provenance_int2ptr =
     strict provenance disallows casting integer `{$expr_ty}` to pointer `{$cast_ty}`
     .suggestion = use `.with_addr()` to adjust a valid pointer in the same allocation, to this address
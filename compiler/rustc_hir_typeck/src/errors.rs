@@ -1,5 +1,7 @@
 //! Errors emitted by `rustc_hir_typeck`.
 use std::borrow::Cow;
+use std::io;
+use std::path::Path;
 
 use crate::fluent_generated as fluent;
 use rustc_errors::{
@@ -534,6 +536,38 @@ pub struct CastEnumDrop<'tcx> {
     pub cast_ty: Ty<'tcx>,
 }
 
+#[derive(LintDiagnostic)]
+#[diag(hir_typeck_closure_captures)]
+pub struct ClosureCaptures {
+    pub descr: &'static str,
+    pub count: usize,
+    #[subdiagnostic]
+    pub captures: Vec<ClosureCapture>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(hir_typeck_closure_capture)]
+pub struct ClosureCapture {
+    #[primary_span]
+    pub span: Span,
+    pub place: String,
+    pub kind: &'static str,
+}
+
+#[derive(LintDiagnostic)]
+#[diag(hir_typeck_large_closure_capture)]
+#[note]
+#[help]
+pub struct LargeClosureCapture<'tcx> {
+    #[label]
+    pub capture_span: Span,
+    pub descr: &'static str,
+    pub place: String,
+    pub ty: Ty<'tcx>,
+    pub size: u64,
+    pub limit: usize,
+}
+
 #[derive(Diagnostic)]
 #[diag(hir_typeck_cast_unknown_pointer, code = E0641)]
 pub struct CastUnknownPointer {
@@ -614,3 +648,10 @@ pub struct SuggestConvertViaMethod<'tcx> {
     pub expected: Ty<'tcx>,
     pub found: Ty<'tcx>,
 }
+
+#[derive(Diagnostic)]
+#[diag(hir_typeck_inferred_types_write_failed)]
+pub struct InferredTypesWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
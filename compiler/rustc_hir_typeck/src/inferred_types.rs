@@ -0,0 +1,114 @@
+//! JSON dumps of the types inferred for `let` bindings, closure parameters and method calls,
+//! written by `-Z print-inferred-types`.
+
+use crate::errors::InferredTypesWriteFailed;
+use rustc_hir as hir;
+use rustc_hir::def_id::LocalDefId;
+use rustc_hir::intravisit::{self, Visitor};
+use rustc_middle::ty::print::with_forced_trimmed_paths;
+use rustc_middle::ty::{Ty, TyCtxt, TypeckResults};
+use rustc_span::symbol::Ident;
+use rustc_span::Span;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Serialize)]
+struct InferredType {
+    /// One of `let`, `closure_param` or `method_call`.
+    kind: &'static str,
+    /// The name of the binding, or of the called method.
+    name: String,
+    span: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+/// Writes the types inferred in all the bodies of the local crate as JSON to `path`.
+pub fn dump_inferred_types(tcx: TyCtxt<'_>, path: &Path) {
+    let mut types = vec![];
+    for def_id in tcx.hir().body_owners() {
+        collect_inferred_types(tcx, def_id, &mut types);
+    }
+    // Body owners aren't visited in source order, closures come after their parent.
+    types.sort_by_key(|&(span, _)| span);
+    let types: Vec<_> = types.into_iter().map(|(_, ty)| ty).collect();
+    if let Err(error) = write_types(&types, path) {
+        tcx.dcx().emit_err(InferredTypesWriteFailed { path, error });
+    }
+}
+
+fn write_types(types: &[InferredType], path: &Path) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, types)?;
+    file.flush()
+}
+
+/// Collects the types of the body of `def_id`. Nested closures are body owners of their own, so
+/// they are collected separately.
+fn collect_inferred_types<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: LocalDefId,
+    types: &mut Vec<(Span, InferredType)>,
+) {
+    let body = tcx.hir().body(tcx.hir().body_owned_by(def_id));
+    let mut collector = InferredTypesCollector { tcx, typeck_results: tcx.typeck(def_id), types };
+    if tcx.is_closure_like(def_id.to_def_id()) {
+        for param in body.params {
+            collector.record_bindings("closure_param", param.pat);
+        }
+    }
+    collector.visit_body(body);
+}
+
+struct InferredTypesCollector<'a, 'tcx> {
+    tcx: TyCtxt<'tcx>,
+    typeck_results: &'tcx TypeckResults<'tcx>,
+    types: &'a mut Vec<(Span, InferredType)>,
+}
+
+impl<'a, 'tcx> InferredTypesCollector<'a, 'tcx> {
+    fn record(&mut self, kind: &'static str, ident: Ident, span: Span, ty: Ty<'tcx>) {
+        // Skip what macros expanded to, the user can't see it.
+        if span.from_expansion() {
+            return;
+        }
+        self.types.push((
+            span,
+            InferredType {
+                kind,
+                name: ident.to_string(),
+                span: self.tcx.sess.source_map().span_to_embeddable_string(span),
+                ty: with_forced_trimmed_paths!(ty.to_string()),
+            },
+        ));
+    }
+
+    fn record_bindings(&mut self, kind: &'static str, pat: &'tcx hir::Pat<'tcx>) {
+        pat.each_binding(|_, hir_id, _, ident| {
+            let Some(ty) = self.typeck_results.node_type_opt(hir_id) else {
+                return;
+            };
+            self.record(kind, ident, ident.span, ty);
+        });
+    }
+}
+
+impl<'a, 'tcx> Visitor<'tcx> for InferredTypesCollector<'a, 'tcx> {
+    fn visit_local(&mut self, local: &'tcx hir::Local<'tcx>) {
+        if local.source == hir::LocalSource::Normal {
+            self.record_bindings("let", local.pat);
+        }
+        intravisit::walk_local(self, local);
+    }
+
+    fn visit_expr(&mut self, expr: &'tcx hir::Expr<'tcx>) {
+        if let hir::ExprKind::MethodCall(segment, _, _, span) = expr.kind
+            && let Some(ty) = self.typeck_results.node_type_opt(expr.hir_id)
+        {
+            self.record("method_call", segment.ident, span, ty);
+        }
+        intravisit::walk_expr(self, expr);
+    }
+}
@@ -32,6 +32,7 @@
 mod fallback;
 mod fn_ctxt;
 mod gather_locals;
+mod inferred_types;
 mod inherited;
 mod intrinsicck;
 mod mem_categorization;
@@ -44,6 +45,7 @@
 mod writeback;
 
 pub use fn_ctxt::FnCtxt;
+pub use inferred_types::dump_inferred_types;
 pub use inherited::Inherited;
 
 use crate::check::check_fn;
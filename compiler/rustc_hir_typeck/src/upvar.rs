@@ -32,6 +32,7 @@
 
 use super::FnCtxt;
 
+use crate::errors;
 use crate::expr_use_visitor as euv;
 use rustc_data_structures::unord::{ExtendUnord, UnordSet};
 use rustc_errors::{Applicability, MultiSpan};
@@ -43,7 +44,8 @@
 use rustc_middle::mir::FakeReadCause;
 use rustc_middle::traits::ObligationCauseCode;
 use rustc_middle::ty::{
-    self, ClosureSizeProfileData, Ty, TyCtxt, TypeckResults, UpvarArgs, UpvarCapture,
+    self, ClosureSizeProfileData, Ty, TyCtxt, TypeVisitableExt, TypeckResults, UpvarArgs,
+    UpvarCapture,
 };
 use rustc_session::lint;
 use rustc_span::sym;
@@ -415,6 +417,7 @@ fn analyze_closure(
         }
 
         self.log_closure_min_capture_info(closure_def_id, span);
+        self.lint_closure_captures(closure_def_id, capture_clause, span);
 
         // Now that we've analyzed the closure, we know how each
         // variable is borrowed, and we know what traits the closure
@@ -1696,6 +1699,97 @@ fn log_closure_min_capture_info(&self, closure_def_id: LocalDefId, closure_span:
         }
     }
 
+    /// Reports the final captures of `closure_def_id` through the `closure_captures` lint, and
+    /// lints large by-value captures of `move` closures through `large_closure_captures`.
+    fn lint_closure_captures(
+        &self,
+        closure_def_id: LocalDefId,
+        capture_clause: hir::CaptureBy,
+        closure_span: Span,
+    ) {
+        let closure_hir_id = self.tcx.local_def_id_to_hir_id(closure_def_id);
+        let lint_enabled = |lint| {
+            !matches!(self.tcx.lint_level_at_node(lint, closure_hir_id).0, lint::Level::Allow)
+        };
+        let report = lint_enabled(lint::builtin::CLOSURE_CAPTURES);
+        let check_size = matches!(capture_clause, hir::CaptureBy::Value { .. })
+            && lint_enabled(lint::builtin::LARGE_CLOSURE_CAPTURES);
+        if !report && !check_size {
+            return;
+        }
+
+        let descr = self.tcx.def_descr(closure_def_id.to_def_id());
+        let head_span = self.tcx.def_span(closure_def_id);
+        let limit = match self.tcx.move_size_limit().0 {
+            0 => DEFAULT_LARGE_CLOSURE_CAPTURE_LIMIT,
+            limit => limit,
+        };
+
+        let mut captures = vec![];
+        for capture in self.typeck_results.borrow().closure_min_captures_flattened(closure_def_id)
+        {
+            let place = capture.to_string(self.tcx);
+            let span = capture
+                .info
+                .capture_kind_expr_id
+                .or(capture.info.path_expr_id)
+                .map_or(closure_span, |e| self.tcx.hir().span(e));
+            let kind = match capture.info.capture_kind {
+                UpvarCapture::ByValue => "by_value",
+                UpvarCapture::ByRef(ty::BorrowKind::ImmBorrow) => "imm_borrow",
+                UpvarCapture::ByRef(ty::BorrowKind::UniqueImmBorrow) => "unique_imm_borrow",
+                UpvarCapture::ByRef(ty::BorrowKind::MutBorrow) => "mut_borrow",
+            };
+
+            if check_size && let UpvarCapture::ByValue = capture.info.capture_kind {
+                let ty = self.tcx.erase_regions(self.resolve_vars_if_possible(capture.place.ty()));
+                // Computing the layout of closures and opaque types may need the typeck results
+                // of this body, which we are still building, so skip those.
+                let needs_typeck = ty.walk().any(|arg| {
+                    arg.as_type().is_some_and(|ty| {
+                        matches!(
+                            ty.kind(),
+                            ty::Closure(..)
+                                | ty::CoroutineClosure(..)
+                                | ty::Coroutine(..)
+                                | ty::Alias(ty::Opaque, _)
+                        )
+                    })
+                });
+                if !needs_typeck
+                    && !ty.has_non_region_infer()
+                    && let Ok(layout) = self.tcx.layout_of(self.param_env.and(ty))
+                    && layout.size.bytes() > limit as u64
+                {
+                    self.tcx.emit_node_span_lint(
+                        lint::builtin::LARGE_CLOSURE_CAPTURES,
+                        closure_hir_id,
+                        head_span,
+                        errors::LargeClosureCapture {
+                            capture_span: span,
+                            descr,
+                            place: place.clone(),
+                            ty,
+                            size: layout.size.bytes(),
+                            limit,
+                        },
+                    );
+                }
+            }
+
+            captures.push(errors::ClosureCapture { span, place, kind });
+        }
+
+        if report {
+            self.tcx.emit_node_span_lint(
+                lint::builtin::CLOSURE_CAPTURES,
+                closure_hir_id,
+                head_span,
+                errors::ClosureCaptures { descr, count: captures.len(), captures },
+            );
+        }
+    }
+
     /// A captured place is mutable if
     /// 1. Projections don't include a Deref of an immut-borrow, **and**
     /// 2. PlaceBase is mut or projections include a Deref of a mut-borrow.
@@ -1739,6 +1833,10 @@ fn determine_capture_mutability(
     }
 }
 
+/// The size above which `large_closure_captures` lints by-value captures, unless
+/// `#![move_size_limit]` sets another one.
+const DEFAULT_LARGE_CLOSURE_CAPTURE_LIMIT: usize = 1024;
+
 /// Truncate the capture so that the place being borrowed is in accordance with RFC 1240,
 /// which states that it's unsafe to take a reference into a struct marked `repr(packed)`.
 fn restrict_repr_packed_field_ref_capture<'tcx>(
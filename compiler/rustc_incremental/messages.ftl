@@ -11,6 +11,9 @@ incremental_associated_value_expected = expected an associated value
 
 incremental_associated_value_expected_for = associated value expected for `{$ident}`
 
+incremental_cache_rejected =
+    not using incremental compilation cache artifact `{$path}`: {$reason}
+
 incremental_canonicalize_path = incremental compilation: error canonicalizing path `{$path}`: {$err}
 
 incremental_cargo_help_1 =
@@ -99,4 +102,7 @@ incremental_unrecognized_depnode_label = dep-node label `{$label}` not recognize
 
 incremental_write_dep_graph = failed to write dependency graph to `{$path}`: {$err}
 
+incremental_write_explanation =
+    failed to write incremental compilation explanation to `{$path}`: {$err}
+
 incremental_write_new = failed to write {$name} to `{$path}`: {$err}
@@ -241,6 +241,13 @@ pub struct FinalizedGcFailed<'a> {
     pub err: std::io::Error,
 }
 
+#[derive(Diagnostic)]
+#[diag(incremental_cache_rejected)]
+pub struct CacheRejected<'a> {
+    pub path: &'a Path,
+    pub reason: &'a str,
+}
+
 #[derive(Diagnostic)]
 #[diag(incremental_session_gc_failed)]
 pub struct SessionGcFailed<'a> {
@@ -306,3 +313,10 @@ pub struct DeleteWorkProduct<'a> {
     pub path: &'a Path,
     pub err: std::io::Error,
 }
+
+#[derive(Diagnostic)]
+#[diag(incremental_write_explanation)]
+pub struct WriteExplanation<'a> {
+    pub path: &'a Path,
+    pub err: std::io::Error,
+}
@@ -0,0 +1,96 @@
+//! Support for `-Zincremental-explain`, which writes a JSON report explaining
+//! why queries had to be re-executed and codegen units had to be re-codegened
+//! during an incremental session.
+//!
+//! Whenever the dep-graph fails to mark a node of the previous session as green,
+//! it records the dependency that prevented it. Following those records from a
+//! node leads to an input that changed, or to a node that had to be recomputed
+//! and produced a different result. The report contains such a chain for every
+//! node that could not be reused, ordered from the changed input to the node.
+
+use crate::errors;
+use rustc_middle::dep_graph::{dep_kinds, DepNode};
+use rustc_middle::ty::TyCtxt;
+
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+#[derive(serde::Serialize)]
+struct Explanation {
+    queries: Vec<ReExecutedQuery>,
+    codegen_units: Vec<ReCodegenedUnit>,
+}
+
+#[derive(serde::Serialize)]
+struct ReExecutedQuery {
+    query: String,
+    /// Whether the query result actually changed compared to the previous session.
+    changed: bool,
+    red_chain: Vec<String>,
+}
+
+#[derive(serde::Serialize)]
+struct ReCodegenedUnit {
+    name: String,
+    reason: &'static str,
+    red_chain: Vec<String>,
+}
+
+pub(crate) fn write_explanation(tcx: TyCtxt<'_>, path: &Path) {
+    let explanation = collect_explanation(tcx);
+    if let Err(err) = write_json(path, &explanation) {
+        tcx.dcx().emit_err(errors::WriteExplanation { path, err });
+    }
+}
+
+fn collect_explanation(tcx: TyCtxt<'_>) -> Explanation {
+    let dep_graph = &tcx.dep_graph;
+    let describe = |chain: Vec<DepNode>| -> Vec<String> {
+        chain.iter().map(|node| format!("{node:?}")).collect()
+    };
+
+    let queries = dep_graph
+        .nodes_with_red_cause()
+        .into_iter()
+        .filter(|node| node.kind != dep_kinds::CompileCodegenUnit)
+        .filter_map(|node| {
+            let chain = dep_graph.red_cause_chain(&node)?;
+            Some(ReExecutedQuery {
+                query: format!("{node:?}"),
+                changed: dep_graph.is_red(&node),
+                red_chain: describe(chain),
+            })
+        })
+        .collect();
+
+    // Codegen units only exist if we generated code, in which case they have already been
+    // partitioned and we don't end up executing any new queries here.
+    let mut codegen_units = Vec::new();
+    if tcx.sess.opts.output_types.should_codegen() {
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            let (reason, red_chain) = match dep_graph.red_cause_chain(&cgu.codegen_dep_node(tcx)) {
+                Some(chain) => ("dependencies changed", describe(chain)),
+                None if dep_graph.previous_work_product(&cgu.work_product_id()).is_none() => {
+                    ("no cached work product", Vec::new())
+                }
+                None => continue,
+            };
+            codegen_units.push(ReCodegenedUnit {
+                name: cgu.name().to_string(),
+                reason,
+                red_chain,
+            });
+        }
+        codegen_units.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    Explanation { queries, codegen_units }
+}
+
+fn write_json(path: &Path, explanation: &Explanation) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, explanation)?;
+    writeln!(file)?;
+    file.flush()
+}
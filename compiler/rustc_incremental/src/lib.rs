@@ -13,6 +13,7 @@
 
 mod assert_dep_graph;
 mod errors;
+mod explain;
 mod persist;
 
 pub use persist::copy_cgu_workproduct_to_incr_comp_cache_dir;
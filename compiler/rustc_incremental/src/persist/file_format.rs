@@ -87,12 +87,7 @@ pub(crate) fn save_in<F>(sess: &Session, path_buf: PathBuf, name: &str, encode:
 ///   incompatible version of the compiler.
 /// - Returns `Err(..)` if some kind of IO error occurred while reading the
 ///   file.
-pub fn read_file(
-    path: &Path,
-    report_incremental_info: bool,
-    is_nightly_build: bool,
-    cfg_version: &'static str,
-) -> io::Result<Option<(Mmap, usize)>> {
+pub fn read_file(sess: &Session, path: &Path) -> io::Result<Option<(Mmap, usize)>> {
     let file = match fs::File::open(path) {
         Ok(file) => file,
         Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
@@ -114,7 +109,7 @@ pub fn read_file(
         let mut file_magic = [0u8; 4];
         file.read_exact(&mut file_magic)?;
         if file_magic != FILE_MAGIC {
-            report_format_mismatch(report_incremental_info, path, "Wrong FILE_MAGIC");
+            report_format_mismatch(sess, path, "Wrong FILE_MAGIC");
             return Ok(None);
         }
     }
@@ -128,7 +123,7 @@ pub fn read_file(
             (header_format_version[0] as u16) | ((header_format_version[1] as u16) << 8);
 
         if header_format_version != HEADER_FORMAT_VERSION {
-            report_format_mismatch(report_incremental_info, path, "Wrong HEADER_FORMAT_VERSION");
+            report_format_mismatch(sess, path, "Wrong HEADER_FORMAT_VERSION");
             return Ok(None);
         }
     }
@@ -141,8 +136,8 @@ pub fn read_file(
         let mut buffer = vec![0; rustc_version_str_len];
         file.read_exact(&mut buffer)?;
 
-        if buffer != rustc_version(is_nightly_build, cfg_version).as_bytes() {
-            report_format_mismatch(report_incremental_info, path, "Different compiler version");
+        if buffer != rustc_version(sess.is_nightly_build(), sess.cfg_version).as_bytes() {
+            report_format_mismatch(sess, path, "Different compiler version");
             return Ok(None);
         }
     }
@@ -151,10 +146,14 @@ pub fn read_file(
     Ok(Some((mmap, post_header_start_pos)))
 }
 
-fn report_format_mismatch(report_incremental_info: bool, file: &Path, message: &str) {
+fn report_format_mismatch(sess: &Session, file: &Path, message: &str) {
     debug!("read_file: {}", message);
 
-    if report_incremental_info {
+    if sess.opts.unstable_opts.incremental_verify_cache {
+        sess.dcx().emit_warn(errors::CacheRejected { path: file, reason: message });
+    }
+
+    if sess.opts.unstable_opts.incremental_info {
         eprintln!(
             "[incremental] ignoring cache artifact `{}`: {}",
             file.file_name().unwrap().to_string_lossy(),
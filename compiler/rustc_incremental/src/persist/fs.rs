@@ -50,6 +50,17 @@
 //! any more and will delete those. It will also delete any finalized session
 //! directories for a given crate except for the most recent one.
 //!
+//! The above only ever looks at the directory of the crate being compiled, so
+//! the session directories of crates that are not compiled any more (or that
+//! are compiled with a different `StableCrateId`) are never cleaned up. When
+//! `-Zincremental-cache-limit` is given, the compiler additionally scans the
+//! whole incremental compilation directory after finalizing its own session
+//! directory and evicts finalized session directories of any crate, least
+//! recently used first, until the cache fits into the configured size and age
+//! budget. The timestamp in the name of a finalized session directory records
+//! when the session that produced it started, which makes it a good proxy for
+//! when that crate's cache was last used.
+//!
 //! ## Synchronization
 //!
 //! There is some synchronization needed in order for the compiler to be able to
@@ -104,12 +115,14 @@
 //! implemented.
 
 use crate::errors;
-use rustc_data_structures::fx::{FxHashSet, FxIndexSet};
+use rustc_data_structures::fx::{FxHashSet, FxIndexMap, FxIndexSet};
 use rustc_data_structures::svh::Svh;
-use rustc_data_structures::unord::{UnordMap, UnordSet};
+use rustc_data_structures::unord::{ExtendUnord, UnordMap, UnordSet};
 use rustc_data_structures::{base_n, flock};
 use rustc_errors::ErrorGuaranteed;
 use rustc_fs_util::{link_or_copy, try_canonicalize, LinkOrCopy};
+use rustc_middle::dep_graph::{WorkProduct, WorkProductId};
+use rustc_session::config::IncrementalCacheLimit;
 use rustc_session::{Session, StableCrateId};
 use rustc_span::Symbol;
 
@@ -374,6 +387,10 @@ pub fn finalize_session_directory(sess: &Session, svh: Option<Svh>) {
     }
 
     let _ = garbage_collect_session_directories(sess);
+
+    if let Some(limit) = sess.opts.unstable_opts.incremental_cache_limit {
+        let _ = enforce_cache_limit(sess, limit);
+    }
 }
 
 pub(crate) fn delete_all_session_dir_contents(sess: &Session) -> io::Result<()> {
@@ -844,6 +861,238 @@ pub(crate) fn garbage_collect_session_directories(sess: &Session) -> io::Result<
     Ok(())
 }
 
+/// A finalized session directory that may be evicted by [`enforce_cache_limit`].
+#[derive(Debug)]
+struct CacheEntry {
+    path: PathBuf,
+    timestamp: SystemTime,
+    size: u64,
+}
+
+/// Evicts whole finalized session directories from the incremental compilation
+/// directory, least recently used first, until the remaining ones fit into the
+/// budget given by `-Zincremental-cache-limit`.
+///
+/// The session directory of the current session is never evicted, and neither
+/// is any directory that is locked by another compiler process.
+pub(crate) fn enforce_cache_limit(sess: &Session, limit: IncrementalCacheLimit) -> io::Result<()> {
+    let _timer = sess.timer("incr_comp_enforce_cache_limit");
+
+    let session_directory = sess.incr_comp_session_dir().clone();
+    let crate_directory = session_directory.parent().unwrap();
+    let incr_comp_directory = crate_directory.parent().unwrap();
+    debug!("enforce_cache_limit() - incremental directory: {}", incr_comp_directory.display());
+
+    let mut total_size = 0;
+    let mut candidates = Vec::new();
+
+    for crate_entry in incr_comp_directory.read_dir()? {
+        let Ok(crate_entry) = crate_entry else {
+            // Ignore any errors
+            continue;
+        };
+        let Ok(session_entries) = crate_entry.path().read_dir() else {
+            // Not a crate directory, leave it alone
+            continue;
+        };
+
+        for dir_entry in session_entries {
+            let Ok(dir_entry) = dir_entry else {
+                continue;
+            };
+
+            let entry_name = dir_entry.file_name();
+            let entry_name = entry_name.to_string_lossy();
+            if !is_session_directory(&entry_name) {
+                continue;
+            }
+
+            let path = dir_entry.path();
+            let size = directory_size(&path);
+            total_size += size;
+
+            // Directories that are still being written to by some compiler
+            // process are accounted for, but never evicted.
+            if !is_finalized(&entry_name) || path == session_directory {
+                continue;
+            }
+
+            let Ok(timestamp) = extract_timestamp_from_session_dir(&entry_name) else {
+                debug!("found session-dir with malformed timestamp: {}", path.display());
+                continue;
+            };
+
+            candidates.push(CacheEntry { path, timestamp, size });
+        }
+    }
+
+    let to_evict = select_for_eviction(candidates, total_size, limit, SystemTime::now());
+
+    let mut reclaimed = 0;
+    let mut evicted = 0;
+    for entry in to_evict {
+        // Get an exclusive lock, so that we don't pull the directory out from
+        // under a process that is currently copying from it.
+        let Ok(lock) = flock::Lock::new(
+            &lock_file_path(&entry.path),
+            false, // don't wait
+            false, // don't create the lock-file
+            true,
+        ) else {
+            debug!("enforce_cache_limit() - not evicting, still in use: {}", entry.path.display());
+            continue;
+        };
+
+        debug!("enforce_cache_limit() - evicting `{}`", entry.path.display());
+        if let Err(err) = safe_remove_dir_all(&entry.path) {
+            sess.dcx().emit_warn(errors::SessionGcFailed { path: &entry.path, err });
+        } else {
+            delete_session_dir_lock_file(sess, &lock_file_path(&entry.path));
+            reclaimed += entry.size;
+            evicted += 1;
+
+            if sess.opts.unstable_opts.incremental_info {
+                eprintln!(
+                    "[incremental] cache limit: evicted `{}` ({} bytes)",
+                    entry.path.display(),
+                    entry.size
+                );
+            }
+
+            // Remove the crate directory if this was the last thing in it. This
+            // fails harmlessly if the directory is not empty.
+            let _ = std_fs::remove_dir(entry.path.parent().unwrap());
+        }
+
+        // Let's make it explicit that the file lock is released at this point,
+        // or rather, that we held on to it until here
+        drop(lock);
+    }
+
+    if sess.opts.unstable_opts.incremental_info {
+        eprintln!(
+            "[incremental] cache limit: evicted {evicted} session directories, \
+             reclaimed {reclaimed} of {total_size} bytes"
+        );
+    }
+
+    Ok(())
+}
+
+/// Picks the session directories that have to be evicted so that the total
+/// cache size, which is `total_size` before eviction, fits into `limit`.
+///
+/// Directories that are older than the age limit are always selected. After
+/// that, the least recently used directories are selected until the size limit
+/// is met.
+fn select_for_eviction(
+    mut candidates: Vec<CacheEntry>,
+    mut total_size: u64,
+    limit: IncrementalCacheLimit,
+    now: SystemTime,
+) -> Vec<CacheEntry> {
+    // Least recently used first
+    candidates.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then_with(|| a.path.cmp(&b.path)));
+
+    let mut to_evict = Vec::new();
+    for entry in candidates {
+        let too_old = limit.max_age.is_some_and(|max_age| {
+            now.duration_since(entry.timestamp).is_ok_and(|age| age > max_age)
+        });
+        let too_big = limit.max_size.is_some_and(|max_size| total_size > max_size);
+
+        if too_old || too_big {
+            total_size -= entry.size;
+            to_evict.push(entry);
+        }
+    }
+    to_evict
+}
+
+/// Computes the size of the files in a session directory. Session directories
+/// are flat, so there is no need to recurse into subdirectories.
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = path.read_dir() else {
+        return 0;
+    };
+    entries
+        .filter_map(|entry| entry.ok()?.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+/// Deletes files in the current session directory that look like the saved files
+/// of a work product, but are not referenced by any of the given work products,
+/// e.g. work products of a previous session that crashed before it could save its
+/// work product index. Other files, like the keys that ThinLTO keeps for the next
+/// session, are left alone.
+pub(crate) fn delete_unreferenced_files(
+    sess: &Session,
+    work_products: &FxIndexMap<WorkProductId, WorkProduct>,
+) {
+    let Ok(entries) = sess.incr_comp_session_dir().read_dir() else {
+        return;
+    };
+
+    let mut referenced = UnordSet::default();
+    let mut saved_file_exts = UnordSet::default();
+    for wp in work_products.values() {
+        referenced.extend_unord(wp.saved_files.items().map(|(_, file_name)| file_name.as_str()));
+        saved_file_exts.extend_unord(wp.saved_files.items().map(|(ext, _)| ext.as_str()));
+    }
+    // Besides their saved files, the compiler also keeps other per-CGU artifacts,
+    // like pre-LTO bitcode, in the session directory. Those are prefixed with
+    // the name of the CGU they belong to.
+    let cgu_names: FxHashSet<&str> =
+        work_products.values().map(|wp| wp.cgu_name.as_str()).collect();
+
+    for entry in entries {
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        let is_known = [
+            DEP_GRAPH_FILENAME,
+            STAGING_DEP_GRAPH_FILENAME,
+            WORK_PRODUCTS_FILENAME,
+            QUERY_CACHE_FILENAME,
+        ]
+        .contains(&&*file_name);
+
+        if is_known
+            || !is_work_product_file(&file_name, &saved_file_exts)
+            || is_cgu_artifact(&file_name, &cgu_names)
+            || referenced.contains(&*file_name)
+        {
+            continue;
+        }
+
+        let path = entry.path();
+        debug!("delete_unreferenced_files() - deleting `{}`", path.display());
+        if let Err(err) = safe_remove_file(&path) {
+            sess.dcx().emit_warn(errors::DeleteWorkProduct { path: &path, err });
+        }
+    }
+}
+
+/// Whether `file_name` is `<cgu name>.<extension>` for one of `cgu_names`. CGU names contain dots
+/// themselves (e.g. `krate.3bd6d5e9bfd1a1e1-cgu.0`), and so can extensions (e.g. `.pre-lto.bc`),
+/// so every dot is a possible end of the CGU name.
+fn is_cgu_artifact(file_name: &str, cgu_names: &FxHashSet<&str>) -> bool {
+    file_name.match_indices('.').any(|(i, _)| cgu_names.contains(&file_name[..i]))
+}
+
+/// Whether `file_name` is `<cgu name>.<extension>` for one of the extensions that work products
+/// save their files with.
+fn is_work_product_file(file_name: &str, saved_file_exts: &UnordSet<&str>) -> bool {
+    file_name
+        .match_indices('.')
+        .any(|(i, _)| i > 0 && saved_file_exts.contains(&file_name[i + 1..]))
+}
+
 fn delete_old(sess: &Session, path: &Path) {
     debug!("garbage_collect_session_directories() - deleting `{}`", path.display());
 
@@ -75,3 +75,69 @@ fn test_find_source_directory_in_iter() {
         None
     );
 }
+
+#[test]
+fn test_select_for_eviction() {
+    let now = UNIX_EPOCH + Duration::from_secs(100);
+    let entry = |secs, name: &str, size| CacheEntry {
+        path: PathBuf::from(name),
+        timestamp: UNIX_EPOCH + Duration::from_secs(secs),
+        size,
+    };
+    let candidates = || vec![entry(30, "c", 10), entry(10, "a", 10), entry(20, "b", 10)];
+    let evicted = |to_evict: Vec<CacheEntry>| {
+        to_evict.into_iter().map(|entry| entry.path).collect::<Vec<_>>()
+    };
+
+    // No limit
+    assert!(
+        select_for_eviction(candidates(), 40, IncrementalCacheLimit::default(), now).is_empty()
+    );
+
+    // Evict least recently used first, until the size limit is met
+    let limit = IncrementalCacheLimit { max_size: Some(25), max_age: None };
+    assert_eq!(
+        evicted(select_for_eviction(candidates(), 40, limit, now)),
+        vec![PathBuf::from("a"), PathBuf::from("b")]
+    );
+
+    // Evict everything older than the age limit
+    let limit = IncrementalCacheLimit { max_size: None, max_age: Some(Duration::from_secs(75)) };
+    assert_eq!(
+        evicted(select_for_eviction(candidates(), 40, limit, now)),
+        vec![PathBuf::from("a"), PathBuf::from("b")]
+    );
+
+    // Both limits at once
+    let limit = IncrementalCacheLimit {
+        max_size: Some(35),
+        max_age: Some(Duration::from_secs(85)),
+    };
+    assert_eq!(
+        evicted(select_for_eviction(candidates(), 40, limit, now)),
+        vec![PathBuf::from("a")]
+    );
+}
+
+#[test]
+fn test_is_cgu_artifact() {
+    let cgu_names: FxHashSet<&str> =
+        ["krate.3bd6d5e9bfd1a1e1-cgu.0", "krate.3bd6d5e9bfd1a1e1-cgu.1"].into_iter().collect();
+
+    assert!(is_cgu_artifact("krate.3bd6d5e9bfd1a1e1-cgu.0.o", &cgu_names));
+    assert!(is_cgu_artifact("krate.3bd6d5e9bfd1a1e1-cgu.1.pre-lto.bc", &cgu_names));
+    assert!(!is_cgu_artifact("krate.3bd6d5e9bfd1a1e1-cgu.2.o", &cgu_names));
+    assert!(!is_cgu_artifact("krate.3bd6d5e9bfd1a1e1-cgu.0", &cgu_names));
+    assert!(!is_cgu_artifact("krate.o", &cgu_names));
+}
+
+#[test]
+fn test_is_work_product_file() {
+    let saved_file_exts: UnordSet<&str> = ["o", "dwo"].into_iter().collect();
+
+    assert!(is_work_product_file("krate.3bd6d5e9bfd1a1e1-cgu.0.o", &saved_file_exts));
+    assert!(is_work_product_file("1fy9tq5sa1h0lhmy.dwo", &saved_file_exts));
+    assert!(!is_work_product_file("thin-lto-past-keys.bin", &saved_file_exts));
+    assert!(!is_work_product_file("krate.3bd6d5e9bfd1a1e1-cgu.0.pre-lto.bc", &saved_file_exts));
+    assert!(!is_work_product_file(".o", &saved_file_exts));
+}
@@ -67,12 +67,7 @@ pub fn open(self, sess: &Session) -> T {
 }
 
 fn load_data(path: &Path, sess: &Session) -> LoadResult<(Mmap, usize)> {
-    match file_format::read_file(
-        path,
-        sess.opts.unstable_opts.incremental_info,
-        sess.is_nightly_build(),
-        sess.cfg_version,
-    ) {
+    match file_format::read_file(sess, path) {
         Ok(Some(data_and_pos)) => LoadResult::Ok { data: data_and_pos },
         Ok(None) => {
             // The file either didn't exist or was produced by an incompatible
@@ -124,6 +119,12 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(SerializedDepGraph, WorkProduct
                     if !exists && sess.opts.unstable_opts.incremental_info {
                         eprintln!("incremental: could not find file for work product: {path}",);
                     }
+                    if !exists && sess.opts.unstable_opts.incremental_verify_cache {
+                        sess.dcx().emit_warn(errors::CacheRejected {
+                            path: &in_incr_comp_dir_sess(sess, path),
+                            reason: "file of work product is missing",
+                        });
+                    }
                     exists
                 });
 
@@ -154,6 +155,12 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(SerializedDepGraph, WorkProduct
                                     differing commandline arguments"
                     );
                 }
+                if sess.opts.unstable_opts.incremental_verify_cache {
+                    sess.dcx().emit_warn(errors::CacheRejected {
+                        path: &path,
+                        reason: "it was produced with differing command-line arguments",
+                    });
+                }
                 // We can't reuse the cache, purge it.
                 debug!("load_dep_graph_new: differing commandline arg hashes");
 
@@ -161,7 +168,28 @@ fn load_dep_graph(sess: &Session) -> LoadResult<(SerializedDepGraph, WorkProduct
                 return LoadResult::DataOutOfDate;
             }
 
-            let dep_graph = SerializedDepGraph::decode::<DepsType>(&mut decoder);
+            let verify = sess.opts.unstable_opts.incremental_verify_cache;
+            let dep_graph = SerializedDepGraph::decode::<DepsType>(&mut decoder).and_then(|graph| {
+                if verify {
+                    let _timer = sess.timer("incr_comp_verify_dep_graph");
+                    graph.verify()?;
+                }
+                Ok(graph)
+            });
+            let dep_graph = match dep_graph {
+                Ok(dep_graph) => dep_graph,
+                Err(reason) => {
+                    if verify {
+                        sess.dcx().emit_warn(errors::CacheRejected {
+                            path: &path,
+                            reason: &reason,
+                        });
+                    }
+                    // The graph is damaged, so we can't reuse the cache.
+                    debug!("load_dep_graph_new: invalid dep-graph: {reason}");
+                    return LoadResult::DataOutOfDate;
+                }
+            };
 
             LoadResult::Ok { data: (dep_graph, prev_work_products) }
         }
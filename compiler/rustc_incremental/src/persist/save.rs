@@ -1,5 +1,6 @@
 use crate::assert_dep_graph::assert_dep_graph;
 use crate::errors;
+use crate::explain;
 use rustc_data_structures::fx::FxIndexMap;
 use rustc_data_structures::sync::join;
 use rustc_middle::dep_graph::{
@@ -47,6 +48,10 @@ pub fn save_dep_graph(tcx: TyCtxt<'_>) {
             tcx.dep_graph.print_incremental_info()
         }
 
+        if let Some(path) = &sess.opts.unstable_opts.incremental_explain {
+            sess.time("incr_comp_explain", || explain::write_explanation(tcx, path));
+        }
+
         join(
             move || {
                 sess.time("incr_comp_persist_dep_graph", || {
@@ -112,6 +117,13 @@ pub fn save_work_product_index(
         }
     }
 
+    // Previous sessions may also have left behind files that are not tracked by
+    // any work product index at all. Only look for those if the user asked us to
+    // keep the cache small, as this requires scanning the session directory.
+    if sess.opts.unstable_opts.incremental_cache_limit.is_some() {
+        delete_unreferenced_files(sess, &new_work_products);
+    }
+
     // Check that we did not delete one of the current work-products:
     debug_assert!({
         new_work_products.iter().all(|(_, wp)| {
@@ -178,5 +190,7 @@ pub(crate) fn build_dep_graph(
         encoder,
         sess.opts.unstable_opts.query_dep_graph,
         sess.opts.unstable_opts.incremental_info,
+        sess.opts.unstable_opts.incremental_explain.is_some(),
+        sess.opts.unstable_opts.incremental_verify_cache,
     ))
 }
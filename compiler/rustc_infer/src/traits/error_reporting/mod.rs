@@ -1,4 +1,4 @@
-use super::ObjectSafetyViolation;
+use super::{MethodViolationCode, ObjectSafetyViolation, ObjectSafetyViolationSolution};
 
 use crate::infer::InferCtxt;
 use rustc_data_structures::fx::FxIndexSet;
@@ -6,7 +6,7 @@
 use rustc_hir as hir;
 use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_middle::ty::print::with_no_trimmed_paths;
-use rustc_middle::ty::{self, TyCtxt};
+use rustc_middle::ty::{self, Ty, TyCtxt};
 use rustc_span::Span;
 use std::fmt;
 use std::iter;
@@ -126,8 +126,20 @@ pub fn report_object_safety_error<'tcx>(
         let mut reported_violations: Vec<_> = reported_violations.into_iter().collect();
         reported_violations.sort();
 
-        let mut potential_solutions: Vec<_> =
-            reported_violations.into_iter().map(|violation| violation.solution()).collect();
+        let mut potential_solutions: Vec<_> = reported_violations
+            .iter()
+            .flat_map(|violation| {
+                iter::once(violation.solution())
+                    .chain(method_solutions(tcx, trait_def_id, violation))
+            })
+            .collect();
+        if let Some(split) = split_trait_solution(tcx, trait_def_id, &reported_violations) {
+            // Splitting the trait already moves the items to another trait.
+            potential_solutions.retain(|solution| {
+                !matches!(solution, ObjectSafetyViolationSolution::MoveToAnotherTrait(_))
+            });
+            potential_solutions.push(split);
+        }
         potential_solutions.sort();
         // Allows us to skip suggesting that the same item should be moved to another trait multiple times.
         potential_solutions.dedup();
@@ -202,3 +214,196 @@ pub fn report_object_safety_error<'tcx>(
 
     err
 }
+
+/// Solutions for a method that can't be called on trait objects, on top of the one given by
+/// [`ObjectSafetyViolation::solution`]: adding a `Self: Sized` bound to it and, if it is generic,
+/// replacing its type parameters with boxed trait objects.
+fn method_solutions<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    trait_def_id: DefId,
+    violation: &ObjectSafetyViolation,
+) -> Vec<ObjectSafetyViolationSolution> {
+    let ObjectSafetyViolation::Method(name, code, _) = violation else {
+        return vec![];
+    };
+    // These already have a suggestion of their own.
+    if matches!(
+        code,
+        MethodViolationCode::StaticMethod(_) | MethodViolationCode::UndispatchableReceiver(Some(_))
+    ) {
+        return vec![];
+    }
+    let Some(method) = tcx
+        .associated_items(trait_def_id)
+        .filter_by_name_unhygienic(*name)
+        .find(|item| item.kind == ty::AssocKind::Fn)
+    else {
+        return vec![];
+    };
+    let Some(hir::Node::TraitItem(hir::TraitItem {
+        generics,
+        kind: hir::TraitItemKind::Fn(sig, _),
+        ..
+    })) = tcx.hir().get_if_local(method.def_id)
+    else {
+        return vec![];
+    };
+
+    let mut solutions = vec![ObjectSafetyViolationSolution::AddSelfSized {
+        name: *name,
+        sugg: (
+            format!("{} Self: Sized", generics.add_where_or_trailing_comma()),
+            generics.tail_span_for_predicate_suggestion(),
+        ),
+    }];
+    if let MethodViolationCode::Generic = code
+        && let Some(sugg) = box_type_params(tcx, method.def_id, generics, sig.decl)
+    {
+        solutions.push(ObjectSafetyViolationSolution::BoxTypeParams { name: *name, sugg });
+    }
+    solutions
+}
+
+/// Replaces each type parameter of the method `def_id` with a boxed trait object of its bounds.
+/// This is only possible if the type parameters are only used as the type of arguments, and are
+/// only bounded by a single object safe trait and auto traits.
+fn box_type_params<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    def_id: DefId,
+    generics: &hir::Generics<'_>,
+    decl: &hir::FnDecl<'_>,
+) -> Option<Vec<(Span, String)>> {
+    if generics.has_where_clause_predicates {
+        return None;
+    }
+    let sm = tcx.sess.source_map();
+    let sig = tcx.fn_sig(def_id).instantiate_identity().skip_binder();
+    let param_def_id_to_index = &tcx.generics_of(def_id).param_def_id_to_index;
+
+    let mut sugg = vec![];
+    let mut has_explicit_params = false;
+    for param in generics.params {
+        match param.kind {
+            hir::GenericParamKind::Lifetime { kind: hir::LifetimeParamKind::Explicit } => {
+                return None;
+            }
+            hir::GenericParamKind::Lifetime { .. } => continue,
+            hir::GenericParamKind::Type { synthetic, .. } => has_explicit_params |= !synthetic,
+            hir::GenericParamKind::Const { .. } => return None,
+        }
+
+        let index = param_def_id_to_index[&param.def_id.to_def_id()];
+        let is_param = |ty: Ty<'_>| matches!(ty.kind(), ty::Param(p) if p.index == index);
+        let mentions = sig
+            .inputs_and_output
+            .iter()
+            .flat_map(|ty| ty.walk())
+            .filter(|arg| arg.as_type().is_some_and(is_param))
+            .count();
+        let arg_spans: Vec<_> = iter::zip(sig.inputs(), decl.inputs)
+            .filter(|(ty, _)| is_param(**ty))
+            .map(|(_, hir_ty)| hir_ty.span)
+            .collect();
+        if arg_spans.is_empty() || arg_spans.len() != mentions {
+            return None;
+        }
+
+        let mut bounds = vec![];
+        let mut has_principal = false;
+        for bound in generics.bounds_for_param(param.def_id).flat_map(|pred| pred.bounds) {
+            match bound {
+                hir::GenericBound::Trait(poly_trait_ref, hir::TraitBoundModifier::None) => {
+                    let trait_def_id = poly_trait_ref.trait_ref.trait_def_id()?;
+                    if !tcx.trait_is_auto(trait_def_id) {
+                        if has_principal || !tcx.check_is_object_safe(trait_def_id) {
+                            return None;
+                        }
+                        has_principal = true;
+                    }
+                }
+                hir::GenericBound::Outlives(_) => {}
+                _ => return None,
+            }
+            bounds.push(sm.span_to_snippet(bound.span()).ok()?);
+        }
+        if bounds.is_empty() {
+            return None;
+        }
+        let boxed = format!("Box<dyn {}>", bounds.join(" + "));
+        sugg.extend(arg_spans.into_iter().map(|span| (span, boxed.clone())));
+    }
+    if has_explicit_params {
+        sugg.push((generics.span, String::new()));
+    }
+    Some(sugg)
+}
+
+/// Moves the items of the trait that aren't object safe to a new trait that has the trait as its
+/// supertrait, if all of the violations come from items that can be moved and some of the items
+/// of the trait are object safe.
+fn split_trait_solution<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    trait_def_id: DefId,
+    violations: &[ObjectSafetyViolation],
+) -> Option<ObjectSafetyViolationSolution> {
+    let hir::Node::Item(item) = tcx.hir_node_by_def_id(trait_def_id.as_local()?) else {
+        return None;
+    };
+    let hir::ItemKind::Trait(hir::IsAuto::No, hir::Unsafety::Normal, generics, _, trait_items) =
+        item.kind
+    else {
+        return None;
+    };
+    if !generics.params.is_empty() || generics.has_where_clause_predicates {
+        return None;
+    }
+
+    let mut names = vec![];
+    for violation in violations {
+        match violation {
+            // Items of supertraits can't be moved from here.
+            ObjectSafetyViolation::Method(name, ..)
+            | ObjectSafetyViolation::AssocConst(name, _)
+            | ObjectSafetyViolation::GAT(name, _)
+                if trait_items.iter().any(|item_ref| item_ref.ident.name == *name) =>
+            {
+                names.push(*name)
+            }
+            _ => return None,
+        }
+    }
+    let (moved, kept): (Vec<_>, Vec<_>) =
+        trait_items.iter().partition(|item_ref| names.contains(&item_ref.ident.name));
+    if moved.is_empty() || kept.is_empty() {
+        return None;
+    }
+
+    let sm = tcx.sess.source_map();
+    let mut sugg = vec![];
+    let mut moved_items = String::new();
+    for item_ref in &moved {
+        // The attributes of the item would be left behind.
+        if !tcx.hir().attrs(item_ref.id.hir_id()).is_empty() {
+            return None;
+        }
+        moved_items.push_str("\n    ");
+        moved_items.push_str(&sm.span_to_snippet(item_ref.span).ok()?);
+        // Also remove the line break and indentation before the item.
+        let span = sm.span_extend_prev_while(item_ref.span, char::is_whitespace).ok()?;
+        sugg.push((span, String::new()));
+    }
+    let subtrait_name = format!("{}Ext", item.ident);
+    let vis = sm.span_to_snippet(item.vis_span).ok()?;
+    let vis = if vis.is_empty() { vis } else { format!("{vis} ") };
+    sugg.push((
+        item.span.shrink_to_hi(),
+        format!("\n\n{vis}trait {subtrait_name}: {} {{{moved_items}\n}}", item.ident),
+    ));
+
+    Some(ObjectSafetyViolationSolution::SplitTrait {
+        trait_name: item.ident.name,
+        subtrait_name,
+        items: moved.iter().map(|item_ref| item_ref.ident.name).collect(),
+        sugg,
+    })
+}
@@ -1,3 +1,6 @@
+interface_cant_emit_cfi_typeids =
+    could not emit CFI type ids: {$error}
+
 interface_cant_emit_mir =
     could not emit MIR: {$error}
 
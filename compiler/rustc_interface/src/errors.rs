@@ -59,6 +59,12 @@ pub struct GeneratedFileConflictsWithDirectory<'a> {
 #[diag(interface_out_dir_error)]
 pub struct OutDirError;
 
+#[derive(Diagnostic)]
+#[diag(interface_cant_emit_cfi_typeids)]
+pub struct CantEmitCfiTypeIds {
+    pub error: io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(interface_cant_emit_mir)]
 pub struct CantEmitMIR {
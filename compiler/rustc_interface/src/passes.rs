@@ -40,7 +40,7 @@
 use std::io::{self, BufWriter, Write};
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
-use std::{env, fs, iter};
+use std::{env, fs, iter, panic};
 
 pub fn parse<'a>(sess: &'a Session) -> PResult<'a, ast::Crate> {
     let krate = sess.time("parse_crate", || match &sess.io.input {
@@ -201,7 +201,15 @@ fn configure_and_expand(
         let mut ecx = ExtCtxt::new(sess, cfg, resolver, Some(&lint_store));
         ecx.num_standard_library_imports = num_standard_library_imports;
         // Expand macros now!
-        let krate = sess.time("expand_crate", || ecx.monotonic_expander().expand_crate(krate));
+        let krate = sess.time("expand_crate", || {
+            let res = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                ecx.monotonic_expander().expand_crate(krate)
+            }));
+            // Write the traces even if expansion aborts with a fatal error, they are most
+            // needed then.
+            ecx.write_macro_traces();
+            res.unwrap_or_else(|payload| panic::resume_unwind(payload))
+        });
 
         // The rest is error reporting
 
@@ -729,6 +737,10 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
     // passes are timed inside typeck
     rustc_hir_analysis::check_crate(tcx)?;
 
+    if let Some(path) = &sess.opts.unstable_opts.print_inferred_types {
+        sess.time("print_inferred_types", || rustc_hir_typeck::dump_inferred_types(tcx, path));
+    }
+
     sess.time("MIR_borrow_checking", || {
         tcx.hir().par_body_owners(|def_id| {
             // Run unsafety check because it's responsible for stealing and
@@ -761,6 +773,7 @@ fn analysis(tcx: TyCtxt<'_>, (): ()) -> Result<()> {
         if tcx.is_coroutine(def_id.to_def_id()) {
             tcx.ensure().mir_coroutine_witnesses(def_id);
             tcx.ensure().check_coroutine_obligations(def_id);
+            tcx.ensure().check_async_fn_not_send(def_id);
         }
     });
 
@@ -940,6 +953,12 @@ pub fn start_codegen<'tcx>(
         }
     }
 
+    if tcx.sess.opts.output_types.contains_key(&OutputType::CfiTypeIds) {
+        if let Err(error) = rustc_symbol_mangling::typeid::emit_cfi_typeids(tcx) {
+            tcx.dcx().emit_fatal(errors::CantEmitCfiTypeIds { error });
+        }
+    }
+
     codegen
 }
 
@@ -5,10 +5,11 @@
 use rustc_session::config::{
     build_configuration, build_session_options, rustc_optgroups, BranchProtection, CFGuard, Cfg,
     CollapseMacroDebuginfo, DebugInfo, DumpMonoStatsFormat, ErrorOutputType, ExternEntry,
-    ExternLocation, Externs, FunctionReturn, InliningThreshold, Input, InstrumentCoverage,
-    InstrumentXRay, LinkSelfContained, LinkerPluginLto, LocationDetail, LtoCli, NextSolverConfig,
-    OomStrategy, Options, OutFileName, OutputType, OutputTypes, PAuthKey, PacRet, Passes, Polonius,
-    ProcMacroExecutionStrategy, Strip, SwitchWithOptPath, SymbolManglingVersion, WasiExecModel,
+    ExternLocation, Externs, FunctionReturn, IncrementalCacheLimit, InliningThreshold, Input,
+    InstrumentCoverage, InstrumentXRay, LinkSelfContained, LinkerPluginLto, LocationDetail, LtoCli,
+    NextSolverConfig, OomStrategy, Options, OutFileName, OutputType, OutputTypes, PAuthKey, PacRet,
+    Passes, Polonius, ProcMacroExecutionStrategy, Strip, SwitchWithOptPath, SymbolManglingVersion,
+    WasiExecModel,
 };
 use rustc_session::lint::Level;
 use rustc_session::search_paths::SearchPath;
@@ -23,6 +24,7 @@
 use std::num::NonZero;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 fn mk_session(matches: getopts::Matches) -> (Session, Cfg) {
     let mut early_dcx = EarlyDiagCtxt::new(ErrorOutputType::default());
@@ -643,6 +645,34 @@ macro_rules! tracked {
     // tidy-alphabetical-end
 }
 
+#[test]
+fn test_relocatable_incremental_paths_tracking_hash() {
+    let mut v1 = Options::default();
+    let mut v2 = Options::default();
+    let mut v3 = Options::default();
+
+    for opts in [&mut v1, &mut v2, &mut v3] {
+        opts.unstable_opts.incremental_relocatable = true;
+    }
+
+    v1.remap_path_prefix = vec![("/home/alice/project".into(), "/src".into())];
+    v1.real_rust_source_base_dir = Some("/home/alice/.rustup/lib/rustlib/src/rust".into());
+    v2.remap_path_prefix = vec![("/builds/ci/project".into(), "/src".into())];
+    v2.real_rust_source_base_dir = Some("/opt/rust/lib/rustlib/src/rust".into());
+    v3.remap_path_prefix = vec![("/home/alice/project".into(), "/project".into())];
+    v3.real_rust_source_base_dir = Some("/home/alice/.rustup/lib/rustlib/src/rust".into());
+
+    // Only the remapped side of the mappings is relevant to the incremental cache.
+    assert_same_hash(&v1, &v2);
+    assert_non_crate_hash_different(&v1, &v3);
+    assert_non_crate_hash_different(&v2, &v3);
+
+    // Without the flag, the local paths are tracked as usual.
+    v1.unstable_opts.incremental_relocatable = false;
+    v2.unstable_opts.incremental_relocatable = false;
+    assert_non_crate_hash_different(&v1, &v2);
+}
+
 #[test]
 fn test_unstable_options_tracking_hash() {
     let reference = Options::default();
@@ -659,6 +689,7 @@ macro_rules! untracked {
     // Make sure that changing an [UNTRACKED] option leaves the hash unchanged.
     // tidy-alphabetical-start
     untracked!(assert_incr_state, Some(String::from("loaded")));
+    untracked!(borrowck_explain, SwitchWithOptPath::Enabled(Some(PathBuf::from("explain"))));
     untracked!(deduplicate_diagnostics, false);
     untracked!(dump_dep_graph, true);
     untracked!(dump_mir, Some(String::from("abc")));
@@ -668,15 +699,26 @@ macro_rules! untracked {
     untracked!(dump_mir_graphviz, true);
     untracked!(dump_mono_stats, SwitchWithOptPath::Enabled(Some("mono-items-dir/".into())));
     untracked!(dump_mono_stats_format, DumpMonoStatsFormat::Json);
+    untracked!(dump_proof_tree_json, Some(PathBuf::from("proof-trees")));
     untracked!(dylib_lto, true);
     untracked!(emit_stack_sizes, true);
     untracked!(future_incompat_test, true);
     untracked!(hir_stats, true);
     untracked!(identify_regions, true);
+    untracked!(
+        incremental_cache_limit,
+        Some(IncrementalCacheLimit {
+            max_size: Some(1 << 30),
+            max_age: Some(Duration::from_secs(24 * 60 * 60)),
+        })
+    );
+    untracked!(incremental_explain, Some(PathBuf::from("explain.json")));
     untracked!(incremental_info, true);
+    untracked!(incremental_verify_cache, true);
     untracked!(incremental_verify_ich, true);
     untracked!(input_stats, true);
     untracked!(link_native_libraries, false);
+    untracked!(link_reproducer, Some(PathBuf::from("reproducer")));
     untracked!(llvm_time_trace, true);
     untracked!(ls, vec!["all".to_owned()]);
     untracked!(macro_backtrace, true);
@@ -690,6 +732,7 @@ macro_rules! untracked {
     // `pre_link_arg` is omitted because it just forwards to `pre_link_args`.
     untracked!(pre_link_args, vec![String::from("abc"), String::from("def")]);
     untracked!(print_codegen_stats, true);
+    untracked!(print_inferred_types, Some(PathBuf::from("inferred-types.json")));
     untracked!(print_llvm_passes, true);
     untracked!(print_mono_items, Some(String::from("abc")));
     untracked!(print_type_sizes, true);
@@ -700,6 +743,7 @@ macro_rules! untracked {
     untracked!(self_profile, SwitchWithOptPath::Enabled(None));
     untracked!(self_profile_events, Some(vec![String::new()]));
     untracked!(shell_argfiles, true);
+    untracked!(size_report, Some(PathBuf::from("size.json")));
     untracked!(span_debug, true);
     untracked!(span_free_formats, true);
     untracked!(temps_dir, Some(String::from("abc")));
@@ -708,12 +752,14 @@ macro_rules! untracked {
     untracked!(time_passes, true);
     untracked!(time_passes_format, TimePassesFormat::Json);
     untracked!(trace_macros, true);
+    untracked!(trace_macros_json, Some(PathBuf::from("trace.json")));
     untracked!(track_diagnostics, true);
     untracked!(trim_diagnostic_paths, false);
     untracked!(ui_testing, true);
     untracked!(unpretty, Some("expanded".to_string()));
     untracked!(unstable_options, true);
     untracked!(validate_mir, true);
+    untracked!(validate_target_spec, true);
     untracked!(write_long_types_to_disk, false);
     // tidy-alphabetical-end
 
@@ -755,6 +801,7 @@ macro_rules! tracked {
     tracked!(emit_thin_lto, false);
     tracked!(export_executable_symbols, true);
     tracked!(fewer_names, Some(true));
+    tracked!(fill_match_arms, Some(8));
     tracked!(flatten_format_args, false);
     tracked!(force_unstable_if_unmarked, true);
     tracked!(fuel, Some(("abc".to_string(), 99)));
@@ -762,6 +809,7 @@ macro_rules! tracked {
     tracked!(function_sections, Some(false));
     tracked!(human_readable_cgu_names, true);
     tracked!(incremental_ignore_spans, true);
+    tracked!(incremental_relocatable, true);
     tracked!(inline_in_all_cgus, Some(true));
     tracked!(inline_mir, Some(true));
     tracked!(inline_mir_hint_threshold, Some(123));
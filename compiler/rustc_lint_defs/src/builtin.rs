@@ -22,12 +22,14 @@
         AMBIGUOUS_GLOB_REEXPORTS,
         ARITHMETIC_OVERFLOW,
         ASM_SUB_REGISTER,
+        ASYNC_FN_NOT_SEND,
         BAD_ASM_STYLE,
         BARE_TRAIT_OBJECTS,
         BINDINGS_WITH_VARIANT_NAME,
         BREAK_WITH_LABEL_AND_LOOP,
         BYTE_SLICE_IN_PACKED_STRUCT_WITH_DERIVE,
         CENUM_IMPL_DROP_CAST,
+        CLOSURE_CAPTURES,
         COHERENCE_LEAK_CHECK,
         CONFLICTING_REPR_HINTS,
         CONST_EVALUATABLE_UNCHECKED,
@@ -56,6 +58,7 @@
         INVALID_TYPE_PARAM_DEFAULT,
         IRREFUTABLE_LET_PATTERNS,
         LARGE_ASSIGNMENTS,
+        LARGE_CLOSURE_CAPTURES,
         LATE_BOUND_LIFETIME_ARGUMENTS,
         LEGACY_DERIVE_HELPERS,
         LONG_RUNNING_CONST_EVAL,
@@ -67,6 +70,8 @@
         MISSING_FRAGMENT_SPECIFIER,
         MUST_NOT_SUSPEND,
         NAMED_ARGUMENTS_USED_POSITIONALLY,
+        NARROW_WILDCARD_ARMS,
+        NON_CONTIGUOUS_RANGE_ENDPOINTS,
         NON_EXHAUSTIVE_OMITTED_PATTERNS,
         ORDER_DEPENDENT_TRAIT_OBJECTS,
         OVERLAPPING_RANGE_ENDPOINTS,
@@ -85,6 +90,7 @@
         RUST_2021_PREFIXES_INCOMPATIBLE_SYNTAX,
         RUST_2021_PRELUDE_COLLISIONS,
         SEMICOLON_IN_EXPRESSIONS_FROM_MACROS,
+        SHADOWED_RANGE_PATTERNS,
         SINGLE_USE_LIFETIMES,
         SOFT_UNSTABLE,
         STABLE_FEATURES,
@@ -455,6 +461,44 @@
     @feature_gate = rustc_span::symbol::sym::must_not_suspend;
 }
 
+declare_lint! {
+    /// The `async_fn_not_send` lint detects public `async fn`s whose returned future does not
+    /// implement `Send`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,edition2018
+    /// #![warn(async_fn_not_send)]
+    /// use std::rc::Rc;
+    ///
+    /// async fn yield_now() {}
+    ///
+    /// pub async fn uhoh() {
+    ///     let rc = Rc::new(0);
+    ///     yield_now().await;
+    ///     drop(rc);
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Whether the future returned by an `async fn` is `Send` depends on the values that are held
+    /// across its `.await`s, and it is easy to make a public `async fn` non-`Send` by accident.
+    /// Callers usually only find out when they try to spawn the future on a multithreaded
+    /// executor, and then the error points at their code rather than at the value that is the
+    /// actual culprit.
+    ///
+    /// This lint points at the values that are held across an await point and are not `Send`
+    /// instead, so that their scope can be shrunk, or they can be replaced by thread-safe
+    /// alternatives (like `Arc` instead of `Rc`). It is "allow" by default because futures that
+    /// are only ever run on a single thread don't need to be `Send`.
+    pub ASYNC_FN_NOT_SEND,
+    Allow,
+    "public `async fn`s whose returned future is not `Send`"
+}
+
 declare_lint! {
     /// The `unused_extern_crates` lint guards against `extern crate` items
     /// that are never used.
@@ -812,6 +856,97 @@
     "detects range patterns with overlapping endpoints"
 }
 
+declare_lint! {
+    /// The `non_contiguous_range_endpoints` lint detects likely off-by-one errors when using
+    /// exclusive [range patterns].
+    ///
+    /// [range patterns]: https://doc.rust-lang.org/nightly/reference/patterns.html#range-patterns
+    ///
+    /// ### Example
+    ///
+    /// ```rust
+    /// # #![feature(exclusive_range_pattern)]
+    /// let x = 123u32;
+    /// match x {
+    ///     0..100 => { println!("small"); }
+    ///     101..1000 => { println!("large"); }
+    ///     _ => { println!("larger"); }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// It is likely a mistake to have range patterns in a match expression that miss out a single
+    /// number. Check that the beginning and end values are what you expect, and keep in mind that
+    /// with `..` the right bound is exclusive.
+    pub NON_CONTIGUOUS_RANGE_ENDPOINTS,
+    Warn,
+    "detects off-by-one errors with exclusive range patterns"
+}
+
+declare_lint! {
+    /// The `shadowed_range_patterns` lint detects [range patterns] that are unreachable because
+    /// the ranges of several earlier arms cover all of their values between them.
+    ///
+    /// [range patterns]: https://doc.rust-lang.org/nightly/reference/patterns.html#range-patterns
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(shadowed_range_patterns)]
+    /// let x = 123u8;
+    /// match x {
+    ///     0..=99 => { println!("small"); }
+    ///     100..=199 => { println!("medium"); }
+    ///     50..=150 => { println!("middle"); }
+    ///     _ => { println!("large"); }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// The `unreachable_patterns` lint already reports these patterns, but doesn't say which arms
+    /// make them unreachable, which can be hard to tell in a long list of ranges. This lint points
+    /// at each of them, with the part of the range they cover. It is "allow" by default because it
+    /// is redundant with `unreachable_patterns`.
+    pub SHADOWED_RANGE_PATTERNS,
+    Allow,
+    "detects range patterns that are unreachable because of the ranges of several earlier arms"
+}
+
+declare_lint! {
+    /// The `narrow_wildcard_arms` lint detects `_` arms of matches on integers that are only
+    /// reachable for a few values.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(narrow_wildcard_arms)]
+    /// let x = 123u8;
+    /// match x {
+    ///     0..=99 => { println!("small"); }
+    ///     101..=255 => { println!("large"); }
+    ///     _ => { println!("one hundred"); }
+    /// }
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Listing the few values that reach the arm makes the match easier to read, and makes it
+    /// fail to compile if the other arms change so that more values reach it. It is "allow" by
+    /// default because a wildcard is sometimes clearer, for example when the other arms are
+    /// generated by a macro.
+    pub NARROW_WILDCARD_ARMS,
+    Allow,
+    "detects `_` arms of integer matches that only match a few values"
+}
+
 declare_lint! {
     /// The `bindings_with_variant_name` lint detects pattern bindings with
     /// the same name as one of the matched variants.
@@ -3115,6 +3250,58 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `closure_captures` lint reports, for each closure, coroutine and async block, which
+    /// places it captures and how.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(closure_captures)]
+    /// let mut point = (1, 2);
+    /// let mut c = || point.0 += 1;
+    /// c();
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Since Rust 2021, closures capture the precise places they use rather than whole
+    /// variables, and pick the capture mode from how each place is used. This lint makes that
+    /// decision visible, which helps when a closure or an `async move` block captures more, or
+    /// less, than expected. It is "allow" by default because it is only meant for debugging.
+    pub CLOSURE_CAPTURES,
+    Allow,
+    "reports the places captured by closures and how they are captured"
+}
+
+declare_lint! {
+    /// The `large_closure_captures` lint detects `move` closures and `async move` blocks that
+    /// capture a large value by value.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,compile_fail
+    /// #![deny(large_closure_captures)]
+    /// let buf = [0u8; 4096];
+    /// let c = move || buf[0];
+    /// c();
+    /// ```
+    ///
+    /// {{produces}}
+    ///
+    /// ### Explanation
+    ///
+    /// Capturing a value by value copies it into the closure, and the closure is then moved
+    /// around as a whole. Capturing a reference instead is often cheaper. The limit is the
+    /// one set by `#![move_size_limit]`, or 1024 bytes if there is none. This lint is "allow"
+    /// by default because large captures are often intended.
+    pub LARGE_CLOSURE_CAPTURES,
+    Allow,
+    "detects `move` closures that capture large values by value"
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
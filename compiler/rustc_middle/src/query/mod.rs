@@ -572,6 +572,11 @@
         desc { |tcx| "verify auto trait bounds for coroutine interior type `{}`", tcx.def_path_str(key) }
     }
 
+    /// Lints the public `async fn` whose body is the coroutine `key` if its future isn't `Send`.
+    query check_async_fn_not_send(key: LocalDefId) {
+        desc { |tcx| "checking whether the future of `{}` is `Send`", tcx.def_path_str(key) }
+    }
+
     /// MIR after our optimization passes have run. This is MIR that is ready
     /// for codegen. This is also the only query that can fetch non-local MIR, at present.
     query optimized_mir(key: DefId) -> &'tcx mir::Body<'tcx> {
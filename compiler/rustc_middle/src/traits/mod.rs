@@ -16,7 +16,7 @@
 use crate::ty::{self, AdtKind, Ty};
 
 use rustc_data_structures::sync::Lrc;
-use rustc_errors::{Applicability, Diag, EmissionGuarantee};
+use rustc_errors::{Applicability, Diag, EmissionGuarantee, SuggestionStyle};
 use rustc_hir as hir;
 use rustc_hir::def_id::DefId;
 use rustc_span::def_id::{LocalDefId, CRATE_DEF_ID};
@@ -905,6 +905,24 @@ pub enum ObjectSafetyViolationSolution {
     },
     ChangeToRefSelf(Symbol, Span),
     MoveToAnotherTrait(Symbol),
+    /// Add a `Self: Sized` bound to the method, so that it doesn't apply to trait objects.
+    AddSelfSized {
+        name: Symbol,
+        sugg: (String, Span),
+    },
+    /// Replace the type parameters of the method with boxed trait objects.
+    BoxTypeParams {
+        name: Symbol,
+        sugg: Vec<(Span, String)>,
+    },
+    /// Move the items that aren't object safe to a new trait that has the trait as its
+    /// supertrait.
+    SplitTrait {
+        trait_name: Symbol,
+        subtrait_name: String,
+        items: Vec<Symbol>,
+        sugg: Vec<(Span, String)>,
+    },
 }
 
 impl ObjectSafetyViolationSolution {
@@ -945,6 +963,51 @@ trait objects"
             ObjectSafetyViolationSolution::MoveToAnotherTrait(name) => {
                 err.help(format!("consider moving `{name}` to another trait"));
             }
+            ObjectSafetyViolationSolution::AddSelfSized { name, sugg } => {
+                err.span_suggestion_hidden(
+                    sugg.1,
+                    format!(
+                        "consider adding a `Self: Sized` bound to `{name}` so it does not apply \
+                         to trait objects"
+                    ),
+                    sugg.0,
+                    Applicability::MachineApplicable,
+                );
+            }
+            ObjectSafetyViolationSolution::BoxTypeParams { name, sugg } => {
+                err.multipart_suggestion_with_style(
+                    format!(
+                        "consider replacing the type parameters of `{name}` with boxed trait \
+                         objects"
+                    ),
+                    sugg,
+                    Applicability::MaybeIncorrect,
+                    SuggestionStyle::HideCodeAlways,
+                );
+            }
+            ObjectSafetyViolationSolution::SplitTrait {
+                trait_name,
+                subtrait_name,
+                items,
+                sugg,
+            } => {
+                let items = match &items[..] {
+                    [rest @ .., last] if !rest.is_empty() => format!(
+                        "{} and `{last}`",
+                        rest.iter().map(|item| format!("`{item}`")).collect::<Vec<_>>().join(", ")
+                    ),
+                    _ => items.iter().map(|item| format!("`{item}`")).collect(),
+                };
+                err.multipart_suggestion_with_style(
+                    format!(
+                        "consider moving {items} to a new trait `{subtrait_name}: {trait_name}`, \
+                         so that `{trait_name}` can be made into an object"
+                    ),
+                    sugg,
+                    Applicability::MaybeIncorrect,
+                    SuggestionStyle::HideCodeAlways,
+                );
+            }
         }
     }
 }
@@ -15,11 +15,11 @@
 };
 use rustc_hir as hir;
 use rustc_hir::def::*;
-use rustc_hir::def_id::LocalDefId;
+use rustc_hir::def_id::{DefId, LocalDefId};
 use rustc_hir::HirId;
 use rustc_middle::thir::visit::Visitor;
 use rustc_middle::thir::*;
-use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::print::{with_crate_prefix, with_no_trimmed_paths};
 use rustc_middle::ty::{self, AdtDef, Ty, TyCtxt};
 use rustc_session::lint::builtin::{
     BINDINGS_WITH_VARIANT_NAME, IRREFUTABLE_LET_PATTERNS, UNREACHABLE_PATTERNS,
@@ -1018,6 +1018,45 @@ fn report_non_exhaustive_match<'p, 'tcx>(
         }
     }
 
+    // With `-Z fill-match-arms`, suggest an arm for each witness, up to the limit, instead of a
+    // single arm. The patterns use crate-qualified paths, so that they can be applied as is unless
+    // one of their types can't be named from the module of the `match`.
+    let mut fill_arms = None;
+    let mut fill_arms_truncated = false;
+    let mut applicability = Applicability::HasPlaceholders;
+    if let Some(limit) = cx.tcx.sess.opts.unstable_opts.fill_match_arms {
+        let mut patterns = vec![];
+        let mut paths_resolve = true;
+        for witness in &witnesses {
+            let pat = cx.hoist_witness_pat(witness);
+            let pattern = with_crate_prefix!(pat.to_string());
+            // A wildcard witness would make the arms after it unreachable.
+            if pattern == "_" || patterns.len() == limit {
+                fill_arms_truncated = true;
+            } else {
+                paths_resolve &= pat_paths_resolve_from(cx.tcx, &pat, cx.module);
+                patterns.push(pattern);
+            }
+        }
+        if fill_arms_truncated || patterns.is_empty() {
+            fill_arms_truncated = true;
+            patterns.push("_".to_string());
+        }
+        if paths_resolve {
+            applicability = Applicability::MachineApplicable;
+        }
+        fill_arms = Some(patterns);
+    }
+    // The arms to insert, separated by `sep`.
+    let arms_sugg = |sep: &str| match &fill_arms {
+        Some(patterns) => patterns
+            .iter()
+            .map(|pattern| format!("{pattern} => todo!()"))
+            .collect::<Vec<_>>()
+            .join(&format!(",{sep}")),
+        None => format!("{pattern} => todo!()"),
+    };
+
     let mut suggestion = None;
     let sm = cx.tcx.sess.source_map();
     match arms {
@@ -1028,9 +1067,10 @@ fn report_non_exhaustive_match<'p, 'tcx>(
             } else {
                 (" ".to_string(), "")
             };
+            let arms = arms_sugg(&format!("{indentation}{more}"));
             suggestion = Some((
                 sp.shrink_to_hi().with_hi(expr_span.hi()),
-                format!(" {{{indentation}{more}{pattern} => todo!(),{indentation}}}",),
+                format!(" {{{indentation}{more}{arms},{indentation}}}",),
             ));
         }
         [only] => {
@@ -1054,10 +1094,9 @@ fn report_non_exhaustive_match<'p, 'tcx>(
             } else {
                 ","
             };
-            suggestion = Some((
-                only.span.shrink_to_hi(),
-                format!("{comma}{pre_indentation}{pattern} => todo!()"),
-            ));
+            let arms = arms_sugg(&pre_indentation);
+            suggestion =
+                Some((only.span.shrink_to_hi(), format!("{comma}{pre_indentation}{arms}")));
         }
         [.., prev, last] => {
             let prev = &thir[*prev];
@@ -1077,48 +1116,93 @@ fn report_non_exhaustive_match<'p, 'tcx>(
                     Some(" ".to_string())
                 };
                 if let Some(spacing) = spacing {
-                    suggestion = Some((
-                        last.span.shrink_to_hi(),
-                        format!("{comma}{spacing}{pattern} => todo!()"),
-                    ));
+                    let arms = arms_sugg(&spacing);
+                    suggestion =
+                        Some((last.span.shrink_to_hi(), format!("{comma}{spacing}{arms}")));
                 }
             }
         }
         _ => {}
     }
 
-    let msg = format!(
-        "ensure that all possible cases are being handled by adding a match arm with a wildcard \
-         pattern{}{}",
-        if patterns_len > 1 && patterns_len < 4 && suggestion.is_some() {
-            ", a match arm with multiple or-patterns"
-        } else {
-            // we are either not suggesting anything, or suggesting `_`
-            ""
-        },
-        match patterns_len {
-            // non-exhaustive enum case
-            0 if suggestion.is_some() => " as shown",
-            0 => "",
-            1 if suggestion.is_some() => " or an explicit pattern as shown",
-            1 => " or an explicit pattern",
-            _ if suggestion.is_some() => " as shown, or multiple match arms",
-            _ => " or multiple match arms",
-        },
-    );
+    let msg = if fill_arms.is_some() {
+        format!(
+            "ensure that all possible cases are being handled by adding a match arm for each \
+             missing pattern{}",
+            if fill_arms_truncated { " and a match arm with a wildcard pattern" } else { "" },
+        )
+    } else {
+        format!(
+            "ensure that all possible cases are being handled by adding a match arm with a \
+             wildcard pattern{}{}",
+            if patterns_len > 1 && patterns_len < 4 && suggestion.is_some() {
+                ", a match arm with multiple or-patterns"
+            } else {
+                // we are either not suggesting anything, or suggesting `_`
+                ""
+            },
+            match patterns_len {
+                // non-exhaustive enum case
+                0 if suggestion.is_some() => " as shown",
+                0 => "",
+                1 if suggestion.is_some() => " or an explicit pattern as shown",
+                1 => " or an explicit pattern",
+                _ if suggestion.is_some() => " as shown, or multiple match arms",
+                _ => " or multiple match arms",
+            },
+        )
+    };
 
     let all_arms_have_guards = arms.iter().all(|arm_id| thir[*arm_id].guard.is_some());
     if !is_empty_match && all_arms_have_guards {
         err.subdiagnostic(cx.tcx.dcx(), NonExhaustiveMatchAllArmsGuarded);
     }
     if let Some((span, sugg)) = suggestion {
-        err.span_suggestion_verbose(span, msg, sugg, Applicability::HasPlaceholders);
+        err.span_suggestion_verbose(span, msg, sugg, applicability);
     } else {
         err.help(msg);
     }
     err.emit()
 }
 
+/// Whether the paths of the types in `pat`, printed with the `crate::` prefix, resolve from
+/// `module`.
+fn pat_paths_resolve_from<'tcx>(tcx: TyCtxt<'tcx>, pat: &Pat<'tcx>, module: DefId) -> bool {
+    let mut resolves = true;
+    pat.walk_always(|pat| {
+        if let PatKind::Variant { .. } | PatKind::Leaf { .. } = pat.kind
+            && let ty::Adt(adt_def, _) = pat.ty.kind()
+        {
+            resolves &= path_resolves_from(tcx, adt_def.did(), module);
+        }
+    });
+    resolves
+}
+
+/// Whether the path of `def_id`, printed with the `crate::` prefix, resolves from `module`.
+fn path_resolves_from(tcx: TyCtxt<'_>, mut def_id: DefId, module: DefId) -> bool {
+    // Before Rust 2018, paths aren't prefixed and are relative to the current module.
+    if !tcx.sess.at_least_rust_2018() && !module.is_crate_root() {
+        return false;
+    }
+    if !def_id.is_local() {
+        // Other crates are named by a visible path.
+        return true;
+    }
+    // The item must be reachable through modules that are accessible from `module`, unlike items
+    // defined in a function body.
+    loop {
+        if !tcx.visibility(def_id).is_accessible_from(module, tcx) {
+            return false;
+        }
+        match tcx.opt_parent(def_id) {
+            Some(parent) if parent.is_crate_root() => return true,
+            Some(parent) if tcx.def_kind(parent) == DefKind::Mod => def_id = parent,
+            _ => return false,
+        }
+    }
+}
+
 fn joined_uncovered_patterns<'p, 'tcx>(
     cx: &MatchCheckCtxt<'p, 'tcx>,
     witnesses: &[WitnessPat<'p, 'tcx>],
@@ -1,4 +1,8 @@
 mir_transform_arithmetic_overflow = this arithmetic operation will overflow
+
+mir_transform_async_fn_not_send = future returned by `{$name}` is not `Send`
+    .help = consider shrinking the scope of these values so that they are dropped before the await points, or using types that are `Send` instead
+
 mir_transform_call_to_unsafe_label = call to unsafe function
 mir_transform_call_to_unsafe_note = consult the function's documentation for information on how to avoid undefined behavior
 mir_transform_const_defined_here = `const` item defined here
@@ -34,6 +38,13 @@ mir_transform_mutation_layout_constrained_borrow_label = borrow of layout constr
 mir_transform_mutation_layout_constrained_borrow_note = references to fields of layout constrained fields lose the constraints. Coupled with interior mutability, the field can be changed to invalid values
 mir_transform_mutation_layout_constrained_label = mutation of layout constrained field
 mir_transform_mutation_layout_constrained_note = mutating layout constrained fields cannot statically be checked for valid values
+mir_transform_not_send_argument = the future captures the argument `{$name}`, and its type `{$ty}` is not `Send`
+mir_transform_not_send_awaited = the future awaited here is not `Send`, its type is `{$ty}`
+mir_transform_not_send_local = `{$name}` is held across this await point, and its type `{$ty}` is not `Send`
+    .label = `{$name}` is declared here
+mir_transform_not_send_temporary = a temporary value is held across this await point, and its type `{$ty}` is not `Send`
+    .label = the temporary value is created here
+
 mir_transform_operation_will_panic = this operation will panic at runtime
 
 mir_transform_requires_unsafe = {$details} is unsafe and requires unsafe {$op_in_unsafe_fn_allowed ->
@@ -62,7 +62,7 @@
 use rustc_errors::pluralize;
 use rustc_hir as hir;
 use rustc_hir::lang_items::LangItem;
-use rustc_hir::{CoroutineDesugaring, CoroutineKind};
+use rustc_hir::{CoroutineDesugaring, CoroutineKind, CoroutineSource};
 use rustc_index::bit_set::{BitMatrix, BitSet, GrowableBitSet};
 use rustc_index::{Idx, IndexVec};
 use rustc_middle::mir::visit::{MutVisitor, PlaceContext, Visitor};
@@ -75,11 +75,15 @@
 };
 use rustc_mir_dataflow::storage::always_storage_live_locals;
 use rustc_mir_dataflow::Analysis;
+use rustc_session::lint::builtin::ASYNC_FN_NOT_SEND;
+use rustc_session::lint::Level;
 use rustc_span::def_id::{DefId, LocalDefId};
 use rustc_span::symbol::sym;
-use rustc_span::Span;
+use rustc_span::{DesugaringKind, Span};
 use rustc_target::abi::{FieldIdx, VariantIdx};
 use rustc_target::spec::PanicStrategy;
+use rustc_trait_selection::infer::TyCtxtInferExt;
+use rustc_trait_selection::traits::type_known_to_meet_bound_modulo_regions;
 use std::{iter, ops};
 
 pub struct StateTransform;
@@ -2095,3 +2099,104 @@ fn check_must_not_suspend_def(
         false
     }
 }
+
+/// Lints public `async fn`s whose returned future is not `Send`, pointing at the arguments and at
+/// the values held across await points that are responsible for it.
+pub(crate) fn check_async_fn_not_send<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) {
+    if tcx.coroutine_kind(def_id)
+        != Some(CoroutineKind::Desugared(CoroutineDesugaring::Async, CoroutineSource::Fn))
+    {
+        return;
+    }
+    let fn_def_id = tcx.local_parent(def_id);
+    let hir_id = tcx.local_def_id_to_hir_id(fn_def_id);
+    if tcx.lint_level_at_node(ASYNC_FN_NOT_SEND, hir_id).0 == Level::Allow
+        || !tcx.effective_visibilities(()).is_exported(fn_def_id)
+    {
+        return;
+    }
+
+    let coroutine_ty = tcx.type_of(def_id).instantiate_identity();
+    let ty::Coroutine(_, args) = *coroutine_ty.kind() else {
+        return;
+    };
+    let Some(send_def_id) = tcx.get_diagnostic_item(sym::Send) else {
+        return;
+    };
+    let Some(layout) = tcx.mir_coroutine_witnesses(def_id) else {
+        return;
+    };
+
+    let param_env = tcx.param_env(def_id);
+    // The types of the saved locals have their regions erased, and borrowck already checked them.
+    let infcx = tcx.infer_ctxt().ignoring_regions().build();
+    let is_send = |ty: Ty<'tcx>| {
+        type_known_to_meet_bound_modulo_regions(&infcx, param_env, ty, send_def_id)
+    };
+    if is_send(coroutine_ty) {
+        return;
+    }
+
+    // The arguments of the `async fn` are captured by the future, whether or not they are held
+    // across an await point.
+    let mut culprits = vec![];
+    for (captured_place, ty) in
+        iter::zip(tcx.closure_captures(def_id), args.as_coroutine().upvar_tys())
+    {
+        if !is_send(ty) {
+            culprits.push(errors::NotSendCulprit::Argument {
+                span: tcx.hir().span(captured_place.get_root_variable()),
+                name: captured_place.to_symbol(),
+                ty,
+            });
+        }
+    }
+
+    // The body of an `async fn` starts by moving its arguments into locals, which we already
+    // pointed at. Those locals are declared with the patterns of the arguments, so they are
+    // recognized by the spans of their bindings.
+    let body = tcx.hir().body(tcx.hir().body_owned_by(def_id));
+    let mut argument_spans = FxHashSet::default();
+    if let hir::ExprKind::Block(block, _) = body.value.kind {
+        for stmt in block.stmts {
+            if let hir::StmtKind::Local(local) = stmt.kind
+                && local.source == hir::LocalSource::AsyncFn
+            {
+                local.pat.each_binding(|_, _, span, _| {
+                    argument_spans.insert(span);
+                });
+            }
+        }
+    }
+
+    let mut seen_locals = FxHashSet::default();
+    for (variant, yield_source_info) in
+        layout.variant_fields.iter().zip(&layout.variant_source_info)
+    {
+        for &local in variant {
+            let decl = &layout.field_tys[local];
+            if decl.ignore_for_traits || !seen_locals.insert(local) || is_send(decl.ty) {
+                continue;
+            }
+            let await_span = yield_source_info.span;
+            let span = decl.source_info.span;
+            let culprit = if span.is_desugaring(DesugaringKind::Await) {
+                errors::NotSendCulprit::Awaited { await_span, ty: decl.ty }
+            } else if argument_spans.contains(&span) {
+                continue;
+            } else if let Some(name) = layout.field_names[local] {
+                errors::NotSendCulprit::Local { await_span, span, name, ty: decl.ty }
+            } else {
+                errors::NotSendCulprit::Temporary { await_span, span, ty: decl.ty }
+            };
+            culprits.push(culprit);
+        }
+    }
+
+    tcx.emit_node_span_lint(
+        ASYNC_FN_NOT_SEND,
+        hir_id,
+        tcx.def_span(fn_def_id),
+        errors::AsyncFnNotSend { name: tcx.item_name(fn_def_id.to_def_id()), culprits, help: () },
+    );
+}
@@ -6,10 +6,10 @@
 };
 use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_middle::mir::{AssertKind, UnsafetyViolationDetails};
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{Ty, TyCtxt};
 use rustc_session::lint::{self, Lint};
 use rustc_span::def_id::DefId;
-use rustc_span::Span;
+use rustc_span::{Span, Symbol};
 
 use crate::fluent_generated as fluent;
 
@@ -293,3 +293,47 @@ pub(crate) struct MustNotSuspendReason {
     pub span: Span,
     pub reason: String,
 }
+
+#[derive(LintDiagnostic)]
+#[diag(mir_transform_async_fn_not_send)]
+pub(crate) struct AsyncFnNotSend<'tcx> {
+    pub name: Symbol,
+    #[subdiagnostic]
+    pub culprits: Vec<NotSendCulprit<'tcx>>,
+    #[help]
+    pub help: (),
+}
+
+#[derive(Subdiagnostic)]
+pub(crate) enum NotSendCulprit<'tcx> {
+    #[note(mir_transform_not_send_argument)]
+    Argument {
+        #[primary_span]
+        span: Span,
+        name: Symbol,
+        ty: Ty<'tcx>,
+    },
+    #[note(mir_transform_not_send_local)]
+    Local {
+        #[primary_span]
+        await_span: Span,
+        #[label]
+        span: Span,
+        name: Symbol,
+        ty: Ty<'tcx>,
+    },
+    #[note(mir_transform_not_send_temporary)]
+    Temporary {
+        #[primary_span]
+        await_span: Span,
+        #[label]
+        span: Span,
+        ty: Ty<'tcx>,
+    },
+    #[note(mir_transform_not_send_awaited)]
+    Awaited {
+        #[primary_span]
+        await_span: Span,
+        ty: Ty<'tcx>,
+    },
+}
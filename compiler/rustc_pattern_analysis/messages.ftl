@@ -1,3 +1,16 @@
+pattern_analysis_exclusive_range_missing_gap = multiple ranges are one apart
+    .label = this range doesn't match `{$gap}` because `..` is an exclusive range
+    .suggestion = use an inclusive range instead
+
+pattern_analysis_exclusive_range_missing_max = exclusive range missing `{$max}`
+    .label = this range doesn't match `{$max}` because `..` is an exclusive range
+    .suggestion = use an inclusive range instead
+
+pattern_analysis_narrow_wildcard_arm = this wildcard only matches a few values
+    .label = only the values `{$patterns}` reach this arm
+    .note = listing the values keeps the match checked for exhaustiveness if the arms above change
+    .suggestion = list the values instead
+
 pattern_analysis_non_exhaustive_omitted_pattern = some variants are not matched explicitly
     .help = ensure that all variants are matched explicitly by adding the suggested match arms
     .note = the matched value is of type `{$scrut_ty}` and the `non_exhaustive_omitted_patterns` attribute was found
@@ -11,6 +24,10 @@ pattern_analysis_overlapping_range_endpoints = multiple patterns overlap on thei
     .label = ... with this range
     .note = you likely meant to write mutually exclusive ranges
 
+pattern_analysis_shadowed_range_pattern = unreachable range pattern
+    .label = this range is unreachable
+    .note = together, the earlier ranges match every value of this range
+
 pattern_analysis_uncovered = {$count ->
         [1] pattern `{$witness_1}`
         [2] patterns `{$witness_1}` and `{$witness_2}`
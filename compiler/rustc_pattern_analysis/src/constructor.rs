@@ -288,11 +288,11 @@ pub fn from_range(lo: MaybeInfiniteInt, mut hi: MaybeInfiniteInt, end: RangeEnd)
         IntRange { lo, hi }
     }
 
-    fn is_subrange(&self, other: &Self) -> bool {
+    pub(crate) fn is_subrange(&self, other: &Self) -> bool {
         other.lo <= self.lo && self.hi <= other.hi
     }
 
-    fn intersection(&self, other: &Self) -> Option<Self> {
+    pub(crate) fn intersection(&self, other: &Self) -> Option<Self> {
         if self.lo < other.hi && other.lo < self.hi {
             Some(IntRange { lo: max(self.lo, other.lo), hi: min(self.hi, other.hi) })
         } else {
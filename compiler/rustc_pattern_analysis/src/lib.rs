@@ -1,4 +1,9 @@
 //! Analysis of patterns, notably match exhaustiveness checking.
+//!
+//! The analysis is generic over a [`TypeCx`], which describes the types being matched on and their
+//! constructors, so that it can be used outside of rustc (e.g. by rust-analyzer) by disabling the
+//! default `rustc` feature. To check a match, lower its patterns to
+//! [`DeconstructedPat`](pat::DeconstructedPat)s and call [`compute_match_usefulness`].
 
 #![allow(rustc::untranslatable_diagnostic)]
 #![allow(rustc::diagnostic_outside_of_impl)]
@@ -71,13 +76,17 @@ pub fn insert(&mut self, elem: T) {
 
 use crate::constructor::{Constructor, ConstructorSet, IntRange};
 #[cfg(feature = "rustc")]
-use crate::lints::lint_nonexhaustive_missing_variants;
+use crate::lints::{
+    lint_narrow_wildcard_arms, lint_nonexhaustive_missing_variants, lint_shadowed_range_patterns,
+};
 use crate::pat::DeconstructedPat;
 use crate::pat_column::PatternColumn;
 #[cfg(feature = "rustc")]
 use crate::rustc::RustcMatchCheckCtxt;
-#[cfg(feature = "rustc")]
-use crate::usefulness::{compute_match_usefulness, ValidityConstraint};
+
+pub use crate::usefulness::{
+    compute_match_usefulness, Usefulness, UsefulnessReport, ValidityConstraint,
+};
 
 pub trait Captures<'a> {}
 impl<'a, T: ?Sized> Captures<'a> for T {}
@@ -142,6 +151,18 @@ fn lint_overlapping_range_endpoints(
         _overlaps_with: &[&DeconstructedPat<Self>],
     ) {
     }
+
+    /// Lint that `gap`, a singleton range, is matched by no pattern, while the range `pat` ends
+    /// just before it and the ranges in `gapped_with` start just after it. `gapped_with` is empty
+    /// if `gap` is the last value of the type.
+    /// The default implementation does nothing.
+    fn lint_non_contiguous_range_endpoints(
+        &self,
+        _pat: &DeconstructedPat<Self>,
+        _gap: IntRange,
+        _gapped_with: &[&DeconstructedPat<Self>],
+    ) {
+    }
 }
 
 /// The arm of a match expression.
@@ -179,5 +200,10 @@ pub fn analyze_match<'p, 'tcx>(
         lint_nonexhaustive_missing_variants(tycx, arms, &pat_column, scrut_ty)?;
     }
 
+    lint_shadowed_range_patterns(tycx, &report);
+    if tycx.refutable {
+        lint_narrow_wildcard_arms(tycx, arms, scrut_ty, &report)?;
+    }
+
     Ok(report)
 }
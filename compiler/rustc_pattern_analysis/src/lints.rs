@@ -1,11 +1,21 @@
-use rustc_session::lint::builtin::NON_EXHAUSTIVE_OMITTED_PATTERNS;
+use rustc_middle::thir::PatKind;
+use rustc_session::lint::builtin::{
+    NARROW_WILDCARD_ARMS, NON_EXHAUSTIVE_OMITTED_PATTERNS, SHADOWED_RANGE_PATTERNS,
+};
 use rustc_span::ErrorGuaranteed;
 
-use crate::constructor::Constructor;
-use crate::errors::{NonExhaustiveOmittedPattern, NonExhaustiveOmittedPatternLintOnArm, Uncovered};
+use crate::constructor::{Constructor, ConstructorSet};
+use crate::errors::{
+    NarrowWildcardArm, NonExhaustiveOmittedPattern, NonExhaustiveOmittedPatternLintOnArm,
+    ShadowedRangePattern, ShadowingRange, Uncovered,
+};
 use crate::pat_column::PatternColumn;
-use crate::rustc::{RevealedTy, RustcMatchCheckCtxt, WitnessPat};
-use crate::MatchArm;
+use crate::rustc::{DeconstructedPat, RevealedTy, RustcMatchCheckCtxt, UsefulnessReport, WitnessPat};
+use crate::usefulness::Usefulness;
+use crate::{MatchArm, TypeCx};
+
+/// The most values a wildcard arm can match for `narrow_wildcard_arms` to suggest listing them.
+const NARROW_WILDCARD_MAX_VALUES: u128 = 4;
 
 /// Traverse the patterns to collect any variants of a non_exhaustive enum that fail to be mentioned
 /// in a given column.
@@ -107,3 +117,153 @@ pub(crate) fn lint_nonexhaustive_missing_variants<'p, 'tcx>(
     }
     Ok(())
 }
+
+/// Collects the alternatives of a top-level or-pattern, recursively, or the pattern itself if it
+/// isn't an or-pattern.
+fn top_level_alternatives<'a, 'p, 'tcx>(
+    pat: &'a DeconstructedPat<'p, 'tcx>,
+    alts: &mut Vec<&'a DeconstructedPat<'p, 'tcx>>,
+) {
+    if let Constructor::Or = pat.ctor() {
+        for alt in pat.iter_fields() {
+            top_level_alternatives(alt, alts);
+        }
+    } else {
+        alts.push(pat);
+    }
+}
+
+/// Lint unreachable range patterns that no single earlier range covers, but that earlier ranges
+/// cover together, like `6..=9` in `0..=7 => {} 8..=10 => {} 6..=9 => {}`.
+pub(crate) fn lint_shadowed_range_patterns<'p, 'tcx>(
+    rcx: &RustcMatchCheckCtxt<'p, 'tcx>,
+    report: &UsefulnessReport<'p, 'tcx>,
+) {
+    if matches!(
+        rcx.tcx.lint_level_at_node(SHADOWED_RANGE_PATTERNS, rcx.match_lint_level).0,
+        rustc_session::lint::Level::Allow
+    ) {
+        return;
+    }
+    for (i, (arm, usefulness)) in report.arm_usefulness.iter().enumerate() {
+        let mut alts = Vec::new();
+        top_level_alternatives(arm.pat, &mut alts);
+        // Only the unreachable top-level alternatives can be shadowed.
+        match usefulness {
+            Usefulness::Redundant => {}
+            Usefulness::Useful(redundant_subpats) => {
+                alts.retain(|alt| redundant_subpats.iter().any(|sub| std::ptr::eq(*alt, *sub)))
+            }
+        }
+        for alt in alts {
+            let Constructor::IntRange(range) = alt.ctor() else { continue };
+            let mut shadowed_by = Vec::new();
+            let mut covered_by_one = false;
+            'arms: for (earlier_arm, _) in report.arm_usefulness[..i].iter() {
+                if earlier_arm.has_guard {
+                    continue;
+                }
+                let mut earlier_alts = Vec::new();
+                top_level_alternatives(earlier_arm.pat, &mut earlier_alts);
+                for earlier in earlier_alts {
+                    let earlier_range = match earlier.ctor() {
+                        Constructor::IntRange(earlier_range) => earlier_range,
+                        Constructor::Wildcard => {
+                            covered_by_one = true;
+                            break 'arms;
+                        }
+                        _ => continue,
+                    };
+                    if range.is_subrange(earlier_range) {
+                        covered_by_one = true;
+                        break 'arms;
+                    }
+                    if let Some(overlap) = range.intersection(earlier_range) {
+                        shadowed_by.push(ShadowingRange {
+                            span: earlier.data().unwrap().span,
+                            overlap: rcx.hoist_pat_range(&overlap, *alt.ty()),
+                        });
+                    }
+                }
+            }
+            if covered_by_one || shadowed_by.len() < 2 {
+                continue;
+            }
+            let span = alt.data().unwrap().span;
+            rcx.tcx.emit_node_span_lint(
+                SHADOWED_RANGE_PATTERNS,
+                rcx.match_lint_level,
+                span,
+                ShadowedRangePattern { range: span, shadowed_by },
+            );
+        }
+    }
+}
+
+/// Lint a `_` arm of an integer match that only matches a handful of values, which could be
+/// listed instead so that the match stays exhaustive if the ranges above it change.
+pub(crate) fn lint_narrow_wildcard_arms<'p, 'tcx>(
+    rcx: &RustcMatchCheckCtxt<'p, 'tcx>,
+    arms: &[MatchArm<'p, RustcMatchCheckCtxt<'p, 'tcx>>],
+    scrut_ty: RevealedTy<'tcx>,
+    report: &UsefulnessReport<'p, 'tcx>,
+) -> Result<(), ErrorGuaranteed> {
+    if rcx.whole_match_span.is_none()
+        || matches!(
+            rcx.tcx.lint_level_at_node(NARROW_WILDCARD_ARMS, rcx.match_lint_level).0,
+            rustc_session::lint::Level::Allow
+        )
+    {
+        return Ok(());
+    }
+    let ctors_for_ty = rcx.ctors_for_ty(&scrut_ty)?;
+    let ConstructorSet::Integers { .. } = ctors_for_ty else {
+        return Ok(());
+    };
+    let Some(wild_idx) = arms.iter().position(|arm| {
+        !arm.has_guard
+            && matches!(arm.pat.ctor(), Constructor::Wildcard)
+            && arm.pat.data().is_some_and(|pat| matches!(pat.kind, PatKind::Wild))
+    }) else {
+        return Ok(());
+    };
+    if let Usefulness::Redundant = report.arm_usefulness[wild_idx].1 {
+        return Ok(());
+    }
+
+    // Compute the values that reach the wildcard from the ranges of the arms above it. We don't
+    // rerun the usefulness algorithm on these arms as it would emit its lints a second time.
+    let mut alts = Vec::new();
+    for arm in arms[..wild_idx].iter().filter(|arm| !arm.has_guard) {
+        top_level_alternatives(arm.pat, &mut alts);
+    }
+    if !alts.iter().all(|alt| matches!(alt.ctor(), Constructor::IntRange(_))) {
+        return Ok(());
+    }
+    let missing = ctors_for_ty.split(alts.iter().map(|alt| alt.ctor())).missing;
+    if missing.is_empty() {
+        return Ok(());
+    }
+    let mut value_count = 0;
+    let mut patterns = Vec::new();
+    for ctor in &missing {
+        let Constructor::IntRange(range) = ctor else { return Ok(()) };
+        let (Some(lo), Some(hi)) = (range.lo.as_finite_uint(), range.hi.as_finite_uint()) else {
+            return Ok(());
+        };
+        value_count += hi - lo;
+        if value_count > NARROW_WILDCARD_MAX_VALUES {
+            return Ok(());
+        }
+        patterns.push(rcx.hoist_pat_range(range, scrut_ty).to_string());
+    }
+    let patterns = patterns.join(" | ");
+    let span = arms[wild_idx].pat.data().unwrap().span;
+    rcx.tcx.emit_node_span_lint(
+        NARROW_WILDCARD_ARMS,
+        rcx.match_lint_level,
+        span,
+        NarrowWildcardArm { span, patterns },
+    );
+    Ok(())
+}
@@ -0,0 +1,286 @@
+//! A toy type system implementing `TypeCx`, and a brute-force enumerator of its values to check
+//! the results of the analysis against.
+#![allow(dead_code)]
+
+use rustc_pattern_analysis::constructor::{
+    Constructor, ConstructorSet, IntRange, MaybeInfiniteInt, RangeEnd, VariantVisibility,
+};
+use rustc_pattern_analysis::pat::{DeconstructedPat, WitnessPat};
+use rustc_pattern_analysis::{
+    compute_match_usefulness, Captures, MatchArm, PrivateUninhabitedField, TypeCx, Usefulness,
+    ValidityConstraint,
+};
+use std::fmt;
+
+/// The types of the toy type system. They are small enough that all their values can be listed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Ty {
+    Bool,
+    U8,
+    Tuple(&'static [Ty]),
+    /// An enum whose variants each have a single field of the given type. Use `Tuple(&[])` for
+    /// a unit variant.
+    Enum(&'static [Ty]),
+}
+
+pub const UNIT: Ty = Ty::Tuple(&[]);
+
+impl Ty {
+    fn sub_tys(&self, ctor: &Constructor<Cx>) -> Vec<Ty> {
+        use Constructor::*;
+        match (ctor, *self) {
+            (Struct, Ty::Tuple(tys)) => tys.to_vec(),
+            (Variant(i), Ty::Enum(tys)) => vec![tys[*i]],
+            (Bool(..) | IntRange(..) | NonExhaustive | Hidden | Missing | Wildcard, _) => vec![],
+            _ => panic!("unexpected constructor {ctor:?} for type {self:?}"),
+        }
+    }
+
+    fn ctor_set(&self) -> ConstructorSet<Cx> {
+        match *self {
+            Ty::Bool => ConstructorSet::Bool,
+            Ty::U8 => ConstructorSet::Integers {
+                range_1: IntRange::from_range(int(0), int(u8::MAX.into()), RangeEnd::Included),
+                range_2: None,
+            },
+            Ty::Tuple(..) => ConstructorSet::Struct { empty: false },
+            Ty::Enum(tys) => ConstructorSet::Variants {
+                variants: tys.iter().map(|_| VariantVisibility::Visible).collect(),
+                non_exhaustive: false,
+            },
+        }
+    }
+
+    /// All the values of this type.
+    pub fn values(&self) -> Vec<Value> {
+        match *self {
+            Ty::Bool => vec![Value::Bool(false), Value::Bool(true)],
+            Ty::U8 => (0..=u8::MAX).map(|n| Value::Int(n.into())).collect(),
+            Ty::Tuple(tys) => {
+                let mut values = vec![vec![]];
+                for ty in tys {
+                    let field_values = ty.values();
+                    values = values
+                        .into_iter()
+                        .flat_map(|prefix| {
+                            field_values.iter().map(move |value| {
+                                let mut fields = prefix.clone();
+                                fields.push(value.clone());
+                                fields
+                            })
+                        })
+                        .collect();
+                }
+                values.into_iter().map(Value::Tuple).collect()
+            }
+            Ty::Enum(tys) => tys
+                .iter()
+                .enumerate()
+                .flat_map(|(i, ty)| {
+                    ty.values().into_iter().map(move |value| Value::Variant(i, Box::new(value)))
+                })
+                .collect(),
+        }
+    }
+}
+
+fn int(n: u128) -> MaybeInfiniteInt {
+    MaybeInfiniteInt::new_finite_uint(n)
+}
+
+#[derive(Debug)]
+pub struct Cx;
+
+impl TypeCx for Cx {
+    type Ty = Ty;
+    type Error = ();
+    type VariantIdx = usize;
+    type StrLit = ();
+    type ArmData = ();
+    type PatData = ();
+
+    fn is_exhaustive_patterns_feature_on(&self) -> bool {
+        false
+    }
+    fn is_min_exhaustive_patterns_feature_on(&self) -> bool {
+        false
+    }
+
+    fn ctor_arity(&self, ctor: &Constructor<Self>, ty: &Self::Ty) -> usize {
+        ty.sub_tys(ctor).len()
+    }
+    fn ctor_sub_tys<'a>(
+        &'a self,
+        ctor: &'a Constructor<Self>,
+        ty: &'a Self::Ty,
+    ) -> impl Iterator<Item = (Self::Ty, PrivateUninhabitedField)> + ExactSizeIterator + Captures<'a>
+    {
+        ty.sub_tys(ctor).into_iter().map(|ty| (ty, PrivateUninhabitedField(false)))
+    }
+    fn ctors_for_ty(&self, ty: &Self::Ty) -> Result<ConstructorSet<Self>, Self::Error> {
+        Ok(ty.ctor_set())
+    }
+
+    fn write_variant_name(
+        f: &mut fmt::Formatter<'_>,
+        pat: &DeconstructedPat<Self>,
+    ) -> fmt::Result {
+        match pat.ctor() {
+            Constructor::Variant(i) => write!(f, "V{i}"),
+            _ => Ok(()),
+        }
+    }
+
+    fn bug(&self, fmt: fmt::Arguments<'_>) -> Self::Error {
+        panic!("{fmt}")
+    }
+}
+
+/// A value of the toy type system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Bool(bool),
+    Int(u128),
+    Tuple(Vec<Value>),
+    Variant(usize, Box<Value>),
+}
+
+/// If `value` has the constructor `ctor`, returns the values of its fields.
+fn ctor_fields<'v>(ctor: &Constructor<Cx>, value: &'v Value) -> Option<Vec<&'v Value>> {
+    match (ctor, value) {
+        (Constructor::Wildcard, _) => Some(vec![]),
+        (Constructor::Bool(b), Value::Bool(v)) => (b == v).then(Vec::new),
+        (Constructor::IntRange(range), Value::Int(v)) => {
+            (range.lo <= int(*v) && int(*v) < range.hi).then(Vec::new)
+        }
+        (Constructor::Struct, Value::Tuple(fields)) => Some(fields.iter().collect()),
+        (Constructor::Variant(i), Value::Variant(j, field)) => (i == j).then(|| vec![&**field]),
+        _ => panic!("unexpected constructor {ctor:?} for value {value:?}"),
+    }
+}
+
+pub fn pat_matches(pat: &DeconstructedPat<Cx>, value: &Value) -> bool {
+    if let Constructor::Or = pat.ctor() {
+        return pat.iter_fields().any(|alt| pat_matches(alt, value));
+    }
+    ctor_fields(pat.ctor(), value).is_some_and(|fields| {
+        pat.iter_fields().zip(fields).all(|(pat, value)| pat_matches(pat, value))
+    })
+}
+
+pub fn witness_matches(pat: &WitnessPat<Cx>, value: &Value) -> bool {
+    ctor_fields(pat.ctor(), value).is_some_and(|fields| {
+        pat.iter_fields().zip(fields).all(|(pat, value)| witness_matches(pat, value))
+    })
+}
+
+/// An arm of a match to analyze.
+#[derive(Debug)]
+pub struct Arm {
+    pub pat: DeconstructedPat<Cx>,
+    pub has_guard: bool,
+}
+
+impl Arm {
+    pub fn new(pat: DeconstructedPat<Cx>) -> Arm {
+        Arm { pat, has_guard: false }
+    }
+    pub fn guarded(pat: DeconstructedPat<Cx>) -> Arm {
+        Arm { pat, has_guard: true }
+    }
+}
+
+/// The results of the analysis of a match that are compared with the brute-force enumeration.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Outcome {
+    pub exhaustive: bool,
+    pub useful_arms: Vec<bool>,
+}
+
+/// Analyzes a match on `ty` with the usefulness algorithm.
+pub fn analyze(ty: Ty, arms: &[Arm]) -> (Outcome, Vec<WitnessPat<Cx>>) {
+    let arms: Vec<_> = arms
+        .iter()
+        .map(|arm| MatchArm { pat: &arm.pat, has_guard: arm.has_guard, arm_data: () })
+        .collect();
+    let report = compute_match_usefulness(&Cx, &arms, ty, ValidityConstraint::ValidOnly).unwrap();
+    let useful_arms = report
+        .arm_usefulness
+        .iter()
+        .map(|(_, usefulness)| matches!(usefulness, Usefulness::Useful(_)))
+        .collect();
+    let witnesses = report.non_exhaustiveness_witnesses;
+    (Outcome { exhaustive: witnesses.is_empty(), useful_arms }, witnesses)
+}
+
+/// Analyzes a match on `ty` by trying every value. An arm is useful if it matches a value that no
+/// unguarded arm above it matches.
+pub fn brute_force(ty: Ty, arms: &[Arm]) -> Outcome {
+    let mut useful_arms = vec![false; arms.len()];
+    let mut exhaustive = true;
+    for value in ty.values() {
+        let mut covered = false;
+        for (i, arm) in arms.iter().enumerate() {
+            if pat_matches(&arm.pat, &value) {
+                useful_arms[i] = true;
+                if !arm.has_guard {
+                    covered = true;
+                    break;
+                }
+            }
+        }
+        exhaustive &= covered;
+    }
+    Outcome { exhaustive, useful_arms }
+}
+
+/// Checks the results of the analysis against the brute-force enumeration, including that every
+/// witness describes at least one value and only values that no unguarded arm matches.
+pub fn check(ty: Ty, arms: &[Arm]) -> Result<Outcome, String> {
+    let (outcome, witnesses) = analyze(ty, arms);
+    let expected = brute_force(ty, arms);
+    if outcome != expected {
+        return Err(format!("analysis gave {outcome:?}, brute force gave {expected:?}"));
+    }
+    for witness in &witnesses {
+        let values: Vec<_> =
+            ty.values().into_iter().filter(|value| witness_matches(witness, value)).collect();
+        if values.is_empty() {
+            return Err(format!("witness {witness:?} matches no value"));
+        }
+        for value in values {
+            if arms.iter().any(|arm| !arm.has_guard && pat_matches(&arm.pat, &value)) {
+                return Err(format!("{value:?} matches witness {witness:?} but is covered"));
+            }
+        }
+    }
+    Ok(outcome)
+}
+
+// Pattern builders.
+
+pub fn wild(ty: Ty) -> DeconstructedPat<Cx> {
+    DeconstructedPat::wildcard(ty)
+}
+
+pub fn bool(b: bool) -> DeconstructedPat<Cx> {
+    DeconstructedPat::new(Constructor::Bool(b), vec![], Ty::Bool, ())
+}
+
+/// The `u8` pattern `lo..=hi`.
+pub fn range(lo: u8, hi: u8) -> DeconstructedPat<Cx> {
+    let range = IntRange::from_range(int(lo.into()), int(hi.into()), RangeEnd::Included);
+    DeconstructedPat::new(Constructor::IntRange(range), vec![], Ty::U8, ())
+}
+
+pub fn tuple(ty: Ty, fields: Vec<DeconstructedPat<Cx>>) -> DeconstructedPat<Cx> {
+    DeconstructedPat::new(Constructor::Struct, fields, ty, ())
+}
+
+pub fn variant(ty: Ty, i: usize, field: DeconstructedPat<Cx>) -> DeconstructedPat<Cx> {
+    DeconstructedPat::new(Constructor::Variant(i), vec![field], ty, ())
+}
+
+pub fn or(ty: Ty, alts: Vec<DeconstructedPat<Cx>>) -> DeconstructedPat<Cx> {
+    DeconstructedPat::new(Constructor::Or, alts, ty, ())
+}
@@ -0,0 +1,115 @@
+//! Differential testing of the usefulness algorithm: random matches on the toy type system are
+//! analyzed, and the results compared with a brute-force enumeration of the values of the type.
+//!
+//! Each match is generated from a seed. The number of matches can be increased to fuzz the
+//! algorithm by setting `RUSTC_PATTERN_ANALYSIS_FUZZ_ITERS`, and a failing seed can be replayed
+//! by setting `RUSTC_PATTERN_ANALYSIS_FUZZ_SEED`.
+
+mod common;
+
+use common::*;
+use rustc_pattern_analysis::pat::DeconstructedPat;
+
+const TYPES: &[Ty] = &[
+    Ty::Bool,
+    Ty::U8,
+    Ty::Tuple(&[Ty::Bool, Ty::Bool, Ty::Bool]),
+    Ty::Tuple(&[Ty::Bool, Ty::U8]),
+    Ty::Enum(&[UNIT, Ty::Bool, Ty::Tuple(&[Ty::Bool, Ty::Bool])]),
+    Ty::Tuple(&[Ty::Enum(&[UNIT, Ty::Bool]), Ty::U8]),
+    Ty::Enum(&[Ty::U8, Ty::Tuple(&[Ty::Bool, Ty::Enum(&[UNIT, Ty::Bool])])]),
+];
+
+/// Integers that range patterns start or end at more often than others, so that the ranges of a
+/// match overlap and touch each other.
+const INTERESTING_INTS: &[u8] = &[0, 1, 2, 9, 10, 127, 128, 254, 255];
+
+/// A xorshift generator, which is good enough to generate patterns.
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Rng {
+        // The state must not be zero.
+        Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    fn int(&mut self) -> u8 {
+        if self.one_in(2) {
+            INTERESTING_INTS[self.below(INTERESTING_INTS.len())]
+        } else {
+            self.next() as u8
+        }
+    }
+}
+
+fn random_pat(rng: &mut Rng, ty: Ty, depth: usize) -> DeconstructedPat<Cx> {
+    if rng.one_in(4) {
+        return wild(ty);
+    }
+    if depth < 2 && rng.one_in(6) {
+        let alts = (0..2 + rng.below(2)).map(|_| random_pat(rng, ty, depth + 1)).collect();
+        return or(ty, alts);
+    }
+    match ty {
+        Ty::Bool => bool(rng.one_in(2)),
+        Ty::U8 => {
+            let (a, b) = (rng.int(), rng.int());
+            range(a.min(b), a.max(b))
+        }
+        Ty::Tuple(tys) => {
+            tuple(ty, tys.iter().map(|&field_ty| random_pat(rng, field_ty, depth)).collect())
+        }
+        Ty::Enum(tys) => {
+            let i = rng.below(tys.len());
+            variant(ty, i, random_pat(rng, tys[i], depth))
+        }
+    }
+}
+
+fn random_match(seed: u64) -> (Ty, Vec<Arm>) {
+    let mut rng = Rng::new(seed);
+    let ty = TYPES[rng.below(TYPES.len())];
+    let arms = (0..1 + rng.below(6))
+        .map(|_| {
+            let pat = random_pat(&mut rng, ty, 0);
+            if rng.one_in(8) { Arm::guarded(pat) } else { Arm::new(pat) }
+        })
+        .collect();
+    (ty, arms)
+}
+
+fn env_var<T: std::str::FromStr>(name: &str) -> Option<T> {
+    std::env::var(name).ok().map(|value| match value.parse() {
+        Ok(value) => value,
+        Err(_) => panic!("invalid value for {name}: {value}"),
+    })
+}
+
+#[test]
+fn matches_brute_force() {
+    let seeds = match env_var("RUSTC_PATTERN_ANALYSIS_FUZZ_SEED") {
+        Some(seed) => seed..seed + 1,
+        None => 0..env_var("RUSTC_PATTERN_ANALYSIS_FUZZ_ITERS").unwrap_or(500),
+    };
+    for seed in seeds {
+        let (ty, arms) = random_match(seed);
+        if let Err(error) = check(ty, &arms) {
+            panic!("seed {seed}: match on {ty:?} with arms {arms:#?}: {error}");
+        }
+    }
+}
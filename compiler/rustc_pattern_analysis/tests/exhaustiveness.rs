@@ -0,0 +1,68 @@
+//! Hand-written checks of exhaustiveness and usefulness on the toy type system.
+
+mod common;
+
+use common::*;
+
+const BOOL_PAIR: Ty = Ty::Tuple(&[Ty::Bool, Ty::Bool]);
+const OPTION_U8: Ty = Ty::Enum(&[UNIT, Ty::U8]);
+
+fn assert_outcome(ty: Ty, arms: &[Arm], exhaustive: bool, useful_arms: &[bool]) {
+    let outcome = check(ty, arms).unwrap();
+    assert_eq!(outcome, Outcome { exhaustive, useful_arms: useful_arms.to_vec() });
+}
+
+#[test]
+fn bools() {
+    let arms = [Arm::new(bool(true)), Arm::new(bool(false)), Arm::new(wild(Ty::Bool))];
+    assert_outcome(Ty::Bool, &arms, true, &[true, true, false]);
+
+    let arms = [Arm::new(bool(true))];
+    assert_outcome(Ty::Bool, &arms, false, &[true]);
+}
+
+#[test]
+fn integer_ranges() {
+    let arms = [Arm::new(range(0, 99)), Arm::new(range(100, 255)), Arm::new(range(50, 60))];
+    assert_outcome(Ty::U8, &arms, true, &[true, true, false]);
+
+    // `255` is missing.
+    let arms = [Arm::new(range(0, 127)), Arm::new(range(128, 254))];
+    assert_outcome(Ty::U8, &arms, false, &[true, true]);
+}
+
+#[test]
+fn tuples() {
+    let arms = [
+        Arm::new(tuple(BOOL_PAIR, vec![bool(true), wild(Ty::Bool)])),
+        Arm::new(tuple(BOOL_PAIR, vec![wild(Ty::Bool), bool(true)])),
+        Arm::new(tuple(BOOL_PAIR, vec![bool(true), bool(true)])),
+    ];
+    assert_outcome(BOOL_PAIR, &arms, false, &[true, true, false]);
+}
+
+#[test]
+fn enums() {
+    let arms = [
+        Arm::new(variant(OPTION_U8, 1, range(0, 9))),
+        Arm::new(variant(OPTION_U8, 0, wild(UNIT))),
+        Arm::new(variant(OPTION_U8, 1, wild(Ty::U8))),
+    ];
+    assert_outcome(OPTION_U8, &arms, true, &[true, true, true]);
+}
+
+#[test]
+fn or_patterns() {
+    let arms = [
+        Arm::new(or(Ty::U8, vec![range(0, 9), range(10, 255)])),
+        Arm::new(range(5, 5)),
+    ];
+    assert_outcome(Ty::U8, &arms, true, &[true, false]);
+}
+
+#[test]
+fn guards() {
+    // A guarded arm doesn't make the arms below it redundant, nor the match exhaustive.
+    let arms = [Arm::guarded(wild(Ty::Bool)), Arm::new(bool(false))];
+    assert_outcome(Ty::Bool, &arms, false, &[true, true]);
+}
@@ -100,6 +100,10 @@ pub(crate) struct DepGraphData<D: Deps> {
     /// a particular query result was decoded from disk
     /// (not just marked green)
     debug_loaded_from_disk: Lock<FxHashSet<DepNode>>,
+
+    /// For each node of the previous session that could not be marked green,
+    /// the dependency that prevented it. Only recorded for `-Zincremental-explain`.
+    red_causes: Option<Lock<FxHashMap<SerializedDepNodeIndex, SerializedDepNodeIndex>>>,
 }
 
 pub fn hash_result<R>(hcx: &mut StableHashingContext<'_>, result: &R) -> Fingerprint
@@ -119,6 +123,8 @@ pub fn new(
         encoder: FileEncoder,
         record_graph: bool,
         record_stats: bool,
+        record_red_causes: bool,
+        record_fingerprint: bool,
     ) -> DepGraph<D> {
         let prev_graph_node_count = prev_graph.node_count();
 
@@ -128,6 +134,7 @@ pub fn new(
             encoder,
             record_graph,
             record_stats,
+            record_fingerprint,
         );
 
         let colors = DepNodeColorMap::new(prev_graph_node_count);
@@ -174,6 +181,7 @@ pub fn new(
                 previous: prev_graph,
                 colors,
                 debug_loaded_from_disk: Default::default(),
+                red_causes: record_red_causes.then(Default::default),
             })),
             virtual_dep_node_index: Lrc::new(AtomicU32::new(0)),
         }
@@ -860,7 +868,12 @@ fn try_mark_previous_green<Qcx: QueryContext<Deps = D>>(
         let prev_deps = self.previous.edge_targets_from(prev_dep_node_index);
 
         for dep_dep_node_index in prev_deps {
-            self.try_mark_parent_green(qcx, dep_dep_node_index, Some(&frame))?;
+            if self.try_mark_parent_green(qcx, dep_dep_node_index, Some(&frame)).is_none() {
+                if let Some(red_causes) = &self.red_causes {
+                    red_causes.lock().insert(prev_dep_node_index, dep_dep_node_index);
+                }
+                return None;
+            }
         }
 
         // If we got here without hitting a `return` that means that all
@@ -972,6 +985,45 @@ pub fn exec_cache_promotions<Tcx: DepContext>(&self, tcx: Tcx) {
         }
     }
 
+    /// Returns all nodes of the previous session that could not be marked green
+    /// during this session, because one of their dependencies changed.
+    ///
+    /// This is only recorded with `-Zincremental-explain`.
+    pub fn nodes_with_red_cause(&self) -> Vec<DepNode> {
+        let Some(data) = &self.data else { return Vec::new() };
+        let Some(red_causes) = &data.red_causes else { return Vec::new() };
+        let mut nodes: Vec<_> = red_causes.lock().keys().copied().collect();
+        nodes.sort_unstable();
+        nodes.into_iter().map(|index| data.previous.index_to_node(index)).collect()
+    }
+
+    /// Returns why `dep_node` could not be marked green during this session as
+    /// a chain of dependencies, starting with the dependency that changed and
+    /// ending with `dep_node` itself. Returns `None` if `dep_node` was not
+    /// tried to be marked green, or if it could be marked green.
+    ///
+    /// This is only recorded with `-Zincremental-explain`.
+    pub fn red_cause_chain(&self, dep_node: &DepNode) -> Option<Vec<DepNode>> {
+        let data = self.data.as_ref()?;
+        let red_causes = data.red_causes.as_ref()?.lock();
+        let mut index = data.previous.node_to_index_opt(dep_node)?;
+        if !red_causes.contains_key(&index) {
+            return None;
+        }
+
+        let mut chain = vec![index];
+        while let Some(&cause) = red_causes.get(&index) {
+            // The previous dep-graph is acyclic, but be defensive about what was
+            // loaded from disk.
+            if chain.contains(&cause) {
+                break;
+            }
+            chain.push(cause);
+            index = cause;
+        }
+        Some(chain.into_iter().rev().map(|index| data.previous.index_to_node(index)).collect())
+    }
+
     pub fn print_incremental_info(&self) {
         if let Some(data) = &self.data {
             data.current.encoder.borrow().print_incremental_info(
@@ -1115,6 +1167,7 @@ fn new(
         encoder: FileEncoder,
         record_graph: bool,
         record_stats: bool,
+        record_fingerprint: bool,
     ) -> Self {
         use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -1150,6 +1203,7 @@ fn new(
                 prev_graph_node_count,
                 record_graph,
                 record_stats,
+                record_fingerprint,
             )),
             new_node_to_index: Sharded::new(|| {
                 FxHashMap::with_capacity_and_hasher(
@@ -2,7 +2,9 @@
 //!
 //! Notionally, the dep-graph is a sequence of NodeInfo with the dependencies
 //! specified inline. The total number of nodes and edges are stored as the last
-//! 16 bytes of the file, so we can find them easily at decoding time.
+//! 16 bytes of the file, so we can find them easily at decoding time. They are preceded
+//! by a byte telling whether a fingerprint of the whole graph, which is only computed for
+//! `-Zincremental-verify-cache`, precedes it in turn.
 //!
 //! The serialisation is performed on-demand when each node is emitted. Using this
 //! scheme, we do not need to keep the current graph in memory.
@@ -87,6 +89,10 @@ pub struct SerializedDepGraph {
     /// Stores a map from fingerprints to nodes per dep node kind.
     /// This is the reciprocal of `nodes`.
     index: Vec<UnhashMap<PackedFingerprint, SerializedDepNodeIndex>>,
+    /// The fingerprint of the whole graph that was stored alongside it, see
+    /// [`combine_graph_fingerprint`]. `None` for a graph that was not loaded from disk, or that
+    /// was saved without one.
+    graph_fingerprint: Option<Fingerprint>,
 }
 
 impl SerializedDepGraph {
@@ -141,6 +147,72 @@ pub fn fingerprint_by_index(&self, dep_node_index: SerializedDepNodeIndex) -> Fi
     pub fn node_count(&self) -> usize {
         self.nodes.len()
     }
+
+    /// Checks the internal consistency of a graph that was loaded from disk.
+    ///
+    /// This is cheap compared to actually using the graph and allows rejecting
+    /// a graph that has been produced elsewhere, e.g. on another machine, and
+    /// that has been damaged on the way. Damage that makes the file impossible
+    /// to decode is already reported by [`SerializedDepGraph::decode`].
+    pub fn verify(&self) -> Result<(), String> {
+        let node_count = self.node_count();
+
+        // Every node must be reachable through the index, i.e. no `DepNode` may
+        // appear twice.
+        let indexed_count: usize = self.index.iter().map(|index| index.len()).sum();
+        if indexed_count != node_count {
+            return Err(format!("{} duplicate dep-nodes", node_count - indexed_count));
+        }
+
+        for source in self.nodes.indices() {
+            if let Some(target) =
+                self.edge_targets_from(source).find(|target| target.index() >= node_count)
+            {
+                return Err(format!(
+                    "dep-node {} has an edge to nonexistent dep-node {}",
+                    source.index(),
+                    target.index()
+                ));
+            }
+        }
+
+        if let Some(expected) = self.graph_fingerprint {
+            let found = self.nodes.iter_enumerated().fold(Fingerprint::ZERO, |acc, (index, node)| {
+                combine_graph_fingerprint(
+                    acc,
+                    node,
+                    self.fingerprints[index],
+                    self.edge_targets_from(index).map(|target| target.as_u32()),
+                )
+            });
+            if found != expected {
+                return Err(format!(
+                    "the fingerprint of the dep-graph is {}, expected {}",
+                    found.to_hex(),
+                    expected.to_hex()
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Folds a node and its edges into the fingerprint of the whole graph, which is stored at the end
+/// of the file so that [`SerializedDepGraph::verify`] can detect a graph that was damaged on disk.
+#[inline]
+fn combine_graph_fingerprint(
+    graph_fingerprint: Fingerprint,
+    node: &DepNode,
+    fingerprint: Fingerprint,
+    edges: impl Iterator<Item = u32>,
+) -> Fingerprint {
+    let hash: Fingerprint = node.hash.into();
+    let head = graph_fingerprint
+        .combine(Fingerprint::new(node.kind.as_inner() as u64, 0u64))
+        .combine(hash)
+        .combine(fingerprint);
+    edges.fold(head, |acc, edge| acc.combine(Fingerprint::new(edge as u64, 0u64)))
 }
 
 /// A packed representation of an edge's start index and byte width.
@@ -175,22 +247,56 @@ fn mask(bits: usize) -> usize {
 }
 
 impl SerializedDepGraph {
+    /// Decodes a graph that was written by [`GraphEncoder`].
+    ///
+    /// The file may have been damaged after it was written, so this never reads out of bounds
+    /// and instead returns an error describing the first inconsistency it runs into.
     #[instrument(level = "debug", skip(d))]
-    pub fn decode<D: Deps>(d: &mut MemDecoder<'_>) -> SerializedDepGraph {
-        // The last 16 bytes are the node count and edge count.
+    pub fn decode<D: Deps>(d: &mut MemDecoder<'_>) -> Result<SerializedDepGraph, String> {
+        const TRAILER_SIZE: usize = 1 + 2 * IntEncodedWithFixedSize::ENCODED_SIZE;
+        const FINGERPRINT_SIZE: usize = std::mem::size_of::<Fingerprint>();
+        let header_size = std::mem::size_of::<SerializedNodeHeader<D>>();
+        let truncated = || "the dep-graph file is truncated".to_string();
+
+        // The last 17 bytes are whether the graph has a fingerprint, the node count and edge
+        // count, and the fingerprint of the graph, if any, comes right before them. Everything
+        // after `graph_end` is therefore guaranteed to be readable, which lets us check the
+        // position only once before each leb128 read.
         debug!("position: {:?}", d.position());
-        let (node_count, edge_count) =
-            d.with_position(d.len() - 2 * IntEncodedWithFixedSize::ENCODED_SIZE, |d| {
-                debug!("position: {:?}", d.position());
-                let node_count = IntEncodedWithFixedSize::decode(d).0 as usize;
-                let edge_count = IntEncodedWithFixedSize::decode(d).0 as usize;
-                (node_count, edge_count)
-            });
+        let trailer_start = d.len().checked_sub(TRAILER_SIZE).ok_or_else(truncated)?;
+        if trailer_start < d.position() {
+            return Err(truncated());
+        }
+        let (has_fingerprint, node_count, edge_count) = d.with_position(trailer_start, |d| {
+            debug!("position: {:?}", d.position());
+            let has_fingerprint = d.read_u8();
+            let node_count = IntEncodedWithFixedSize::decode(d).0 as usize;
+            let edge_count = IntEncodedWithFixedSize::decode(d).0 as usize;
+            (has_fingerprint, node_count, edge_count)
+        });
+        let (graph_end, graph_fingerprint) = match has_fingerprint {
+            0 => (trailer_start, None),
+            1 => {
+                let graph_end = trailer_start.checked_sub(FINGERPRINT_SIZE).ok_or_else(truncated)?;
+                if graph_end < d.position() {
+                    return Err(truncated());
+                }
+                let graph_fingerprint =
+                    d.with_position(graph_end, |d| Fingerprint::from_le_bytes(d.read_array()));
+                (graph_end, Some(graph_fingerprint))
+            }
+            _ => return Err("the dep-graph file has an invalid trailer".to_string()),
+        };
         debug!("position: {:?}", d.position());
 
         debug!(?node_count, ?edge_count);
 
-        let graph_bytes = d.len() - (2 * IntEncodedWithFixedSize::ENCODED_SIZE) - d.position();
+        let graph_bytes = graph_end - d.position();
+        // Check the node count before using it to allocate anything.
+        let headers_bytes = node_count
+            .checked_mul(header_size)
+            .filter(|&headers_bytes| headers_bytes <= graph_bytes)
+            .ok_or_else(|| format!("the dep-graph file is too short for {node_count} dep-nodes"))?;
 
         let mut nodes = IndexVec::with_capacity(node_count);
         let mut fingerprints = IndexVec::with_capacity(node_count);
@@ -204,17 +310,26 @@ pub fn decode<D: Deps>(d: &mut MemDecoder<'_>) -> SerializedDepGraph {
         // for a node with length 64, which means the spilled 1-byte leb128 length is 1 byte of at
         // least (34 byte header + 1 byte len + 64 bytes edge data), which is ~1%. A 2-byte leb128
         // length is about the same fractional overhead and it amortizes for yet greater lengths.
-        let mut edge_list_data = Vec::with_capacity(
-            graph_bytes - node_count * std::mem::size_of::<SerializedNodeHeader<D>>(),
-        );
+        let mut edge_list_data = Vec::with_capacity(graph_bytes - headers_bytes);
+        let mut decoded_edge_count = 0usize;
+        let mut kind_counts = vec![0u32; D::DEP_KIND_MAX as usize + 1];
 
         for _index in 0..node_count {
+            if graph_end.saturating_sub(d.position()) < header_size {
+                return Err(truncated());
+            }
             // Decode the header for this edge; the header packs together as many of the fixed-size
             // fields as possible to limit the number of times we update decoder state.
             let node_header =
                 SerializedNodeHeader::<D> { bytes: d.read_array(), _marker: PhantomData };
 
-            let _i: SerializedDepNodeIndex = nodes.push(node_header.node());
+            let node = node_header.node();
+            if node.kind.as_inner() > D::DEP_KIND_MAX {
+                return Err(format!("dep-node {_index} has invalid kind {}", node.kind.as_inner()));
+            }
+            kind_counts[node.kind.as_usize()] += 1;
+
+            let _i: SerializedDepNodeIndex = nodes.push(node);
             debug_assert_eq!(_i.index(), _index);
 
             let _i: SerializedDepNodeIndex = fingerprints.push(node_header.fingerprint());
@@ -222,12 +337,21 @@ pub fn decode<D: Deps>(d: &mut MemDecoder<'_>) -> SerializedDepGraph {
 
             // If the length of this node's edge list is small, the length is stored in the header.
             // If it is not, we fall back to another decoder call.
-            let num_edges = node_header.len().unwrap_or_else(|| d.read_usize());
+            let num_edges = match node_header.len() {
+                Some(num_edges) => num_edges,
+                None if d.position() < graph_end => d.read_usize(),
+                None => return Err(truncated()),
+            };
+            decoded_edge_count = decoded_edge_count.saturating_add(num_edges);
 
             // The edges index list uses the same varint strategy as rmeta tables; we select the
             // number of byte elements per-array not per-element. This lets us read the whole edge
             // list for a node with one decoder call and also use the on-disk format in memory.
-            let edges_len_bytes = node_header.bytes_per_index() * num_edges;
+            let edges_len_bytes = node_header
+                .bytes_per_index()
+                .checked_mul(num_edges)
+                .filter(|&len| len <= graph_end.saturating_sub(d.position()))
+                .ok_or_else(truncated)?;
             // The in-memory structure for the edges list stores the byte width of the edges on
             // this node with the offset into the global edge data array.
             let edges_header = node_header.edges_header(&edge_list_data);
@@ -238,21 +362,47 @@ pub fn decode<D: Deps>(d: &mut MemDecoder<'_>) -> SerializedDepGraph {
             debug_assert_eq!(_i.index(), _index);
         }
 
+        if decoded_edge_count != edge_count {
+            return Err(format!(
+                "the dep-graph has {decoded_edge_count} edges, expected {edge_count}"
+            ));
+        }
+
         // When we access the edge list data, we do a fixed-size read from the edge list data then
         // mask off the bytes that aren't for that edge index, so the last read may dangle off the
         // end of the array. This padding ensure it doesn't.
         edge_list_data.extend(&[0u8; DEP_NODE_PAD]);
 
         // Read the number of each dep kind and use it to create an hash map with a suitable size.
-        let mut index: Vec<_> = (0..(D::DEP_KIND_MAX + 1))
-            .map(|_| UnhashMap::with_capacity_and_hasher(d.read_u32() as usize, Default::default()))
-            .collect();
+        let mut index = Vec::with_capacity(kind_counts.len());
+        for (kind, count) in kind_counts.into_iter().enumerate() {
+            if d.position() >= graph_end {
+                return Err(truncated());
+            }
+            let expected = d.read_u32();
+            if count != expected {
+                return Err(format!(
+                    "the dep-graph has {count} dep-nodes of kind {kind}, expected {expected}"
+                ));
+            }
+            index.push(UnhashMap::with_capacity_and_hasher(count as usize, Default::default()));
+        }
+        if d.position() != graph_end {
+            return Err("unexpected data at the end of the dep-graph file".to_string());
+        }
 
         for (idx, node) in nodes.iter_enumerated() {
             index[node.kind.as_usize()].insert(node.hash, idx);
         }
 
-        SerializedDepGraph { nodes, fingerprints, edge_list_indices, edge_list_data, index }
+        Ok(SerializedDepGraph {
+            nodes,
+            fingerprints,
+            edge_list_indices,
+            edge_list_data,
+            index,
+            graph_fingerprint,
+        })
     }
 }
 
@@ -418,6 +568,8 @@ struct EncoderState<D: Deps> {
     encoder: FileEncoder,
     total_node_count: usize,
     total_edge_count: usize,
+    /// The fingerprint of the graph so far, if it is recorded for `-Zincremental-verify-cache`.
+    graph_fingerprint: Option<Fingerprint>,
     stats: Option<FxHashMap<DepKind, Stat>>,
 
     /// Stores the number of times we've encoded each dep kind.
@@ -426,11 +578,12 @@ struct EncoderState<D: Deps> {
 }
 
 impl<D: Deps> EncoderState<D> {
-    fn new(encoder: FileEncoder, record_stats: bool) -> Self {
+    fn new(encoder: FileEncoder, record_stats: bool, record_fingerprint: bool) -> Self {
         Self {
             encoder,
             total_edge_count: 0,
             total_node_count: 0,
+            graph_fingerprint: record_fingerprint.then_some(Fingerprint::ZERO),
             stats: record_stats.then(FxHashMap::default),
             kind_stats: iter::repeat(0).take(D::DEP_KIND_MAX as usize + 1).collect(),
             marker: PhantomData,
@@ -448,6 +601,14 @@ fn encode_node(
 
         let edge_count = node.edges.len();
         self.total_edge_count += edge_count;
+        if let Some(graph_fingerprint) = &mut self.graph_fingerprint {
+            *graph_fingerprint = combine_graph_fingerprint(
+                *graph_fingerprint,
+                &node.node,
+                node.fingerprint,
+                node.edges.iter().map(|edge| edge.as_u32()),
+            );
+        }
 
         if let Some(record_graph) = &record_graph {
             // Do not ICE when a query is called from within `with_query`.
@@ -474,6 +635,7 @@ fn finish(self, profiler: &SelfProfilerRef) -> FileEncodeResult {
             mut encoder,
             total_node_count,
             total_edge_count,
+            graph_fingerprint,
             stats: _,
             kind_stats,
             marker: _,
@@ -489,6 +651,10 @@ fn finish(self, profiler: &SelfProfilerRef) -> FileEncodeResult {
 
         debug!(?node_count, ?edge_count);
         debug!("position: {:?}", encoder.position());
+        if let Some(graph_fingerprint) = graph_fingerprint {
+            encoder.write_array(graph_fingerprint.to_le_bytes());
+        }
+        encoder.emit_u8(graph_fingerprint.is_some() as u8);
         IntEncodedWithFixedSize(node_count).encode(&mut encoder);
         IntEncodedWithFixedSize(edge_count).encode(&mut encoder);
         debug!("position: {:?}", encoder.position());
@@ -514,9 +680,10 @@ pub fn new(
         prev_node_count: usize,
         record_graph: bool,
         record_stats: bool,
+        record_fingerprint: bool,
     ) -> Self {
         let record_graph = record_graph.then(|| Lock::new(DepGraphQuery::new(prev_node_count)));
-        let status = Lock::new(EncoderState::new(encoder, record_stats));
+        let status = Lock::new(EncoderState::new(encoder, record_stats, record_fingerprint));
         GraphEncoder { status, record_graph }
     }
 
@@ -595,3 +762,6 @@ pub fn finish(self, profiler: &SelfProfilerRef) -> FileEncodeResult {
         self.status.into_inner().finish(profiler)
     }
 }
+
+#[cfg(test)]
+mod tests;
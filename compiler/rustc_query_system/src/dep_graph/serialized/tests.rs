@@ -0,0 +1,92 @@
+use super::*;
+use crate::dep_graph::TaskDepsRef;
+
+struct TestDeps;
+
+impl Deps for TestDeps {
+    fn with_deps<OP, R>(_: TaskDepsRef<'_>, op: OP) -> R
+    where
+        OP: FnOnce() -> R,
+    {
+        op()
+    }
+
+    fn read_deps<OP>(_: OP)
+    where
+        OP: for<'a> FnOnce(TaskDepsRef<'a>),
+    {
+    }
+
+    const DEP_KIND_NULL: DepKind = DepKind::new(0);
+    const DEP_KIND_RED: DepKind = DepKind::new(1);
+    const DEP_KIND_MAX: u16 = 3;
+}
+
+/// Encodes a small graph in which every node depends on all the nodes before it.
+fn encode_graph() -> Vec<u8> {
+    encode_graph_with(true)
+}
+
+fn encode_graph_with(record_fingerprint: bool) -> Vec<u8> {
+    let tmpfile = tempfile::NamedTempFile::new().unwrap();
+    let encoder = FileEncoder::new(tmpfile.path()).unwrap();
+    let mut state = EncoderState::<TestDeps>::new(encoder, false, record_fingerprint);
+    for i in 0..10 {
+        let node = NodeInfo {
+            node: DepNode {
+                kind: DepKind::new(i as u16 % 4),
+                hash: Fingerprint::new(i, i + 1).into(),
+            },
+            fingerprint: Fingerprint::new(i, 0u64),
+            edges: (0..i as usize).map(DepNodeIndex::new).collect(),
+        };
+        state.encode_node(&node, &None);
+    }
+    state.finish(&SelfProfilerRef::new(None, None)).unwrap();
+    std::fs::read(tmpfile.path()).unwrap()
+}
+
+fn decode_and_verify(bytes: &[u8]) -> Result<SerializedDepGraph, String> {
+    let graph = SerializedDepGraph::decode::<TestDeps>(&mut MemDecoder::new(bytes, 0))?;
+    graph.verify()?;
+    Ok(graph)
+}
+
+#[test]
+fn roundtrip() {
+    let graph = decode_and_verify(&encode_graph()).unwrap();
+    assert_eq!(graph.node_count(), 10);
+    let edges: Vec<_> = graph.edge_targets_from(SerializedDepNodeIndex::new(3)).collect();
+    assert_eq!(edges, (0..3).map(SerializedDepNodeIndex::new).collect::<Vec<_>>());
+    let fingerprint = graph.fingerprint_by_index(SerializedDepNodeIndex::new(7));
+    assert_eq!(fingerprint, Fingerprint::new(7u64, 0u64));
+}
+
+#[test]
+fn without_fingerprint() {
+    let bytes = encode_graph_with(false);
+    assert_eq!(bytes.len() + 16, encode_graph().len());
+    let graph = decode_and_verify(&bytes).unwrap();
+    assert_eq!(graph.node_count(), 10);
+    for len in 0..bytes.len() {
+        assert!(decode_and_verify(&bytes[..len]).is_err(), "truncated to {len} bytes");
+    }
+}
+
+#[test]
+fn truncated() {
+    let bytes = encode_graph();
+    for len in 0..bytes.len() {
+        assert!(decode_and_verify(&bytes[..len]).is_err(), "truncated to {len} bytes");
+    }
+}
+
+#[test]
+fn corrupted() {
+    let bytes = encode_graph();
+    for position in 0..bytes.len() {
+        let mut corrupted = bytes.clone();
+        corrupted[position] ^= 0x55;
+        assert!(decode_and_verify(&corrupted).is_err(), "corrupted byte {position}");
+    }
+}
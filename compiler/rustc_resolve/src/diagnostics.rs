@@ -20,6 +20,7 @@
 use rustc_session::lint::builtin::AMBIGUOUS_GLOB_IMPORTS;
 use rustc_session::lint::builtin::MACRO_EXPANDED_MACRO_EXPORTS_ACCESSED_BY_ABSOLUTE_PATHS;
 use rustc_session::lint::{AmbiguityErrorDiag, BuiltinLintDiagnostics};
+use rustc_session::parse::add_feature_diagnostics;
 use rustc_session::Session;
 use rustc_span::edit_distance::find_best_match_for_name;
 use rustc_span::edition::Edition;
@@ -1456,6 +1457,14 @@ pub(crate) fn unresolved_macro_suggestions(
             return;
         }
 
+        // The field attributes of the built-in derives are only helpers with the feature enabled.
+        if macro_kind == MacroKind::Attr
+            && matches!(ident.name, sym::eq | sym::ord | sym::hash)
+            && !self.tcx.features().derive_field_attrs
+        {
+            add_feature_diagnostics(err, self.tcx.sess, sym::derive_field_attrs);
+        }
+
         if self.macro_names.contains(&ident.normalize_to_macros_2_0()) {
             err.subdiagnostic(self.dcx(), AddedMacroUse);
             return;
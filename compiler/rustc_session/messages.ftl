@@ -40,6 +40,13 @@ session_hexadecimal_float_literal_not_supported = hexadecimal float literal is n
 session_incompatible_linker_flavor = linker flavor `{$flavor}` is incompatible with the current target
     .note = compatible flavors are: {$compatible_list}
 
+session_incremental_relocatable_requires_all_remap_scopes =
+    `-Zincremental-relocatable` requires paths to be remapped in all scopes
+
+session_incremental_relocatable_requires_remapped_working_dir =
+    `-Zincremental-relocatable` requires the working directory to be remapped
+    .help = pass `--remap-path-prefix` with a prefix of the working directory
+
 session_instrumentation_not_supported = {$us} instrumentation is not supported for this target
 
 session_int_literal_too_large = integer literal is too large
@@ -33,6 +33,7 @@
 use std::path::{Path, PathBuf};
 use std::str::{self, FromStr};
 use std::sync::LazyLock;
+use std::time::Duration;
 
 pub mod sigpipe;
 
@@ -419,6 +420,7 @@ pub enum OutputType {
     Object,
     Exe,
     DepInfo,
+    CfiTypeIds,
 }
 
 // Safety: Trivial C-Style enums have a stable sort order across compilation sessions.
@@ -437,7 +439,10 @@ fn to_stable_hash_key(&self, _: &HCX) -> Self::KeyType {
 impl OutputType {
     fn is_compatible_with_codegen_units_and_single_output_file(&self) -> bool {
         match *self {
-            OutputType::Exe | OutputType::DepInfo | OutputType::Metadata => true,
+            OutputType::Exe
+            | OutputType::DepInfo
+            | OutputType::Metadata
+            | OutputType::CfiTypeIds => true,
             OutputType::Bitcode
             | OutputType::Assembly
             | OutputType::LlvmAssembly
@@ -456,6 +461,7 @@ pub fn shorthand(&self) -> &'static str {
             OutputType::Metadata => "metadata",
             OutputType::Exe => "link",
             OutputType::DepInfo => "dep-info",
+            OutputType::CfiTypeIds => "cfi-typeids",
         }
     }
 
@@ -469,13 +475,14 @@ fn from_shorthand(shorthand: &str) -> Option<Self> {
             "metadata" => OutputType::Metadata,
             "link" => OutputType::Exe,
             "dep-info" => OutputType::DepInfo,
+            "cfi-typeids" => OutputType::CfiTypeIds,
             _ => return None,
         })
     }
 
     fn shorthands_display() -> String {
         format!(
-            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
+            "`{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`, `{}`",
             OutputType::Bitcode.shorthand(),
             OutputType::Assembly.shorthand(),
             OutputType::LlvmAssembly.shorthand(),
@@ -484,6 +491,7 @@ fn shorthands_display() -> String {
             OutputType::Metadata.shorthand(),
             OutputType::Exe.shorthand(),
             OutputType::DepInfo.shorthand(),
+            OutputType::CfiTypeIds.shorthand(),
         )
     }
 
@@ -496,6 +504,7 @@ pub fn extension(&self) -> &'static str {
             OutputType::Object => "o",
             OutputType::Metadata => "rmeta",
             OutputType::DepInfo => "d",
+            OutputType::CfiTypeIds => "cfi-typeids",
             OutputType::Exe => "",
         }
     }
@@ -505,7 +514,8 @@ pub fn is_text_output(&self) -> bool {
             OutputType::Assembly
             | OutputType::LlvmAssembly
             | OutputType::Mir
-            | OutputType::DepInfo => true,
+            | OutputType::DepInfo
+            | OutputType::CfiTypeIds => true,
             OutputType::Bitcode | OutputType::Object | OutputType::Metadata | OutputType::Exe => {
                 false
             }
@@ -590,7 +600,8 @@ pub fn should_codegen(&self) -> bool {
             | OutputType::LlvmAssembly
             | OutputType::Mir
             | OutputType::Object
-            | OutputType::Exe => true,
+            | OutputType::Exe
+            | OutputType::CfiTypeIds => true,
             OutputType::Metadata | OutputType::DepInfo => false,
         })
     }
@@ -604,7 +615,8 @@ pub fn should_link(&self) -> bool {
             | OutputType::Mir
             | OutputType::Metadata
             | OutputType::Object
-            | OutputType::DepInfo => false,
+            | OutputType::DepInfo
+            | OutputType::CfiTypeIds => false,
             OutputType::Exe => true,
         })
     }
@@ -717,6 +729,7 @@ pub enum PrintKind {
     CodeModels,
     TlsModels,
     TargetSpec,
+    TargetSpecJsonSchema,
     AllTargetSpecs,
     NativeStaticLibs,
     StackProtectorStrategies,
@@ -1582,6 +1595,21 @@ pub(super) fn build_target_config(
         early_dcx.early_warn(warning)
     }
 
+    if opts.unstable_opts.validate_target_spec {
+        let problems = target.validate_spec();
+        if !problems.is_empty() {
+            let mut error =
+                early_dcx.early_struct_fatal("the target specification is inconsistent");
+            for problem in problems {
+                // FIXME: make this translatable
+                #[allow(rustc::diagnostic_outside_of_impl)]
+                #[allow(rustc::untranslatable_diagnostic)]
+                error.note(problem);
+            }
+            error.emit();
+        }
+    }
+
     if !matches!(target.pointer_width, 16 | 32 | 64) {
         early_dcx.early_fatal(format!(
             "target specification was invalid: unrecognized target-pointer-width {}",
@@ -1725,7 +1753,7 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "emit",
             "Comma separated list of types of output for \
              the compiler to emit",
-            "[asm|llvm-bc|llvm-ir|obj|metadata|link|dep-info|mir]",
+            "[asm|llvm-bc|llvm-ir|obj|metadata|link|dep-info|mir|cfi-typeids]",
         ),
         opt::multi_s(
             "",
@@ -1733,8 +1761,8 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "Compiler information to print on stdout",
             "[crate-name|file-names|sysroot|target-libdir|cfg|calling-conventions|\
              target-list|target-cpus|target-features|relocation-models|code-models|\
-             tls-models|target-spec-json|all-target-specs-json|native-static-libs|\
-             stack-protector-strategies|link-args|deployment-target]",
+             tls-models|target-spec-json|target-spec-json-schema|all-target-specs-json|\
+             native-static-libs|stack-protector-strategies|link-args|deployment-target]",
         ),
         opt::flagmulti_s("g", "", "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
@@ -2057,6 +2085,12 @@ fn parse_output_types(
                         display = OutputType::shorthands_display(),
                     ))
                 });
+                if output_type == OutputType::CfiTypeIds && !unstable_opts.unstable_options {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to enable \
+                        `--emit=cfi-typeids`",
+                    );
+                }
                 output_types.insert(output_type, path);
             }
         }
@@ -2156,6 +2190,7 @@ fn collect_print_requests(
         ("target-libdir", PrintKind::TargetLibdir),
         ("target-list", PrintKind::TargetList),
         ("target-spec-json", PrintKind::TargetSpec),
+        ("target-spec-json-schema", PrintKind::TargetSpecJsonSchema),
         ("tls-models", PrintKind::TlsModels),
         // tidy-alphabetical-end
     ];
@@ -2180,6 +2215,16 @@ fn collect_print_requests(
                     );
                 }
             }
+            Some((_, PrintKind::TargetSpecJsonSchema)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::TargetSpecJsonSchema
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the target-spec-json-schema print option",
+                    );
+                }
+            }
             Some((_, PrintKind::AllTargetSpecs)) => {
                 if unstable_opts.unstable_options {
                     PrintKind::AllTargetSpecs
@@ -3215,9 +3260,9 @@ pub(crate) mod dep_tracking {
         BranchProtection, CFGuard, CFProtection, CollapseMacroDebuginfo, CrateType, DebugInfo,
         DebugInfoCompression, ErrorOutputType, FunctionReturn, InliningThreshold,
         InstrumentCoverage, InstrumentXRay, LinkerPluginLto, LocationDetail, LtoCli,
-        NextSolverConfig, OomStrategy, OptLevel, OutFileName, OutputType, OutputTypes, Polonius,
-        RemapPathScopeComponents, ResolveDocLinks, SourceFileHashAlgorithm, SplitDwarfKind,
-        SwitchWithOptPath, SymbolManglingVersion, WasiExecModel,
+        NextSolverConfig, OomStrategy, OptLevel, Options, OutFileName, OutputType, OutputTypes,
+        Polonius, RemapPathScopeComponents, ResolveDocLinks, SourceFileHashAlgorithm,
+        SplitDwarfKind, SwitchWithOptPath, SymbolManglingVersion, WasiExecModel,
     };
     use crate::lint;
     use crate::utils::NativeLib;
@@ -3412,6 +3457,53 @@ fn hash(
         }
     }
 
+    /// The path options that the incremental compilation cache depends on under
+    /// `-Zincremental-relocatable`.
+    ///
+    /// With path remapping applied to all scopes, the cache only ever contains
+    /// remapped paths. The local side of each `--remap-path-prefix` mapping and the
+    /// local location of the Rust sources are thus irrelevant to it, and leaving
+    /// them out of the hash allows reusing a cache that was produced on another
+    /// machine or in another directory.
+    pub(crate) struct RelocatablePaths<'a> {
+        remap_path_prefix: &'a [(PathBuf, PathBuf)],
+    }
+
+    impl<'a> RelocatablePaths<'a> {
+        pub(crate) fn new(opts: &'a Options, for_crate_hash: bool) -> Option<Self> {
+            // The path options are not part of the crate hash in the first place.
+            if for_crate_hash || !opts.unstable_opts.incremental_relocatable {
+                return None;
+            }
+            Some(RelocatablePaths { remap_path_prefix: &opts.remap_path_prefix })
+        }
+
+        /// Replaces the hashes of the path options in `sub_hashes` with ones that
+        /// only depend on the remapped paths.
+        pub(crate) fn replace_sub_hashes<'b>(
+            &'b self,
+            sub_hashes: &mut BTreeMap<&'static str, &'b dyn DepTrackingHash>,
+        ) {
+            sub_hashes.insert("remap_path_prefix", self);
+            sub_hashes.remove("real_rust_source_base_dir");
+        }
+    }
+
+    impl DepTrackingHash for RelocatablePaths<'_> {
+        fn hash(
+            &self,
+            hasher: &mut DefaultHasher,
+            error_format: ErrorOutputType,
+            for_crate_hash: bool,
+        ) {
+            Hash::hash(&self.remap_path_prefix.len(), hasher);
+            for (index, (_from, to)) in self.remap_path_prefix.iter().enumerate() {
+                Hash::hash(&index, hasher);
+                DepTrackingHash::hash(to, hasher, error_format, for_crate_hash);
+            }
+        }
+    }
+
     // This is a stable hash because BTreeMap is a sorted container
     pub(crate) fn stable_hash(
         sub_hashes: BTreeMap<&'static str, &dyn DepTrackingHash>,
@@ -3547,3 +3639,13 @@ pub enum FunctionReturn {
     /// Replace returns with jumps to thunk, without emitting the thunk.
     ThunkExtern,
 }
+
+/// The budget for the incremental compilation directory set by `-Zincremental-cache-limit`.
+#[derive(Clone, Copy, PartialEq, Hash, Debug, Default)]
+pub struct IncrementalCacheLimit {
+    /// The maximum total size, in bytes, of all session directories in the
+    /// incremental compilation directory.
+    pub max_size: Option<u64>,
+    /// Session directories that have not been used for longer than this are evicted.
+    pub max_age: Option<Duration>,
+}
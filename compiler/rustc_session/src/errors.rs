@@ -444,6 +444,15 @@ pub struct IncompatibleLinkerFlavor {
 #[diag(session_function_return_thunk_extern_requires_non_large_code_model)]
 pub(crate) struct FunctionReturnThunkExternRequiresNonLargeCodeModel;
 
+#[derive(Diagnostic)]
+#[diag(session_incremental_relocatable_requires_remapped_working_dir)]
+#[help]
+pub(crate) struct IncrementalRelocatableRequiresRemappedWorkingDir;
+
+#[derive(Diagnostic)]
+#[diag(session_incremental_relocatable_requires_all_remap_scopes)]
+pub(crate) struct IncrementalRelocatableRequiresAllRemapScopes;
+
 #[derive(Diagnostic)]
 #[diag(session_failed_to_create_profiler)]
 pub struct FailedToCreateProfiler {
@@ -24,6 +24,7 @@
 use std::num::{IntErrorKind, NonZero};
 use std::path::PathBuf;
 use std::str;
+use std::time::Duration;
 
 macro_rules! insert {
     ($opt_name:ident, $opt_expr:expr, $sub_hashes:expr) => {
@@ -77,6 +78,7 @@ pub struct Options {
 
         impl Options {
             pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> u64 {
+                let relocatable_paths = dep_tracking::RelocatablePaths::new(self, for_crate_hash);
                 let mut sub_hashes = BTreeMap::new();
                 $({
                     hash_opt!($opt,
@@ -85,6 +87,9 @@ pub fn dep_tracking_hash(&self, for_crate_hash: bool) -> u64 {
                                 for_crate_hash,
                                 [$dep_tracking_marker]);
                 })*
+                if let Some(relocatable_paths) = &relocatable_paths {
+                    relocatable_paths.replace_sub_hashes(&mut sub_hashes);
+                }
                 let mut hasher = DefaultHasher::new();
                 dep_tracking::stable_hash(sub_hashes,
                                           &mut hasher,
@@ -436,6 +441,8 @@ mod desc {
         "either a boolean (`yes`, `no`, `on`, `off`, etc), or a non-negative number";
     pub const parse_llvm_module_flag: &str = "<key>:<type>:<value>:<behavior>. Type must currently be `u32`. Behavior should be one of (`error`, `warning`, `require`, `override`, `append`, `appendunique`, `max`, `min`)";
     pub const parse_function_return: &str = "`keep` or `thunk-extern`";
+    pub const parse_incremental_cache_limit: &str =
+        "a comma separated list of `size=<bytes>[K|M|G|T]` and `age=<number>(s|m|h|d)`";
 }
 
 mod parse {
@@ -1388,6 +1395,51 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         }
         true
     }
+
+    pub(crate) fn parse_incremental_cache_limit(
+        slot: &mut Option<IncrementalCacheLimit>,
+        v: Option<&str>,
+    ) -> bool {
+        let Some(v) = v else { return false };
+        let mut limit = IncrementalCacheLimit::default();
+        for setting in v.split(',') {
+            let Some((key, value)) = setting.split_once('=') else { return false };
+            match key {
+                "size" => {
+                    let (number, multiplier) = match value.char_indices().last() {
+                        Some((idx, 'k' | 'K')) => (&value[..idx], 1 << 10),
+                        Some((idx, 'm' | 'M')) => (&value[..idx], 1 << 20),
+                        Some((idx, 'g' | 'G')) => (&value[..idx], 1 << 30),
+                        Some((idx, 't' | 'T')) => (&value[..idx], 1 << 40),
+                        _ => (value, 1),
+                    };
+                    let Some(size) =
+                        number.parse::<u64>().ok().and_then(|n| n.checked_mul(multiplier))
+                    else {
+                        return false;
+                    };
+                    limit.max_size = Some(size);
+                }
+                "age" => {
+                    let (number, seconds) = match value.char_indices().last() {
+                        Some((idx, 's')) => (&value[..idx], 1),
+                        Some((idx, 'm')) => (&value[..idx], 60),
+                        Some((idx, 'h')) => (&value[..idx], 60 * 60),
+                        Some((idx, 'd')) => (&value[..idx], 24 * 60 * 60),
+                        _ => return false,
+                    };
+                    let Some(age) = number.parse::<u64>().ok().and_then(|n| n.checked_mul(seconds))
+                    else {
+                        return false;
+                    };
+                    limit.max_age = Some(Duration::from_secs(age));
+                }
+                _ => return false,
+            }
+        }
+        *slot = Some(limit);
+        true
+    }
 }
 
 options! {
@@ -1545,6 +1597,10 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
     binary_dep_depinfo: bool = (false, parse_bool, [TRACKED],
         "include artifacts (sysroot, crate dependencies) used during compilation in dep-info \
         (default: no)"),
+    borrowck_explain: SwitchWithOptPath = (SwitchWithOptPath::Disabled,
+        parse_switch_with_opt_path, [UNTRACKED],
+        "explain lifetime errors with the chain of outlives constraints causing them, also \
+        written as graphviz files to the given directory, if any"),
     box_noalias: bool = (true, parse_bool, [TRACKED],
         "emit noalias metadata for box (default: yes)"),
     branch_protection: Option<BranchProtection> = (None, parse_branch_protection, [TRACKED],
@@ -1603,6 +1659,8 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
         "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+    dump_proof_tree_json: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "dump the proof tree of each unsatisfied trait bound as JSON into the given directory"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1624,6 +1682,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
     fewer_names: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "reduce memory use by retaining fewer names within compilation artifacts (LLVM-IR) \
         (default: no)"),
+    fill_match_arms: Option<usize> = (None, parse_opt_number, [TRACKED],
+        "suggest a match arm for each pattern not covered by a non-exhaustive match, up to the \
+        given number of arms"),
     flatten_format_args: bool = (true, parse_bool, [TRACKED],
         "flatten nested format_args!() and literals into a simplified format_args!() call \
         (default: yes)"),
@@ -1652,11 +1713,24 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "display unnamed regions as `'<id>`, using a non-ident unique id (default: no)"),
     ignore_directory_in_diagnostics_source_blocks: Vec<String> = (Vec::new(), parse_string_push, [UNTRACKED],
         "do not display the source code block in diagnostics for files in the directory"),
+    incremental_cache_limit: Option<IncrementalCacheLimit> = (None, parse_incremental_cache_limit, [UNTRACKED],
+        "evict the least recently used session directories from the incremental compilation \
+        directory once they exceed the given size and/or age (e.g. `size=10G,age=30d`)"),
+    incremental_explain: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON report to the given file explaining which changed inputs caused queries \
+        to be re-executed and codegen units to be re-codegened"),
     incremental_ignore_spans: bool = (false, parse_bool, [TRACKED],
         "ignore spans during ICH computation -- used for testing (default: no)"),
     incremental_info: bool = (false, parse_bool, [UNTRACKED],
         "print high-level information about incremental reuse (or the lack thereof) \
         (default: no)"),
+    incremental_relocatable: bool = (false, parse_bool, [TRACKED],
+        "make the incremental compilation cache independent of the local side of \
+        `--remap-path-prefix` mappings, so it can be reused from another location (default: no)"),
+    incremental_verify_cache: bool = (false, parse_bool, [UNTRACKED],
+        "verify the incremental compilation cache before using it and report why it \
+        cannot be used, if so, and record a fingerprint of the dependency graph to verify \
+        when saving it (default: no)"),
     incremental_verify_ich: bool = (false, parse_bool, [UNTRACKED],
         "verify incr. comp. hashes of green query instances (default: no)"),
     inline_in_all_cgus: Option<bool> = (None, parse_opt_bool, [TRACKED],
@@ -1691,6 +1765,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "link native libraries in the linker invocation (default: yes)"),
     link_only: bool = (false, parse_bool, [TRACKED],
         "link the `.rlink` file generated by `-Z no-link` (default: no)"),
+    link_reproducer: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "copy the inputs of each linker invocation into this directory, along with a script \
+        that reproduces it"),
     lint_mir: bool = (false, parse_bool, [UNTRACKED],
         "lint MIR before and after each transformation"),
     llvm_module_flag: Vec<(String, u32, String)> = (Vec::new(), parse_llvm_module_flag, [TRACKED],
@@ -1799,6 +1876,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "print codegen statistics (default: no)"),
     print_fuel: Option<String> = (None, parse_opt_string, [TRACKED],
         "make rustc print the total optimization fuel used by a crate"),
+    print_inferred_types: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write the types inferred for `let` bindings, closure parameters and method calls \
+        as JSON to the given file"),
     print_llvm_passes: bool = (false, parse_bool, [UNTRACKED],
         "print the LLVM optimization passes being run (default: no)"),
     print_mono_items: Option<String> = (None, parse_opt_string, [UNTRACKED],
@@ -1877,6 +1957,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
     simulate_remapped_rust_src_base: Option<PathBuf> = (None, parse_opt_pathbuf, [TRACKED],
         "simulate the effect of remap-debuginfo = true at bootstrapping by remapping path \
         to rust's source base directory. only meant for testing purposes"),
+    size_report: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON report attributing the size of each linked output to crates, modules and \
+        items to this file, and print a summary of it to stderr"),
     span_debug: bool = (false, parse_bool, [UNTRACKED],
         "forward proc_macro::Span's `Debug` impl to `Span`"),
     /// o/w tests have closure@path
@@ -1938,6 +2021,9 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "choose the TLS model to use (`rustc --print tls-models` for details)"),
     trace_macros: bool = (false, parse_bool, [UNTRACKED],
         "for every macro invocation, print its name and arguments (default: no)"),
+    trace_macros_json: Option<PathBuf> = (None, parse_opt_pathbuf, [UNTRACKED],
+        "write a JSON trace of every `macro_rules!` expansion, with the outcome of each arm, \
+        the bindings of metavariables and the produced tokens, to the given file"),
     track_diagnostics: bool = (false, parse_bool, [UNTRACKED],
         "tracks where in rustc a diagnostic was emitted"),
     // Diagnostics are considered side-effects of a query (see `QuerySideEffects`) and are saved
@@ -1992,6 +2078,8 @@ pub(crate) fn parse_function_return(slot: &mut FunctionReturn, v: Option<&str>)
         "Generate sync unwind tables instead of async unwind tables (default: no)"),
     validate_mir: bool = (false, parse_bool, [UNTRACKED],
         "validate MIR after each transformation"),
+    validate_target_spec: bool = (false, parse_bool, [UNTRACKED],
+        "check the target specification for inconsistent options (default: no)"),
     #[rustc_lint_opt_deny_field_access("use `Session::verbose_internals` instead of this field")]
     verbose_internals: bool = (false, parse_bool, [TRACKED_NO_CRATE_HASH],
         "in general, enable more debug printouts (default: no)"),
@@ -29,7 +29,7 @@
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
 use rustc_span::source_map::{FileLoader, FilePathMapping, RealFileLoader, SourceMap};
-use rustc_span::{SourceFileHashAlgorithm, Span, Symbol};
+use rustc_span::{RealFileName, SourceFileHashAlgorithm, Span, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{CodeModel, PanicStrategy, RelocModel, RelroLevel};
 use rustc_target::spec::{
@@ -1359,6 +1359,16 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
         }
     }
 
+    if sess.opts.unstable_opts.incremental_relocatable {
+        // A cache that embeds local paths can't be reused from another location.
+        if !matches!(sess.opts.working_dir, RealFileName::Remapped { .. }) {
+            sess.dcx().emit_err(errors::IncrementalRelocatableRequiresRemappedWorkingDir);
+        }
+        if sess.opts.unstable_opts.remap_path_scope != RemapPathScopeComponents::all() {
+            sess.dcx().emit_err(errors::IncrementalRelocatableRequiresAllRemapScopes);
+        }
+    }
+
     // The code model check applies to `thunk` and `thunk-extern`, but not `thunk-inline`, so it is
     // kept as a `match` to force a change if new ones are added, even if we currently only support
     // `thunk-extern` like Clang.
@@ -670,6 +670,8 @@
         derive,
         derive_const,
         derive_default_enum,
+        derive_default_extensions,
+        derive_field_attrs,
         destruct,
         destructuring_assignment,
         diagnostic,
@@ -1047,6 +1049,7 @@
         macro_lifetime_matcher,
         macro_literal_matcher,
         macro_metavar_expr,
+        macro_metavar_expr_concat,
         macro_reexport,
         macro_use,
         macro_vis_matcher,
@@ -1186,6 +1189,7 @@
         next,
         nll,
         no,
+        no_bound,
         no_builtins,
         no_core,
         no_coverage,
@@ -1238,6 +1242,7 @@
         options,
         or,
         or_patterns,
+        ord,
         ord_cmp_method,
         other,
         out,
@@ -1415,6 +1420,7 @@
         resume,
         return_position_impl_trait_in_trait,
         return_type_notation,
+        reverse,
         rhs,
         rintf32,
         rintf64,
@@ -1891,6 +1897,7 @@
         while_let,
         windows,
         windows_subsystem,
+        with,
         with_negative_coherence,
         wrapping_add,
         wrapping_div,
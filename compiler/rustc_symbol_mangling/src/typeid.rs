@@ -4,9 +4,16 @@
 /// For more information about LLVM CFI and cross-language LLVM CFI support for the Rust compiler,
 /// see design document in the tracking issue #89653.
 use bitflags::bitflags;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::middle::exported_symbols::ExportedSymbol;
 use rustc_middle::ty::{Instance, Ty, TyCtxt};
+use rustc_session::config::{OutFileName, OutputType};
 use rustc_target::abi::call::FnAbi;
+use rustc_target::spec::abi::Abi;
+use std::fs::File;
 use std::hash::Hasher;
+use std::io::{self, Write};
 use twox_hash::XxHash64;
 
 bitflags! {
@@ -64,3 +71,65 @@ pub fn kcfi_typeid_for_instance<'tcx>(
     hash.write(typeid_itanium_cxx_abi::typeid_for_instance(tcx, instance, options).as_bytes());
     hash.finish() as u32
 }
+
+/// Writes the `--emit=cfi-typeids` output, which lists the type metadata identifiers of the
+/// exported `extern "C"` functions of the local crate, so that C and C++ code calling them through
+/// function pointers can check that it computes the same identifiers for their prototypes.
+///
+/// The identifiers are computed with the options selected by `-Zsanitizer-cfi-generalize-pointers`
+/// and `-Zsanitizer-cfi-normalize-integers`, which correspond to the Clang options
+/// `-fsanitize-cfi-icall-generalize-pointers` and
+/// `-fsanitize-cfi-icall-experimental-normalize-integers`.
+pub fn emit_cfi_typeids(tcx: TyCtxt<'_>) -> io::Result<()> {
+    match tcx.output_filenames(()).path(OutputType::CfiTypeIds) {
+        OutFileName::Stdout => write_cfi_typeids(tcx, &mut io::stdout().lock()),
+        OutFileName::Real(path) => {
+            let mut f = io::BufWriter::new(File::create(&path)?);
+            write_cfi_typeids(tcx, &mut f)?;
+            f.flush()
+        }
+    }
+}
+
+fn write_cfi_typeids(tcx: TyCtxt<'_>, out: &mut dyn Write) -> io::Result<()> {
+    let mut options = TypeIdOptions::empty();
+    if tcx.sess.is_sanitizer_cfi_generalize_pointers_enabled() {
+        options.insert(TypeIdOptions::GENERALIZE_POINTERS);
+    }
+    if tcx.sess.is_sanitizer_cfi_normalize_integers_enabled() {
+        options.insert(TypeIdOptions::NORMALIZE_INTEGERS);
+    }
+
+    let mut functions: Vec<_> = tcx
+        .exported_symbols(LOCAL_CRATE)
+        .iter()
+        .filter_map(|&(symbol, _)| match symbol {
+            ExportedSymbol::NonGeneric(def_id)
+                if matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+                    && matches!(tcx.fn_sig(def_id).skip_binder().abi(), Abi::C { .. }) =>
+            {
+                Some(Instance::mono(tcx, def_id))
+            }
+            _ => None,
+        })
+        .map(|instance| {
+            let typeid = typeid_for_instance(tcx, &instance, options);
+            let kcfi_typeid = kcfi_typeid_for_instance(tcx, &instance, options);
+            (tcx.symbol_name(instance).name, typeid, kcfi_typeid)
+        })
+        .collect();
+    functions.sort();
+
+    writeln!(out, "# CFI type metadata identifiers of `{}`", tcx.crate_name(LOCAL_CRATE))?;
+    writeln!(
+        out,
+        "# generalize-pointers: {}, normalize-integers: {}",
+        options.contains(TypeIdOptions::GENERALIZE_POINTERS),
+        options.contains(TypeIdOptions::NORMALIZE_INTEGERS),
+    )?;
+    writeln!(out, "# symbol typeid kcfi-typeid")?;
+    for (name, typeid, kcfi_typeid) in functions {
+        writeln!(out, "{name} {typeid} {kcfi_typeid:#010x}")?;
+    }
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! The [JSON Schema](https://json-schema.org/) of target specifications, printed by
+//! `rustc --print target-spec-json-schema` so that editors can complete and check the keys of
+//! custom target specifications.
+//!
+//! The keys of [`TargetOptions`](super::TargetOptions) come from `for_each_target_option!`, the
+//! list that [`Target::from_json`] reads, so adding a key of a new kind fails to compile until the
+//! kind is described here. The tests of the built-in targets check that every key written by
+//! `Target::to_json` is part of the schema.
+
+use super::{
+    for_each_target_option, LinkSelfContainedComponents, LinkerFlavorCli, SanitizerSet, Target,
+};
+use crate::json::Json;
+use serde_json::json;
+
+impl Target {
+    pub fn json_schema() -> Json {
+        let linker_flavors: Vec<_> = LinkerFlavorCli::all().iter().map(|f| f.desc()).collect();
+        let link_output_kinds = [
+            "dynamic-nopic-exe",
+            "dynamic-pic-exe",
+            "static-nopic-exe",
+            "static-pic-exe",
+            "dynamic-dylib",
+            "static-dylib",
+            "wasi-reactor-exe",
+        ];
+        let split_debuginfo = ["off", "unpacked", "packed"];
+
+        // Describes the values accepted by each kind of key in `Target::from_json`.
+        macro_rules! schema {
+            ($key_name:ident) => (string());
+            ($key_name:ident, optional) => (string());
+            ($key_name:ident, bool) => (boolean());
+            ($key_name:ident, Option<bool>) => (boolean());
+            ($key_name:ident, u32) => (json!({ "type": "integer", "minimum": 1, "maximum": 5 }));
+            ($key_name:ident, Option<u64>) => (integer());
+            ($key_name:ident, list) => (string_list());
+            ($key_name:ident, opt_list) => (string_list());
+            ($key_name:ident, env) => (
+                array(json!({ "type": "string", "pattern": "^[^=]*=[^=]*$" }))
+            );
+            ($key_name:ident, LinkerFlavor) => (one_of(linker_flavors.iter().copied()));
+            ($key_name:ident, LldFlavor) => (one_of(["darwin", "gnu", "link", "wasm"]));
+            ($key_name:ident, link_args) => (
+                object_of(linker_flavors.iter().copied(), string_list())
+            );
+            ($key_name:ident, link_objects) => (object_of(link_output_kinds, string_list()));
+            ($key_name:ident, link_self_contained_backwards_compatible) => (
+                one_of(["false", "true", "wasm", "musl", "mingw"])
+            );
+            ($key_name:ident, link_self_contained_components) => ({
+                let components = LinkSelfContainedComponents::all_components()
+                    .into_iter()
+                    .filter_map(|component| component.as_str());
+                json!({
+                    "type": "object",
+                    "properties": { "components": array(one_of(components)) },
+                    "required": ["components"],
+                })
+            });
+            ($key_name:ident, RelocModel) => (
+                one_of(["static", "pic", "pie", "dynamic-no-pic", "ropi", "rwpi", "ropi-rwpi"])
+            );
+            ($key_name:ident, CodeModel) => (
+                one_of(["tiny", "small", "kernel", "medium", "large"])
+            );
+            ($key_name:ident, TlsModel) => (one_of([
+                "global-dynamic",
+                "local-dynamic",
+                "initial-exec",
+                "local-exec",
+                "emulated",
+            ]));
+            ($key_name:ident, RelroLevel) => (one_of(["full", "partial", "off", "none"]));
+            ($key_name:ident, PanicStrategy) => (one_of(["unwind", "abort"]));
+            ($key_name:ident, MergeFunctions) => (
+                one_of(["disabled", "trampolines", "aliases"])
+            );
+            ($key_name:ident, DebuginfoKind) => (one_of(["dwarf", "dwarf-dsym", "pdb"]));
+            ($key_name:ident, SplitDebuginfo) => (one_of(split_debuginfo));
+            // The values of a `fallible_list` depend on the key, so each one is listed here.
+            (supported_split_debuginfo, fallible_list) => (array(one_of(split_debuginfo)));
+            ($key_name:ident, SanitizerSet) => ({
+                let sanitizers =
+                    SanitizerSet::all().iter().filter_map(|sanitizer| sanitizer.as_str());
+                array(one_of(sanitizers))
+            });
+            ($key_name:ident, Option<Abi>) => (one_of(super::abi::all_names()));
+            ($key_name:ident, StackProbeType) => (json!({
+                "type": "object",
+                "properties": {
+                    "kind": one_of(["none", "inline", "call", "inline-or-call"]),
+                    "min-llvm-version-for-inline": array(integer()),
+                },
+                "required": ["kind"],
+            }));
+            ($key_name:ident, Conv) => (string());
+        }
+
+        let mut properties = serde_json::Map::new();
+        for key in ["llvm-target", "data-layout", "arch"] {
+            properties.insert(key.into(), string());
+        }
+        properties.insert(
+            "target-pointer-width".into(),
+            json!({ "type": "string", "enum": ["16", "32", "64"] }),
+        );
+        properties.insert("target-endian".into(), one_of(["little", "big"]));
+        properties.insert("frame-pointer".into(), one_of(["always", "non-leaf", "may-omit"]));
+
+        macro_rules! key {
+            ($key_name:ident, TargetFamilies) => {
+                properties.insert(
+                    "target-family".into(),
+                    json!({ "type": ["string", "array"], "items": string() }),
+                );
+            };
+            ($key_name:ident = $json_name:literal $(, $($kind:tt)+)?) => {
+                properties.insert($json_name.into(), schema!($key_name $(, $($kind)+)?));
+            };
+            ($key_name:ident $(, $($kind:tt)+)?) => {{
+                let name = stringify!($key_name).replace('_', "-");
+                properties.insert(name, schema!($key_name $(, $($kind)+)?));
+            }};
+        }
+        for_each_target_option!(key);
+
+        json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "title": "rustc target specification",
+            "type": "object",
+            "required": ["llvm-target", "target-pointer-width", "data-layout", "arch"],
+            "properties": properties,
+            "additionalProperties": false,
+        })
+    }
+}
+
+fn string() -> Json {
+    json!({ "type": "string" })
+}
+
+fn boolean() -> Json {
+    json!({ "type": "boolean" })
+}
+
+fn integer() -> Json {
+    json!({ "type": "integer", "minimum": 0 })
+}
+
+fn array(items: Json) -> Json {
+    json!({ "type": "array", "items": items })
+}
+
+fn string_list() -> Json {
+    array(string())
+}
+
+fn one_of<'a>(values: impl IntoIterator<Item = &'a str>) -> Json {
+    json!({ "type": "string", "enum": values.into_iter().collect::<Vec<_>>() })
+}
+
+/// An object whose keys are `keys`, all with values described by `values`.
+fn object_of<'a>(keys: impl IntoIterator<Item = &'a str>, values: Json) -> Json {
+    json!({
+        "type": "object",
+        "propertyNames": { "enum": keys.into_iter().collect::<Vec<_>>() },
+        "additionalProperties": values,
+    })
+}
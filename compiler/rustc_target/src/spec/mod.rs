@@ -57,6 +57,8 @@
 pub mod crt_objects;
 
 mod base;
+mod json_schema;
+mod validate;
 pub use base::apple::deployment_target as current_apple_deployment_target;
 pub use base::apple::platform as current_apple_platform;
 pub use base::apple::sdk_version as current_apple_sdk_version;
@@ -123,6 +125,9 @@ pub enum LinkerFlavor {
     Bpf,
     /// Linker tool for Nvidia PTX.
     Ptx,
+    /// The in-process linker built into rustc, which produces static ELF executables without
+    /// invoking any external tool.
+    Builtin,
 }
 
 /// Linker flavors available externally through command line (`-Clinker-flavor`)
@@ -141,6 +146,7 @@ pub enum LinkerFlavorCli {
     EmCc,
     Bpf,
     Ptx,
+    Builtin,
 
     // Legacy stable values
     Gcc,
@@ -160,7 +166,8 @@ pub fn is_unstable(&self) -> bool {
             | LinkerFlavorCli::Msvc(Lld::Yes)
             | LinkerFlavorCli::EmCc
             | LinkerFlavorCli::Bpf
-            | LinkerFlavorCli::Ptx => true,
+            | LinkerFlavorCli::Ptx
+            | LinkerFlavorCli::Builtin => true,
             LinkerFlavorCli::Gcc
             | LinkerFlavorCli::Ld
             | LinkerFlavorCli::Lld(..)
@@ -220,6 +227,7 @@ fn from_cli_json(cli: LinkerFlavorCli, lld_flavor: LldFlavor, is_gnu: bool) -> L
             LinkerFlavorCli::EmCc => LinkerFlavor::EmCc,
             LinkerFlavorCli::Bpf => LinkerFlavor::Bpf,
             LinkerFlavorCli::Ptx => LinkerFlavor::Ptx,
+            LinkerFlavorCli::Builtin => LinkerFlavor::Builtin,
 
             // Below: legacy stable values
             LinkerFlavorCli::Gcc => match lld_flavor {
@@ -259,6 +267,7 @@ fn to_cli(self) -> LinkerFlavorCli {
             LinkerFlavor::EmCc => LinkerFlavorCli::Em,
             LinkerFlavor::Bpf => LinkerFlavorCli::Bpf,
             LinkerFlavor::Ptx => LinkerFlavorCli::Ptx,
+            LinkerFlavor::Builtin => LinkerFlavorCli::Builtin,
         }
     }
 
@@ -273,6 +282,7 @@ fn to_cli_counterpart(self) -> LinkerFlavorCli {
             LinkerFlavor::EmCc => LinkerFlavorCli::EmCc,
             LinkerFlavor::Bpf => LinkerFlavorCli::Bpf,
             LinkerFlavor::Ptx => LinkerFlavorCli::Ptx,
+            LinkerFlavor::Builtin => LinkerFlavorCli::Builtin,
         }
     }
 
@@ -285,7 +295,7 @@ fn infer_cli_hints(cli: LinkerFlavorCli) -> (Option<Cc>, Option<Lld>) {
             LinkerFlavorCli::Unix(cc) => (Some(cc), None),
             LinkerFlavorCli::Msvc(lld) => (Some(Cc::No), Some(lld)),
             LinkerFlavorCli::EmCc => (Some(Cc::Yes), Some(Lld::Yes)),
-            LinkerFlavorCli::Bpf | LinkerFlavorCli::Ptx => (None, None),
+            LinkerFlavorCli::Bpf | LinkerFlavorCli::Ptx | LinkerFlavorCli::Builtin => (None, None),
 
             // Below: legacy stable values
             LinkerFlavorCli::Gcc => (Some(Cc::Yes), None),
@@ -340,11 +350,17 @@ fn with_hints(self, (cc_hint, lld_hint): (Option<Cc>, Option<Lld>)) -> LinkerFla
             LinkerFlavor::WasmLld(cc) => LinkerFlavor::WasmLld(cc_hint.unwrap_or(cc)),
             LinkerFlavor::Unix(cc) => LinkerFlavor::Unix(cc_hint.unwrap_or(cc)),
             LinkerFlavor::Msvc(lld) => LinkerFlavor::Msvc(lld_hint.unwrap_or(lld)),
-            LinkerFlavor::EmCc | LinkerFlavor::Bpf | LinkerFlavor::Ptx => self,
+            LinkerFlavor::EmCc | LinkerFlavor::Bpf | LinkerFlavor::Ptx | LinkerFlavor::Builtin => {
+                self
+            }
         }
     }
 
     pub fn with_cli_hints(self, cli: LinkerFlavorCli) -> LinkerFlavor {
+        // The builtin linker replaces, rather than refines, the linker of ELF targets.
+        if cli == LinkerFlavorCli::Builtin && self.is_gnu() {
+            return LinkerFlavor::Builtin;
+        }
         self.with_hints(LinkerFlavor::infer_cli_hints(cli))
     }
 
@@ -364,7 +380,8 @@ pub fn check_compatibility(self, cli: LinkerFlavorCli) -> Option<String> {
                 | (LinkerFlavor::Msvc(..), LinkerFlavorCli::Msvc(..))
                 | (LinkerFlavor::EmCc, LinkerFlavorCli::EmCc)
                 | (LinkerFlavor::Bpf, LinkerFlavorCli::Bpf)
-                | (LinkerFlavor::Ptx, LinkerFlavorCli::Ptx) => return true,
+                | (LinkerFlavor::Ptx, LinkerFlavorCli::Ptx)
+                | (LinkerFlavor::Builtin, LinkerFlavorCli::Builtin) => return true,
                 _ => {}
             }
 
@@ -387,7 +404,8 @@ pub fn lld_flavor(self) -> LldFlavor {
             | LinkerFlavor::Unix(..)
             | LinkerFlavor::EmCc
             | LinkerFlavor::Bpf
-            | LinkerFlavor::Ptx => LldFlavor::Ld,
+            | LinkerFlavor::Ptx
+            | LinkerFlavor::Builtin => LldFlavor::Ld,
             LinkerFlavor::Darwin(..) => LldFlavor::Ld64,
             LinkerFlavor::WasmLld(..) => LldFlavor::Wasm,
             LinkerFlavor::Msvc(..) => LldFlavor::Link,
@@ -412,7 +430,8 @@ pub fn uses_lld(self) -> bool {
             | LinkerFlavor::Msvc(_)
             | LinkerFlavor::Unix(_)
             | LinkerFlavor::Bpf
-            | LinkerFlavor::Ptx => false,
+            | LinkerFlavor::Ptx
+            | LinkerFlavor::Builtin => false,
         }
     }
 
@@ -431,7 +450,8 @@ pub fn uses_cc(self) -> bool {
             | LinkerFlavor::Msvc(_)
             | LinkerFlavor::Unix(_)
             | LinkerFlavor::Bpf
-            | LinkerFlavor::Ptx => false,
+            | LinkerFlavor::Ptx
+            | LinkerFlavor::Builtin => false,
         }
     }
 }
@@ -481,6 +501,7 @@ pub fn desc(self) -> &'static str {
     (LinkerFlavorCli::EmCc) "em-cc"
     (LinkerFlavorCli::Bpf) "bpf"
     (LinkerFlavorCli::Ptx) "ptx"
+    (LinkerFlavorCli::Builtin) "builtin"
 
     // Legacy stable flavors
     (LinkerFlavorCli::Gcc) "gcc"
@@ -1749,6 +1770,128 @@ pub struct Target {
     pub options: TargetOptions,
 }
 
+/// Invokes `$key!` once for each key of [`TargetOptions`] that a target specification can set,
+/// with the field, its JSON name if it isn't derived from the field name, and how the value is
+/// parsed. [`Target::from_json`] uses this to read the keys and [`Target::json_schema`] to
+/// describe them, so the two can't disagree. `$try` is appended to the fallible invocations.
+macro_rules! for_each_target_option {
+    ($key:ident $(, $try:tt)?) => {
+        $key!(is_builtin, bool);
+        $key!(c_int_width = "target-c-int-width");
+        $key!(c_enum_min_bits, Option<u64>); // if None, matches c_int_width
+        $key!(os);
+        $key!(env);
+        $key!(abi);
+        $key!(vendor);
+        $key!(linker, optional);
+        $key!(linker_flavor_json = "linker-flavor", LinkerFlavor)$($try)?;
+        $key!(lld_flavor_json = "lld-flavor", LldFlavor)$($try)?;
+        $key!(linker_is_gnu_json = "linker-is-gnu", bool);
+        $key!(pre_link_objects = "pre-link-objects", link_objects);
+        $key!(post_link_objects = "post-link-objects", link_objects);
+        $key!(pre_link_objects_self_contained = "pre-link-objects-fallback", link_objects);
+        $key!(post_link_objects_self_contained = "post-link-objects-fallback", link_objects);
+        // Deserializes the backwards-compatible variants of `-Clink-self-contained`
+        $key!(
+            link_self_contained = "crt-objects-fallback",
+            link_self_contained_backwards_compatible
+        )$($try)?;
+        // Deserializes the components variant of `-Clink-self-contained`
+        $key!(link_self_contained, link_self_contained_components)$($try)?;
+        $key!(pre_link_args_json = "pre-link-args", link_args);
+        $key!(late_link_args_json = "late-link-args", link_args);
+        $key!(late_link_args_dynamic_json = "late-link-args-dynamic", link_args);
+        $key!(late_link_args_static_json = "late-link-args-static", link_args);
+        $key!(post_link_args_json = "post-link-args", link_args);
+        $key!(link_script, optional);
+        $key!(link_env, env);
+        $key!(link_env_remove, list);
+        $key!(asm_args, list);
+        $key!(cpu);
+        $key!(features);
+        $key!(dynamic_linking, bool);
+        $key!(direct_access_external_data, Option<bool>);
+        $key!(dll_tls_export, bool);
+        $key!(only_cdylib, bool);
+        $key!(executables, bool);
+        $key!(relocation_model, RelocModel)$($try)?;
+        $key!(code_model, CodeModel)$($try)?;
+        $key!(tls_model, TlsModel)$($try)?;
+        $key!(disable_redzone, bool);
+        $key!(function_sections, bool);
+        $key!(dll_prefix);
+        $key!(dll_suffix);
+        $key!(exe_suffix);
+        $key!(staticlib_prefix);
+        $key!(staticlib_suffix);
+        $key!(families, TargetFamilies);
+        $key!(abi_return_struct_as_int, bool);
+        $key!(is_like_aix, bool);
+        $key!(is_like_osx, bool);
+        $key!(is_like_solaris, bool);
+        $key!(is_like_windows, bool);
+        $key!(is_like_msvc, bool);
+        $key!(is_like_wasm, bool);
+        $key!(is_like_android, bool);
+        $key!(default_dwarf_version, u32);
+        $key!(allows_weak_linkage, bool);
+        $key!(has_rpath, bool);
+        $key!(no_default_libraries, bool);
+        $key!(position_independent_executables, bool);
+        $key!(static_position_independent_executables, bool);
+        $key!(plt_by_default, bool);
+        $key!(relro_level, RelroLevel)$($try)?;
+        $key!(archive_format);
+        $key!(allow_asm, bool);
+        $key!(main_needs_argc_argv, bool);
+        $key!(has_thread_local, bool);
+        $key!(obj_is_bitcode, bool);
+        $key!(forces_embed_bitcode, bool);
+        $key!(bitcode_llvm_cmdline);
+        $key!(max_atomic_width, Option<u64>);
+        $key!(min_atomic_width, Option<u64>);
+        $key!(atomic_cas, bool);
+        $key!(panic_strategy, PanicStrategy)$($try)?;
+        $key!(crt_static_allows_dylibs, bool);
+        $key!(crt_static_default, bool);
+        $key!(crt_static_respected, bool);
+        $key!(stack_probes, StackProbeType)$($try)?;
+        $key!(min_global_align, Option<u64>);
+        $key!(default_codegen_units, Option<u64>);
+        $key!(trap_unreachable, bool);
+        $key!(requires_lto, bool);
+        $key!(singlethread, bool);
+        $key!(no_builtins, bool);
+        $key!(default_hidden_visibility, bool);
+        $key!(emit_debug_gdb_scripts, bool);
+        $key!(requires_uwtable, bool);
+        $key!(default_uwtable, bool);
+        $key!(simd_types_indirect, bool);
+        $key!(limit_rdylib_exports, bool);
+        $key!(override_export_symbols, opt_list);
+        $key!(merge_functions, MergeFunctions)$($try)?;
+        $key!(mcount = "target-mcount");
+        $key!(llvm_mcount_intrinsic, optional);
+        $key!(llvm_abiname);
+        $key!(relax_elf_relocations, bool);
+        $key!(llvm_args, list);
+        $key!(use_ctors_section, bool);
+        $key!(eh_frame_header, bool);
+        $key!(has_thumb_interworking, bool);
+        $key!(debuginfo_kind, DebuginfoKind)$($try)?;
+        $key!(split_debuginfo, SplitDebuginfo)$($try)?;
+        $key!(supported_split_debuginfo, fallible_list)$($try)?;
+        $key!(supported_sanitizers, SanitizerSet)$($try)?;
+        $key!(default_adjusted_cabi, Option<Abi>)$($try)?;
+        $key!(generate_arange_section, bool);
+        $key!(supports_stack_protector, bool);
+        $key!(entry_name);
+        $key!(entry_abi, Conv)$($try)?;
+        $key!(supports_xray, bool);
+    };
+}
+pub(crate) use for_each_target_option;
+
 impl Target {
     pub fn parse_data_layout(&self) -> Result<TargetDataLayout, TargetDataLayoutErrors<'_>> {
         let mut dl = TargetDataLayout::parse_from_llvm_datalayout_string(&self.data_layout)?;
@@ -2195,7 +2338,8 @@ fn add_link_args_iter(
         | LinkerFlavor::Unix(..)
         | LinkerFlavor::EmCc
         | LinkerFlavor::Bpf
-        | LinkerFlavor::Ptx => {}
+        | LinkerFlavor::Ptx
+        | LinkerFlavor::Builtin => {}
     }
 }
 
@@ -2983,118 +3127,7 @@ macro_rules! key {
             }
         }
 
-        key!(is_builtin, bool);
-        key!(c_int_width = "target-c-int-width");
-        key!(c_enum_min_bits, Option<u64>); // if None, matches c_int_width
-        key!(os);
-        key!(env);
-        key!(abi);
-        key!(vendor);
-        key!(linker, optional);
-        key!(linker_flavor_json = "linker-flavor", LinkerFlavor)?;
-        key!(lld_flavor_json = "lld-flavor", LldFlavor)?;
-        key!(linker_is_gnu_json = "linker-is-gnu", bool);
-        key!(pre_link_objects = "pre-link-objects", link_objects);
-        key!(post_link_objects = "post-link-objects", link_objects);
-        key!(pre_link_objects_self_contained = "pre-link-objects-fallback", link_objects);
-        key!(post_link_objects_self_contained = "post-link-objects-fallback", link_objects);
-        // Deserializes the backwards-compatible variants of `-Clink-self-contained`
-        key!(
-            link_self_contained = "crt-objects-fallback",
-            link_self_contained_backwards_compatible
-        )?;
-        // Deserializes the components variant of `-Clink-self-contained`
-        key!(link_self_contained, link_self_contained_components)?;
-        key!(pre_link_args_json = "pre-link-args", link_args);
-        key!(late_link_args_json = "late-link-args", link_args);
-        key!(late_link_args_dynamic_json = "late-link-args-dynamic", link_args);
-        key!(late_link_args_static_json = "late-link-args-static", link_args);
-        key!(post_link_args_json = "post-link-args", link_args);
-        key!(link_script, optional);
-        key!(link_env, env);
-        key!(link_env_remove, list);
-        key!(asm_args, list);
-        key!(cpu);
-        key!(features);
-        key!(dynamic_linking, bool);
-        key!(direct_access_external_data, Option<bool>);
-        key!(dll_tls_export, bool);
-        key!(only_cdylib, bool);
-        key!(executables, bool);
-        key!(relocation_model, RelocModel)?;
-        key!(code_model, CodeModel)?;
-        key!(tls_model, TlsModel)?;
-        key!(disable_redzone, bool);
-        key!(function_sections, bool);
-        key!(dll_prefix);
-        key!(dll_suffix);
-        key!(exe_suffix);
-        key!(staticlib_prefix);
-        key!(staticlib_suffix);
-        key!(families, TargetFamilies);
-        key!(abi_return_struct_as_int, bool);
-        key!(is_like_aix, bool);
-        key!(is_like_osx, bool);
-        key!(is_like_solaris, bool);
-        key!(is_like_windows, bool);
-        key!(is_like_msvc, bool);
-        key!(is_like_wasm, bool);
-        key!(is_like_android, bool);
-        key!(default_dwarf_version, u32);
-        key!(allows_weak_linkage, bool);
-        key!(has_rpath, bool);
-        key!(no_default_libraries, bool);
-        key!(position_independent_executables, bool);
-        key!(static_position_independent_executables, bool);
-        key!(plt_by_default, bool);
-        key!(relro_level, RelroLevel)?;
-        key!(archive_format);
-        key!(allow_asm, bool);
-        key!(main_needs_argc_argv, bool);
-        key!(has_thread_local, bool);
-        key!(obj_is_bitcode, bool);
-        key!(forces_embed_bitcode, bool);
-        key!(bitcode_llvm_cmdline);
-        key!(max_atomic_width, Option<u64>);
-        key!(min_atomic_width, Option<u64>);
-        key!(atomic_cas, bool);
-        key!(panic_strategy, PanicStrategy)?;
-        key!(crt_static_allows_dylibs, bool);
-        key!(crt_static_default, bool);
-        key!(crt_static_respected, bool);
-        key!(stack_probes, StackProbeType)?;
-        key!(min_global_align, Option<u64>);
-        key!(default_codegen_units, Option<u64>);
-        key!(trap_unreachable, bool);
-        key!(requires_lto, bool);
-        key!(singlethread, bool);
-        key!(no_builtins, bool);
-        key!(default_hidden_visibility, bool);
-        key!(emit_debug_gdb_scripts, bool);
-        key!(requires_uwtable, bool);
-        key!(default_uwtable, bool);
-        key!(simd_types_indirect, bool);
-        key!(limit_rdylib_exports, bool);
-        key!(override_export_symbols, opt_list);
-        key!(merge_functions, MergeFunctions)?;
-        key!(mcount = "target-mcount");
-        key!(llvm_mcount_intrinsic, optional);
-        key!(llvm_abiname);
-        key!(relax_elf_relocations, bool);
-        key!(llvm_args, list);
-        key!(use_ctors_section, bool);
-        key!(eh_frame_header, bool);
-        key!(has_thumb_interworking, bool);
-        key!(debuginfo_kind, DebuginfoKind)?;
-        key!(split_debuginfo, SplitDebuginfo)?;
-        key!(supported_split_debuginfo, fallible_list)?;
-        key!(supported_sanitizers, SanitizerSet)?;
-        key!(default_adjusted_cabi, Option<Abi>)?;
-        key!(generate_arange_section, bool);
-        key!(supports_stack_protector, bool);
-        key!(entry_name);
-        key!(entry_abi, Conv)?;
-        key!(supports_xray, bool);
+        for_each_target_option!(key, ?);
 
         if base.is_builtin {
             // This can cause unfortunate ICEs later down the line.
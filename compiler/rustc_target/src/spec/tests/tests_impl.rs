@@ -6,10 +6,29 @@ pub(super) fn test_target(mut target: Target) {
     let recycled_target = Target::from_json(target.to_json()).map(|(j, _)| j);
     target.update_to_cli();
     target.check_consistency();
+    target.check_json_schema();
+    target.check_validate_spec();
     assert_eq!(recycled_target, Ok(target));
 }
 
 impl Target {
+    fn check_validate_spec(&self) {
+        let mut problems = self.validate_spec();
+        // FIXME: the AVR targets default to `panic=unwind`, although there is no unwinder for them.
+        if self.arch == "avr" {
+            problems.retain(|problem| !problem.starts_with("`panic-strategy` is `unwind`"));
+        }
+        assert_eq!(problems, Vec::<String>::new());
+    }
+
+    fn check_json_schema(&self) {
+        let schema = Target::json_schema();
+        let properties = schema["properties"].as_object().unwrap();
+        for key in self.to_json().as_object().unwrap().keys() {
+            assert!(properties.contains_key(key), "`{key}` is missing from the JSON schema");
+        }
+    }
+
     fn check_consistency(&self) {
         assert_eq!(self.is_like_osx, self.vendor == "apple");
         assert_eq!(self.is_like_solaris, self.os == "solaris" || self.os == "illumos");
@@ -56,7 +75,10 @@ fn check_consistency(&self) {
                     LinkerFlavor::Msvc(..) => {
                         assert_matches!(flavor, LinkerFlavor::Msvc(..))
                     }
-                    LinkerFlavor::EmCc | LinkerFlavor::Bpf | LinkerFlavor::Ptx => {
+                    LinkerFlavor::EmCc
+                    | LinkerFlavor::Bpf
+                    | LinkerFlavor::Ptx
+                    | LinkerFlavor::Builtin => {
                         assert_eq!(flavor, self.linker_flavor)
                     }
                 }
@@ -0,0 +1,137 @@
+//! Consistency checks of target specifications, enabled with `-Z validate-target-spec`.
+//!
+//! [`Target::from_json`] only checks that each key has a valid value, so custom targets can
+//! combine options that can't work together, which then fails late and confusingly, for example
+//! when linking. These checks catch the combinations that are known to be wrong.
+
+use super::{load_builtin, LinkerFlavor, PanicStrategy, RelocModel, Target, TARGETS};
+use crate::abi::{Endian, TargetDataLayout};
+use std::sync::OnceLock;
+
+impl Target {
+    /// Returns a description of each inconsistency found in the target specification.
+    pub fn validate_spec(&self) -> Vec<String> {
+        let mut problems = vec![];
+
+        // Errors parsing the data layout and mismatches with `target-endian` and
+        // `target-pointer-width` are already reported when the target is used.
+        if let Ok(dl) = TargetDataLayout::parse_from_llvm_datalayout_string(&self.data_layout) {
+            let layout = (dl.endian, dl.pointer_size.bits());
+            let mut builtin_layouts: Vec<_> = builtin_layouts()
+                .iter()
+                .filter(|(arch, ..)| *arch == self.arch)
+                .map(|&(_, endian, bits)| (endian, bits))
+                .collect();
+            builtin_layouts.sort_by_key(|&(endian, bits)| (endian.as_str(), bits));
+            builtin_layouts.dedup();
+            if builtin_layouts.is_empty() {
+                problems.push(format!(
+                    "`arch` is `{}`, which isn't the architecture of any built-in target",
+                    self.arch
+                ));
+            } else if !builtin_layouts.contains(&layout) {
+                let describe = |(endian, bits): (Endian, u64)| {
+                    format!("{bits}-bit {}-endian", endian.as_str())
+                };
+                let expected: Vec<_> = builtin_layouts.into_iter().map(describe).collect();
+                problems.push(format!(
+                    "`data-layout` describes a {} target, but the built-in `{}` targets are {}",
+                    describe(layout),
+                    self.arch,
+                    expected.join(" or "),
+                ));
+            }
+        }
+
+        if self.panic_strategy == PanicStrategy::Unwind && self.os == "none" {
+            problems.push(
+                "`panic-strategy` is `unwind`, but `os` is `none`, for which the standard \
+                library provides no unwinder; use `abort` instead"
+                    .to_string(),
+            );
+        }
+        if let (Some(min), Some(max)) = (self.min_atomic_width, self.max_atomic_width) {
+            if min > max {
+                problems.push(format!(
+                    "`min-atomic-width` ({min}) is larger than `max-atomic-width` ({max})"
+                ));
+            }
+        }
+        for (key, width) in [
+            ("min-atomic-width", self.min_atomic_width),
+            ("max-atomic-width", self.max_atomic_width),
+        ] {
+            if let Some(width) = width {
+                // A width of 0 means that there are no atomic operations.
+                if !matches!(width, 0 | 8 | 16 | 32 | 64 | 128) {
+                    problems.push(format!(
+                        "`{key}` is {width}, which isn't the size of an integer type"
+                    ));
+                }
+            }
+        }
+
+        if self.is_like_msvc && !self.is_like_windows {
+            problems.push("`is-like-msvc` is set, but `is-like-windows` isn't".to_string());
+        }
+        if self.is_like_osx && self.vendor != "apple" {
+            problems.push(format!(
+                "`is-like-osx` is set, but `vendor` is `{}` instead of `apple`",
+                self.vendor
+            ));
+        }
+        if self.is_like_msvc != matches!(self.linker_flavor, LinkerFlavor::Msvc(..)) {
+            problems.push(format!(
+                "`linker-flavor` is `{}`, which doesn't match `is-like-msvc` being {}",
+                self.linker_flavor_json.desc(),
+                self.is_like_msvc,
+            ));
+        }
+        if self.is_like_osx != matches!(self.linker_flavor, LinkerFlavor::Darwin(..)) {
+            problems.push(format!(
+                "`linker-flavor` is `{}`, which doesn't match `is-like-osx` being {}",
+                self.linker_flavor_json.desc(),
+                self.is_like_osx,
+            ));
+        }
+
+        if self.position_independent_executables
+            && !matches!(self.relocation_model, RelocModel::Pic | RelocModel::Pie)
+        {
+            problems.push(format!(
+                "`position-independent-executables` is set, but `relocation-model` is `{}`",
+                self.relocation_model.desc(),
+            ));
+        }
+        if self.static_position_independent_executables
+            && !self.position_independent_executables
+        {
+            problems.push(
+                "`static-position-independent-executables` is set, but \
+                `position-independent-executables` isn't"
+                    .to_string(),
+            );
+        }
+        if self.crt_static_default && !self.crt_static_respected {
+            problems.push(
+                "`crt-static-default` is set, but has no effect as `crt-static-respected` isn't"
+                    .to_string(),
+            );
+        }
+
+        problems
+    }
+}
+
+/// The architecture, endianness and pointer width of each built-in target. Loading all of them
+/// takes a while, so they are only loaded once rather than for each target that is validated.
+fn builtin_layouts() -> &'static [(String, Endian, u64)] {
+    static BUILTIN_LAYOUTS: OnceLock<Vec<(String, Endian, u64)>> = OnceLock::new();
+    BUILTIN_LAYOUTS.get_or_init(|| {
+        TARGETS
+            .iter()
+            .filter_map(|triple| load_builtin(triple))
+            .map(|target| (target.arch.to_string(), target.endian, u64::from(target.pointer_width)))
+            .collect()
+    })
+}
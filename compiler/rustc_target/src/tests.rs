@@ -55,3 +55,43 @@ fn no_warnings_for_valid_target() {
     let warnings = Target::from_json(json).unwrap().1;
     assert_eq!(warnings.warning_messages().len(), 0);
 }
+
+#[test]
+fn validate_target_spec() {
+    let json = serde_json::from_str(
+        r#"
+    {
+        "arch": "x86_64",
+        "data-layout": "E-m:e-p:32:32-i64:64-n32",
+        "llvm-target": "x86_64-elf",
+        "target-pointer-width": "32",
+        "target-endian": "big",
+        "panic-strategy": "abort",
+        "max-atomic-width": 48
+    }
+    "#,
+    )
+    .unwrap();
+    let problems = Target::from_json(json).unwrap().0.validate_spec();
+    assert_eq!(problems.len(), 2, "{problems:?}");
+    assert!(problems[0].contains("32-bit big-endian"));
+    assert!(problems[1].contains("max-atomic-width"));
+}
+
+#[test]
+fn validate_target_spec_unwind() {
+    let json = serde_json::from_str(
+        r#"
+    {
+        "arch": "powerpc64",
+        "data-layout": "e-m:e-i64:64-n32:64",
+        "llvm-target": "powerpc64le-elf",
+        "target-pointer-width": "64"
+    }
+    "#,
+    )
+    .unwrap();
+    let problems = Target::from_json(json).unwrap().0.validate_spec();
+    assert_eq!(problems.len(), 1, "{problems:?}");
+    assert!(problems[0].contains("panic-strategy"));
+}
@@ -6,6 +6,8 @@
 use rustc_macros::Diagnostic;
 use rustc_middle::ty::{self, ClosureKind, PolyTraitRef, Ty};
 use rustc_span::{Span, Symbol};
+use std::io;
+use std::path::Path;
 
 #[derive(Diagnostic)]
 #[diag(trait_selection_dump_vtable_entries)]
@@ -16,6 +18,13 @@ pub struct DumpVTableEntries<'a> {
     pub entries: String,
 }
 
+#[derive(Diagnostic)]
+#[diag(trait_selection_proof_tree_write_failed)]
+pub struct ProofTreeWriteFailed<'a> {
+    pub path: &'a Path,
+    pub error: io::Error,
+}
+
 #[derive(Diagnostic)]
 #[diag(trait_selection_unable_to_construct_constant_value)]
 pub struct UnableToConstructConstantValue<'a> {
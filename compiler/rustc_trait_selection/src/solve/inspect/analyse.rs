@@ -47,6 +47,12 @@ pub fn result(&self) -> Result<Certainty, NoSolution> {
         self.result.map(|c| c.value.certainty)
     }
 
+    /// The number of nested goals of this candidate, including those that
+    /// [`Self::visit_nested`] doesn't visit because the proof tree is too deep.
+    pub fn num_nested_goals(&self) -> usize {
+        self.nested_goals.len()
+    }
+
     /// Visit the nested goals of this candidate.
     ///
     /// FIXME(@lcnr): we have to slightly adapt this API
@@ -3,6 +3,7 @@
 mod ambiguity;
 mod infer_ctxt_ext;
 pub mod on_unimplemented;
+mod proof_tree_json;
 pub mod suggestions;
 mod type_err_ctxt_ext;
 
@@ -40,7 +41,12 @@ pub struct ImplCandidate<'tcx> {
 
 enum GetSafeTransmuteErrorAndReason {
     Silent,
-    Error { err_msg: String, safe_transmute_explanation: String },
+    Error {
+        err_msg: String,
+        safe_transmute_explanation: String,
+        /// Notes on where exactly the types are incompatible.
+        safe_transmute_notes: Vec<String>,
+    },
 }
 
 struct UnsatisfiedConst(pub bool);
@@ -0,0 +1,237 @@
+//! JSON dumps of the proof trees of unsatisfied trait bounds, written by
+//! `-Z dump-proof-tree-json`.
+//!
+//! Only the next trait solver records proof trees. With the old solver, the dump is limited to the
+//! failing obligation and the obligations that required it.
+
+use super::PredicateObligation;
+use crate::errors::ProofTreeWriteFailed;
+use crate::infer::InferCtxt;
+use crate::solve::inspect::{
+    self, InspectCandidate, InspectGoal, ProofTreeInferCtxtExt, ProofTreeVisitor,
+};
+use rustc_data_structures::fx::FxHasher;
+use rustc_hir::def_id::LOCAL_CRATE;
+use rustc_middle::traits::query::NoSolution;
+use rustc_middle::traits::solve::{CandidateSource, Certainty, Goal, MaybeCause};
+use rustc_middle::ty::TyCtxt;
+use rustc_span::Span;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufWriter, Write};
+use std::ops::ControlFlow;
+use std::path::Path;
+
+/// How the obligation whose proof tree is dumped failed.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(super) enum ProofTreeError {
+    /// The obligation doesn't hold.
+    Unimplemented,
+    /// The obligation may hold, but there isn't enough information to tell.
+    Ambiguity,
+}
+
+#[derive(Serialize)]
+struct ProofTreeReport {
+    error: ProofTreeError,
+    obligation: String,
+    span: String,
+    /// The obligations that required the failing one, innermost first.
+    required_by: Vec<String>,
+    root_obligation: String,
+    solver: &'static str,
+    /// The proof tree of the root obligation, if the next trait solver is used.
+    proof_tree: Option<GoalNode>,
+}
+
+#[derive(Serialize)]
+struct GoalNode {
+    goal: String,
+    result: &'static str,
+    candidates: Vec<CandidateNode>,
+}
+
+#[derive(Serialize)]
+struct CandidateNode {
+    kind: &'static str,
+    description: String,
+    span: Option<String>,
+    result: &'static str,
+    /// Why the candidate doesn't prove the goal, if it fails or is ambiguous.
+    rejected_because: Option<Rejection>,
+    nested_goals: Vec<GoalNode>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "reason", rename_all = "kebab-case")]
+enum Rejection {
+    /// The first nested goal that doesn't hold, or that is ambiguous for ambiguous candidates.
+    NestedGoal { index: usize, goal: String, result: &'static str },
+    /// The goal doesn't unify with the candidate, like the header of an impl or a where-clause,
+    /// so no nested goal is involved.
+    Unification,
+}
+
+/// Writes the proof tree of `root_obligation`, which failed because of the `error` of
+/// `obligation`, as JSON to a new file in `dir`.
+pub(super) fn dump_proof_tree_json<'tcx>(
+    infcx: &InferCtxt<'tcx>,
+    error: ProofTreeError,
+    obligation: &PredicateObligation<'tcx>,
+    root_obligation: &PredicateObligation<'tcx>,
+    dir: &Path,
+) {
+    let tcx = infcx.tcx;
+    let mut required_by = vec![];
+    let mut code = obligation.cause.code();
+    while let Some((parent_code, parent_trait_pred)) = code.parent() {
+        if let Some(parent_trait_pred) = parent_trait_pred {
+            required_by.push(infcx.resolve_vars_if_possible(parent_trait_pred).to_string());
+        }
+        code = parent_code;
+    }
+
+    let proof_tree = if infcx.next_trait_solver() {
+        let goal =
+            Goal { predicate: root_obligation.predicate, param_env: root_obligation.param_env };
+        let mut visitor = CollectGoals { goals: vec![] };
+        let _ = infcx.visit_proof_tree(goal, &mut visitor);
+        visitor.goals.pop()
+    } else {
+        None
+    };
+
+    let report = ProofTreeReport {
+        error,
+        obligation: infcx.resolve_vars_if_possible(obligation.predicate).to_string(),
+        span: span_to_string(tcx, obligation.cause.span),
+        required_by,
+        root_obligation: infcx.resolve_vars_if_possible(root_obligation.predicate).to_string(),
+        solver: if infcx.next_trait_solver() { "next" } else { "old" },
+        proof_tree,
+    };
+    // Each failing obligation gets its own file, named after a hash of the obligation and its span
+    // so that the names don't depend on the order in which errors are reported.
+    let mut hasher = FxHasher::default();
+    (&report.obligation, &report.span, &report.root_obligation).hash(&mut hasher);
+    let path = dir.join(format!("{}.{:016x}.json", tcx.crate_name(LOCAL_CRATE), hasher.finish()));
+    if let Err(error) = write_report(&report, dir, &path) {
+        tcx.dcx().emit_err(ProofTreeWriteFailed { path: &path, error });
+    }
+}
+
+fn write_report(report: &ProofTreeReport, dir: &Path, path: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let mut file = BufWriter::new(File::create(path)?);
+    serde_json::to_writer_pretty(&mut file, report)?;
+    file.flush()
+}
+
+struct CollectGoals {
+    goals: Vec<GoalNode>,
+}
+
+impl<'tcx> ProofTreeVisitor<'tcx> for CollectGoals {
+    type BreakTy = ();
+
+    fn visit_goal(&mut self, goal: &InspectGoal<'_, 'tcx>) -> ControlFlow<()> {
+        self.goals.push(goal_node(goal));
+        ControlFlow::Continue(())
+    }
+}
+
+fn goal_node<'tcx>(goal: &InspectGoal<'_, 'tcx>) -> GoalNode {
+    let tcx = goal.infcx().tcx;
+    let candidates = goal
+        .candidates()
+        .iter()
+        .map(|candidate| {
+            let mut nested = CollectGoals { goals: vec![] };
+            let _ = candidate.visit_nested(&mut nested);
+            let rejected_because = rejection(candidate, &nested.goals);
+            let (kind, description, span) = describe_candidate(tcx, goal, candidate.kind());
+            CandidateNode {
+                kind,
+                description,
+                span: span.map(|span| span_to_string(tcx, span)),
+                result: result_to_str(candidate.result()),
+                rejected_because,
+                nested_goals: nested.goals,
+            }
+        })
+        .collect();
+    GoalNode {
+        goal: goal.goal().predicate.to_string(),
+        result: result_to_str(goal.result()),
+        candidates,
+    }
+}
+
+fn rejection(candidate: &InspectCandidate<'_, '_>, nested_goals: &[GoalNode]) -> Option<Rejection> {
+    let failing_goal = match candidate.result() {
+        Ok(Certainty::Yes) => return None,
+        Ok(Certainty::Maybe(_)) => nested_goals.iter().position(|goal| goal.result != "yes"),
+        Err(NoSolution) => nested_goals.iter().position(|goal| goal.result == "no"),
+    };
+    if let Some(index) = failing_goal {
+        let goal = &nested_goals[index];
+        Some(Rejection::NestedGoal { index, goal: goal.goal.clone(), result: goal.result })
+    } else if candidate.result().is_err() && nested_goals.len() == candidate.num_nested_goals() {
+        Some(Rejection::Unification)
+    } else {
+        // Either the nested goals are too deep to be visited, or the candidate is ambiguous by
+        // itself, e.g. because the self type isn't known yet.
+        None
+    }
+}
+
+fn describe_candidate<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    goal: &InspectGoal<'_, 'tcx>,
+    kind: inspect::ProbeKind<'tcx>,
+) -> (&'static str, String, Option<Span>) {
+    match kind {
+        inspect::ProbeKind::TraitCandidate { source, result: _ } => match source {
+            CandidateSource::Impl(def_id) => {
+                let description = match tcx.impl_trait_ref(def_id) {
+                    Some(trait_ref) => {
+                        let trait_ref = trait_ref.instantiate_identity();
+                        format!(
+                            "impl {} for {}",
+                            trait_ref.print_only_trait_path(),
+                            trait_ref.self_ty()
+                        )
+                    }
+                    None => tcx.def_path_str(def_id),
+                };
+                ("impl", description, Some(tcx.def_span(def_id)))
+            }
+            CandidateSource::BuiltinImpl(_) => ("builtin", "builtin impl".to_string(), None),
+            CandidateSource::ParamEnv(index) => {
+                let clause = goal.goal().param_env.caller_bounds()[index];
+                ("where-clause", clause.to_string(), None)
+            }
+            CandidateSource::AliasBound => {
+                ("alias-bound", "bound of an associated type or opaque type".to_string(), None)
+            }
+        },
+        inspect::ProbeKind::MiscCandidate { name, result: _ } => ("misc", name.to_string(), None),
+        inspect::ProbeKind::Root { result: _ } => ("root", "the goal itself".to_string(), None),
+        _ => ("other", format!("{kind:?}"), None),
+    }
+}
+
+fn result_to_str(result: Result<Certainty, NoSolution>) -> &'static str {
+    match result {
+        Ok(Certainty::Yes) => "yes",
+        Ok(Certainty::Maybe(MaybeCause::Ambiguity)) => "ambiguous",
+        Ok(Certainty::Maybe(MaybeCause::Overflow { .. })) => "overflow",
+        Err(NoSolution) => "no",
+    }
+}
+
+fn span_to_string(tcx: TyCtxt<'_>, span: Span) -> String {
+    tcx.sess.source_map().span_to_embeddable_string(span)
+}
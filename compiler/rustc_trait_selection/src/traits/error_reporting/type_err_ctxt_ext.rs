@@ -49,6 +49,7 @@
 use std::fmt;
 use std::iter;
 
+use super::proof_tree_json::{dump_proof_tree_json, ProofTreeError};
 use super::{
     dump_proof_tree, ArgKind, CandidateSimilarity, FindExprBySpan, FindTypeParam,
     GetSafeTransmuteErrorAndReason, HasNumericInferVisitor, ImplCandidate, UnsatisfiedConst,
@@ -369,6 +370,17 @@ fn report_selection_error(
         {
             dump_proof_tree(root_obligation, self.infcx);
         }
+        if let SelectionError::Unimplemented = error
+            && let Some(dir) = &tcx.sess.opts.unstable_opts.dump_proof_tree_json
+        {
+            dump_proof_tree_json(
+                self.infcx,
+                ProofTreeError::Unimplemented,
+                &obligation,
+                root_obligation,
+                dir,
+            );
+        }
 
         let mut span = obligation.cause.span;
 
@@ -486,28 +498,34 @@ fn report_selection_error(
                             post_message,
                         );
 
-                        let (err_msg, safe_transmute_explanation) = if Some(trait_ref.def_id())
-                            == self.tcx.lang_items().transmute_trait()
-                        {
-                            // Recompute the safe transmute reason and use that for the error reporting
-                            match self.get_safe_transmute_error_and_reason(
-                                obligation.clone(),
-                                trait_ref,
-                                span,
-                            ) {
-                                GetSafeTransmuteErrorAndReason::Silent => {
-                                    return self.dcx().span_delayed_bug(
-                                        span, "silent safe transmute error"
-                                    );
+                        let (err_msg, safe_transmute_explanation, safe_transmute_notes) =
+                            if Some(trait_ref.def_id()) == self.tcx.lang_items().transmute_trait() {
+                                // Recompute the safe transmute reason and use that for the error
+                                // reporting
+                                match self.get_safe_transmute_error_and_reason(
+                                    obligation.clone(),
+                                    trait_ref,
+                                    span,
+                                ) {
+                                    GetSafeTransmuteErrorAndReason::Silent => {
+                                        return self.dcx().span_delayed_bug(
+                                            span,
+                                            "silent safe transmute error",
+                                        );
+                                    }
+                                    GetSafeTransmuteErrorAndReason::Error {
+                                        err_msg,
+                                        safe_transmute_explanation,
+                                        safe_transmute_notes,
+                                    } => (
+                                        err_msg,
+                                        Some(safe_transmute_explanation),
+                                        safe_transmute_notes,
+                                    ),
                                 }
-                                GetSafeTransmuteErrorAndReason::Error {
-                                    err_msg,
-                                    safe_transmute_explanation,
-                                } => (err_msg, Some(safe_transmute_explanation)),
-                            }
-                        } else {
-                            (err_msg, None)
-                        };
+                            } else {
+                                (err_msg, None, vec![])
+                            };
 
                         let mut err = struct_span_code_err!(self.dcx(), span, E0277, "{}", err_msg);
 
@@ -576,6 +594,9 @@ fn report_selection_error(
                             }
                         } else if let Some(custom_explanation) = safe_transmute_explanation {
                             err.span_label(span, custom_explanation);
+                            for note in safe_transmute_notes {
+                                err.note(note);
+                            }
                         } else {
                             err.span_label(span, explanation);
                         }
@@ -1429,6 +1450,15 @@ fn report_fulfillment_error(&self, error: &FulfillmentError<'tcx>) -> ErrorGuara
                 self.report_projection_error(&error.obligation, e)
             }
             FulfillmentErrorCode::Ambiguity { overflow: None } => {
+                if let Some(dir) = &self.tcx.sess.opts.unstable_opts.dump_proof_tree_json {
+                    dump_proof_tree_json(
+                        self.infcx,
+                        ProofTreeError::Ambiguity,
+                        &error.obligation,
+                        &error.root_obligation,
+                        dir,
+                    );
+                }
                 self.maybe_report_ambiguity(&error.obligation)
             }
             FulfillmentErrorCode::Ambiguity { overflow: Some(suggest_increasing_limit) } => {
@@ -2993,6 +3023,7 @@ fn get_safe_transmute_error_and_reason(
                 let dst = trait_ref.args.type_at(0);
                 let src = trait_ref.args.type_at(1);
                 let err_msg = format!("`{src}` cannot be safely transmuted into `{dst}`");
+                let mut safe_transmute_notes = vec![];
                 let safe_transmute_explanation = match reason {
                     rustc_transmute::Reason::SrcIsUnspecified => {
                         format!("`{src}` does not have a well-specified layout")
@@ -3006,6 +3037,38 @@ fn get_safe_transmute_error_and_reason(
                         format!("At least one value of `{src}` isn't a bit-valid value of `{dst}`")
                     }
 
+                    rustc_transmute::Reason::DstIsBitIncompatibleAt(incompatibility) => {
+                        let (src_field, dst_field) =
+                            rustc_transmute::TransmuteTypeEnv::new(self.infcx)
+                                .incompatible_fields(src_and_dst, &incompatibility);
+                        let offset = incompatibility.offset;
+                        let src_byte = match incompatibility.src_byte {
+                            Some(b) => format!("`{b:#04x}`"),
+                            None => "uninitialized".to_string(),
+                        };
+                        safe_transmute_notes.push(format!(
+                            "the byte at offset {offset} of `{src}`{} may be {src_byte}",
+                            self.describe_transmuted_field(src_field),
+                        ));
+                        let dst_bytes = match &incompatibility.dst_bytes[..] {
+                            [] => "can't be a byte".to_string(),
+                            [range] => format!("must be {}", byte_range_to_string(*range)),
+                            ranges => format!(
+                                "must be one of {}",
+                                ranges
+                                    .iter()
+                                    .map(|&range| byte_range_to_string(range))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            ),
+                        };
+                        safe_transmute_notes.push(format!(
+                            "the byte at offset {offset} of `{dst}`{} {dst_bytes}",
+                            self.describe_transmuted_field(dst_field),
+                        ));
+                        format!("At least one value of `{src}` isn't a bit-valid value of `{dst}`")
+                    }
+
                     rustc_transmute::Reason::DstMayHaveSafetyInvariants => {
                         format!("`{dst}` may carry safety invariants")
                     }
@@ -3044,7 +3107,11 @@ fn get_safe_transmute_error_and_reason(
                         format!("`{dst}` has an unknown layout")
                     }
                 };
-                GetSafeTransmuteErrorAndReason::Error { err_msg, safe_transmute_explanation }
+                GetSafeTransmuteErrorAndReason::Error {
+                    err_msg,
+                    safe_transmute_explanation,
+                    safe_transmute_notes,
+                }
             }
             // Should never get a Yes at this point! We already ran it before, and did not get a Yes.
             Answer::Yes => span_bug!(
@@ -3055,6 +3122,21 @@ fn get_safe_transmute_error_and_reason(
         }
     }
 
+    /// Describes the field of a transmuted type that contains an incompatible byte, e.g.
+    /// ", in field `bar` of `Foo`".
+    fn describe_transmuted_field(&self, field: Option<&'tcx ty::FieldDef>) -> String {
+        let Some(field) = field else {
+            return String::new();
+        };
+        // The fields of enums belong to their variants.
+        let mut adt_def_id = self.tcx.parent(field.did);
+        if let DefKind::Variant = self.tcx.def_kind(adt_def_id) {
+            adt_def_id = self.tcx.parent(adt_def_id);
+        }
+        let adt_ty = self.tcx.type_of(adt_def_id).instantiate_identity();
+        format!(", in field `{}` of `{adt_ty}`", field.name)
+    }
+
     fn add_tuple_trait_message(
         &self,
         obligation_cause_code: &ObligationCauseCode<'tcx>,
@@ -3484,3 +3566,7 @@ fn report_not_const_evaluatable_error(
         }
     }
 }
+
+fn byte_range_to_string((lo, hi): (u8, u8)) -> String {
+    if lo == hi { format!("`{lo:#04x}`") } else { format!("`{lo:#04x}..={hi:#04x}`") }
+}
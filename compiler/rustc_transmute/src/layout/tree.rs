@@ -116,6 +116,64 @@ pub(crate) fn prune<F>(self, f: &F) -> Tree<!, R>
         }
     }
 
+    /// Finds a path through the layout to a byte at `offset` for which `accepts` produces `true`,
+    /// and produces the `Def` nodes before that byte on the path, outermost first.
+    pub(crate) fn defs_before<F>(&self, offset: usize, accepts: &F) -> Option<Vec<D>>
+    where
+        F: Fn(Byte) -> bool,
+    {
+        self.find_byte(0, vec![], offset, accepts).ok()
+    }
+
+    /// Looks for the byte at `offset` in this node, which starts at `start` and is preceded by
+    /// the `Def` nodes `defs`. If the byte isn't found, produces the end of the node and the
+    /// `Def` nodes up to it instead.
+    fn find_byte<F>(
+        &self,
+        start: usize,
+        mut defs: Vec<D>,
+        offset: usize,
+        accepts: &F,
+    ) -> Result<Vec<D>, (usize, Vec<D>)>
+    where
+        F: Fn(Byte) -> bool,
+    {
+        match self {
+            Self::Seq(elts) => {
+                let mut end = (start, defs);
+                for elt in elts {
+                    if end.0 > offset {
+                        // The byte isn't in the rest of the sequence.
+                        break;
+                    }
+                    end = elt.find_byte(end.0, end.1, offset, accepts)?;
+                }
+                Err(end)
+            }
+            Self::Alt(alts) => {
+                // All the alternatives have the same length, so any of them gives the end.
+                let mut end = None;
+                for alt in alts {
+                    match alt.find_byte(start, defs.clone(), offset, accepts) {
+                        Ok(found) => return Ok(found),
+                        Err(alt_end) => {
+                            end.get_or_insert(alt_end);
+                        }
+                    }
+                }
+                Err(end.unwrap_or((start, defs)))
+            }
+            Self::Def(d) => {
+                defs.push(*d);
+                Err((start, defs))
+            }
+            Self::Ref(r) => Err((start + r.size(), defs)),
+            Self::Byte(b) => {
+                if start == offset && accepts(*b) { Ok(defs) } else { Err((start + 1, defs)) }
+            }
+        }
+    }
+
     /// Produces `true` if `Tree` is an inhabited type; otherwise false.
     pub(crate) fn is_inhabited(&self) -> bool {
         match self {
@@ -371,13 +429,15 @@ pub fn from_ty(ty: Ty<'tcx>, tcx: TyCtxt<'tcx>) -> Result<Self, Err> {
                     }))
                 }
 
-                ty::Ref(lifetime, ty, mutability) => {
-                    let align = layout_of(tcx, *ty)?.align();
+                ty::Ref(lifetime, referent_ty, mutability) => {
+                    let align = layout_of(tcx, *referent_ty)?.align();
+                    let size = layout_of(tcx, ty)?.size();
                     Ok(Tree::Ref(Ref {
                         lifetime: *lifetime,
-                        ty: *ty,
+                        ty: *referent_ty,
                         mutability: *mutability,
                         align,
+                        size,
                     }))
                 }
 
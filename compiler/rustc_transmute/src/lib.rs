@@ -47,8 +47,12 @@ pub enum Reason {
     SrcIsUnspecified,
     /// The layout of the destination type is unspecified.
     DstIsUnspecified,
-    /// The layout of the destination type is bit-incompatible with the source type.
+    /// The layout of the destination type is bit-incompatible with the source type, but not at
+    /// a single byte, e.g. because the source has a reference where the destination doesn't.
     DstIsBitIncompatible,
+    /// The layout of the destination type is bit-incompatible with the source type, starting at
+    /// a known byte.
+    DstIsBitIncompatibleAt(BitIncompatibility),
     /// The destination type may carry safety invariants.
     DstMayHaveSafetyInvariants,
     /// `Dst` is larger than `Src`, and the excess bytes were not exclusively uninitialized.
@@ -69,15 +73,29 @@ pub enum Reason {
     DstSizeOverflow,
 }
 
+/// Where the layouts of the source and destination types stop being bit-compatible.
+#[derive(Debug, Hash, Eq, PartialEq, PartialOrd, Ord, Clone)]
+pub struct BitIncompatibility {
+    /// The offset of the incompatible byte, in bytes.
+    pub offset: usize,
+    /// A value the source byte may have, or `None` if it may be uninitialized.
+    pub src_byte: Option<u8>,
+    /// The values the destination byte may have, as inclusive ranges.
+    pub dst_bytes: Vec<(u8, u8)>,
+}
+
 #[cfg(feature = "rustc")]
 mod rustc {
     use super::*;
 
+    use crate::layout::rustc::Def;
+    use crate::layout::{Byte, Tree};
     use rustc_hir::lang_items::LangItem;
     use rustc_infer::infer::InferCtxt;
     use rustc_macros::TypeVisitable;
     use rustc_middle::traits::ObligationCause;
     use rustc_middle::ty::Const;
+    use rustc_middle::ty::FieldDef;
     use rustc_middle::ty::ParamEnv;
     use rustc_middle::ty::Ty;
     use rustc_middle::ty::TyCtxt;
@@ -116,6 +134,38 @@ pub fn is_transmutable(
             )
             .answer()
         }
+
+        /// Finds the innermost fields of the source and destination types that contain the byte
+        /// at which they are bit-incompatible.
+        pub fn incompatible_fields(
+            &self,
+            types: Types<'tcx>,
+            incompatibility: &BitIncompatibility,
+        ) -> (Option<&'tcx FieldDef>, Option<&'tcx FieldDef>) {
+            let tcx = self.infcx.tcx;
+            let offset = incompatibility.offset;
+            let src_byte = match incompatibility.src_byte {
+                Some(b) => Byte::Init(b),
+                None => Byte::Uninit,
+            };
+            // The last field before the byte on its path is the innermost field containing it,
+            // except in trailing padding, which we attribute to the last field as well.
+            let last_field = |defs: Vec<Def<'tcx>>| {
+                defs.into_iter().rev().find_map(|def| match def {
+                    Def::Field(field) => Some(field),
+                    _ => None,
+                })
+            };
+            let src = Tree::from_ty(types.src, tcx)
+                .ok()
+                .and_then(|src| src.defs_before(offset, &|byte| byte == src_byte))
+                .and_then(last_field);
+            let dst = Tree::from_ty(types.dst, tcx)
+                .ok()
+                .and_then(|dst| dst.defs_before(offset, &|_| true))
+                .and_then(last_field);
+            (src, dst)
+        }
     }
 
     impl Assume {
@@ -5,7 +5,7 @@
 use crate::{
     layout::{self, dfa, Byte, Def, Dfa, Nfa, Ref, Tree, Uninhabited},
     maybe_transmutable::query_context::QueryContext,
-    Answer, Condition, Map, Reason,
+    Answer, BitIncompatibility, Condition, Map, Reason,
 };
 
 pub(crate) struct MaybeTransmutableQuery<L, C>
@@ -148,7 +148,7 @@ impl<C> MaybeTransmutableQuery<Dfa<<C as QueryContext>::Ref>, C>
 {
     /// Answers whether a `Dfa` is transmutable into another `Dfa`.
     pub(crate) fn answer(self) -> Answer<<C as QueryContext>::Ref> {
-        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start)
+        self.answer_memo(&mut Map::default(), self.src.start, self.dst.start, 0)
     }
 
     #[inline(always)]
@@ -158,6 +158,9 @@ fn answer_memo(
         cache: &mut Map<(dfa::State, dfa::State), Answer<<C as QueryContext>::Ref>>,
         src_state: dfa::State,
         dst_state: dfa::State,
+        // The offset of the states in bytes. All paths to a state have the same length, so this
+        // is determined by the states, and doesn't need to be part of the cache key.
+        offset: usize,
     ) -> Answer<<C as QueryContext>::Ref> {
         if let Some(answer) = cache.get(&(src_state, dst_state)) {
             answer.clone()
@@ -189,7 +192,7 @@ fn answer_memo(
             } else if src_state == self.src.accepting {
                 // extension: `size_of(Src) >= size_of(Dst)`
                 if let Some(dst_state_prime) = self.dst.byte_from(dst_state, Byte::Uninit) {
-                    self.answer_memo(cache, src_state, dst_state_prime)
+                    self.answer_memo(cache, src_state, dst_state_prime, offset + 1)
                 } else {
                     Answer::No(Reason::DstIsTooBig)
                 }
@@ -214,17 +217,26 @@ fn answer_memo(
                             if let Some(dst_state_prime) =
                                 self.dst.byte_from(dst_state, src_validity)
                             {
-                                self.answer_memo(cache, src_state_prime, dst_state_prime)
+                                let offset = offset + 1;
+                                self.answer_memo(cache, src_state_prime, dst_state_prime, offset)
                             } else if let Some(dst_state_prime) =
                                 // otherwise, see if `dst_state` has any outgoing `Uninit` transitions
                                 // (any init byte is a valid uninit byte)
                                 self.dst.byte_from(dst_state, Byte::Uninit)
                             {
-                                self.answer_memo(cache, src_state_prime, dst_state_prime)
+                                let offset = offset + 1;
+                                self.answer_memo(cache, src_state_prime, dst_state_prime, offset)
                             } else {
                                 // otherwise, we've exhausted our options.
                                 // the DFAs, from this point onwards, are bit-incompatible.
-                                Answer::No(Reason::DstIsBitIncompatible)
+                                Answer::No(Reason::DstIsBitIncompatibleAt(BitIncompatibility {
+                                    offset,
+                                    src_byte: match src_validity {
+                                        Byte::Init(b) => Some(b),
+                                        Byte::Uninit => None,
+                                    },
+                                    dst_bytes: byte_ranges(self.dst.bytes_from(dst_state)),
+                                }))
                             }
                         },
                     ),
@@ -278,6 +290,7 @@ fn answer_memo(
                                                     cache,
                                                     src_state_prime,
                                                     dst_state_prime,
+                                                    offset + src_ref.size(),
                                                 ),
                                             )
                                         }
@@ -301,6 +314,27 @@ fn answer_memo(
     }
 }
 
+/// The initialized values of `bytes`, as sorted inclusive ranges.
+fn byte_ranges<S>(bytes: Option<&Map<Byte, S>>) -> Vec<(u8, u8)> {
+    let mut values: Vec<u8> = bytes
+        .into_iter()
+        .flat_map(|bytes| bytes.keys())
+        .filter_map(|byte| match byte {
+            Byte::Init(b) => Some(*b),
+            Byte::Uninit => None,
+        })
+        .collect();
+    values.sort_unstable();
+    let mut ranges: Vec<(u8, u8)> = vec![];
+    for value in values {
+        match ranges.last_mut() {
+            Some((_, hi)) if hi.checked_add(1) == Some(value) => *hi = value,
+            _ => ranges.push((value, value)),
+        }
+    }
+    ranges
+}
+
 fn and<R>(lhs: Answer<R>, rhs: Answer<R>) -> Answer<R>
 where
     R: PartialEq,
@@ -1,6 +1,6 @@
 use super::query_context::test::{Def, UltraMinimal};
 use crate::maybe_transmutable::MaybeTransmutableQuery;
-use crate::{layout, Reason};
+use crate::{layout, BitIncompatibility, Reason};
 use itertools::Itertools;
 
 mod safety {
@@ -150,21 +150,96 @@ fn should_permit_validity_expansion_and_reject_contraction() {
                         dst_layout
                     );
                 } else {
-                    assert_eq!(
-                        Answer::No(Reason::DstIsBitIncompatible),
-                        MaybeTransmutableQuery::new(
-                            src_layout.clone(),
-                            dst_layout.clone(),
-                            crate::Assume { validity: false, ..crate::Assume::default() },
-                            UltraMinimal,
-                        )
-                        .answer(),
-                        "{:?} should NOT be transmutable into {:?}",
+                    let answer = MaybeTransmutableQuery::new(
+                        src_layout.clone(),
+                        dst_layout.clone(),
+                        crate::Assume { validity: false, ..crate::Assume::default() },
+                        UltraMinimal,
+                    )
+                    .answer();
+                    assert!(
+                        matches!(
+                            answer,
+                            Answer::No(Reason::DstIsBitIncompatibleAt(BitIncompatibility {
+                                offset: 0,
+                                ..
+                            }))
+                        ),
+                        "{:?} should NOT be transmutable into {:?}, got {:?}",
                         src_layout,
-                        dst_layout
+                        dst_layout,
+                        answer
                     );
                 }
             }
         }
     }
 }
+
+mod bit_incompatibility {
+    use crate::Answer;
+
+    use super::*;
+
+    type Tree = layout::Tree<Def, !>;
+
+    #[test]
+    fn reports_first_incompatible_byte() {
+        let src = Tree::u8().then(Tree::u8());
+        let dst = Tree::u8().then(Tree::bool());
+        let answer =
+            MaybeTransmutableQuery::new(src, dst, crate::Assume::default(), UltraMinimal).answer();
+        assert_eq!(
+            answer,
+            Answer::No(Reason::DstIsBitIncompatibleAt(BitIncompatibility {
+                offset: 1,
+                src_byte: Some(0x02),
+                dst_bytes: vec![(0x00, 0x01)],
+            }))
+        );
+    }
+
+    #[test]
+    fn reports_uninit_source_byte() {
+        let src = Tree::u8().then(Tree::uninit());
+        let dst = Tree::u8().then(Tree::u8());
+        let answer =
+            MaybeTransmutableQuery::new(src, dst, crate::Assume::default(), UltraMinimal).answer();
+        assert_eq!(
+            answer,
+            Answer::No(Reason::DstIsBitIncompatibleAt(BitIncompatibility {
+                offset: 1,
+                src_byte: None,
+                dst_bytes: vec![(0x00, 0xff)],
+            }))
+        );
+    }
+
+    #[test]
+    fn finds_defs_before_byte() {
+        use Def::*;
+        let tree = Tree::Def(NoSafetyInvariants)
+            .then(Tree::u8())
+            .then(Tree::Def(HasSafetyInvariants))
+            .then(Tree::bool());
+        assert_eq!(tree.defs_before(0, &|_| true), Some(vec![NoSafetyInvariants]));
+        assert_eq!(
+            tree.defs_before(1, &|_| true),
+            Some(vec![NoSafetyInvariants, HasSafetyInvariants])
+        );
+        assert_eq!(tree.defs_before(1, &|byte| byte == layout::Byte::Init(0x02)), None);
+        assert_eq!(tree.defs_before(2, &|_| true), None);
+    }
+
+    #[test]
+    fn finds_defs_in_matching_alternative() {
+        use Def::*;
+        let first = Tree::Def(NoSafetyInvariants).then(Tree::from_bits(0x00));
+        let second = Tree::Def(HasSafetyInvariants).then(Tree::from_bits(0x01));
+        let tree = first.or(second);
+        assert_eq!(
+            tree.defs_before(0, &|byte| byte == layout::Byte::Init(0x01)),
+            Some(vec![HasSafetyInvariants])
+        );
+    }
+}
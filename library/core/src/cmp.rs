@@ -267,7 +267,7 @@ fn ne(&self, other: &Rhs) -> bool {
 
 /// Derive macro generating an impl of the trait [`PartialEq`].
 /// The behavior of this macro is described in detail [here](PartialEq#derivable).
-#[rustc_builtin_macro]
+#[rustc_builtin_macro(PartialEq, attributes(eq))]
 #[stable(feature = "builtin_macro_prelude", since = "1.38.0")]
 #[allow_internal_unstable(core_intrinsics, structural_match)]
 pub macro PartialEq($item:item) {
@@ -342,7 +342,7 @@ fn assert_receiver_is_total_eq(&self) {}
 }
 
 /// Derive macro generating an impl of the trait [`Eq`].
-#[rustc_builtin_macro]
+#[rustc_builtin_macro(Eq, attributes(eq))]
 #[stable(feature = "builtin_macro_prelude", since = "1.38.0")]
 #[allow_internal_unstable(core_intrinsics, derive_eq, structural_match)]
 #[allow_internal_unstable(coverage_attribute)]
@@ -911,7 +911,7 @@ fn clamp(self, min: Self, max: Self) -> Self
 
 /// Derive macro generating an impl of the trait [`Ord`].
 /// The behavior of this macro is described in detail [here](Ord#derivable).
-#[rustc_builtin_macro]
+#[rustc_builtin_macro(Ord, attributes(ord))]
 #[stable(feature = "builtin_macro_prelude", since = "1.38.0")]
 #[allow_internal_unstable(core_intrinsics)]
 pub macro Ord($item:item) {
@@ -1225,7 +1225,7 @@ fn ge(&self, other: &Rhs) -> bool {
 
 /// Derive macro generating an impl of the trait [`PartialOrd`].
 /// The behavior of this macro is described in detail [here](PartialOrd#derivable).
-#[rustc_builtin_macro]
+#[rustc_builtin_macro(PartialOrd, attributes(ord))]
 #[stable(feature = "builtin_macro_prelude", since = "1.38.0")]
 #[allow_internal_unstable(core_intrinsics)]
 pub macro PartialOrd($item:item) {
@@ -1,7 +1,12 @@
+use object::{Object, ObjectSymbol, SymbolKind};
 use regex::Regex;
 use rustc_demangle::demangle;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
 use std::str::Lines;
 
+pub mod v0;
+
 const REPLACE_COLONS: &str = "::";
 
 pub fn create_disambiguator_re() -> Regex {
@@ -19,3 +24,90 @@ pub fn demangle_lines(lines: Lines<'_>, strip_crate_disambiguators: Option<Regex
     }
     demangled_lines
 }
+
+/// Returns the names of the symbols in the symbol tables of an ELF or Mach-O file, sorted and
+/// without duplicates.
+pub fn object_symbols(data: &[u8]) -> Result<Vec<String>, object::Error> {
+    let file = object::File::parse(data)?;
+    let mut names = BTreeSet::new();
+    for symbol in file.symbols().chain(file.dynamic_symbols()) {
+        if !matches!(
+            symbol.kind(),
+            SymbolKind::Text | SymbolKind::Data | SymbolKind::Tls | SymbolKind::Unknown
+        ) {
+            continue;
+        }
+        if let Ok(name) = symbol.name() {
+            if !name.is_empty() {
+                names.insert(name.to_owned());
+            }
+        }
+    }
+    Ok(names.into_iter().collect())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mangling {
+    V0,
+    Legacy,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymbolInfo {
+    pub mangled: String,
+    /// The symbol as demangled by `rustc-demangle`, including crate disambiguators.
+    pub demangled: Option<String>,
+    /// The mangling scheme of the symbol, or `None` if it's not a Rust symbol.
+    pub mangling: Option<Mangling>,
+    /// The structure of the symbol, for the v0 symbols supported by [`v0::parse`].
+    #[serde(flatten)]
+    pub v0: Option<v0::Symbol>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CrateSymbols {
+    #[serde(flatten)]
+    pub krate: v0::Crate,
+    pub symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// The v0 symbols, grouped by the crate defining them.
+    pub crates: Vec<CrateSymbols>,
+    /// All other symbols. Legacy symbols only contain a hash of the whole symbol, so they can't
+    /// be attributed to a crate reliably.
+    pub ungrouped: Vec<SymbolInfo>,
+}
+
+/// Demangles `symbols`, grouping the v0 symbols by crate name and crate disambiguator. The
+/// disambiguator identifies one build of a crate, which allows to map the symbols of a binary
+/// back to the sources of the crates it was built from.
+pub fn symbol_report<'a>(symbols: impl IntoIterator<Item = &'a str>) -> Report {
+    let mut crates = BTreeMap::<v0::Crate, Vec<SymbolInfo>>::new();
+    let mut ungrouped = vec![];
+    for mangled in symbols {
+        let demangled = rustc_demangle::try_demangle(mangled).ok().map(|d| d.to_string());
+        let is_v0 = ["_R", "R", "__R"].iter().any(|prefix| mangled.starts_with(prefix));
+        let mangling = match (&demangled, is_v0) {
+            (None, _) => None,
+            (Some(_), true) => Some(Mangling::V0),
+            (Some(_), false) => Some(Mangling::Legacy),
+        };
+        let v0 = if mangling == Some(Mangling::V0) { v0::parse(mangled).ok() } else { None };
+        let krate = v0.as_ref().map(|symbol| symbol.krate.clone());
+        let info = SymbolInfo { mangled: mangled.to_owned(), demangled, mangling, v0 };
+        match krate {
+            Some(krate) => crates.entry(krate).or_default().push(info),
+            None => ungrouped.push(info),
+        }
+    }
+    Report {
+        crates: crates
+            .into_iter()
+            .map(|(krate, symbols)| CrateSymbols { krate, symbols })
+            .collect(),
+        ungrouped,
+    }
+}
@@ -36,6 +36,7 @@
 //! they are not removed.
 
 use rust_demangler::*;
+use std::fs;
 use std::io::{self, Read, Write};
 
 fn main() -> io::Result<()> {
@@ -59,14 +60,21 @@ fn main() -> io::Result<()> {
     // exceed the value 9999.
     let mut strip_crate_disambiguators = Some(create_disambiguator_re());
 
+    let mut json = false;
+    let mut files = vec![];
+
     let mut args = std::env::args();
     let progname = args.next().unwrap();
     for arg in args {
         if arg == "--disambiguators" || arg == "-d" {
             strip_crate_disambiguators = None;
+        } else if arg == "--json" {
+            json = true;
+        } else if !arg.starts_with('-') {
+            files.push(arg);
         } else {
             eprintln!();
-            eprintln!("Usage: {} [-d|--disambiguators]", progname);
+            eprintln!("Usage: {} [-d|--disambiguators] [--json] [FILE...]", progname);
             eprintln!();
             eprintln!(
                 "This tool converts a list of Rust mangled symbols (one per line) into a\n\
@@ -84,13 +92,46 @@ fn main() -> io::Result<()> {
                 expression. (See the `rust-demangler` doc comments for more information.)"
             );
             eprintln!();
+            eprintln!(
+                "If FILEs are given, the symbols are read from the symbol tables of these ELF or\n\
+                Mach-O files instead of from stdin."
+            );
+            eprintln!();
+            eprintln!(
+                "With --json, a JSON report is printed instead, which groups v0 symbols by crate\n\
+                name and crate disambiguator, and lists the path, namespaces, disambiguators and\n\
+                generic arguments of each of them."
+            );
+            eprintln!();
             std::process::exit(1)
         }
     }
 
-    let mut buffer = String::new();
-    io::stdin().read_to_string(&mut buffer)?;
-    let mut demangled_lines = demangle_lines(buffer.lines(), strip_crate_disambiguators);
+    let symbols = if files.is_empty() {
+        let mut buffer = String::new();
+        io::stdin().read_to_string(&mut buffer)?;
+        buffer.lines().map(str::to_owned).collect()
+    } else {
+        let mut symbols = vec![];
+        for file in &files {
+            let data = fs::read(file)?;
+            let names = object_symbols(&data).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("{file}: {err}"))
+            })?;
+            symbols.extend(names);
+        }
+        symbols
+    };
+
+    if json {
+        let report = symbol_report(symbols.iter().map(String::as_str));
+        serde_json::to_writer_pretty(io::stdout().lock(), &report)?;
+        println!();
+        return Ok(());
+    }
+
+    let mut demangled_lines =
+        demangle_lines(symbols.join("\n").lines(), strip_crate_disambiguators);
     demangled_lines.push("".to_string()); // ensure a trailing newline
     io::stdout().write_all(demangled_lines.join("\n").as_bytes())?;
     Ok(())
@@ -0,0 +1,513 @@
+//! A parser for symbols mangled with the v0 scheme ([RFC 2603]), as produced by
+//! `rustc_symbol_mangling::v0`.
+//!
+//! Unlike `rustc-demangle`, which only renders a symbol as a string, this parser exposes the
+//! structure of the symbol: the crate (and crate disambiguator) the item belongs to, every segment
+//! of its path with its namespace and disambiguator, and the generic arguments of each segment.
+//! Types and constants appearing in generic arguments are rendered as strings, without crate
+//! disambiguators.
+//!
+//! [RFC 2603]: https://rust-lang.github.io/rfcs/2603-rust-symbol-name-mangling-v0.html
+
+use serde::Serialize;
+
+/// Nesting deeper than this is rejected, to avoid overflowing the stack on malicious input.
+const MAX_DEPTH: u32 = 500;
+
+/// A crate, identified by its name and its disambiguator, which is derived from the crate's
+/// `StableCrateId` and thus distinguishes different builds and versions of crates with the same
+/// name.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Crate {
+    pub name: String,
+    /// The disambiguator in hexadecimal, as printed by `rustc-demangle`.
+    pub hash: String,
+}
+
+/// A segment of the path of a symbol.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Segment {
+    /// The name of the segment, as printed by `rustc-demangle`. For example `spawn`, `{closure#0}`
+    /// or, for the items of impls, `<core::slice::Iter<u8> as core::iter::Iterator>`.
+    pub name: String,
+    /// `"type"` or `"value"` for named items, `"closure"` or `"shim"` for compiler generated ones,
+    /// or the raw namespace tag for other namespaces. Absent for impls.
+    pub namespace: Option<String>,
+    /// Distinguishes items of the same namespace with the same name, like several closures.
+    pub disambiguator: u64,
+    pub generic_args: Vec<String>,
+}
+
+/// The structure of a v0 symbol.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Symbol {
+    /// The crate defining the item. For the items of impls, that's the crate containing the impl.
+    #[serde(rename = "crate")]
+    pub krate: Crate,
+    pub path: Vec<Segment>,
+    /// The crate that instantiated a generic item, if it's not the crate defining it.
+    pub instantiating_crate: Option<Crate>,
+    /// A vendor specific suffix, like the `.llvm.<hash>` added by LLVM to promoted symbols.
+    pub suffix: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError;
+
+/// Parses a v0 symbol, with or without the leading underscore(s) of the platform.
+pub fn parse(symbol: &str) -> Result<Symbol, ParseError> {
+    let inner = ["_R", "R", "__R"]
+        .iter()
+        .find_map(|prefix| symbol.strip_prefix(prefix))
+        .ok_or(ParseError)?;
+    // Only version 0 of the scheme, which has no explicit version number, is supported.
+    if inner.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(ParseError);
+    }
+
+    let mut parser = Parser { sym: inner.as_bytes(), pos: 0, depth: 0 };
+    let (krate, path) = parser.path()?;
+    let instantiating_crate = if parser.peek().is_some_and(|b| b.is_ascii_uppercase()) {
+        Some(parser.path()?.0)
+    } else {
+        None
+    };
+    let suffix = match &inner[parser.pos..] {
+        "" => None,
+        suffix if suffix.starts_with(['.', '$']) => Some(suffix.to_owned()),
+        _ => return Err(ParseError),
+    };
+    Ok(Symbol { krate, path, instantiating_crate, suffix })
+}
+
+/// Renders a path the way `rustc-demangle` does in its alternate format, omitting the crate
+/// disambiguators and the module path of impls.
+fn render(krate: &Crate, path: &[Segment]) -> String {
+    let mut out = krate.name.clone();
+    for segment in path {
+        if segment.namespace.is_none() {
+            out = segment.name.clone();
+        } else {
+            out.push_str("::");
+            out.push_str(&segment.name);
+        }
+        if !segment.generic_args.is_empty() {
+            out.push('<');
+            out.push_str(&segment.generic_args.join(", "));
+            out.push('>');
+        }
+    }
+    out
+}
+
+struct Parser<'s> {
+    sym: &'s [u8],
+    pos: usize,
+    depth: u32,
+}
+
+impl<'s> Parser<'s> {
+    fn peek(&self) -> Option<u8> {
+        self.sym.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        let found = self.peek() == Some(b);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn next(&mut self) -> Result<u8, ParseError> {
+        let b = self.peek().ok_or(ParseError)?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn push_depth(&mut self) -> Result<(), ParseError> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH { Err(ParseError) } else { Ok(()) }
+    }
+
+    /// Parses `<decimal-number>`.
+    fn decimal(&mut self) -> Result<usize, ParseError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits = std::str::from_utf8(&self.sym[start..self.pos]).unwrap();
+        if digits.is_empty() || (digits.len() > 1 && digits.starts_with('0')) {
+            return Err(ParseError);
+        }
+        digits.parse().map_err(|_| ParseError)
+    }
+
+    /// Parses `<base-62-number>`.
+    fn integer_62(&mut self) -> Result<u64, ParseError> {
+        if self.eat(b'_') {
+            return Ok(0);
+        }
+        let mut x: u64 = 0;
+        while !self.eat(b'_') {
+            let d = match self.next()? {
+                d @ b'0'..=b'9' => d - b'0',
+                d @ b'a'..=b'z' => 10 + (d - b'a'),
+                d @ b'A'..=b'Z' => 36 + (d - b'A'),
+                _ => return Err(ParseError),
+            };
+            x = x.checked_mul(62).and_then(|x| x.checked_add(d.into())).ok_or(ParseError)?;
+        }
+        x.checked_add(1).ok_or(ParseError)
+    }
+
+    /// Parses a base-62 number prefixed by `tag`, which is 0 if the tag is missing.
+    fn opt_integer_62(&mut self, tag: u8) -> Result<u64, ParseError> {
+        if !self.eat(tag) {
+            return Ok(0);
+        }
+        self.integer_62()?.checked_add(1).ok_or(ParseError)
+    }
+
+    fn disambiguator(&mut self) -> Result<u64, ParseError> {
+        self.opt_integer_62(b's')
+    }
+
+    /// Parses `<undisambiguated-identifier>`.
+    fn ident(&mut self) -> Result<String, ParseError> {
+        let is_punycode = self.eat(b'u');
+        let len = self.decimal()?;
+        self.eat(b'_');
+        let start = self.pos;
+        let end = start.checked_add(len).filter(|&end| end <= self.sym.len()).ok_or(ParseError)?;
+        self.pos = end;
+        let ident = std::str::from_utf8(&self.sym[start..end]).map_err(|_| ParseError)?;
+        if is_punycode { decode_punycode(ident) } else { Ok(ident.to_owned()) }
+    }
+
+    /// Runs `f` at the position a `<backref>` points to, and continues after the backref.
+    fn backref<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        let start = self.pos - 1;
+        let target = self.integer_62()?;
+        if target >= start as u64 {
+            return Err(ParseError);
+        }
+        let pos = std::mem::replace(&mut self.pos, target as usize);
+        let result = f(self);
+        self.pos = pos;
+        result
+    }
+
+    /// Parses `<path>`, returning its crate root and its segments.
+    fn path(&mut self) -> Result<(Crate, Vec<Segment>), ParseError> {
+        self.push_depth()?;
+        let result = match self.next()? {
+            b'C' => {
+                let disambiguator = self.disambiguator()?;
+                let name = self.ident()?;
+                Ok((Crate { name, hash: format!("{disambiguator:x}") }, vec![]))
+            }
+            b'N' => {
+                let namespace = self.next()?;
+                if !namespace.is_ascii_alphabetic() {
+                    return Err(ParseError);
+                }
+                let (krate, mut path) = self.path()?;
+                let disambiguator = self.disambiguator()?;
+                let ident = self.ident()?;
+                let (name, namespace) = if namespace.is_ascii_lowercase() {
+                    let namespace = match namespace {
+                        b't' => "type".to_owned(),
+                        b'v' => "value".to_owned(),
+                        other => char::from(other).to_string(),
+                    };
+                    (ident, namespace)
+                } else {
+                    let namespace = match namespace {
+                        b'C' => "closure".to_owned(),
+                        b'S' => "shim".to_owned(),
+                        other => char::from(other).to_string(),
+                    };
+                    let name = if ident.is_empty() {
+                        format!("{{{namespace}#{disambiguator}}}")
+                    } else {
+                        format!("{{{namespace}:{ident}#{disambiguator}}}")
+                    };
+                    (name, namespace)
+                };
+                path.push(Segment {
+                    name,
+                    namespace: Some(namespace),
+                    disambiguator,
+                    generic_args: vec![],
+                });
+                Ok((krate, path))
+            }
+            tag @ (b'M' | b'X') => {
+                let disambiguator = self.disambiguator()?;
+                let (krate, mut path) = self.path()?;
+                let self_ty = self.ty()?;
+                let name = if tag == b'X' {
+                    format!("<{self_ty} as {}>", self.rendered_path()?)
+                } else {
+                    format!("<{self_ty}>")
+                };
+                path.push(Segment { name, namespace: None, disambiguator, generic_args: vec![] });
+                Ok((krate, path))
+            }
+            b'Y' => {
+                let self_ty = self.ty()?;
+                let (krate, trait_path) = self.path()?;
+                let name = format!("<{self_ty} as {}>", render(&krate, &trait_path));
+                let segment =
+                    Segment { name, namespace: None, disambiguator: 0, generic_args: vec![] };
+                Ok((krate, vec![segment]))
+            }
+            b'I' => {
+                let (krate, mut path) = self.path()?;
+                let args = self.generic_args()?;
+                path.last_mut().ok_or(ParseError)?.generic_args = args;
+                Ok((krate, path))
+            }
+            b'B' => self.backref(Self::path),
+            _ => Err(ParseError),
+        };
+        self.depth -= 1;
+        result
+    }
+
+    fn rendered_path(&mut self) -> Result<String, ParseError> {
+        let (krate, path) = self.path()?;
+        Ok(render(&krate, &path))
+    }
+
+    /// Parses `{<generic-arg>} "E"`, skipping lifetimes, which are always erased in symbols.
+    fn generic_args(&mut self) -> Result<Vec<String>, ParseError> {
+        let mut args = vec![];
+        while !self.eat(b'E') {
+            if self.eat(b'L') {
+                self.integer_62()?;
+            } else if self.eat(b'K') {
+                args.push(self.konst()?);
+            } else {
+                args.push(self.ty()?);
+            }
+        }
+        Ok(args)
+    }
+
+    /// Parses `<type>`.
+    fn ty(&mut self) -> Result<String, ParseError> {
+        if let Some(basic) = self.peek().and_then(basic_type) {
+            self.pos += 1;
+            return Ok(basic.to_owned());
+        }
+        self.push_depth()?;
+        let result = match self.next()? {
+            b'A' => {
+                let ty = self.ty()?;
+                let len = self.konst()?;
+                Ok(format!("[{ty}; {len}]"))
+            }
+            b'S' => Ok(format!("[{}]", self.ty()?)),
+            b'T' => {
+                let mut tys = vec![];
+                while !self.eat(b'E') {
+                    tys.push(self.ty()?);
+                }
+                if tys.len() == 1 {
+                    Ok(format!("({},)", tys[0]))
+                } else {
+                    Ok(format!("({})", tys.join(", ")))
+                }
+            }
+            tag @ (b'R' | b'Q') => {
+                if self.eat(b'L') {
+                    self.integer_62()?;
+                }
+                let mutbl = if tag == b'Q' { "mut " } else { "" };
+                Ok(format!("&{mutbl}{}", self.ty()?))
+            }
+            b'P' => Ok(format!("*const {}", self.ty()?)),
+            b'O' => Ok(format!("*mut {}", self.ty()?)),
+            b'F' => self.fn_sig(),
+            b'D' => self.dyn_bounds(),
+            b'B' => self.backref(Self::ty),
+            _ => {
+                self.pos -= 1;
+                self.rendered_path()
+            }
+        };
+        self.depth -= 1;
+        result
+    }
+
+    /// Parses `<fn-sig>`.
+    fn fn_sig(&mut self) -> Result<String, ParseError> {
+        self.opt_integer_62(b'G')?;
+        let mut out = String::new();
+        if self.eat(b'U') {
+            out.push_str("unsafe ");
+        }
+        if self.eat(b'K') {
+            let abi = if self.eat(b'C') { "C".to_owned() } else { self.ident()?.replace('_', "-") };
+            out.push_str(&format!("extern \"{abi}\" "));
+        }
+        let mut inputs = vec![];
+        while !self.eat(b'E') {
+            inputs.push(self.ty()?);
+        }
+        out.push_str(&format!("fn({})", inputs.join(", ")));
+        let output = self.ty()?;
+        if output != "()" {
+            out.push_str(&format!(" -> {output}"));
+        }
+        Ok(out)
+    }
+
+    /// Parses `<dyn-bounds> <lifetime>`.
+    fn dyn_bounds(&mut self) -> Result<String, ParseError> {
+        self.opt_integer_62(b'G')?;
+        let mut traits = vec![];
+        while !self.eat(b'E') {
+            let (krate, mut path) = self.path()?;
+            while self.eat(b'p') {
+                let name = self.ident()?;
+                let ty = self.ty()?;
+                path.last_mut().ok_or(ParseError)?.generic_args.push(format!("{name} = {ty}"));
+            }
+            traits.push(render(&krate, &path));
+        }
+        if !self.eat(b'L') {
+            return Err(ParseError);
+        }
+        self.integer_62()?;
+        Ok(format!("dyn {}", traits.join(" + ")))
+    }
+
+    /// Parses `<const>`. Only the constants of integer, `bool` and `char` type are supported.
+    fn konst(&mut self) -> Result<String, ParseError> {
+        if self.eat(b'p') {
+            return Ok("_".to_owned());
+        }
+        if self.eat(b'B') {
+            return self.backref(Self::konst);
+        }
+        let ty = self.next()?;
+        let negative = self.eat(b'n');
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_hexdigit()) {
+            self.pos += 1;
+        }
+        let digits = std::str::from_utf8(&self.sym[start..self.pos]).unwrap();
+        if !self.eat(b'_') {
+            return Err(ParseError);
+        }
+        let value = if digits.is_empty() {
+            0
+        } else {
+            u128::from_str_radix(digits, 16).map_err(|_| ParseError)?
+        };
+        match ty {
+            b'b' if !negative => match value {
+                0 => Ok("false".to_owned()),
+                1 => Ok("true".to_owned()),
+                _ => Err(ParseError),
+            },
+            b'c' if !negative => {
+                let c = u32::try_from(value).ok().and_then(char::from_u32).ok_or(ParseError)?;
+                Ok(format!("{c:?}"))
+            }
+            b'a' | b'h' | b'i' | b'j' | b'l' | b'm' | b'n' | b'o' | b's' | b't' | b'x' | b'y' => {
+                let sign = if negative { "-" } else { "" };
+                Ok(format!("{sign}{value}{}", basic_type(ty).unwrap()))
+            }
+            _ => Err(ParseError),
+        }
+    }
+}
+
+fn basic_type(tag: u8) -> Option<&'static str> {
+    Some(match tag {
+        b'a' => "i8",
+        b'b' => "bool",
+        b'c' => "char",
+        b'd' => "f64",
+        b'e' => "str",
+        b'f' => "f32",
+        b'h' => "u8",
+        b'i' => "isize",
+        b'j' => "usize",
+        b'l' => "i32",
+        b'm' => "u32",
+        b'n' => "i128",
+        b'o' => "u128",
+        b's' => "i16",
+        b't' => "u16",
+        b'u' => "()",
+        b'v' => "...",
+        b'x' => "i64",
+        b'y' => "u64",
+        b'z' => "!",
+        b'p' => "_",
+        _ => return None,
+    })
+}
+
+/// Decodes a Punycode identifier, which v0 symbols use for identifiers that aren't ASCII, with
+/// `_` instead of `-` as the delimiter between the basic and the encoded code points.
+fn decode_punycode(ident: &str) -> Result<String, ParseError> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+    const SKEW: u32 = 38;
+    const DAMP: u32 = 700;
+
+    let (basic, encoded) = match ident.rfind('_') {
+        Some(i) => (&ident[..i], &ident[i + 1..]),
+        None => ("", ident),
+    };
+    let mut out: Vec<char> = basic.chars().collect();
+    let mut n: u32 = 0x80;
+    let mut i: u32 = 0;
+    let mut bias: u32 = 72;
+    let mut bytes = encoded.bytes().peekable();
+    while bytes.peek().is_some() {
+        let old_i = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+        loop {
+            let digit = match bytes.next().ok_or(ParseError)? {
+                d @ b'a'..=b'z' => u32::from(d - b'a'),
+                d @ b'0'..=b'9' => u32::from(d - b'0') + 26,
+                _ => return Err(ParseError),
+            };
+            i = digit.checked_mul(w).and_then(|d| i.checked_add(d)).ok_or(ParseError)?;
+            let t = if k <= bias { T_MIN } else if k >= bias + T_MAX { T_MAX } else { k - bias };
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t).ok_or(ParseError)?;
+            k += BASE;
+        }
+
+        let len = out.len() as u32 + 1;
+        let mut delta = if old_i == 0 { (i - old_i) / DAMP } else { (i - old_i) / 2 };
+        delta += delta / len;
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        bias = k + ((BASE - T_MIN + 1) * delta) / (delta + SKEW);
+
+        n = n.checked_add(i / len).ok_or(ParseError)?;
+        i %= len;
+        out.insert(i as usize, char::from_u32(n).ok_or(ParseError)?);
+        i += 1;
+    }
+    Ok(out.into_iter().collect())
+}
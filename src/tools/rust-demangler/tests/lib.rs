@@ -82,3 +82,64 @@ fn test_demangle_lines_no_crate_disambiguators() {
         assert_eq!(expected, actual);
     }
 }
+
+fn segment_names(symbol: &v0::Symbol) -> Vec<&str> {
+    symbol.path.iter().map(|segment| segment.name.as_str()).collect()
+}
+
+#[test]
+fn test_parse_v0_closures() {
+    let symbol = v0::parse("_RNCNCNgCs6DXkGYLi8lr_2cc5spawn00B5_").unwrap();
+    assert_eq!(symbol.krate, v0::Crate { name: "cc".into(), hash: "4d6468d6c9fd4bb3".into() });
+    assert_eq!(segment_names(&symbol), ["spawn", "{closure#0}", "{closure#0}"]);
+    assert_eq!(symbol.path[1].namespace.as_deref(), Some("closure"));
+    assert_eq!(symbol.instantiating_crate, None);
+}
+
+#[test]
+fn test_parse_v0_generic_args() {
+    let symbol = v0::parse(
+        "_RINbNbCskIICzLVDPPb_5alloc5alloc8box_free\
+        DINbNiB4_5boxed5FnBoxuEp6OutputuEL_ECs1iopQbuBiw2_3std",
+    )
+    .unwrap();
+    assert_eq!(symbol.krate, v0::Crate { name: "alloc".into(), hash: "f15a878b47eb696b".into() });
+    assert_eq!(segment_names(&symbol), ["alloc", "box_free"]);
+    assert_eq!(symbol.path[1].generic_args, ["dyn alloc::boxed::FnBox<(), Output = ()>"]);
+    assert_eq!(symbol.instantiating_crate.unwrap().name, "std");
+}
+
+#[test]
+fn test_parse_v0_impl_and_punycode() {
+    let symbol = v0::parse("_RNvNvMCs4fqI2P2rA04_13const_genericINtB4_3FooKpE3foo3FOO").unwrap();
+    assert_eq!(symbol.krate.name, "const_generic");
+    assert_eq!(segment_names(&symbol), ["<const_generic::Foo<_>>", "foo", "FOO"]);
+    assert_eq!(symbol.path[0].namespace, None);
+
+    let symbol =
+        v0::parse("_RNqCs4fqI2P2rA04_11utf8_identsu30____7hkackfecea1cbdathfdh9hlq6y").unwrap();
+    assert_eq!(segment_names(&symbol), ["საჭმელად_გემრიელი_სადილი"]);
+
+    let symbol = v0::parse("_RC3foo.llvm.9D1C9369").unwrap();
+    assert_eq!(symbol.suffix.as_deref(), Some(".llvm.9D1C9369"));
+    assert!(v0::parse("_ZN3foo3barE").is_err());
+}
+
+#[test]
+fn test_symbol_report() {
+    let report = symbol_report([
+        "_RNvC6_123foo3bar",
+        "_RNvNvMCs4fqI2P2rA04_13const_genericINtB4_3FooKpE3foo3FOO",
+        "_RMCs4fqI2P2rA04_13const_genericINtB0_4BoolKb1_E",
+        "_ZN3foo3bar17h05af221e174051e9E",
+        "memcpy",
+    ]);
+    let crates: Vec<_> = report
+        .crates
+        .iter()
+        .map(|krate| (krate.krate.name.as_str(), krate.symbols.len()))
+        .collect();
+    assert_eq!(crates, [("123foo", 1), ("const_generic", 2)]);
+    let ungrouped: Vec<_> = report.ungrouped.iter().map(|symbol| symbol.mangling).collect();
+    assert_eq!(ungrouped, [Some(Mangling::Legacy), None]);
+}
@@ -0,0 +1,8 @@
+fn longest<'a, 'b>(x: &'a str, y: &'b str) -> &'a str {
+    let shorter = if x.len() < y.len() { x } else { y };
+    shorter
+}
+
+fn main() {
+    longest("a", "b");
+}
@@ -0,0 +1,16 @@
+use std::cell::Cell;
+use std::panic;
+
+thread_local! {
+    static COUNTER: Cell<u32> = Cell::new(0);
+}
+
+fn main() {
+    let result = panic::catch_unwind(|| panic!("boom"));
+    println!("caught: {}", result.unwrap_err().downcast_ref::<&str>().unwrap());
+
+    for _ in 0..3 {
+        COUNTER.with(|counter| counter.set(counter.get() + 1));
+    }
+    println!("counter: {}", COUNTER.with(Cell::get));
+}
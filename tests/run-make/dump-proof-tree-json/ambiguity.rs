@@ -0,0 +1,14 @@
+trait Convert<T> {}
+
+impl Convert<u8> for u32 {}
+impl Convert<u16> for u32 {}
+
+fn convert<T>()
+where
+    u32: Convert<T>,
+{
+}
+
+fn main() {
+    convert();
+}
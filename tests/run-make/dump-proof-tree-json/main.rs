@@ -0,0 +1,18 @@
+trait Greet {}
+
+impl<T: Clone> Greet for T {}
+
+struct NotClone;
+
+fn greet<T: Greet>(_: T) {}
+
+trait Pair {}
+
+impl<T> Pair for (T, T) {}
+
+fn pair<T: Pair>(_: T) {}
+
+fn main() {
+    greet(NotClone);
+    pair((1u8, 2u16));
+}
@@ -0,0 +1,10 @@
+#[no_mangle]
+pub extern "C" fn foo_void() {}
+
+#[no_mangle]
+pub extern "C" fn foo_add(a: i32, b: i32) -> i32 {
+    a.wrapping_add(b)
+}
+
+#[no_mangle]
+pub fn foo_rust() {}
@@ -0,0 +1,7 @@
+pub fn square(x: u32) -> u32 {
+    x * x
+}
+
+fn main() {
+    println!("{}", square(std::env::args().count() as u32));
+}
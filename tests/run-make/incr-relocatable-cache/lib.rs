@@ -0,0 +1,7 @@
+pub fn foo() -> &'static str {
+    file!()
+}
+
+pub fn bar() -> u32 {
+    line!()
+}
@@ -0,0 +1,9 @@
+#[link(name = "foo", kind = "static")]
+extern "C" {
+    fn foo() -> i32;
+}
+
+fn main() {
+    assert_eq!(unsafe { foo() }, 42);
+    println!("hello from the reproducer");
+}
@@ -0,0 +1,10 @@
+struct Point {
+    x: i32,
+}
+
+fn main() {
+    let points = vec![Point { x: 1 }];
+    let total: i32 = points.iter().map(|p| p.x).sum();
+    let Point { ref x } = points[0];
+    println!("{total} {x}");
+}
@@ -0,0 +1,9 @@
+#[inline(never)]
+fn generic<T: std::fmt::Debug>(value: T) -> String {
+    format!("{value:?}")
+}
+
+fn main() {
+    println!("{}", generic(1u8));
+    println!("{}", generic("two"));
+}
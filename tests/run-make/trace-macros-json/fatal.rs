@@ -0,0 +1,10 @@
+macro_rules! load {
+    () => {
+        #[path = concat!("a", ".rs")]
+        mod a;
+    };
+}
+
+load!();
+
+fn main() {}
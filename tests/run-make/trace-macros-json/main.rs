@@ -0,0 +1,8 @@
+macro_rules! sum {
+    () => { 0 };
+    ($first:expr $(, $rest:expr)*) => { $first + sum!($($rest),*) };
+}
+
+fn main() {
+    assert_eq!(sum!(1, 2, 3), 6);
+}
@@ -0,0 +1,55 @@
+//@ edition:2021
+//@ compile-flags: --crate-type lib
+// Check that `async_fn_not_send` points at what makes the future returned by a public
+// `async fn` not `Send`.
+
+#![deny(async_fn_not_send)]
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+async fn yield_now() {}
+
+pub async fn held_local() {
+    //~^ ERROR future returned by `held_local` is not `Send`
+    let rc = Rc::new(0);
+    yield_now().await;
+    drop(rc);
+}
+
+pub async fn argument(cell: &Cell<i32>) {
+    //~^ ERROR future returned by `argument` is not `Send`
+    yield_now().await;
+    cell.set(0);
+}
+
+pub async fn shadowed_argument(cell: i32) {
+    //~^ ERROR future returned by `shadowed_argument` is not `Send`
+    let cell = Rc::new(cell);
+    yield_now().await;
+    drop(cell);
+}
+
+pub async fn awaits_private() {
+    //~^ ERROR future returned by `awaits_private` is not `Send`
+    private().await;
+}
+
+pub async fn dropped_before_await() {
+    {
+        let rc = Rc::new(0);
+        drop(rc);
+    }
+    yield_now().await;
+}
+
+async fn private() {
+    let rc = Rc::new(0);
+    yield_now().await;
+    drop(rc);
+}
+
+#[allow(async_fn_not_send)]
+pub async fn allowed() {
+    private().await;
+}
@@ -0,0 +1,24 @@
+//@ edition:2021
+//@ check-pass
+
+#![warn(closure_captures)]
+
+struct Point {
+    x: i32,
+    name: String,
+}
+
+fn main() {
+    let mut p = Point { x: 1, name: String::new() };
+    let mut c = || {
+        //~^ WARN this closure captures 2 places
+        p.x += 1;
+        drop(p.name);
+    };
+    c();
+
+    let t = (1, String::new());
+    let d = move || t.0 + 1;
+    //~^ WARN this closure captures one place
+    d();
+}
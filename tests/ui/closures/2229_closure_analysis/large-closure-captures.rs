@@ -0,0 +1,19 @@
+//@ edition:2021
+//@ check-pass
+
+#![warn(large_closure_captures)]
+
+fn main() {
+    let buf = [0u8; 4096];
+    let small = [0u8; 16];
+    let c = move || buf[0] + small[0];
+    //~^ WARN this `move` closure captures `buf` by value, which is 4096 bytes
+    c();
+
+    // Neither borrowing closures nor moved references are linted.
+    let d = || buf[1];
+    d();
+    let r = &buf;
+    let e = move || r[2];
+    e();
+}
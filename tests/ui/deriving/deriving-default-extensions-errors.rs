@@ -0,0 +1,13 @@
+#![feature(derive_default_extensions)]
+
+#[derive(Default)]
+struct Builder<#[default(bound)] T> {
+    //~^ ERROR the `#[default]` attribute on type parameters only accepts `no_bound`
+    items: Vec<T>,
+}
+
+#[derive(Default)]
+struct Wrapper<#[default] T>(Vec<T>);
+//~^ ERROR the `#[default]` attribute on type parameters only accepts `no_bound`
+
+fn main() {}
@@ -0,0 +1,42 @@
+//@ run-pass
+// Checks `#[derive(Default)]` with `#[default]` on variants with fields and with
+// `#[default(no_bound)]` on type parameters.
+
+#![feature(derive_default_extensions)]
+
+use std::marker::PhantomData;
+
+#[derive(Debug, PartialEq)]
+struct NotDefault;
+
+// Only the fields of the default variant need to be `Default`.
+#[derive(Debug, PartialEq, Default)]
+enum Setting<T> {
+    #[default]
+    Value(T, u8),
+    Other(NotDefault),
+}
+
+#[derive(Debug, PartialEq, Default)]
+enum Options<T> {
+    Disabled(T),
+    #[default]
+    Enabled { level: u8, name: String },
+}
+
+#[derive(Debug, Default)]
+struct Builder<#[default(no_bound)] T> {
+    items: Vec<T>,
+    marker: PhantomData<T>,
+}
+
+fn main() {
+    assert_eq!(Setting::<u32>::default(), Setting::Value(0, 0));
+
+    // `T` doesn't appear in the default variant, so it doesn't need to be `Default`.
+    let options = Options::<NotDefault>::default();
+    assert_eq!(options, Options::Enabled { level: 0, name: String::new() });
+
+    let builder = Builder::<NotDefault>::default();
+    assert!(builder.items.is_empty());
+}
@@ -0,0 +1,41 @@
+// Checks the errors for invalid and inconsistent field attributes of builtin derives.
+
+#![feature(derive_field_attrs)]
+
+#[derive(PartialEq, Hash)]
+struct SkippedByEqButHashed {
+    #[eq(skip)]
+    cache: u8, //~ ERROR field is skipped by `#[eq(skip)]`, but not by `#[hash(skip)]`
+}
+
+#[derive(PartialEq, PartialOrd)]
+struct SkippedByEqButOrdered {
+    #[eq(skip)]
+    cache: u8, //~ ERROR field is skipped by `#[eq(skip)]`, but not by `#[ord(skip)]`
+}
+
+#[derive(PartialEq, PartialOrd)]
+struct SkippedByOrdButCompared {
+    #[ord(skip)]
+    cache: u8, //~ ERROR field is skipped by `#[ord(skip)]`, but not by `#[eq(skip)]`
+}
+
+#[derive(PartialEq, Hash)]
+struct SkippedByHashOnly {
+    #[hash(skip)]
+    cache: u8,
+}
+
+#[derive(PartialEq)]
+struct UnknownOption {
+    #[eq(reverse)] //~ ERROR unknown option `reverse` in `#[eq]`
+    value: u8,
+}
+
+#[derive(Hash)]
+struct MissingOptions {
+    #[hash] //~ ERROR `#[hash]` expects a list of options
+    value: u8,
+}
+
+fn main() {}
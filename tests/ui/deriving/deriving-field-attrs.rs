@@ -0,0 +1,68 @@
+//@ run-pass
+// Checks the field attributes customizing the builtin comparison and hashing derives.
+
+#![feature(derive_field_attrs)]
+#![allow(dead_code)]
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn hash_abs<H: Hasher>(value: &i32, state: &mut H) {
+    value.unsigned_abs().hash(state);
+}
+
+// `f32` isn't `Eq`, `Ord` or `Hash`, so this only compiles if the field is skipped.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Cached {
+    value: u32,
+    #[eq(skip)]
+    #[ord(skip)]
+    #[hash(skip)]
+    cache: f32,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Priority(#[ord(reverse)] u32, u32);
+
+#[derive(Debug, PartialEq, Hash)]
+struct Signed(#[hash(with = hash_abs)] i32, #[hash(skip)] u8);
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Shape {
+    Circle {
+        #[ord(reverse)]
+        radius: u32,
+    },
+    Label(u32, #[eq(skip)] #[ord(skip)] #[hash(skip)] &'static str),
+}
+
+fn main() {
+    let a = Cached { value: 1, cache: 1.0 };
+    let b = Cached { value: 1, cache: f32::NAN };
+    assert_eq!(a, b);
+    assert_eq!(a.cmp(&b), Ordering::Equal);
+    assert_eq!(hash(&a), hash(&b));
+    assert!(a < Cached { value: 2, cache: 0.0 });
+
+    assert!(Priority(2, 0) < Priority(1, 0));
+    assert_eq!(Priority(2, 0).partial_cmp(&Priority(1, 0)), Some(Ordering::Less));
+    assert_eq!(Priority(1, 0).cmp(&Priority(1, 1)), Ordering::Less);
+
+    assert_ne!(Signed(5, 0), Signed(-5, 0));
+    assert_eq!(hash(&Signed(5, 0)), hash(&Signed(-5, 1)));
+    assert_ne!(hash(&Signed(5, 0)), hash(&Signed(6, 0)));
+
+    assert!(Shape::Circle { radius: 2 } < Shape::Circle { radius: 1 });
+    assert!(Shape::Circle { radius: 1 } < Shape::Label(0, "a"));
+    assert_eq!(Shape::Label(0, "a"), Shape::Label(0, "b"));
+    assert_eq!(Shape::Label(0, "a").cmp(&Shape::Label(0, "b")), Ordering::Equal);
+    assert_eq!(hash(&Shape::Label(0, "a")), hash(&Shape::Label(0, "b")));
+    assert_ne!(Shape::Label(0, "a"), Shape::Label(1, "a"));
+}
@@ -0,0 +1,13 @@
+#[derive(Default)]
+enum Data {
+    #[default]
+    Value(u8), //~ ERROR `#[default]` on enum variants with fields is experimental
+    Empty,
+}
+
+#[derive(Default)]
+struct Builder<#[default(no_bound)] T> { //~ ERROR `#[default(no_bound)]` on type parameters is experimental
+    items: Vec<T>,
+}
+
+fn main() {}
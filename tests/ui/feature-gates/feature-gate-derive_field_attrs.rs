@@ -0,0 +1,9 @@
+#[derive(PartialEq, Hash)]
+struct Cached {
+    value: u8,
+    #[eq(skip)] //~ ERROR cannot find attribute `eq` in this scope
+    #[hash(skip)] //~ ERROR cannot find attribute `hash` in this scope
+    cache: u8,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+macro_rules! create_getter {
+    ($name:ident) => {
+        fn ${concat(get_, $name)}() {}
+        //~^ ERROR the `concat` meta-variable expression is unstable
+    };
+}
+
+create_getter!(value);
+
+fn main() {}
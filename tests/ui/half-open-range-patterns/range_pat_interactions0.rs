@@ -1,6 +1,7 @@
 //@ run-pass
 #![feature(exclusive_range_pattern)]
 #![feature(inline_const_pat)]
+#![allow(non_contiguous_range_endpoints)]
 
 fn main() {
     let mut if_lettable = vec![];
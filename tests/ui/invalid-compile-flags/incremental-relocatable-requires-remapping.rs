@@ -0,0 +1,8 @@
+//@ revisions: no-remap scope
+//@ compile-flags: -Zincremental-relocatable
+//@[scope] compile-flags: -Zremap-path-scope=object
+
+// Check that `-Zincremental-relocatable` is rejected when the incremental cache
+// could still contain local paths.
+
+fn main() {}
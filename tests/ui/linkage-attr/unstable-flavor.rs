@@ -1,11 +1,15 @@
-// Even though this test only checks 2 of the 10 or so unstable linker flavors, it exercizes the
+// Even though this test only checks 3 of the 10 or so unstable linker flavors, it exercizes the
 // unique codepath checking all unstable options (see `LinkerFlavorCli::is_unstable` and its
 // caller). If it passes, all the other unstable options are rejected as well.
 //
-//@ revisions: bpf ptx
+//@ revisions: bpf builtin ptx
 //@ [bpf] compile-flags: --target=bpfel-unknown-none -C linker-flavor=bpf --crate-type=rlib
 //@ [bpf] error-pattern: linker flavor `bpf` is unstable, the `-Z unstable-options` flag
 //@ [bpf] needs-llvm-components:
+//@ [builtin] compile-flags: --target=x86_64-unknown-linux-musl -C linker-flavor=builtin
+//@ [builtin] compile-flags: --crate-type=rlib
+//@ [builtin] error-pattern: linker flavor `builtin` is unstable, the `-Z unstable-options` flag
+//@ [builtin] needs-llvm-components: x86
 //@ [ptx] compile-flags: --target=nvptx64-nvidia-cuda -C linker-flavor=ptx --crate-type=rlib
 //@ [ptx] error-pattern: linker flavor `ptx` is unstable, the `-Z unstable-options` flag
 //@ [ptx] needs-llvm-components:
@@ -82,7 +82,7 @@ enum ManyDefaultAttrs {
 #[derive(Default)]
 enum DefaultHasFields {
     #[default]
-    Foo {}, //~ ERROR the `#[default]` attribute may only be used on unit enum variants
+    Foo {}, //~ ERROR `#[default]` on enum variants with fields is experimental
     Bar,
 }
 
@@ -0,0 +1,37 @@
+#![feature(macro_metavar_expr_concat)]
+
+macro_rules! one_element {
+    ($a:ident) => {
+        ${concat($a)}
+        //~^ ERROR `concat` must have at least two elements, separated by commas
+        //~| ERROR expected expression, found `$`
+    };
+}
+
+macro_rules! not_an_ident {
+    ($name:ident, $suffix:literal) => {
+        fn ${concat($name, $suffix)}() {}
+        //~^ ERROR `concat` can only concatenate identifiers, but `$suffix` is not bound to one
+    };
+}
+
+macro_rules! still_repeating {
+    ($($name:ident)*) => {
+        fn ${concat(get_, $name)}() {}
+        //~^ ERROR variable 'name' is still repeating at this depth
+    };
+}
+
+macro_rules! unknown_var {
+    ($name:ident) => {
+        fn ${concat(get_, $nmae)}() {}
+        //~^ ERROR variable `nmae` is not recognized in meta-variable expression
+    };
+}
+
+fn main() {
+    not_an_ident!(get, 1);
+    still_repeating!(a b);
+    unknown_var!(value);
+    let _ = one_element!(x);
+}
@@ -0,0 +1,49 @@
+//@ run-pass
+
+#![feature(macro_metavar_expr_concat)]
+
+macro_rules! getters {
+    ($ty:ident { $($field:ident: $field_ty:ty),* }) => {
+        struct $ty {
+            $($field: $field_ty),*
+        }
+
+        impl $ty {
+            $(
+                fn ${concat(get_, $field)}(&self) -> &$field_ty {
+                    &self.$field
+                }
+            )*
+        }
+    };
+}
+
+getters!(Point { x: i32, y: i32 });
+
+macro_rules! const_names {
+    ($($prefix:tt)*) => {
+        $(const ${concat($prefix, _LIMIT)}: u32 = 1;)*
+    };
+}
+
+const_names!(LOWER UPPER);
+
+macro_rules! shadow {
+    ($var:ident) => {{
+        // The identifier produced by `concat` has the span of the macro definition, so it can't
+        // be named by the caller, like an identifier written in the macro.
+        let ${concat($var, _copy)} = 0;
+        ${concat($var, _copy)} + $var
+    }};
+}
+
+fn main() {
+    let point = Point { x: 1, y: 2 };
+    assert_eq!(*point.get_x() + *point.get_y(), 3);
+    assert_eq!(LOWER_LIMIT + UPPER_LIMIT, 2);
+
+    let value = 5;
+    let value_copy = 10;
+    assert_eq!(shadow!(value), 5);
+    assert_eq!(value_copy, 10);
+}
@@ -0,0 +1,19 @@
+//@ run-rustfix
+//@ rustfix-only-machine-applicable
+// Check that we suggest adding `Self: Sized` bounds to the methods that keep a trait from being
+// made into an object, at every place the trait is used as an object.
+
+trait Shape {
+    fn area(&self) -> f64;
+    fn duplicate(&self) -> Self;
+    fn describe<W: std::fmt::Write>(&self, out: W);
+    fn pair<T>(&self, t: T) where T: Copy;
+}
+
+fn print(_: &dyn Shape) {}
+//~^ ERROR the trait `Shape` cannot be made into an object
+
+fn compare(_: &dyn Shape) {}
+//~^ ERROR the trait `Shape` cannot be made into an object
+
+fn main() {}
@@ -0,0 +1,13 @@
+// Check that we suggest adding `Self: Sized` bounds, boxing type parameters and moving the
+// offending items to a subtrait when a trait can't be made into an object.
+
+trait Shape {
+    fn area(&self) -> f64;
+    fn describe<W: std::fmt::Write>(&self, out: W);
+    const SIDES: usize;
+}
+
+fn print(_: &dyn Shape) {}
+//~^ ERROR the trait `Shape` cannot be made into an object
+
+fn main() {}
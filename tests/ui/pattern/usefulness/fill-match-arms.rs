@@ -0,0 +1,78 @@
+//@ edition:2021
+//@ compile-flags: -Zfill-match-arms=3
+//@ run-rustfix
+//@ rustfix-only-machine-applicable
+// Check that `-Zfill-match-arms` suggests a match arm for each missing pattern, naming types by
+// paths that resolve from the `match` even where they aren't imported, and falls back to a
+// wildcard arm past the limit.
+#![allow(dead_code)]
+
+mod shapes {
+    pub enum Shape {
+        Circle,
+        Square(u32),
+        Rect { w: u32, h: u32 },
+    }
+}
+
+use shapes::Shape;
+
+enum Outer {
+    A(Option<Shape>),
+    B,
+}
+
+enum Digit {
+    Zero,
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+fn shape(s: Shape) {
+    match s {
+        //~^ ERROR non-exhaustive patterns: `Shape::Square(_)` and `Shape::Rect { .. }` not covered
+        Shape::Circle => (),
+    }
+}
+
+fn outer(o: Outer) {
+    match o {
+        //~^ ERROR non-exhaustive patterns
+        Outer::A(None) => (),
+        Outer::A(Some(Shape::Circle)) => (),
+    }
+}
+
+fn digit(d: Digit) {
+    match d {
+        //~^ ERROR non-exhaustive patterns
+        Digit::Zero => (),
+    }
+}
+
+mod inner {
+    enum Mode {
+        On,
+        Off,
+    }
+
+    fn toggle(m: Mode) {
+        match m {
+            //~^ ERROR non-exhaustive patterns: `Mode::Off` not covered
+            Mode::On => (),
+        }
+    }
+}
+
+mod unimported {
+    fn shape(s: crate::shapes::Shape) {
+        match s {
+            //~^ ERROR non-exhaustive patterns: `Shape::Circle` not covered
+            crate::shapes::Shape::Square(_) | crate::shapes::Shape::Rect { .. } => (),
+        }
+    }
+}
+
+fn main() {}
@@ -1,5 +1,5 @@
 #![feature(exclusive_range_pattern)]
-#![allow(overlapping_range_endpoints)]
+#![allow(overlapping_range_endpoints, non_contiguous_range_endpoints)]
 #![deny(unreachable_patterns)]
 
 macro_rules! m {
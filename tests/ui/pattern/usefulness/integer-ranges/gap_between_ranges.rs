@@ -0,0 +1,32 @@
+#![feature(exclusive_range_pattern)]
+#![deny(non_contiguous_range_endpoints)]
+
+fn main() {
+    match 0u8 {
+        20..30 => {} //~ ERROR multiple ranges are one apart
+        31..=40 => {}
+        _ => {}
+    }
+    match 0u8 {
+        0..10 => {} //~ ERROR multiple ranges are one apart
+        11..20 => {}
+        11..=20 => {}
+        _ => {}
+    }
+    match 0u8 {
+        0..255 => {} //~ ERROR exclusive range missing `u8::MAX`
+        _ => {}
+    }
+    // No lint when the range before the gap is inclusive, or when the gap is matched.
+    match 0u8 {
+        0..=9 => {}
+        11..=20 => {}
+        _ => {}
+    }
+    match 0u8 {
+        0..10 => {}
+        10 => {}
+        11..20 => {}
+        _ => {}
+    }
+}
@@ -0,0 +1,24 @@
+#![deny(narrow_wildcard_arms)]
+
+fn main() {
+    match 0u8 {
+        0..=9 => {}
+        11..=254 => {}
+        _ => {} //~ ERROR this wildcard only matches a few values
+    }
+    match 0i8 {
+        i8::MIN..=-1 => {}
+        2..=i8::MAX => {}
+        _ => {} //~ ERROR this wildcard only matches a few values
+    }
+    // No lint when the wildcard matches many values, or binds the value.
+    match 0u8 {
+        0..=9 => {}
+        _ => {}
+    }
+    match 0u8 {
+        0..=9 => {}
+        11..=254 => {}
+        x => {}
+    }
+}
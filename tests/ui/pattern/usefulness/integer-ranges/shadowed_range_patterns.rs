@@ -0,0 +1,32 @@
+#![feature(exclusive_range_pattern)]
+#![allow(unreachable_patterns)]
+#![deny(shadowed_range_patterns)]
+
+fn main() {
+    match 0u8 {
+        0..=7 => {}
+        8..=10 => {}
+        6..=9 => {} //~ ERROR unreachable range pattern
+        _ => {}
+    }
+    match 0i8 {
+        -10..0 => {}
+        0..=5 => {}
+        6 | -5..=2 => {} //~ ERROR unreachable range pattern
+        _ => {}
+    }
+    // No lint when a single earlier range covers the range.
+    match 0u8 {
+        0..=3 => {}
+        0..=10 => {}
+        5..=8 => {}
+        _ => {}
+    }
+    // No lint when the range is reachable because an earlier arm has a guard.
+    match 0u8 {
+        0..=7 if true => {}
+        8..=10 => {}
+        6..=9 => {}
+        _ => {}
+    }
+}
@@ -0,0 +1,19 @@
+// Without `#![feature(derive_field_attrs)]`, `#[eq]`, `#[ord]` and `#[hash]` aren't helper
+// attributes of the built-in derives, so attribute macros with these names stay unambiguous.
+
+//@ check-pass
+//@ aux-build:test-macros.rs
+
+extern crate test_macros;
+
+use test_macros::identity_attr as eq;
+use test_macros::identity_attr as hash;
+use test_macros::identity_attr as ord;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[eq]
+#[ord]
+#[hash]
+struct S(u8);
+
+fn main() {}
@@ -0,0 +1,23 @@
+//@ check-fail
+#![feature(transmutability)]
+
+mod assert {
+    use std::mem::{Assume, BikeshedIntrinsicFrom};
+
+    pub fn is_maybe_transmutable<Src, Dst>()
+    where
+        Dst: BikeshedIntrinsicFrom<Src, {
+            Assume {
+                alignment: true,
+                lifetimes: true,
+                safety: true,
+                validity: false,
+            }
+        }>
+    {}
+}
+
+fn main() {
+    // There is no single byte at which the reference and the integer disagree.
+    assert::is_maybe_transmutable::<&'static u8, usize>(); //~ ERROR cannot be safely transmuted
+}